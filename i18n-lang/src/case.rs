@@ -0,0 +1,242 @@
+/// A casing transformation that can be applied to user-visible text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `ALL UPPERCASE`.
+    Upper,
+    /// `all lowercase`.
+    Lower,
+    /// `Every Word Capitalized`.
+    Title,
+    /// `Only the first word capitalized`, leaving the rest of the text as is.
+    Sentence,
+}
+
+/// Options for the language-aware casing functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CaseOptions {
+    /// Uppercases the German `ß` to the capital `ẞ` instead of `SS`.
+    pub capital_sharp_s: bool,
+}
+
+/// Applies the given casing to a string, respecting the rules of the language `lang`.
+pub fn to_case(lang: &str, s: &str, case: Case) -> String {
+    match case {
+        Case::Upper => to_upper(lang, s),
+        Case::Lower => to_lower(lang, s),
+        Case::Title => to_title(lang, s),
+        Case::Sentence => to_sentence(lang, s),
+    }
+}
+
+/// Uppercases a string, respecting the rules of the language `lang`.
+///
+/// Handles the Turkish and Azerbaijani dotted and dotless `i`, the Lithuanian dot above,
+/// the German `ß` and Greek accents, and falls back to `str::to_uppercase` otherwise.
+pub fn to_upper(lang: &str, s: &str) -> String {
+    to_upper_with(lang, s, CaseOptions::default())
+}
+
+/// Uppercases a string, respecting the rules of the language `lang` and the given options.
+pub fn to_upper_with(lang: &str, s: &str, options: CaseOptions) -> String {
+    let lang = primary_language(lang);
+    let mut out = String::with_capacity(s.len());
+    let mut prev = None;
+    for ch in s.chars() {
+        match (lang, ch) {
+            ("tr" | "az", 'i') => out.push('İ'),
+            ("tr" | "az", 'ı') => out.push('I'),
+            // the dot above a soft-dotted letter is implied by the lowercase letter only
+            ("lt", '\u{307}') if prev.is_some_and(is_soft_dotted) => {}
+            ("de", 'ß') if options.capital_sharp_s => out.push('ẞ'),
+            ("el", _) => push_greek_upper(&mut out, ch),
+            _ => out.extend(ch.to_uppercase()),
+        }
+        prev = Some(ch);
+    }
+
+    out
+}
+
+/// Lowercases a string, respecting the rules of the language `lang`.
+///
+/// Handles the Turkish and Azerbaijani dotted and dotless `I` and the Lithuanian dot above, and
+/// falls back to `str::to_lowercase` otherwise, which already handles the Greek final sigma.
+pub fn to_lower(lang: &str, s: &str) -> String {
+    let lang = primary_language(lang);
+    match lang {
+        "tr" | "az" => s
+            .chars()
+            .map(|ch| match ch {
+                'I' => "ı".to_string(),
+                'İ' => "i".to_string(),
+                _ => ch.to_lowercase().collect(),
+            })
+            .collect(),
+        "lt" => {
+            let mut out = String::with_capacity(s.len());
+            let mut chars = s.chars().peekable();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    'Ì' => out.push_str("i\u{307}\u{300}"),
+                    'Í' => out.push_str("i\u{307}\u{301}"),
+                    'Ĩ' => out.push_str("i\u{307}\u{303}"),
+                    'I' | 'J' | 'Į' if chars.peek().is_some_and(|next| is_accent_above(*next)) => {
+                        out.extend(ch.to_lowercase());
+                        out.push('\u{307}');
+                    }
+                    _ => out.extend(ch.to_lowercase()),
+                }
+            }
+            out
+        }
+        _ => s.to_lowercase(),
+    }
+}
+
+/// Capitalizes every word of a string and lowercases the rest, respecting the rules of the language `lang`.
+///
+/// The Dutch `ij` digraph is capitalized as a whole.
+pub fn to_title(lang: &str, s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut word = String::default();
+    for ch in s.chars() {
+        if ch.is_alphanumeric() || ch == '\'' || is_combining(ch) {
+            word.push(ch);
+        } else {
+            out.push_str(&capitalize(lang, &to_lower(lang, &word)));
+            word.clear();
+            out.push(ch);
+        }
+    }
+    out.push_str(&capitalize(lang, &to_lower(lang, &word)));
+
+    out
+}
+
+/// Capitalizes the first letter of a string, leaving the rest as is, respecting the rules of the language `lang`.
+pub fn to_sentence(lang: &str, s: &str) -> String {
+    let start = s.find(char::is_alphanumeric).unwrap_or(s.len());
+    let (prefix, rest) = s.split_at(start);
+    format!("{prefix}{}", capitalize(lang, rest))
+}
+
+/// Uppercases the first letter of a word, leaving the rest as is.
+fn capitalize(lang: &str, word: &str) -> String {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return String::default();
+    };
+
+    let rest = chars.as_str();
+    if primary_language(lang) == "nl" && first == 'i' && rest.starts_with('j') {
+        return format!("IJ{}", &rest[1..]);
+    }
+
+    let mut first_len = first.len_utf8();
+    // keep the combining marks attached to the first letter
+    for ch in rest.chars().take_while(|ch| is_combining(*ch)) {
+        first_len += ch.len_utf8();
+    }
+    format!(
+        "{}{}",
+        to_upper(lang, &word[..first_len]),
+        &word[first_len..]
+    )
+}
+
+/// Extracts the primary language subtag of a language identifier (e.g. `tr` from `tr-TR`).
+fn primary_language(lang: &str) -> &str {
+    lang.split(['-', '_']).next().unwrap_or(lang)
+}
+
+/// Whether the letter has an implicit dot above that is removed when an accent is placed above it.
+fn is_soft_dotted(ch: char) -> bool {
+    matches!(ch, 'i' | 'j' | 'į' | 'ɨ' | 'ʝ' | 'ј' | 'і')
+}
+
+/// Whether the character is a combining accent placed above the letter.
+fn is_accent_above(ch: char) -> bool {
+    matches!(ch, '\u{300}'..='\u{314}' | '\u{33d}'..='\u{344}' | '\u{346}' | '\u{34a}'..='\u{34c}')
+}
+
+/// Whether the character is a combining diacritical mark.
+fn is_combining(ch: char) -> bool {
+    matches!(ch, '\u{300}'..='\u{36f}')
+}
+
+/// Pushes the uppercase form of a character without the tonos, as Greek drops it in uppercase,
+/// whether the vowel is precomposed or followed by a combining accent as in NFD text.
+fn push_greek_upper(out: &mut String, ch: char) {
+    let upper = match ch {
+        'ά' | 'Ά' => 'Α',
+        'έ' | 'Έ' => 'Ε',
+        'ή' | 'Ή' => 'Η',
+        'ί' | 'Ί' => 'Ι',
+        'ό' | 'Ό' => 'Ο',
+        'ύ' | 'Ύ' => 'Υ',
+        'ώ' | 'Ώ' => 'Ω',
+        'ΐ' | 'ϊ' => 'Ϊ',
+        'ΰ' | 'ϋ' => 'Ϋ',
+        '\u{301}' => return,
+        // the dialytika is kept, as for the precomposed `ΐ`
+        '\u{344}' => '\u{308}',
+        _ => return out.extend(ch.to_uppercase()),
+    };
+    out.push(upper);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_if_turkish_dotted_and_dotless_i_round_trip() {
+        assert_eq!(to_upper("tr-TR", "istanbul ılık"), "İSTANBUL ILIK");
+        assert_eq!(to_lower("tr-TR", "İSTANBUL ILIK"), "istanbul ılık");
+        assert_eq!(to_lower("tr", &to_upper("tr", "iı")), "iı");
+        assert_eq!(to_title("az", "ilk ışık"), "İlk Işık");
+        // other languages use the default mapping
+        assert_eq!(to_upper("en-US", "istanbul"), "ISTANBUL");
+    }
+
+    #[test]
+    fn test_if_greek_sigma_and_accents_are_handled() {
+        // the final sigma is `ς`, any other sigma is `σ`
+        assert_eq!(to_lower("el", "ΟΔΟΣ"), "\u{3bf}\u{3b4}\u{3bf}\u{3c2}");
+        assert_eq!(
+            to_lower("el-GR", "ΣΟΦΟΣ ΟΔΟΣ"),
+            "\u{3c3}\u{3bf}\u{3c6}\u{3bf}\u{3c2} \u{3bf}\u{3b4}\u{3bf}\u{3c2}"
+        );
+        assert_eq!(to_upper("el", "άλφα όμικρον"), "ΑΛΦΑ ΟΜΙΚΡΟΝ");
+        // decomposed accents are dropped as well
+        assert_eq!(to_upper("el", "α\u{301}λφα"), "ΑΛΦΑ");
+        assert_eq!(to_upper("el", "ι\u{344}"), "Ι\u{308}");
+        // a character uppercasing to several keeps all of them
+        assert_eq!(to_upper("el", "ᾳ"), "ΑΙ");
+    }
+
+    #[test]
+    fn test_if_lithuanian_dot_above_is_handled() {
+        assert_eq!(to_lower("lt", "Ì"), "i\u{307}\u{300}");
+        assert_eq!(to_lower("lt", "I\u{301}"), "i\u{307}\u{301}");
+        assert_eq!(to_upper("lt", "i\u{307}\u{301}"), "I\u{301}");
+    }
+
+    #[test]
+    fn test_if_german_sharp_s_is_handled() {
+        assert_eq!(to_upper("de", "straße"), "STRASSE");
+        let options = CaseOptions {
+            capital_sharp_s: true,
+        };
+        assert_eq!(to_upper_with("de-DE", "straße", options), "STRAẞE");
+    }
+
+    #[test]
+    fn test_if_title_and_sentence_case_work() {
+        assert_eq!(to_title("en", "the QUICK brown fox"), "The Quick Brown Fox");
+        assert_eq!(to_title("nl", "ijsselmeer"), "IJsselmeer");
+        assert_eq!(to_sentence("en", "hello world"), "Hello world");
+        assert_eq!(to_sentence("tr", "¿istanbul?"), "¿İstanbul?");
+        assert_eq!(to_case("en", "Hello", Case::Upper), "HELLO");
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{Continent, Lang, ScriptFamily};
+use std::collections::BTreeMap;
+
+/// The classification to group languages by, see `group_langs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// Groups by `Lang::script_family`.
+    Script,
+    /// Groups by `Lang::region_continent`.
+    Continent,
+}
+
+/// The label of a group of languages produced by `group_langs`.
+///
+/// Labels are ordered by their variant and then by the classification, with `Unknown` last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GroupLabel {
+    Script(ScriptFamily),
+    Continent(Continent),
+    /// The languages without a region, when grouping by continent.
+    Unknown,
+}
+
+impl GroupLabel {
+    /// The English name of the group.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Script(script) => script.name(),
+            Self::Continent(continent) => continent.name(),
+            Self::Unknown => "Other",
+        }
+    }
+}
+
+impl std::fmt::Display for GroupLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Groups languages by script family or continent.
+///
+/// The groups are ordered by their label, while the languages within a group keep their order
+/// from `langs`, so sorting the input first yields a sorted menu.
+///
+/// # Example
+///
+/// ```
+/// use i18n_lang::{group_langs, GroupBy, GroupLabel, Lang, ScriptFamily};
+///
+/// let langs = ["ru-RU", "en-US", "hr-HR"].map(|lang| Lang::new(lang.parse().unwrap()));
+/// let groups = group_langs(&langs, GroupBy::Script);
+/// assert_eq!(groups[0].0, GroupLabel::Script(ScriptFamily::Latin));
+/// assert_eq!(groups[0].1, [&langs[1], &langs[2]]);
+/// ```
+pub fn group_langs(langs: &[Lang], by: GroupBy) -> Vec<(GroupLabel, Vec<&Lang>)> {
+    let mut groups: BTreeMap<GroupLabel, Vec<&Lang>> = BTreeMap::new();
+    for lang in langs {
+        let label = match by {
+            GroupBy::Script => GroupLabel::Script(lang.script_family()),
+            GroupBy::Continent => lang
+                .region_continent()
+                .map_or(GroupLabel::Unknown, GroupLabel::Continent),
+        };
+        groups.entry(label).or_default().push(lang);
+    }
+
+    groups.into_iter().collect()
+}
@@ -0,0 +1,118 @@
+use crate::{
+    langid_to_dir, langid_to_flag, langid_to_name, langid_to_script_family, region_to_continent,
+    script_to_family, Continent, ScriptFamily,
+};
+
+/// Provides all information on a language like `Lang`, borrowing the identifier instead of
+/// allocating, so that it is available without `std`.
+///
+/// The identifier is kept as given, e.g. `sr_Latn_RS`, while the lookups are case-insensitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LangRef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub flag: &'static str,
+    pub dir: &'static str,
+}
+
+impl LangRef {
+    /// A new `LangRef` object for a langid such as `en-US` or `pt_BR`.
+    pub fn new(id: &'static str) -> Self {
+        let mut buf = [0; 8];
+        let language =
+            ascii_case(language_subtag(id), &mut buf, u8::to_ascii_lowercase).unwrap_or_default();
+        let flag = region_subtag(id)
+            .and_then(|region| {
+                let mut buf = [0; 2];
+                langid_to_flag(ascii_case(region, &mut buf, u8::to_ascii_uppercase)?)
+            })
+            .unwrap_or_default();
+
+        Self {
+            id,
+            name: langid_to_name(language),
+            flag,
+            dir: langid_to_dir(language),
+        }
+    }
+
+    /// The family of the script the language is written in, see `Lang::script_family`.
+    pub fn script_family(&self) -> ScriptFamily {
+        let script = subtags(self.id).skip(1).find(|subtag| subtag.len() == 4);
+        if let Some(script) = script {
+            // script subtags are title case, e.g. `Cyrl`
+            let mut buf = [0; 4];
+            for (idx, (out, byte)) in buf.iter_mut().zip(script.as_bytes()).enumerate() {
+                *out = match idx {
+                    0 => byte.to_ascii_uppercase(),
+                    _ => byte.to_ascii_lowercase(),
+                };
+            }
+            return script_to_family(core::str::from_utf8(&buf).unwrap_or_default());
+        }
+
+        let mut buf = [0; 8];
+        let language = ascii_case(language_subtag(self.id), &mut buf, u8::to_ascii_lowercase);
+        langid_to_script_family(language.unwrap_or_default())
+    }
+
+    /// The continent of the language's region, if it has one.
+    pub fn region_continent(&self) -> Option<Continent> {
+        let mut buf = [0; 2];
+        region_to_continent(ascii_case(
+            region_subtag(self.id)?,
+            &mut buf,
+            u8::to_ascii_uppercase,
+        )?)
+    }
+}
+
+fn subtags(id: &str) -> impl Iterator<Item = &str> {
+    id.split(['-', '_'])
+}
+
+fn language_subtag(id: &str) -> &str {
+    subtags(id).next().unwrap_or_default()
+}
+
+fn region_subtag(id: &str) -> Option<&str> {
+    subtags(id).skip(1).find(|subtag| subtag.len() == 2)
+}
+
+/// Converts the case of `subtag` into `buf`, returning `None` if it does not fit.
+fn ascii_case<'b>(subtag: &str, buf: &'b mut [u8], convert: fn(&u8) -> u8) -> Option<&'b str> {
+    let buf = buf.get_mut(..subtag.len())?;
+    for (out, byte) in buf.iter_mut().zip(subtag.as_bytes()) {
+        *out = convert(byte);
+    }
+    // only ASCII bytes are converted, so the result is as valid as the input
+    core::str::from_utf8(buf).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_if_lang_refs_are_looked_up_without_allocating() {
+        assert_eq!(
+            LangRef::new("en-US"),
+            LangRef {
+                id: "en-US",
+                name: "English",
+                flag: "🇺🇸",
+                dir: "ltr"
+            }
+        );
+        let lang = LangRef::new("AR_eg");
+        assert_eq!((lang.name, lang.flag, lang.dir), ("العربية", "🇪🇬", "rtl"));
+        assert_eq!(lang.region_continent(), Some(Continent::Africa));
+
+        let lang = LangRef::new("sr-cyrl-RS");
+        assert_eq!(lang.flag, "🇷🇸");
+        assert_eq!(lang.script_family(), ScriptFamily::Cyrillic);
+        assert_eq!(LangRef::new("ja").script_family(), ScriptFamily::Cjk);
+        assert_eq!(LangRef::new("ja").region_continent(), None);
+        assert_eq!(LangRef::new("").name, "Unknown language");
+    }
+}
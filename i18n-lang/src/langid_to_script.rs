@@ -0,0 +1,247 @@
+/// A family of writing systems, as used to group languages in language menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScriptFamily {
+    Latin,
+    Cyrillic,
+    Greek,
+    Armenian,
+    Georgian,
+    Arabic,
+    Hebrew,
+    Thaana,
+    /// The Brahmic scripts of South and Southeast Asia, such as Devanagari, Bengali, Tamil or Thai.
+    Brahmic,
+    Ethiopic,
+    /// The Chinese, Japanese and Korean scripts.
+    Cjk,
+    Other,
+}
+
+impl ScriptFamily {
+    /// The English name of the script family.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Latin => "Latin",
+            Self::Cyrillic => "Cyrillic",
+            Self::Greek => "Greek",
+            Self::Armenian => "Armenian",
+            Self::Georgian => "Georgian",
+            Self::Arabic => "Arabic",
+            Self::Hebrew => "Hebrew",
+            Self::Thaana => "Thaana",
+            Self::Brahmic => "Brahmic",
+            Self::Ethiopic => "Ethiopic",
+            Self::Cjk => "CJK",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// Converts a Unicode langid into the family of the script the language is usually written in.
+pub fn langid_to_script_family(langid: &str) -> ScriptFamily {
+    match langid {
+        "aa" => ScriptFamily::Latin,
+        "ab" => ScriptFamily::Cyrillic,
+        "ae" => ScriptFamily::Other,
+        "af" => ScriptFamily::Latin,
+        "ak" => ScriptFamily::Latin,
+        "am" => ScriptFamily::Ethiopic,
+        "an" => ScriptFamily::Latin,
+        "ar" => ScriptFamily::Arabic,
+        "as" => ScriptFamily::Brahmic,
+        "av" => ScriptFamily::Cyrillic,
+        "ay" => ScriptFamily::Latin,
+        "az" => ScriptFamily::Latin,
+        "ba" => ScriptFamily::Cyrillic,
+        "be" => ScriptFamily::Cyrillic,
+        "bg" => ScriptFamily::Cyrillic,
+        "bi" => ScriptFamily::Latin,
+        "bm" => ScriptFamily::Latin,
+        "bn" => ScriptFamily::Brahmic,
+        "bo" => ScriptFamily::Brahmic,
+        "br" => ScriptFamily::Latin,
+        "bs" => ScriptFamily::Latin,
+        "ca" => ScriptFamily::Latin,
+        "ce" => ScriptFamily::Cyrillic,
+        "ch" => ScriptFamily::Latin,
+        "co" => ScriptFamily::Latin,
+        "cr" => ScriptFamily::Other,
+        "cs" => ScriptFamily::Latin,
+        "cu" => ScriptFamily::Cyrillic,
+        "cv" => ScriptFamily::Cyrillic,
+        "cy" => ScriptFamily::Latin,
+        "da" => ScriptFamily::Latin,
+        "de" => ScriptFamily::Latin,
+        "dv" => ScriptFamily::Thaana,
+        "dz" => ScriptFamily::Brahmic,
+        "ee" => ScriptFamily::Latin,
+        "el" => ScriptFamily::Greek,
+        "en" => ScriptFamily::Latin,
+        "eo" => ScriptFamily::Latin,
+        "es" => ScriptFamily::Latin,
+        "et" => ScriptFamily::Latin,
+        "eu" => ScriptFamily::Latin,
+        "fa" => ScriptFamily::Arabic,
+        "ff" => ScriptFamily::Latin,
+        "fi" => ScriptFamily::Latin,
+        "fj" => ScriptFamily::Latin,
+        "fo" => ScriptFamily::Latin,
+        "fr" => ScriptFamily::Latin,
+        "fy" => ScriptFamily::Latin,
+        "ga" => ScriptFamily::Latin,
+        "gd" => ScriptFamily::Latin,
+        "gl" => ScriptFamily::Latin,
+        "gn" => ScriptFamily::Latin,
+        "gu" => ScriptFamily::Brahmic,
+        "gv" => ScriptFamily::Latin,
+        "ha" => ScriptFamily::Latin,
+        "he" => ScriptFamily::Hebrew,
+        "hi" => ScriptFamily::Brahmic,
+        "ho" => ScriptFamily::Latin,
+        "hr" => ScriptFamily::Latin,
+        "ht" => ScriptFamily::Latin,
+        "hu" => ScriptFamily::Latin,
+        "hy" => ScriptFamily::Armenian,
+        "hz" => ScriptFamily::Latin,
+        "ia" => ScriptFamily::Latin,
+        "id" => ScriptFamily::Latin,
+        "ie" => ScriptFamily::Latin,
+        "ig" => ScriptFamily::Latin,
+        "ii" => ScriptFamily::Other,
+        "ik" => ScriptFamily::Latin,
+        "io" => ScriptFamily::Latin,
+        "is" => ScriptFamily::Latin,
+        "it" => ScriptFamily::Latin,
+        "iu" => ScriptFamily::Other,
+        "ja" => ScriptFamily::Cjk,
+        "jv" => ScriptFamily::Latin,
+        "ka" => ScriptFamily::Georgian,
+        "kg" => ScriptFamily::Latin,
+        "ki" => ScriptFamily::Latin,
+        "kj" => ScriptFamily::Latin,
+        "kk" => ScriptFamily::Cyrillic,
+        "kl" => ScriptFamily::Latin,
+        "km" => ScriptFamily::Brahmic,
+        "kn" => ScriptFamily::Brahmic,
+        "ko" => ScriptFamily::Cjk,
+        "kr" => ScriptFamily::Latin,
+        "ks" => ScriptFamily::Arabic,
+        "ku" => ScriptFamily::Latin,
+        "kv" => ScriptFamily::Cyrillic,
+        "kw" => ScriptFamily::Latin,
+        "ky" => ScriptFamily::Cyrillic,
+        "la" => ScriptFamily::Latin,
+        "lb" => ScriptFamily::Latin,
+        "lg" => ScriptFamily::Latin,
+        "li" => ScriptFamily::Latin,
+        "ln" => ScriptFamily::Latin,
+        "lo" => ScriptFamily::Brahmic,
+        "lt" => ScriptFamily::Latin,
+        "lu" => ScriptFamily::Latin,
+        "lv" => ScriptFamily::Latin,
+        "mg" => ScriptFamily::Latin,
+        "mh" => ScriptFamily::Latin,
+        "mi" => ScriptFamily::Latin,
+        "mk" => ScriptFamily::Cyrillic,
+        "ml" => ScriptFamily::Brahmic,
+        "mn" => ScriptFamily::Cyrillic,
+        "mr" => ScriptFamily::Brahmic,
+        "ms" => ScriptFamily::Latin,
+        "mt" => ScriptFamily::Latin,
+        "my" => ScriptFamily::Brahmic,
+        "na" => ScriptFamily::Latin,
+        "nb" => ScriptFamily::Latin,
+        "nd" => ScriptFamily::Latin,
+        "ne" => ScriptFamily::Brahmic,
+        "ng" => ScriptFamily::Latin,
+        "nl" => ScriptFamily::Latin,
+        "nn" => ScriptFamily::Latin,
+        "no" => ScriptFamily::Latin,
+        "nr" => ScriptFamily::Latin,
+        "nv" => ScriptFamily::Latin,
+        "ny" => ScriptFamily::Latin,
+        "oc" => ScriptFamily::Latin,
+        "oj" => ScriptFamily::Other,
+        "om" => ScriptFamily::Latin,
+        "or" => ScriptFamily::Brahmic,
+        "os" => ScriptFamily::Cyrillic,
+        "pa" => ScriptFamily::Brahmic,
+        "pi" => ScriptFamily::Brahmic,
+        "pl" => ScriptFamily::Latin,
+        "ps" => ScriptFamily::Arabic,
+        "pt" => ScriptFamily::Latin,
+        "qu" => ScriptFamily::Latin,
+        "rm" => ScriptFamily::Latin,
+        "rn" => ScriptFamily::Latin,
+        "ro" => ScriptFamily::Latin,
+        "ru" => ScriptFamily::Cyrillic,
+        "rw" => ScriptFamily::Latin,
+        "sa" => ScriptFamily::Brahmic,
+        "sc" => ScriptFamily::Latin,
+        "sd" => ScriptFamily::Arabic,
+        "se" => ScriptFamily::Latin,
+        "sg" => ScriptFamily::Latin,
+        "si" => ScriptFamily::Brahmic,
+        "sk" => ScriptFamily::Latin,
+        "sl" => ScriptFamily::Latin,
+        "sm" => ScriptFamily::Latin,
+        "sn" => ScriptFamily::Latin,
+        "so" => ScriptFamily::Latin,
+        "sq" => ScriptFamily::Latin,
+        "sr" => ScriptFamily::Cyrillic,
+        "ss" => ScriptFamily::Latin,
+        "st" => ScriptFamily::Latin,
+        "su" => ScriptFamily::Latin,
+        "sv" => ScriptFamily::Latin,
+        "sw" => ScriptFamily::Latin,
+        "ta" => ScriptFamily::Brahmic,
+        "te" => ScriptFamily::Brahmic,
+        "tg" => ScriptFamily::Cyrillic,
+        "th" => ScriptFamily::Brahmic,
+        "ti" => ScriptFamily::Ethiopic,
+        "tk" => ScriptFamily::Latin,
+        "tl" => ScriptFamily::Latin,
+        "tn" => ScriptFamily::Latin,
+        "to" => ScriptFamily::Latin,
+        "tr" => ScriptFamily::Latin,
+        "ts" => ScriptFamily::Latin,
+        "tt" => ScriptFamily::Cyrillic,
+        "tw" => ScriptFamily::Latin,
+        "ty" => ScriptFamily::Latin,
+        "ug" => ScriptFamily::Arabic,
+        "uk" => ScriptFamily::Cyrillic,
+        "ur" => ScriptFamily::Arabic,
+        "uz" => ScriptFamily::Latin,
+        "ve" => ScriptFamily::Latin,
+        "vi" => ScriptFamily::Latin,
+        "vo" => ScriptFamily::Latin,
+        "wa" => ScriptFamily::Latin,
+        "wo" => ScriptFamily::Latin,
+        "xh" => ScriptFamily::Latin,
+        "yi" => ScriptFamily::Hebrew,
+        "yo" => ScriptFamily::Latin,
+        "za" => ScriptFamily::Latin,
+        "zh" => ScriptFamily::Cjk,
+        "zu" => ScriptFamily::Latin,
+        _ => ScriptFamily::Other,
+    }
+}
+
+/// Converts an ISO 15924 script subtag, such as `Cyrl`, into its script family.
+pub fn script_to_family(script: &str) -> ScriptFamily {
+    match script {
+        "Latn" => ScriptFamily::Latin,
+        "Cyrl" => ScriptFamily::Cyrillic,
+        "Grek" => ScriptFamily::Greek,
+        "Armn" => ScriptFamily::Armenian,
+        "Geor" => ScriptFamily::Georgian,
+        "Arab" => ScriptFamily::Arabic,
+        "Hebr" => ScriptFamily::Hebrew,
+        "Thaa" => ScriptFamily::Thaana,
+        "Beng" | "Deva" | "Gujr" | "Guru" | "Khmr" | "Knda" | "Laoo" | "Mlym" | "Mymr" | "Orya"
+        | "Sinh" | "Taml" | "Telu" | "Thai" | "Tibt" => ScriptFamily::Brahmic,
+        "Ethi" => ScriptFamily::Ethiopic,
+        "Hang" | "Hani" | "Hans" | "Hant" | "Hira" | "Jpan" | "Kana" | "Kore" => ScriptFamily::Cjk,
+        _ => ScriptFamily::Other,
+    }
+}
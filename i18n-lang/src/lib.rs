@@ -1,14 +1,39 @@
 //! Provides mapping of language identifiers to information pertaining to the country.
+//!
+//! The lookup tables and `LangRef` are available without the default `std` feature, for use in
+//! `no_std` environments. `Lang`, the casing functions and `group_langs` require it.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+mod case;
+#[cfg(feature = "std")]
+mod group;
+mod lang_ref;
 mod langid_to_country_flag;
 mod langid_to_dir;
 mod langid_to_name;
+mod langid_to_script;
+mod region_to_continent;
+#[cfg(feature = "sys_locale")]
+mod sys_locale;
+#[cfg(feature = "std")]
+pub use case::*;
+#[cfg(feature = "std")]
+pub use group::*;
+pub use lang_ref::*;
 pub use langid_to_country_flag::*;
 pub use langid_to_dir::*;
 pub use langid_to_name::*;
+pub use langid_to_script::*;
+pub use region_to_continent::*;
+#[cfg(feature = "sys_locale")]
+pub use sys_locale::*;
+#[cfg(feature = "std")]
 use unic_langid::LanguageIdentifier;
 
 /// Provides all information on a language.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Lang {
     pub id: String,
@@ -17,13 +42,43 @@ pub struct Lang {
     pub dir: &'static str,
 }
 
+#[cfg(feature = "std")]
 impl Lang {
     /// A new `Lang` object.
     pub fn new(langid: LanguageIdentifier) -> Self {
         Self::from(langid)
     }
+
+    /// The family of the script the language is written in.
+    ///
+    /// An explicit script subtag takes precedence over the script the language is usually written in.
+    pub fn script_family(&self) -> ScriptFamily {
+        let mut subtags = self.id.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default();
+        match subtags.find(|subtag| subtag.len() == 4) {
+            Some(script) => {
+                let (first, rest) = script.split_at(1);
+                script_to_family(&format!(
+                    "{}{}",
+                    first.to_uppercase(),
+                    rest.to_lowercase()
+                ))
+            }
+            None => langid_to_script_family(&language.to_lowercase()),
+        }
+    }
+
+    /// The continent of the language's region, if it has one.
+    pub fn region_continent(&self) -> Option<Continent> {
+        self.id
+            .split(['-', '_'])
+            .skip(1)
+            .find(|subtag| subtag.len() == 2)
+            .and_then(|region| region_to_continent(&region.to_uppercase()))
+    }
 }
 
+#[cfg(feature = "std")]
 impl From<LanguageIdentifier> for Lang {
     fn from(value: LanguageIdentifier) -> Self {
         let langid = value.to_string();
@@ -47,8 +102,7 @@ impl From<LanguageIdentifier> for Lang {
         };
         let name = langid_to_name(&langid);
         let flag = region
-            .map(|region| langid_to_flag(&region))
-            .flatten()
+            .and_then(|region| langid_to_flag(&region))
             .unwrap_or_default();
         let dir = langid_to_dir(&langid);
 
@@ -61,14 +115,18 @@ impl From<LanguageIdentifier> for Lang {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::hash::Hash for Lang {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.id.hash(state);
     }
 }
 
+#[cfg(feature = "std")]
 #[cfg(test)]
 mod tests {
+    use crate::*;
+
     #[test]
     fn test_if_parsing_from_a_langid_works() {
         let lang = crate::Lang::new(unic_langid::langid!("en-US"));
@@ -82,4 +140,69 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_if_every_flagged_region_has_a_continent() {
+        for a in b'A'..=b'Z' {
+            for b in b'A'..=b'Z' {
+                let region = String::from_utf8(vec![a, b]).unwrap();
+                assert_eq!(
+                    langid_to_flag(&region).is_some(),
+                    region_to_continent(&region).is_some(),
+                    "{region}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_if_langs_are_grouped() {
+        let langs = [
+            "sr-Cyrl-RS",
+            "ja-JP",
+            "en-US",
+            "ru-RU",
+            "ar-EG",
+            "de-AT",
+            "hi-IN",
+            "pt-BR",
+            "hr-HR",
+            "en-AU",
+        ]
+        .map(|lang| Lang::new(lang.parse().unwrap()));
+        let ids = |groups: Vec<(GroupLabel, Vec<&Lang>)>| {
+            groups
+                .into_iter()
+                .map(|(label, langs)| {
+                    let ids = langs.iter().map(|lang| lang.id.as_str()).collect::<Vec<_>>();
+                    (label.to_string(), ids.join(" "))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(group_langs(&langs, GroupBy::Script)),
+            [
+                ("Latin", "en-US de-AT pt-BR hr-HR en-AU"),
+                ("Cyrillic", "sr-CYRL ru-RU"),
+                ("Arabic", "ar-EG"),
+                ("Brahmic", "hi-IN"),
+                ("CJK", "ja-JP"),
+            ]
+            .map(|(label, ids)| (label.to_string(), ids.to_string()))
+        );
+        assert_eq!(
+            ids(group_langs(&langs, GroupBy::Continent)),
+            [
+                ("Africa", "ar-EG"),
+                ("Asia", "ja-JP hi-IN"),
+                ("Europe", "ru-RU de-AT hr-HR"),
+                ("North America", "en-US"),
+                ("Oceania", "en-AU"),
+                ("South America", "pt-BR"),
+                ("Other", "sr-CYRL"),
+            ]
+            .map(|(label, ids)| (label.to_string(), ids.to_string()))
+        );
+    }
 }
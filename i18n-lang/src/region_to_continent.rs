@@ -0,0 +1,284 @@
+/// A continent, as used to group regions in language menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Continent {
+    Africa,
+    Antarctica,
+    Asia,
+    Europe,
+    NorthAmerica,
+    Oceania,
+    SouthAmerica,
+}
+
+impl Continent {
+    /// The English name of the continent.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Africa => "Africa",
+            Self::Antarctica => "Antarctica",
+            Self::Asia => "Asia",
+            Self::Europe => "Europe",
+            Self::NorthAmerica => "North America",
+            Self::Oceania => "Oceania",
+            Self::SouthAmerica => "South America",
+        }
+    }
+}
+
+/// Converts a Unicode region subtag into the continent the region belongs to.
+///
+/// The table covers the same regions as `langid_to_flag` and follows the UN M49 geoscheme.
+pub fn region_to_continent(region: &str) -> Option<Continent> {
+    match region {
+        "AD" => Some(Continent::Europe),
+        "AE" => Some(Continent::Asia),
+        "AF" => Some(Continent::Asia),
+        "AG" => Some(Continent::NorthAmerica),
+        "AI" => Some(Continent::NorthAmerica),
+        "AL" => Some(Continent::Europe),
+        "AM" => Some(Continent::Asia),
+        "AO" => Some(Continent::Africa),
+        "AQ" => Some(Continent::Antarctica),
+        "AR" => Some(Continent::SouthAmerica),
+        "AS" => Some(Continent::Oceania),
+        "AT" => Some(Continent::Europe),
+        "AU" => Some(Continent::Oceania),
+        "AW" => Some(Continent::NorthAmerica),
+        "AX" => Some(Continent::Europe),
+        "AZ" => Some(Continent::Asia),
+        "BA" => Some(Continent::Europe),
+        "BB" => Some(Continent::NorthAmerica),
+        "BD" => Some(Continent::Asia),
+        "BE" => Some(Continent::Europe),
+        "BF" => Some(Continent::Africa),
+        "BG" => Some(Continent::Europe),
+        "BH" => Some(Continent::Asia),
+        "BI" => Some(Continent::Africa),
+        "BJ" => Some(Continent::Africa),
+        "BL" => Some(Continent::NorthAmerica),
+        "BM" => Some(Continent::NorthAmerica),
+        "BN" => Some(Continent::Asia),
+        "BO" => Some(Continent::SouthAmerica),
+        "BQ" => Some(Continent::NorthAmerica),
+        "BR" => Some(Continent::SouthAmerica),
+        "BS" => Some(Continent::NorthAmerica),
+        "BT" => Some(Continent::Asia),
+        "BV" => Some(Continent::SouthAmerica),
+        "BW" => Some(Continent::Africa),
+        "BY" => Some(Continent::Europe),
+        "BZ" => Some(Continent::NorthAmerica),
+        "CA" => Some(Continent::NorthAmerica),
+        "CC" => Some(Continent::Oceania),
+        "CD" => Some(Continent::Africa),
+        "CF" => Some(Continent::Africa),
+        "CG" => Some(Continent::Africa),
+        "CH" => Some(Continent::Europe),
+        "CI" => Some(Continent::Africa),
+        "CK" => Some(Continent::Oceania),
+        "CL" => Some(Continent::SouthAmerica),
+        "CM" => Some(Continent::Africa),
+        "CN" => Some(Continent::Asia),
+        "CO" => Some(Continent::SouthAmerica),
+        "CR" => Some(Continent::NorthAmerica),
+        "CU" => Some(Continent::NorthAmerica),
+        "CV" => Some(Continent::Africa),
+        "CW" => Some(Continent::NorthAmerica),
+        "CX" => Some(Continent::Oceania),
+        "CY" => Some(Continent::Asia),
+        "CZ" => Some(Continent::Europe),
+        "DE" => Some(Continent::Europe),
+        "DJ" => Some(Continent::Africa),
+        "DK" => Some(Continent::Europe),
+        "DM" => Some(Continent::NorthAmerica),
+        "DO" => Some(Continent::NorthAmerica),
+        "DZ" => Some(Continent::Africa),
+        "EC" => Some(Continent::SouthAmerica),
+        "EE" => Some(Continent::Europe),
+        "EG" => Some(Continent::Africa),
+        "EH" => Some(Continent::Africa),
+        "ER" => Some(Continent::Africa),
+        "ES" => Some(Continent::Europe),
+        "ET" => Some(Continent::Africa),
+        "FI" => Some(Continent::Europe),
+        "FJ" => Some(Continent::Oceania),
+        "FK" => Some(Continent::SouthAmerica),
+        "FM" => Some(Continent::Oceania),
+        "FO" => Some(Continent::Europe),
+        "FR" => Some(Continent::Europe),
+        "GA" => Some(Continent::Africa),
+        "GB" => Some(Continent::Europe),
+        "GD" => Some(Continent::NorthAmerica),
+        "GE" => Some(Continent::Asia),
+        "GF" => Some(Continent::SouthAmerica),
+        "GG" => Some(Continent::Europe),
+        "GH" => Some(Continent::Africa),
+        "GI" => Some(Continent::Europe),
+        "GL" => Some(Continent::NorthAmerica),
+        "GM" => Some(Continent::Africa),
+        "GN" => Some(Continent::Africa),
+        "GP" => Some(Continent::NorthAmerica),
+        "GQ" => Some(Continent::Africa),
+        "GR" => Some(Continent::Europe),
+        "GS" => Some(Continent::SouthAmerica),
+        "GT" => Some(Continent::NorthAmerica),
+        "GU" => Some(Continent::Oceania),
+        "GW" => Some(Continent::Africa),
+        "GY" => Some(Continent::SouthAmerica),
+        "HK" => Some(Continent::Asia),
+        "HM" => Some(Continent::Oceania),
+        "HN" => Some(Continent::NorthAmerica),
+        "HR" => Some(Continent::Europe),
+        "HT" => Some(Continent::NorthAmerica),
+        "HU" => Some(Continent::Europe),
+        "ID" => Some(Continent::Asia),
+        "IE" => Some(Continent::Europe),
+        "IL" => Some(Continent::Asia),
+        "IM" => Some(Continent::Europe),
+        "IN" => Some(Continent::Asia),
+        "IO" => Some(Continent::Africa),
+        "IQ" => Some(Continent::Asia),
+        "IR" => Some(Continent::Asia),
+        "IS" => Some(Continent::Europe),
+        "IT" => Some(Continent::Europe),
+        "JE" => Some(Continent::Europe),
+        "JM" => Some(Continent::NorthAmerica),
+        "JO" => Some(Continent::Asia),
+        "JP" => Some(Continent::Asia),
+        "KE" => Some(Continent::Africa),
+        "KG" => Some(Continent::Asia),
+        "KH" => Some(Continent::Asia),
+        "KI" => Some(Continent::Oceania),
+        "KM" => Some(Continent::Africa),
+        "KN" => Some(Continent::NorthAmerica),
+        "KP" => Some(Continent::Asia),
+        "KR" => Some(Continent::Asia),
+        "KW" => Some(Continent::Asia),
+        "KY" => Some(Continent::NorthAmerica),
+        "KZ" => Some(Continent::Asia),
+        "LA" => Some(Continent::Asia),
+        "LB" => Some(Continent::Asia),
+        "LC" => Some(Continent::NorthAmerica),
+        "LI" => Some(Continent::Europe),
+        "LK" => Some(Continent::Asia),
+        "LR" => Some(Continent::Africa),
+        "LS" => Some(Continent::Africa),
+        "LT" => Some(Continent::Europe),
+        "LU" => Some(Continent::Europe),
+        "LV" => Some(Continent::Europe),
+        "LY" => Some(Continent::Africa),
+        "MA" => Some(Continent::Africa),
+        "MC" => Some(Continent::Europe),
+        "MD" => Some(Continent::Europe),
+        "ME" => Some(Continent::Europe),
+        "MF" => Some(Continent::NorthAmerica),
+        "MG" => Some(Continent::Africa),
+        "MH" => Some(Continent::Oceania),
+        "MK" => Some(Continent::Europe),
+        "ML" => Some(Continent::Africa),
+        "MM" => Some(Continent::Asia),
+        "MN" => Some(Continent::Asia),
+        "MO" => Some(Continent::Asia),
+        "MP" => Some(Continent::Oceania),
+        "MQ" => Some(Continent::NorthAmerica),
+        "MR" => Some(Continent::Africa),
+        "MS" => Some(Continent::NorthAmerica),
+        "MT" => Some(Continent::Europe),
+        "MU" => Some(Continent::Africa),
+        "MV" => Some(Continent::Asia),
+        "MW" => Some(Continent::Africa),
+        "MX" => Some(Continent::NorthAmerica),
+        "MY" => Some(Continent::Asia),
+        "MZ" => Some(Continent::Africa),
+        "NA" => Some(Continent::Africa),
+        "NC" => Some(Continent::Oceania),
+        "NE" => Some(Continent::Africa),
+        "NF" => Some(Continent::Oceania),
+        "NG" => Some(Continent::Africa),
+        "NI" => Some(Continent::NorthAmerica),
+        "NL" => Some(Continent::Europe),
+        "NO" => Some(Continent::Europe),
+        "NP" => Some(Continent::Asia),
+        "NR" => Some(Continent::Oceania),
+        "NU" => Some(Continent::Oceania),
+        "NZ" => Some(Continent::Oceania),
+        "OM" => Some(Continent::Asia),
+        "PA" => Some(Continent::NorthAmerica),
+        "PE" => Some(Continent::SouthAmerica),
+        "PF" => Some(Continent::Oceania),
+        "PG" => Some(Continent::Oceania),
+        "PH" => Some(Continent::Asia),
+        "PK" => Some(Continent::Asia),
+        "PL" => Some(Continent::Europe),
+        "PM" => Some(Continent::NorthAmerica),
+        "PN" => Some(Continent::Oceania),
+        "PR" => Some(Continent::NorthAmerica),
+        "PS" => Some(Continent::Asia),
+        "PT" => Some(Continent::Europe),
+        "PW" => Some(Continent::Oceania),
+        "PY" => Some(Continent::SouthAmerica),
+        "QA" => Some(Continent::Asia),
+        "RE" => Some(Continent::Africa),
+        "RO" => Some(Continent::Europe),
+        "RS" => Some(Continent::Europe),
+        "RU" => Some(Continent::Europe),
+        "RW" => Some(Continent::Africa),
+        "SA" => Some(Continent::Asia),
+        "SB" => Some(Continent::Oceania),
+        "SC" => Some(Continent::Africa),
+        "SD" => Some(Continent::Africa),
+        "SE" => Some(Continent::Europe),
+        "SG" => Some(Continent::Asia),
+        "SH" => Some(Continent::Africa),
+        "SI" => Some(Continent::Europe),
+        "SJ" => Some(Continent::Europe),
+        "SK" => Some(Continent::Europe),
+        "SL" => Some(Continent::Africa),
+        "SM" => Some(Continent::Europe),
+        "SN" => Some(Continent::Africa),
+        "SO" => Some(Continent::Africa),
+        "SR" => Some(Continent::SouthAmerica),
+        "SS" => Some(Continent::Africa),
+        "ST" => Some(Continent::Africa),
+        "SV" => Some(Continent::NorthAmerica),
+        "SX" => Some(Continent::NorthAmerica),
+        "SY" => Some(Continent::Asia),
+        "SZ" => Some(Continent::Africa),
+        "TC" => Some(Continent::NorthAmerica),
+        "TD" => Some(Continent::Africa),
+        "TF" => Some(Continent::Africa),
+        "TG" => Some(Continent::Africa),
+        "TH" => Some(Continent::Asia),
+        "TJ" => Some(Continent::Asia),
+        "TK" => Some(Continent::Oceania),
+        "TL" => Some(Continent::Asia),
+        "TM" => Some(Continent::Asia),
+        "TN" => Some(Continent::Africa),
+        "TO" => Some(Continent::Oceania),
+        "TR" => Some(Continent::Asia),
+        "TT" => Some(Continent::NorthAmerica),
+        "TV" => Some(Continent::Oceania),
+        "TW" => Some(Continent::Asia),
+        "TZ" => Some(Continent::Africa),
+        "UA" => Some(Continent::Europe),
+        "UG" => Some(Continent::Africa),
+        "UM" => Some(Continent::Oceania),
+        "US" => Some(Continent::NorthAmerica),
+        "UY" => Some(Continent::SouthAmerica),
+        "UZ" => Some(Continent::Asia),
+        "VA" => Some(Continent::Europe),
+        "VC" => Some(Continent::NorthAmerica),
+        "VE" => Some(Continent::SouthAmerica),
+        "VG" => Some(Continent::NorthAmerica),
+        "VI" => Some(Continent::NorthAmerica),
+        "VN" => Some(Continent::Asia),
+        "VU" => Some(Continent::Oceania),
+        "WF" => Some(Continent::Oceania),
+        "WS" => Some(Continent::Oceania),
+        "YE" => Some(Continent::Asia),
+        "YT" => Some(Continent::Africa),
+        "ZA" => Some(Continent::Africa),
+        "ZM" => Some(Continent::Africa),
+        "ZW" => Some(Continent::Africa),
+        _ => None,
+    }
+}
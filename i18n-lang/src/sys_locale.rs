@@ -0,0 +1,220 @@
+// the unix parsing is only used on unix, but it is tested everywhere
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use unic_langid::LanguageIdentifier;
+
+/// Detects the languages preferred by the user from the operating system, most preferred first.
+///
+/// - On unix, the colon-separated `LANGUAGE` list is followed by the locale messages are
+///   displayed in, i.e. the first of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set. POSIX locale
+///   names such as `de_DE.UTF-8` or `sr_RS@latin` are reduced to their language and region.
+///   As with gettext, `LANGUAGE` is ignored if that locale is `C` or `POSIX`.
+/// - On Windows, the user interface languages of the user are read with
+///   `GetUserPreferredUILanguages`.
+/// - In the browser, i.e. on `wasm32-unknown-unknown`, the languages are read from
+///   `navigator.languages`, or `navigator.language` if the list is empty.
+/// - Other targets, including WASI, detect nothing.
+///
+/// Unknown or malformed entries are skipped and duplicates are removed, so the list may be empty.
+/// Match it against the available languages, e.g. those listed by `langs!`, picking the first one
+/// with the same language.
+pub fn detect_system_langs() -> Vec<LanguageIdentifier> {
+    #[cfg(unix)]
+    let langs = unix_langs(|name| std::env::var(name).ok());
+    #[cfg(windows)]
+    let langs = windows::user_ui_langs();
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    let langs = wasm::navigator_langs();
+    #[cfg(not(any(unix, windows, all(target_arch = "wasm32", target_os = "unknown"))))]
+    let langs = Vec::new();
+
+    dedup(langs)
+}
+
+/// Reads the preferred languages from the unix locale environment variables, read with `var`.
+fn unix_langs(var: impl Fn(&str) -> Option<String>) -> Vec<LanguageIdentifier> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(&var)
+        .find(|value| !value.is_empty());
+    if locale.as_deref().is_some_and(is_posix_default) {
+        return Vec::new();
+    }
+
+    var("LANGUAGE")
+        .unwrap_or_default()
+        .split(':')
+        .chain(locale.as_deref())
+        .filter_map(parse_posix_locale)
+        .collect()
+}
+
+/// Whether the locale is the default `C` or `POSIX` locale, which names no language.
+fn is_posix_default(locale: &str) -> bool {
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+    name == "C" || name == "POSIX"
+}
+
+/// Parses a POSIX locale name of the form `language[_territory][.codeset][@modifier]`.
+fn parse_posix_locale(locale: &str) -> Option<LanguageIdentifier> {
+    if locale.is_empty() || is_posix_default(locale) {
+        return None;
+    }
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+    name.replace('_', "-").parse().ok()
+}
+
+/// Removes the repeated languages, keeping their first occurrence.
+fn dedup(langs: Vec<LanguageIdentifier>) -> Vec<LanguageIdentifier> {
+    let mut unique = Vec::with_capacity(langs.len());
+    for lang in langs {
+        if !unique.contains(&lang) {
+            unique.push(lang);
+        }
+    }
+
+    unique
+}
+
+#[cfg(windows)]
+mod windows {
+    use unic_langid::LanguageIdentifier;
+
+    /// Requests the languages as names such as `en-US`, rather than as hexadecimal identifiers.
+    const MUI_LANGUAGE_NAME: u32 = 0x8;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetUserPreferredUILanguages(
+            flags: u32,
+            num_languages: *mut u32,
+            languages: *mut u16,
+            languages_len: *mut u32,
+        ) -> i32;
+    }
+
+    /// Reads the user interface languages of the user, most preferred first.
+    pub fn user_ui_langs() -> Vec<LanguageIdentifier> {
+        let mut count = 0;
+        let mut len = 0;
+        // SAFETY: a null buffer only queries the required length
+        let ok = unsafe {
+            GetUserPreferredUILanguages(
+                MUI_LANGUAGE_NAME,
+                &mut count,
+                std::ptr::null_mut(),
+                &mut len,
+            )
+        };
+        if ok == 0 || len == 0 {
+            return Vec::new();
+        }
+
+        let mut buffer = vec![0u16; len as usize];
+        // SAFETY: the buffer holds the `len` characters reported by the previous call
+        let ok = unsafe {
+            GetUserPreferredUILanguages(
+                MUI_LANGUAGE_NAME,
+                &mut count,
+                buffer.as_mut_ptr(),
+                &mut len,
+            )
+        };
+        if ok == 0 {
+            return Vec::new();
+        }
+
+        // the languages are null-separated and the list ends with an empty one
+        buffer[..(len as usize).min(buffer.len())]
+            .split(|c| *c == 0)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| String::from_utf16(name).ok()?.parse().ok())
+            .collect()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+mod wasm {
+    use unic_langid::LanguageIdentifier;
+
+    /// Reads the languages preferred by the user from the browser, most preferred first.
+    ///
+    /// Outside of a window, e.g. in a worker, nothing is detected.
+    pub fn navigator_langs() -> Vec<LanguageIdentifier> {
+        let Some(navigator) = web_sys::window().map(|window| window.navigator()) else {
+            return Vec::new();
+        };
+
+        let languages: js_sys::Array = navigator.languages();
+        let names = match languages.length() {
+            0 => navigator.language().into_iter().collect(),
+            _ => languages
+                .iter()
+                .filter_map(|lang| lang.as_string())
+                .collect::<Vec<_>>(),
+        };
+        names.iter().filter_map(|name| name.parse().ok()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn langs(vars: &[(&str, &str)]) -> Vec<String> {
+        let vars = vars.iter().copied().collect::<HashMap<_, _>>();
+        dedup(unix_langs(|name| {
+            vars.get(name).map(|value| value.to_string())
+        }))
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+    }
+
+    #[test]
+    fn test_if_posix_locale_names_are_parsed() {
+        assert_eq!(
+            parse_posix_locale("de_DE.UTF-8"),
+            Some("de-DE".parse().unwrap())
+        );
+        assert_eq!(
+            parse_posix_locale("sr_RS@latin"),
+            Some("sr-RS".parse().unwrap())
+        );
+        assert_eq!(parse_posix_locale("fr"), Some("fr".parse().unwrap()));
+        assert_eq!(parse_posix_locale("C.UTF-8"), None);
+        assert_eq!(parse_posix_locale("POSIX"), None);
+        assert_eq!(parse_posix_locale("not a locale"), None);
+        assert_eq!(parse_posix_locale(""), None);
+    }
+
+    #[test]
+    fn test_if_unix_variables_are_read_in_order() {
+        assert_eq!(
+            langs(&[("LANG", "en_US.UTF-8"), ("LC_MESSAGES", "hr_HR.UTF-8")]),
+            ["hr-HR"]
+        );
+        assert_eq!(
+            langs(&[("LC_ALL", "de_DE.UTF-8"), ("LC_MESSAGES", "hr_HR.UTF-8")]),
+            ["de-DE"]
+        );
+        assert_eq!(
+            langs(&[("LANGUAGE", "hr:en_GB::bogus!"), ("LANG", "en_GB.UTF-8")]),
+            ["hr", "en-GB"]
+        );
+        assert_eq!(langs(&[("LANGUAGE", "hr"), ("LANG", "C.UTF-8")]), [""; 0]);
+        assert_eq!(langs(&[("LC_ALL", ""), ("LANG", "fr_FR")]), ["fr-FR"]);
+        assert_eq!(langs(&[]), [""; 0]);
+    }
+
+    /// Names the detection of every target, so that each one is compiled wherever it applies.
+    #[test]
+    fn test_if_the_detection_of_every_target_compiles() {
+        #[cfg(windows)]
+        let _: fn() -> Vec<LanguageIdentifier> = windows::user_ui_langs;
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        let _: fn() -> Vec<LanguageIdentifier> = wasm::navigator_langs;
+        let _: fn() -> Vec<LanguageIdentifier> = detect_system_langs;
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{FluentArgs, FluentValue, Query};
+use std::borrow::Cow;
+
+/// A struct whose fields are the arguments of queries, usually implemented with
+/// `#[derive(IntoQueryArgs)]`, see `Query::with_arg_struct`.
+///
+/// The derive maps every field to an argument named after the field in kebab case, e.g.
+/// `user_name` to `$user-name`, unless renamed with `#[arg(rename = "userName")]`. Fields that are
+/// `None` are omitted.
+pub trait IntoQueryArgs {
+    /// Converts the fields into arguments, borrowing their values.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_args(&self) -> FluentArgs<'_>;
+}
+
+/// A field type supported by `#[derive(IntoQueryArgs)]`: numbers, strings and options thereof.
+pub trait ArgValue {
+    /// Converts the field into the value of an argument, or `None` to omit the argument.
+    fn arg_value(&self) -> Option<FluentValue<'_>>;
+}
+
+impl ArgValue for str {
+    fn arg_value(&self) -> Option<FluentValue<'_>> {
+        Some(FluentValue::from(self))
+    }
+}
+
+impl ArgValue for String {
+    fn arg_value(&self) -> Option<FluentValue<'_>> {
+        self.as_str().arg_value()
+    }
+}
+
+impl ArgValue for Cow<'_, str> {
+    fn arg_value(&self) -> Option<FluentValue<'_>> {
+        self.as_ref().arg_value()
+    }
+}
+
+impl<T: ArgValue + ?Sized> ArgValue for &T {
+    fn arg_value(&self) -> Option<FluentValue<'_>> {
+        (**self).arg_value()
+    }
+}
+
+impl<T: ArgValue> ArgValue for Option<T> {
+    fn arg_value(&self) -> Option<FluentValue<'_>> {
+        self.as_ref()?.arg_value()
+    }
+}
+
+macro_rules! number_arg_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ArgValue for $ty {
+                fn arg_value(&self) -> Option<FluentValue<'_>> {
+                    Some(FluentValue::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+number_arg_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl<'a> Query<'a> {
+    /// Adds the fields of `args` as arguments for the main message value, as with
+    /// `Query::with_args`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, FluentArgs, IntoQueryArgs, Locales, Query};
+    ///
+    /// struct User {
+    ///     name: String,
+    /// }
+    ///
+    /// impl IntoQueryArgs for User {
+    ///     fn into_args(&self) -> FluentArgs<'_> {
+    ///         FluentArgs::from_iter([("name", self.name.as_str())])
+    ///     }
+    /// }
+    ///
+    /// let locales = Locales::builder()
+    ///     .use_isolating(false)
+    ///     .build_from_strs([("en-US", "hello = Hello, { $name }!")])
+    ///     .unwrap();
+    /// let user = User { name: "Alex".to_string() };
+    /// let query = Query::new("hello").with_arg_struct(&user);
+    /// assert_eq!(locales.query(&langid!("en-US"), &query).unwrap().value, "Hello, Alex!");
+    /// ```
+    pub fn with_arg_struct<S: IntoQueryArgs + ?Sized>(self, args: &'a S) -> Self {
+        self.with_args(args.into_args())
+    }
+}
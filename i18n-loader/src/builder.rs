@@ -0,0 +1,774 @@
+use crate::{normalize_bytes, normalize_source, parse_sources};
+use crate::{
+    ErrorHandler, FluentArgs, FluentResource, FluentValue, LanguageIdentifier, LoadError,
+    QueryError,
+};
+use crate::{
+    LocaleOptions, Locales, MissingValuePolicy, Normalization, ResourceConflict,
+    ResourceConflictError, UnknownLocale,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A builder for `Locales`, consolidating all configuration options in one place.
+///
+/// Every terminal method (`build`, `build_from_dir`, `build_from_strs`, `build_from_url`,
+/// `build_from_fetcher`) consumes the accumulated options.
+///
+/// There is no cache to size: formatted messages are not cached, and the resources shared
+/// between locales are only held as long as a locale uses them, so the builder has no
+/// `cache_capacity` option.
+///
+/// # Example
+///
+/// ```
+/// use i18n_loader::LocalesBuilder;
+///
+/// let locales = LocalesBuilder::new()
+///     .fallback("en-US")
+///     .use_isolating(false)
+///     .build_from_strs([("en-US", "hello-user = Hello, { $userName }!")])
+///     .unwrap();
+/// ```
+pub struct LocalesBuilder {
+    /// The language to use as a fallback if a message is not found in the requested language.
+    fallback_lang: LanguageIdentifier,
+    /// Whether the fallback language was set, rather than defaulted, so that it takes precedence
+    /// over the one suggested by a remote payload.
+    fallback_set: bool,
+    /// An optional error handler to be called with any localization errors.
+    on_error: Option<ErrorHandler>,
+    /// An optional handler to be called whenever an inline default replaces a missing message.
+    on_inline_default: Option<fn(&LanguageIdentifier, &str)>,
+    /// The options applied to every `Locale` in the collection.
+    options: LocaleOptions,
+    /// The Unicode normalization applied to sources before parsing.
+    normalization: Normalization,
+    /// Whether sources containing junk entries are accepted.
+    allow_junk: bool,
+    /// The files re-read by `Locales::reload`, each with the language it belongs to.
+    reload_files: Vec<(String, PathBuf)>,
+    /// How a network load bounds and retries its requests.
+    #[cfg(feature = "net")]
+    net: crate::NetConfig,
+    /// The token through which a network load can be abandoned.
+    #[cfg(feature = "net")]
+    cancel_token: Option<crate::CancellationToken>,
+    /// The URL template from which `Locales::ensure_loaded` fetches missing languages.
+    #[cfg(feature = "net")]
+    remote_source: Option<String>,
+    /// The language of a single FTL source loaded with `build_from_url`.
+    #[cfg(feature = "net")]
+    url_lang: Option<LanguageIdentifier>,
+    /// The languages loaded from a remote payload, or `None` for all of them.
+    #[cfg(feature = "fetch")]
+    langs: Option<Vec<LanguageIdentifier>>,
+}
+
+impl Default for LocalesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalesBuilder {
+    /// Creates a new builder with `en-US` as the fallback language and no error handler.
+    pub fn new() -> Self {
+        Self {
+            fallback_lang: unic_langid::langid!("en-US"),
+            fallback_set: false,
+            on_error: None,
+            on_inline_default: None,
+            options: LocaleOptions::default(),
+            normalization: Normalization::default(),
+            allow_junk: false,
+            reload_files: Vec::new(),
+            #[cfg(feature = "net")]
+            net: crate::NetConfig::default(),
+            #[cfg(feature = "net")]
+            cancel_token: None,
+            #[cfg(feature = "net")]
+            remote_source: None,
+            #[cfg(feature = "net")]
+            url_lang: None,
+            #[cfg(feature = "fetch")]
+            langs: None,
+        }
+    }
+
+    /// Sets the fallback language from a string.
+    ///
+    /// # Panics
+    /// Panics if `lang_str` is not a valid language identifier.
+    pub fn fallback(self, lang_str: &str) -> Self {
+        let lang_id = lang_str.parse().expect("Language ID should be valid");
+        self.fallback_lang(lang_id)
+    }
+
+    /// Sets the fallback language.
+    pub fn fallback_lang(mut self, lang: LanguageIdentifier) -> Self {
+        self.fallback_lang = lang;
+        self.fallback_set = true;
+        self
+    }
+
+    /// Sets the callback that will be invoked with any errors that occur during message formatting.
+    ///
+    /// Both plain functions and closures are accepted, e.g. one capturing a metrics client.
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: Fn(&QueryError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(on_error));
+        self
+    }
+
+    /// Sets the callback that will be invoked with the language and message ID whenever an inline
+    /// default replaces a missing message, see `Locales::query_or_default`.
+    pub fn on_inline_default(mut self, on_inline_default: fn(&LanguageIdentifier, &str)) -> Self {
+        self.on_inline_default = Some(on_inline_default);
+        self
+    }
+
+    /// Enables or disables wrapping placeables in Unicode bidi isolation characters (`\u{2068}`, `\u{2069}`).
+    ///
+    /// Enabled by default.
+    pub fn use_isolating(mut self, use_isolating: bool) -> Self {
+        self.options.use_isolating = use_isolating;
+        self
+    }
+
+    /// Registers a custom function callable from Fluent patterns (e.g. `{ PLATFORM() }`).
+    ///
+    /// Registering a function under an already registered name replaces it.
+    pub fn function<F>(mut self, id: &str, func: F) -> Self
+    where
+        F: for<'a> Fn(&[FluentValue<'a>], &FluentArgs) -> FluentValue<'a> + Send + Sync + 'static,
+    {
+        self.options
+            .functions
+            .retain(|(existing, _)| existing != id);
+        self.options
+            .functions
+            .push((id.to_string(), Arc::new(func)));
+        self
+    }
+
+    /// Sets the Unicode normalization form applied to sources before parsing.
+    ///
+    /// Regardless of this setting, a leading byte order mark is always stripped and line endings
+    /// are always normalized to `\n`.
+    pub fn normalize(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Accepts sources containing junk entries, i.e. parts that are not valid Fluent syntax.
+    ///
+    /// By default, a source with any junk entry is rejected with a `ParserError` pointing at the
+    /// offending lines. With this enabled, the junk is dropped and the valid entries are kept.
+    pub fn allow_junk(mut self, allow_junk: bool) -> Self {
+        self.allow_junk = allow_junk;
+        self
+    }
+
+    /// Sets the `.ftl` files re-read by `Locales::reload`, each paired with the language
+    /// identifier it belongs to, as for `Locales::reload_from_files`.
+    ///
+    /// This is how `load!` with `dev_reload = true` lets debug builds pick up edited files.
+    pub fn reload_files<L, P>(mut self, files: impl IntoIterator<Item = (L, P)>) -> Self
+    where
+        L: Into<String>,
+        P: Into<PathBuf>,
+    {
+        self.reload_files = files
+            .into_iter()
+            .map(|(lang, path)| (lang.into(), path.into()))
+            .collect();
+        self
+    }
+
+    /// Sets how entries defined by more than one resource of a language are resolved, e.g. when a
+    /// shared `common.ftl` is overridden by app-specific files.
+    ///
+    /// Defaults to `ResourceConflict::Error`, which rejects the language.
+    pub fn resource_conflict(mut self, conflict: ResourceConflict) -> Self {
+        self.options.resource_conflict = conflict;
+        self
+    }
+
+    /// Sets what a message without a value formats to. Defaults to `MissingValuePolicy::AngleBrackets`.
+    pub fn missing_value(mut self, policy: MissingValuePolicy) -> Self {
+        self.options.missing_value = policy;
+        self
+    }
+
+    /// Sets the time after which `build_from_url`, `build_from_url_lenient`,
+    /// `Locales::ensure_loaded`, `Locales::add_locale_from_url` and `Locales::refresh_from_url`
+    /// give up with `NetError::Timeout`.
+    ///
+    /// The timeout covers the requests, their retries and reading the response. Defaults to 30 seconds.
+    #[cfg(feature = "net")]
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.net.timeout = timeout;
+        self
+    }
+
+    /// Sets how `build_from_url`, `build_from_url_lenient`, `Locales::ensure_loaded`,
+    /// `Locales::add_locale_from_url` and `Locales::refresh_from_url` bound and retry their
+    /// requests, replacing any timeout set before. Defaults to `NetConfig::default()`.
+    #[cfg(feature = "net")]
+    pub fn net_config(mut self, config: crate::NetConfig) -> Self {
+        self.net = config;
+        self
+    }
+
+    /// Sets a token through which `build_from_url`, `build_from_url_lenient`,
+    /// `Locales::ensure_loaded`, `Locales::add_locale_from_url` and `Locales::refresh_from_url`
+    /// can be abandoned with `NetError::Cancelled`, e.g. to proceed with embedded translations
+    /// instead.
+    #[cfg(feature = "net")]
+    pub fn cancel_token(mut self, token: crate::CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Sets the URL template from which `Locales::ensure_loaded` fetches languages on demand,
+    /// see `Locales::set_remote_source`.
+    #[cfg(feature = "net")]
+    pub fn remote_source(mut self, url_template: impl Into<String>) -> Self {
+        self.remote_source = Some(url_template.into());
+        self
+    }
+
+    /// Restricts the languages loaded from a remote payload by `build_from_url` and
+    /// `build_from_fetcher` to `langs` and the fallback language, e.g. only the language of the
+    /// user.
+    ///
+    /// Languages match on their language subtag alone, so that `en` selects both `en-US` and
+    /// `en-GB`. The sources of the other languages are skipped without being parsed.
+    #[cfg(feature = "fetch")]
+    pub fn langs(mut self, langs: impl IntoIterator<Item = LanguageIdentifier>) -> Self {
+        self.langs = Some(langs.into_iter().collect());
+        self
+    }
+
+    /// Sets the language of the source loaded by `build_from_url` when the server responds with
+    /// a single FTL file rather than a JSON map.
+    ///
+    /// Without it, the language is taken from the `Content-Language` header of the response.
+    /// If both are present they must agree.
+    #[cfg(feature = "net")]
+    pub fn url_lang(mut self, lang: LanguageIdentifier) -> Self {
+        self.url_lang = Some(lang);
+        self
+    }
+
+    /// Builds an empty `Locales` collection.
+    pub fn build(self) -> Locales {
+        Locales {
+            locales: Default::default(),
+            fallback_lang: std::sync::RwLock::new(self.fallback_lang),
+            on_error: self.on_error,
+            on_inline_default: self.on_inline_default,
+            options: self.options,
+            normalization: self.normalization,
+            allow_junk: self.allow_junk,
+            reload_files: self.reload_files,
+            overlays: Default::default(),
+            usage: Default::default(),
+            arg_sanitizer: Default::default(),
+            miss_reporter: Default::default(),
+            observer: Default::default(),
+            strict: Default::default(),
+            resource_cache: Default::default(),
+            #[cfg(feature = "net")]
+            net: self.net,
+            #[cfg(feature = "net")]
+            cancel_token: self.cancel_token,
+            #[cfg(feature = "net")]
+            remote_source: std::sync::RwLock::new(self.remote_source),
+            #[cfg(feature = "net")]
+            refresh_validators: Default::default(),
+        }
+    }
+
+    /// Builds a `Locales` collection from FTL sources, keyed by their language identifier.
+    ///
+    /// A language may appear multiple times, in which case all of its sources are combined.
+    pub fn build_from_strs<I, L, S>(self, sources: I) -> Result<Locales, LoadError>
+    where
+        I: IntoIterator<Item = (L, S)>,
+        L: AsRef<str>,
+        S: Into<String>,
+    {
+        let (resources, parser_errors) = self.parse_sources(
+            sources
+                .into_iter()
+                .map(|(langid, source)| (langid.as_ref().to_string(), source.into())),
+        );
+        if !parser_errors.is_empty() {
+            return Err(LoadError::Parser(parser_errors));
+        }
+
+        self.build_from_resources(resources)
+    }
+
+    /// Builds a `Locales` collection from already parsed resources, keyed by their language identifier.
+    ///
+    /// # Errors
+    /// Returns `LoadError::MissingFallback` if there are no resources for the fallback language,
+    /// since every query for a missing language is resolved through it, and
+    /// `LoadError::ResourceConflict` if the resources of a language define the same entry under
+    /// `ResourceConflict::Error`, see `LocalesBuilder::resource_conflict`.
+    pub fn build_from_resources(
+        self,
+        resources: HashMap<LanguageIdentifier, Vec<FluentResource>>,
+    ) -> Result<Locales, LoadError> {
+        self.check_fallback(&resources)?;
+        Ok(self.insert_resources(resources, Vec::new())?)
+    }
+
+    /// Checks that the fallback language is among the languages of `resources`.
+    fn check_fallback(
+        &self,
+        resources: &HashMap<LanguageIdentifier, Vec<FluentResource>>,
+    ) -> Result<(), UnknownLocale> {
+        match resources.contains_key(&self.fallback_lang) {
+            true => Ok(()),
+            false => Err(UnknownLocale {
+                lang: self.fallback_lang.clone(),
+            }),
+        }
+    }
+
+    /// Builds a `Locales` collection from already parsed resources, whose fallback language has
+    /// been checked, followed by the `common` resources shared by every language.
+    fn insert_resources(
+        self,
+        resources: HashMap<LanguageIdentifier, Vec<FluentResource>>,
+        common: Vec<Arc<FluentResource>>,
+    ) -> Result<Locales, ResourceConflictError> {
+        let locales = self.build();
+        for (lang, resources) in resources.into_iter() {
+            let resources = resources.into_iter().map(Arc::new).collect();
+            locales.insert_shared_locale(lang, resources, common.clone())?;
+        }
+
+        Ok(locales)
+    }
+
+    /// Builds a `Locales` collection from a directory on disk at runtime.
+    ///
+    /// The directory is expected to have the same layout as for the `load!` macro: every
+    /// subdirectory is named after a language identifier and contains that language's `.ftl` files,
+    /// nested directories included, and every flat `<lang>.ftl` file is the source of that
+    /// language. A language with both is merged, the flat file being its last resource. Names are
+    /// canonicalized, so that a `hr-hr` directory holds the locale `hr-HR`. Hidden entries are
+    /// skipped.
+    ///
+    /// The resources of a `_common` directory or `_common.ftl` file are shared by every language,
+    /// whose own entries override them, see `Locales::add_shared_locale_with_common`. Errors in
+    /// them are reported for the fallback language.
+    ///
+    /// # Errors
+    /// Returns `LoadError::DuplicateLocale` if two directories or two flat files are named after
+    /// the same locale, e.g. `hr-hr` and `hr-HR`, and the errors of `build_from_strs`.
+    pub fn build_from_dir(self, path: impl AsRef<Path>) -> Result<Locales, LoadError> {
+        let DirSources { sources, common } = read_dir_sources(path.as_ref())?;
+        let fallback_lang = self.fallback_lang.to_string();
+        let (mut common, mut parser_errors) = self.parse_sources(
+            common
+                .into_iter()
+                .map(|source| (fallback_lang.clone(), source)),
+        );
+        let (resources, errors) = self.parse_sources(sources);
+        parser_errors.extend(errors);
+        if !parser_errors.is_empty() {
+            return Err(LoadError::Parser(parser_errors));
+        }
+
+        let common = common
+            .remove(&self.fallback_lang)
+            .unwrap_or_default()
+            .into_iter()
+            .map(Arc::new)
+            .collect();
+        self.check_fallback(&resources)?;
+        Ok(self.insert_resources(resources, common)?)
+    }
+
+    /// Builds a `Locales` collection from a network resource.
+    ///
+    /// The resource is decoded according to the `Content-Type` of the response:
+    /// * `text/plain` and `application/fluent` are a single FTL source, whose language is set
+    ///   with `url_lang` or by the `Content-Language` header of the response.
+    /// * Anything else, including `application/json` and a missing header, is expected to be a
+    ///   JSON object mapping language identifiers to FTL sources, either bare or wrapped in a
+    ///   versioned envelope, see `build_from_url_with_meta`.
+    ///
+    /// It is fetched with `hyper`; use `build_from_fetcher` to bring another HTTP client.
+    ///
+    /// # Errors
+    /// Returns `NetError::MissingContentLanguage` or `NetError::ContentLanguageMismatch` if the
+    /// language of an FTL source is unknown or contradicts `url_lang`.
+    #[cfg(feature = "net")]
+    pub async fn build_from_url(self, url: &str) -> Result<Locales, crate::NetError> {
+        let (locales, _) = self.build_from_url_with_meta(url).await?;
+        Ok(locales)
+    }
+
+    /// Builds a `Locales` collection from a network resource as for `build_from_url`, returning
+    /// the metadata of the payload along with it.
+    ///
+    /// Besides the bare JSON object mapping language identifiers to FTL sources, the payload may
+    /// be an envelope carrying metadata:
+    ///
+    /// ```json
+    /// {
+    ///   "version": 2,
+    ///   "generated_at": "2024-05-01T12:00:00Z",
+    ///   "fallback": "en-US",
+    ///   "locales": { "en-US": "hello = Hello", "de": "hello = Hallo" }
+    /// }
+    /// ```
+    ///
+    /// The `fallback` of the envelope is used unless the fallback language is set on the builder,
+    /// or the envelope doesn't contain that language.
+    ///
+    /// # Errors
+    /// Returns `NetError::UnsupportedVersion` for an envelope of a version other than `2`, and the
+    /// errors of `build_from_url`.
+    #[cfg(feature = "net")]
+    pub async fn build_from_url_with_meta(
+        self,
+        url: &str,
+    ) -> Result<(Locales, crate::RemoteMeta), crate::NetError> {
+        let payload = self.fetch_url_payload(url).await?;
+        Ok(self.build_from_payload::<crate::NetError>(payload)?)
+    }
+
+    /// Builds a `Locales` collection from a resource fetched by `fetch`, e.g. with an HTTP client
+    /// the application already uses.
+    ///
+    /// `fetch` is called with `url` and returns the body of the resource, which is expected to be
+    /// a JSON object mapping language identifiers to FTL sources or a versioned envelope, as for
+    /// `build_from_url_with_meta`.
+    ///
+    /// # Errors
+    /// Returns `FetchError::Fetch` with the error of `fetch`, or the errors of parsing the body
+    /// and its sources.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{FetchError, Locales, LocalesBuilder};
+    ///
+    /// async fn load(cached: Vec<u8>) -> Result<Locales, FetchError<std::io::Error>> {
+    ///     LocalesBuilder::new()
+    ///         .build_from_fetcher("https://example.com/locales.json", |_url| async {
+    ///             Ok(cached)
+    ///         })
+    ///         .await
+    /// }
+    /// ```
+    #[cfg(feature = "fetch")]
+    pub async fn build_from_fetcher<F, Fut, E>(
+        self,
+        url: &str,
+        fetch: F,
+    ) -> Result<Locales, crate::FetchError<E>>
+    where
+        F: FnOnce(&str) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<u8>, E>>,
+    {
+        let payload = fetch_payload(url, fetch).await?;
+        let (locales, _) = self.build_from_payload(payload)?;
+        Ok(locales)
+    }
+
+    /// Parses and loads the FTL sources of a remote payload, failing on any error.
+    #[cfg(feature = "fetch")]
+    fn build_from_payload<E>(
+        mut self,
+        payload: crate::payload::Payload,
+    ) -> Result<(Locales, crate::RemoteMeta), crate::FetchError<E>> {
+        self.apply_remote_fallback(&payload);
+        let (resources, parser_errors) = self.parse_sources(self.filter_langs(payload.sources));
+        if !parser_errors.is_empty() {
+            return Err(crate::FetchError::Parser(parser_errors));
+        }
+
+        self.check_fallback(&resources)?;
+        Ok((self.insert_resources(resources, Vec::new())?, payload.meta))
+    }
+
+    /// Drops the sources of the languages excluded by `langs`.
+    #[cfg(feature = "fetch")]
+    fn filter_langs(&self, mut sources: HashMap<String, String>) -> HashMap<String, String> {
+        if let Some(langs) = &self.langs {
+            sources.retain(|langid, _| {
+                langid.parse::<LanguageIdentifier>().is_ok_and(|langid| {
+                    langs
+                        .iter()
+                        .chain([&self.fallback_lang])
+                        .any(|lang| lang.language == langid.language)
+                })
+            });
+        }
+        sources
+    }
+
+    /// Uses the fallback language suggested by a remote payload, unless one was set or the
+    /// payload doesn't contain that language.
+    #[cfg(feature = "fetch")]
+    fn apply_remote_fallback(&mut self, payload: &crate::payload::Payload) {
+        let Some(fallback) = payload
+            .meta
+            .fallback
+            .as_ref()
+            .filter(|_| !self.fallback_set)
+        else {
+            return;
+        };
+        let contained = payload.sources.keys().any(|langid| {
+            langid
+                .parse::<LanguageIdentifier>()
+                .is_ok_and(|langid| langid == *fallback)
+        });
+        if contained {
+            self.fallback_lang = fallback.clone();
+        }
+    }
+
+    /// Builds a `Locales` collection from a network resource, skipping the locales that fail to parse.
+    ///
+    /// Unlike `build_from_url`, a single invalid locale does not abort the whole load: every valid
+    /// locale is loaded and the errors of the skipped ones are returned alongside the collection.
+    /// The fallback language failing to parse or missing altogether is still an error, as no query
+    /// could be satisfied without it.
+    #[cfg(feature = "net")]
+    pub async fn build_from_url_lenient(self, url: &str) -> Result<PartialLoad, crate::NetError> {
+        let payload = self.fetch_url_payload(url).await?;
+        Ok(self.build_from_payload_lenient::<crate::NetError>(payload)?)
+    }
+
+    /// Builds a `Locales` collection from a resource fetched by `fetch`, skipping the locales that
+    /// fail to parse, see `build_from_fetcher` and `build_from_url_lenient`.
+    #[cfg(feature = "fetch")]
+    pub async fn build_from_fetcher_lenient<F, Fut, E>(
+        self,
+        url: &str,
+        fetch: F,
+    ) -> Result<PartialLoad, crate::FetchError<E>>
+    where
+        F: FnOnce(&str) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<u8>, E>>,
+    {
+        let payload = fetch_payload(url, fetch).await?;
+        self.build_from_payload_lenient(payload)
+    }
+
+    /// Parses and loads the FTL sources of a remote payload, skipping the locales that fail to
+    /// parse except for the fallback language.
+    #[cfg(feature = "fetch")]
+    fn build_from_payload_lenient<E>(
+        mut self,
+        payload: crate::payload::Payload,
+    ) -> Result<PartialLoad, crate::FetchError<E>> {
+        self.apply_remote_fallback(&payload);
+        let (resources, parser_errors) = self.parse_sources(self.filter_langs(payload.sources));
+        let (fallback_errors, errors): (Vec<_>, Vec<_>) =
+            parser_errors.into_iter().partition(|err| {
+                matches!(err, crate::ParserError::ParserError { langid, .. } if *langid == self.fallback_lang)
+            });
+        if !fallback_errors.is_empty() {
+            return Err(crate::FetchError::Parser(fallback_errors));
+        }
+
+        self.check_fallback(&resources)?;
+        Ok(PartialLoad {
+            locales: self.insert_resources(resources, Vec::new())?,
+            errors,
+        })
+    }
+
+    /// Normalizes and parses FTL sources keyed by their language identifier, see `parse_sources`.
+    fn parse_sources(
+        &self,
+        sources: impl IntoIterator<Item = (String, String)>,
+    ) -> (
+        HashMap<LanguageIdentifier, Vec<FluentResource>>,
+        Vec<crate::ParserError>,
+    ) {
+        let normalization = self.normalization;
+        parse_sources(
+            sources
+                .into_iter()
+                .map(|(langid, source)| (langid, normalize_source(source, normalization).source)),
+            self.allow_junk,
+        )
+    }
+
+    /// Fetches and decodes a network resource with `hyper` as configured by `net_config`, see
+    /// `build_from_url`.
+    #[cfg(feature = "net")]
+    async fn fetch_url_payload(
+        &self,
+        url: &str,
+    ) -> Result<crate::payload::Payload, crate::NetError> {
+        let response = crate::retry::bounded(
+            &self.net,
+            self.cancel_token.as_ref(),
+            crate::retry::fetch_with_retries(url, &self.net),
+        )
+        .await?;
+        response.into_payload(url, self.url_lang.as_ref(), crate::remote::BodyFormat::Json)
+    }
+}
+
+/// Fetches and decodes a remote payload with `fetch`.
+#[cfg(feature = "fetch")]
+async fn fetch_payload<F, Fut, E>(
+    url: &str,
+    fetch: F,
+) -> Result<crate::payload::Payload, crate::FetchError<E>>
+where
+    F: FnOnce(&str) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<u8>, E>>,
+{
+    let body = fetch(url).await.map_err(crate::FetchError::Fetch)?;
+    Ok(crate::payload::decode_payload(&body)?)
+}
+
+/// A `Locales` collection loaded from a source in which some locales failed to parse.
+#[cfg(feature = "fetch")]
+pub struct PartialLoad {
+    /// The collection of every locale that parsed successfully.
+    pub locales: Locales,
+    /// The errors of the locales that were skipped.
+    pub errors: Vec<crate::ParserError>,
+}
+
+/// The name of the directory or flat `.ftl` file of resources shared by every locale, as for the
+/// `load!` macro.
+const COMMON_NAME: &str = "_common";
+
+/// The FTL sources of a directory laid out as for the `load!` macro, see `read_dir_sources`.
+pub(crate) struct DirSources {
+    /// The sources of every locale, keyed by its canonical language identifier.
+    pub(crate) sources: Vec<(String, String)>,
+    /// The sources shared by every locale.
+    pub(crate) common: Vec<String>,
+}
+
+/// Reads the FTL sources of a directory laid out as for the `load!` macro: the `.ftl` files of
+/// every subdirectory named after a locale, walked recursively, followed by the flat
+/// `<locale>.ftl` file of the locale, if any.
+pub(crate) fn read_dir_sources(path: &Path) -> Result<DirSources, LoadError> {
+    let mut sources = Vec::default();
+    let mut common = Vec::default();
+    // merged in as the last resource of their locale, as `load!` does
+    let mut flat_sources = Vec::default();
+    // the entry defining every locale, by whether it is a directory
+    let mut defined = HashMap::<(String, bool), PathBuf>::default();
+    for entry in read_sorted_dir(path)? {
+        let entry_path = entry.path();
+        let is_dir = entry_path.is_dir();
+        let name = match is_dir {
+            true => entry.file_name().to_string_lossy().to_string(),
+            false if is_ftl(&entry_path) => entry_path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            false => continue,
+        };
+        if name == COMMON_NAME {
+            match is_dir {
+                true => common.extend(read_ftl_tree(&entry_path)?),
+                false => common.push(read_source(&entry_path)?),
+            }
+            continue;
+        }
+
+        // e.g. `hr-HR` for a `hr-hr` directory, as locales are looked up by their exact identifier
+        let langid = match name.parse::<LanguageIdentifier>() {
+            Ok(lang) => lang.to_string(),
+            Err(_) => name,
+        };
+        if let Some(other) = defined.insert((langid.clone(), is_dir), entry_path.clone()) {
+            return Err(LoadError::DuplicateLocale {
+                lang: langid,
+                paths: [other, entry_path],
+            });
+        }
+        match is_dir {
+            true => sources.extend(
+                read_ftl_tree(&entry_path)?
+                    .into_iter()
+                    .map(|source| (langid.clone(), source)),
+            ),
+            false => flat_sources.push((langid, read_source(&entry_path)?)),
+        }
+    }
+    sources.extend(flat_sources);
+
+    Ok(DirSources { sources, common })
+}
+
+/// Reads the `.ftl` files within `dir` and its nested directories, those of a directory in name
+/// order before those of its subdirectories, as `load!` does.
+fn read_ftl_tree(dir: &Path) -> Result<Vec<String>, LoadError> {
+    let mut sources = Vec::default();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let mut subdirs = Vec::default();
+        for entry in read_sorted_dir(&dir)? {
+            let path = entry.path();
+            if path.is_dir() {
+                subdirs.push(path);
+            } else if is_ftl(&path) {
+                sources.push(read_source(&path)?);
+            }
+        }
+        // popped in order, so that sibling directories are walked by name
+        dirs.extend(subdirs.into_iter().rev());
+    }
+
+    Ok(sources)
+}
+
+/// Whether the file at `path` is an FTL source.
+fn is_ftl(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("ftl")
+}
+
+/// Reads the entries of the directory at `path` sorted by name, skipping hidden ones.
+fn read_sorted_dir(path: &Path) -> Result<Vec<std::fs::DirEntry>, LoadError> {
+    let mut entries = std::fs::read_dir(path)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(|source| LoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    entries.retain(|entry| !entry.file_name().to_string_lossy().starts_with('.'));
+    entries.sort_by_key(|entry| entry.file_name());
+    Ok(entries)
+}
+
+/// Reads the FTL source at `path`, stripping a byte order mark.
+pub(crate) fn read_source(path: &Path) -> Result<String, LoadError> {
+    let bytes = std::fs::read(path).map_err(|source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let content =
+        normalize_bytes(bytes, Normalization::None).map_err(|err| LoadError::InvalidUtf8 {
+            path: path.to_path_buf(),
+            offset: err.valid_up_to(),
+        })?;
+
+    Ok(content.source)
+}
@@ -0,0 +1,322 @@
+//! Locale-aware implementations of the Fluent built-in functions `NUMBER` and `DATETIME`.
+//!
+//! This is a lightweight formatter rather than a full implementation of the CLDR. Numbers are
+//! formatted with the decimal and grouping separators of the language, while dates and times are
+//! formatted numerically in the order of the language (`3/5/2024` in `en-US`, `5. 3. 2024.` in
+//! `hr-HR`), always in UTC. Languages without a dedicated rule are formatted like English.
+
+use crate::{FluentArgs, FluentBundle, FluentResource, FluentValue, LanguageIdentifier};
+use fluent_bundle::types::{FluentNumber, FluentNumberStyle};
+use intl_memoizer::concurrent::IntlLangMemoizer;
+use intl_memoizer::Memoizable;
+use std::sync::Arc;
+
+/// Registers `NUMBER` and `DATETIME` on `bundle`, along with the formatter of its numbers.
+///
+/// `NUMBER` keeps its argument a number, so that it can still be selected on by its plural
+/// category, and merges the named options into it (e.g. `minimumFractionDigits: 2`). The
+/// functions for which `is_custom` holds are skipped, so that custom functions replace them.
+pub(crate) fn register(
+    bundle: &mut FluentBundle<Arc<FluentResource>>,
+    lang: &LanguageIdentifier,
+    is_custom: impl Fn(&str) -> bool,
+) {
+    if !is_custom("NUMBER") {
+        bundle
+            .add_function("NUMBER", fluent_bundle::builtins::NUMBER)
+            .expect("builtins are registered first");
+    }
+    if !is_custom("DATETIME") {
+        let date_format = DateFormat::of(lang);
+        bundle
+            .add_function("DATETIME", move |positional, named| {
+                datetime(&date_format, positional, named)
+            })
+            .expect("builtins are registered first");
+    }
+    bundle.set_formatter(Some(format_value));
+}
+
+/// Formats the numbers of a bundle, see `FluentBundle::set_formatter`.
+fn format_value(value: &FluentValue, intls: &IntlLangMemoizer) -> Option<String> {
+    let FluentValue::Number(number) = value else {
+        return None;
+    };
+    intls
+        .with_try_get::<NumberFormat, _, _>((), |format| format.format(number))
+        .ok()
+        .flatten()
+}
+
+/// The separators a language formats numbers with.
+struct NumberFormat {
+    decimal: char,
+    group: &'static str,
+    /// The number of integer digits from which on the digits are grouped.
+    min_grouped: usize,
+    /// The separator between a number and the percent sign.
+    percent: &'static str,
+}
+
+impl Memoizable for NumberFormat {
+    type Args = ();
+    type Error = ();
+
+    fn construct(lang: LanguageIdentifier, _: Self::Args) -> Result<Self, Self::Error> {
+        let (decimal, group, min_grouped, percent) = match lang.language.as_str() {
+            "de" if lang.region.is_some_and(|region| region.as_str() == "CH") => ('.', "’", 4, ""),
+            "de" | "da" | "hr" | "sl" | "sr" | "bs" | "ro" | "tr" | "el" => (',', ".", 4, "\u{a0}"),
+            "es" => (',', ".", 5, "\u{a0}"),
+            "it" | "nl" | "id" | "vi" | "pt" => (',', ".", 4, ""),
+            "fr" => (',', "\u{202f}", 4, "\u{202f}"),
+            "pl" => (',', "\u{a0}", 5, ""),
+            "ru" | "uk" | "be" | "bg" | "cs" | "sk" | "fi" | "sv" | "nb" | "no" | "nn" | "hu"
+            | "lt" | "lv" | "et" => (',', "\u{a0}", 4, "\u{a0}"),
+            _ => ('.', ",", 4, ""),
+        };
+        Ok(Self {
+            decimal,
+            group,
+            min_grouped,
+            percent,
+        })
+    }
+}
+
+impl NumberFormat {
+    /// Formats `number` according to its options, returning `None` for the options that are not
+    /// supported, i.e. significant digits, which are left to the default formatting.
+    fn format(&self, number: &FluentNumber) -> Option<String> {
+        let options = &number.options;
+        if options.minimum_significant_digits.is_some()
+            || options.maximum_significant_digits.is_some()
+            || !number.value.is_finite()
+        {
+            return None;
+        }
+
+        let value = match options.style {
+            FluentNumberStyle::Percent => number.value * 100.0,
+            _ => number.value,
+        };
+        let min_fraction = options.minimum_fraction_digits.unwrap_or(0);
+        let max_fraction = options
+            .maximum_fraction_digits
+            .unwrap_or(match options.style {
+                FluentNumberStyle::Percent => 0,
+                _ => 3,
+            })
+            .max(min_fraction);
+        let digits = format!("{:.*}", max_fraction, value.abs());
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        let fraction = fraction.trim_end_matches('0');
+        let fraction = format!("{fraction:0<min_fraction$}");
+        let integer = format!(
+            "{integer:0>width$}",
+            width = options.minimum_integer_digits.unwrap_or(1)
+        );
+
+        let mut formatted = String::new();
+        // `-0` after rounding is formatted as `0`
+        if value < 0.0 && digits.bytes().any(|digit| matches!(digit, b'1'..=b'9')) {
+            formatted.push('-');
+        }
+        if options.use_grouping && integer.len() >= self.min_grouped {
+            for (idx, digit) in integer.chars().enumerate() {
+                if idx > 0 && (integer.len() - idx) % 3 == 0 {
+                    formatted.push_str(self.group);
+                }
+                formatted.push(digit);
+            }
+        } else {
+            formatted.push_str(&integer);
+        }
+        if !fraction.is_empty() {
+            formatted.push(self.decimal);
+            formatted.push_str(&fraction);
+        }
+
+        match options.style {
+            FluentNumberStyle::Percent => {
+                formatted.push_str(self.percent);
+                formatted.push('%');
+            }
+            FluentNumberStyle::Currency => {
+                if let Some(currency) = &options.currency {
+                    formatted.push('\u{a0}');
+                    formatted.push_str(currency);
+                }
+            }
+            FluentNumberStyle::Decimal => {}
+        }
+        Some(formatted)
+    }
+}
+
+/// The numeric date and time patterns of a language.
+struct DateFormat {
+    /// The date pattern, where `d` and `M` are the day and month, `dd` and `MM` the same padded to
+    /// two digits, and `y` the year.
+    date: &'static str,
+    /// Whether the hours are formatted on a 12-hour clock, e.g. `2:30 PM`.
+    hour12: bool,
+}
+
+impl DateFormat {
+    fn of(lang: &LanguageIdentifier) -> Self {
+        let region = lang.region.as_ref().map(|region| region.as_str());
+        let (date, hour12) = match lang.language.as_str() {
+            "en" if matches!(region, None | Some("US" | "PH")) => ("M/d/y", true),
+            "en" if region == Some("CA") => ("y-MM-dd", true),
+            "en" => ("dd/MM/y", false),
+            "hr" | "sr" | "bs" => ("d. M. y.", false),
+            "cs" | "sk" | "sl" => ("d. M. y", false),
+            "de" | "ru" | "uk" | "be" | "pl" | "tr" | "nb" | "no" | "nn" | "da" | "ro" | "bg" => {
+                ("dd.MM.y", false)
+            }
+            "fi" => ("d.M.y", false),
+            "fr" | "it" | "pt" | "el" | "vi" | "id" => ("dd/MM/y", false),
+            "es" => ("d/M/y", false),
+            "nl" => ("d-M-y", false),
+            "sv" | "lt" => ("y-MM-dd", false),
+            "hu" => ("y. MM. dd.", false),
+            "ja" | "zh" => ("y/M/d", false),
+            "ko" => ("y. M. d.", true),
+            _ => ("M/d/y", true),
+        };
+        Self { date, hour12 }
+    }
+
+    fn format_date(&self, year: i64, month: u32, day: u32) -> String {
+        let mut formatted = String::new();
+        let mut pattern = self.date.chars().peekable();
+        while let Some(c) = pattern.next() {
+            let padded = pattern.next_if_eq(&c).is_some();
+            match (c, padded) {
+                ('d', false) => formatted.push_str(&day.to_string()),
+                ('d', true) => formatted.push_str(&format!("{day:02}")),
+                ('M', false) => formatted.push_str(&month.to_string()),
+                ('M', true) => formatted.push_str(&format!("{month:02}")),
+                ('y', _) => formatted.push_str(&year.to_string()),
+                (c, padded) => {
+                    formatted.push(c);
+                    if padded {
+                        formatted.push(c);
+                    }
+                }
+            }
+        }
+        formatted
+    }
+
+    fn format_time(&self, hour: u32, minute: u32, second: Option<u32>) -> String {
+        let seconds = second.map(|second| format!(":{second:02}"));
+        let seconds = seconds.unwrap_or_default();
+        if self.hour12 {
+            let period = if hour < 12 { "AM" } else { "PM" };
+            let hour = match hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            format!("{hour}:{minute:02}{seconds}\u{202f}{period}")
+        } else {
+            format!("{hour:02}:{minute:02}{seconds}")
+        }
+    }
+}
+
+/// The `DATETIME` function.
+///
+/// The date is either a number of milliseconds since the Unix epoch, or a string in the form
+/// `YYYY-MM-DD`, optionally followed by `THH:MM` or `THH:MM:SS` and a trailing `Z`. The named
+/// options `dateStyle` and `timeStyle` select what is formatted: `"none"` omits the part,
+/// `"medium"` adds the seconds to the time, and anything else formats the part without
+/// seconds. By default only the date is formatted. Any other argument fails the function, which
+/// Fluent formats as `DATETIME()`.
+fn datetime<'a>(
+    format: &DateFormat,
+    positional: &[FluentValue<'a>],
+    named: &FluentArgs,
+) -> FluentValue<'a> {
+    let Some((days, seconds)) = positional.first().and_then(parse_datetime) else {
+        return FluentValue::Error;
+    };
+    let style = |name: &str| match named.get(name) {
+        Some(FluentValue::String(style)) => Some(style.to_string()),
+        _ => None,
+    };
+    let date_style = style("dateStyle");
+    let time_style = style("timeStyle").unwrap_or_else(|| "none".to_string());
+
+    let mut parts = Vec::new();
+    if date_style.as_deref() != Some("none") {
+        let (year, month, day) = civil_from_days(days);
+        parts.push(format.format_date(year, month, day));
+    }
+    if time_style != "none" {
+        let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        let second = (time_style == "medium").then_some(second);
+        parts.push(format.format_time(hour, minute, second));
+    }
+    FluentValue::String(parts.join(" ").into())
+}
+
+/// Parses the argument of `DATETIME` into the days since the Unix epoch and the seconds of the day.
+fn parse_datetime(value: &FluentValue) -> Option<(i64, u32)> {
+    match value {
+        FluentValue::Number(number) if number.value.is_finite() => {
+            let seconds = (number.value / 1000.0).floor() as i64;
+            Some((
+                seconds.div_euclid(86_400),
+                seconds.rem_euclid(86_400) as u32,
+            ))
+        }
+        FluentValue::String(s) => {
+            let s = s.strip_suffix('Z').unwrap_or(s);
+            let (date, time) = s.split_once('T').unwrap_or((s, "00:00"));
+            let mut date = date.splitn(3, '-').map(str::parse::<u32>);
+            let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+            let mut time = time.splitn(3, ':').map(str::parse::<u32>);
+            let (hour, minute) = (time.next()?.ok()?, time.next()?.ok()?);
+            let second = time.next().transpose().ok()?.unwrap_or(0);
+            if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+                return None;
+            }
+            Some((
+                days_from_civil(year.into(), month, day),
+                hour * 3600 + minute * 60 + second.min(59),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Converts a date of the proleptic Gregorian calendar into the days since the Unix epoch.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts the days since the Unix epoch into a date of the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
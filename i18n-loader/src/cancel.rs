@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// A handle through which a supervisor can abandon a network load, see `LocalesBuilder::cancel_token`.
+///
+/// Clones share the same state, so cancelling any clone cancels every load watching the token.
+/// The token does not depend on a particular runtime.
+///
+/// # Example
+///
+/// ```
+/// use i18n_loader::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let supervisor = token.clone();
+/// supervisor.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Whether the token was cancelled, along with the loads waiting for it to be.
+    inner: Arc<(AtomicBool, Notify)>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every load watching this token. Loads that already completed are unaffected.
+    pub fn cancel(&self) {
+        self.inner.0.store(true, Ordering::SeqCst);
+        self.inner.1.notify_waiters();
+    }
+
+    /// Whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.0.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            // register for the notification before checking the flag, so that it cannot be missed
+            let notified = self.inner.1.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Locale-aware string collation for sorting user-visible lists.
+//!
+//! This is a lightweight collator rather than a full implementation of the Unicode Collation
+//! Algorithm. Strings are compared on three levels:
+//! 1. the base letters, with diacritics and case folded away (`Á` sorts as `a`),
+//! 2. the diacritics, so that unaccented letters sort before accented ones,
+//! 3. the case, so that lowercase letters sort before uppercase ones.
+//!
+//! Languages which treat some accented letters as distinct letters of the alphabet are
+//! tailored accordingly, e.g. Swedish and Finnish sort `å`, `ä` and `ö` after `z`,
+//! Danish and Norwegian sort `æ`, `ø` and `å` after `z`, and Spanish sorts `ñ` after `n`.
+//! Characters outside of the Latin script are compared by their code point.
+
+use crate::{LanguageIdentifier, Locales};
+use i18n_lang::Lang;
+use std::cmp::Ordering;
+
+/// A single collation element of a character.
+struct Element {
+    /// The primary weight, i.e. the base letter shifted left to make room for tailorings.
+    primary: u32,
+    /// The secondary weight, i.e. the diacritic; `0` if there is none.
+    secondary: u32,
+    /// The tertiary weight, i.e. the case; `0` for lowercase.
+    tertiary: u32,
+}
+
+impl Locales {
+    /// Computes a binary sort key for `s` under the collation rules of `lang`.
+    ///
+    /// Comparing two sort keys bytewise yields the same ordering as `Locales::compare`,
+    /// which makes them suitable for sorting large lists or storing in a database.
+    pub fn sort_key(&self, lang: &LanguageIdentifier, s: &str) -> Vec<u8> {
+        sort_key(lang, s)
+    }
+
+    /// Compares two strings under the collation rules of `lang`.
+    pub fn compare(&self, lang: &LanguageIdentifier, a: &str, b: &str) -> Ordering {
+        compare(lang, a, b)
+    }
+}
+
+/// Computes a binary sort key for `s` under the collation rules of `lang`.
+pub fn sort_key(lang: &LanguageIdentifier, s: &str) -> Vec<u8> {
+    let elements = s
+        .chars()
+        .flat_map(|ch| elements_of(lang.language.as_str(), ch))
+        .collect::<Vec<_>>();
+
+    // every weight is encoded as a non-zero 4-byte unit, so a zeroed unit
+    // terminates a level and sorts shorter strings first
+    let mut key = Vec::with_capacity(elements.len() * 12 + 8);
+    for level in [
+        |el: &Element| el.primary,
+        |el: &Element| el.secondary + 1,
+        |el: &Element| el.tertiary + 1,
+    ] {
+        for el in elements.iter() {
+            key.extend_from_slice(&level(el).to_be_bytes());
+        }
+        key.extend_from_slice(&[0; 4]);
+    }
+
+    key
+}
+
+/// Compares two strings under the collation rules of `lang`.
+pub fn compare(lang: &LanguageIdentifier, a: &str, b: &str) -> Ordering {
+    sort_key(lang, a).cmp(&sort_key(lang, b))
+}
+
+/// Sorts languages by their display name under the collation rules of `in_lang`.
+pub fn sort_langs_for_display(langs: &mut [Lang], in_lang: &LanguageIdentifier) {
+    langs.sort_by_cached_key(|lang| sort_key(in_lang, lang.name));
+}
+
+/// Splits a character into its collation elements under the rules of `language`.
+fn elements_of(language: &str, ch: char) -> Vec<Element> {
+    let lower = ch.to_lowercase().next().unwrap_or(ch);
+    let tertiary = u32::from(lower != ch);
+
+    if let Some(tailoring) = tailoring(language, lower) {
+        return vec![Element {
+            primary: tailoring,
+            secondary: 0,
+            tertiary,
+        }];
+    }
+
+    let (base, secondary) = match fold(lower) {
+        Some(base) => (base, lower as u32),
+        None => (lower.encode_utf8(&mut [0; 4]).to_string(), 0),
+    };
+    base.chars()
+        .map(|base| Element {
+            primary: (base as u32) << 8 | 1,
+            secondary,
+            tertiary,
+        })
+        .collect()
+}
+
+/// Returns the primary weight of letters that are distinct letters of the alphabet in `language`.
+fn tailoring(language: &str, ch: char) -> Option<u32> {
+    let after = |letter: char, offset: u32| Some((letter as u32) << 8 | (offset + 1));
+    match (language, ch) {
+        ("sv" | "fi", 'å') => after('z', 1),
+        ("sv" | "fi", 'ä' | 'æ') => after('z', 2),
+        ("sv" | "fi", 'ö' | 'ø') => after('z', 3),
+        ("da" | "nb" | "nn" | "no", 'æ' | 'ä') => after('z', 1),
+        ("da" | "nb" | "nn" | "no", 'ø' | 'ö') => after('z', 2),
+        ("da" | "nb" | "nn" | "no", 'å') => after('z', 3),
+        ("es", 'ñ') => after('n', 1),
+        _ => None,
+    }
+}
+
+/// Folds a lowercase Latin letter with diacritics into its base letters.
+fn fold(ch: char) -> Option<String> {
+    let base = match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+
+    Some(base.to_string())
+}
@@ -0,0 +1,95 @@
+use crate::{FluentBundle, FluentError, FluentResource, LanguageIdentifier};
+use std::sync::Arc;
+
+/// How a `Locale` treats a message or term defined by more than one of its resources, e.g. a
+/// shared `common.ftl` along with app-specific overrides.
+///
+/// Resources are added in order, i.e. in file name order for `load!` and `build_from_dir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResourceConflict {
+    /// Rejects the locale with a `ResourceConflictError`.
+    #[default]
+    Error,
+    /// Keeps the definition of the first resource.
+    KeepFirst,
+    /// Replaces the definition with the one of the later resource.
+    Override,
+}
+
+impl std::str::FromStr for ResourceConflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "keep_first" => Ok(Self::KeepFirst),
+            "override" => Ok(Self::Override),
+            _ => Err(format!(
+                "unknown resource conflict policy '{s}', expected 'error', 'keep_first' or 'override'"
+            )),
+        }
+    }
+}
+
+/// The error returned for a locale whose resources define the same entries under `ResourceConflict::Error`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("the resources of {lang} define {} more than once", ids.join(", "))]
+pub struct ResourceConflictError {
+    /// The language of the locale.
+    pub lang: LanguageIdentifier,
+    /// The IDs of the entries defined more than once, in the order they were found. Terms are
+    /// listed with their leading `-`.
+    pub ids: Vec<String>,
+}
+
+/// Adds `resources` to `bundle` in order, resolving the entries defined more than once by `conflict`.
+pub(crate) fn add_resources(
+    bundle: &mut FluentBundle<Arc<FluentResource>>,
+    lang: &LanguageIdentifier,
+    resources: &[Arc<FluentResource>],
+    conflict: ResourceConflict,
+) -> Result<(), ResourceConflictError> {
+    let mut ids = Vec::new();
+    for resource in resources.iter() {
+        if conflict == ResourceConflict::Override {
+            bundle.add_resource_overriding(resource.clone());
+            continue;
+        }
+
+        // the entries that do not conflict are added either way, keeping the first definition
+        let Err(errs) = bundle.add_resource(resource.clone()) else {
+            continue;
+        };
+        for err in errs {
+            if let FluentError::Overriding { kind, id } = err {
+                // `EntryKind` is not exported, but displays as the name of the kind
+                let id = match kind.to_string().as_str() {
+                    "term" => format!("-{id}"),
+                    _ => id,
+                };
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+
+    match conflict {
+        ResourceConflict::Error if !ids.is_empty() => Err(ResourceConflictError {
+            lang: lang.clone(),
+            ids,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Adds the resources shared by every locale to `bundle`, keeping the entries it already defines.
+pub(crate) fn add_common_resources(
+    bundle: &mut FluentBundle<Arc<FluentResource>>,
+    common: &[Arc<FluentResource>],
+) {
+    for resource in common {
+        // the overridden entries are reported, and the others added either way
+        _ = bundle.add_resource(resource.clone());
+    }
+}
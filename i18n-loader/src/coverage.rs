@@ -0,0 +1,127 @@
+use crate::{LanguageIdentifier, Locale, Locales, VARIANT_SEPARATOR};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// How completely every locale translates the messages of the fallback language, as produced by
+/// `Locales::coverage`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CoverageReport {
+    /// The fallback language every locale is compared against.
+    pub reference: LanguageIdentifier,
+    /// The coverage of every other language, sorted by language.
+    pub locales: Vec<LocaleCoverage>,
+}
+
+/// How completely a single language translates the messages of the fallback language.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LocaleCoverage {
+    /// The language compared against the fallback language.
+    pub lang: LanguageIdentifier,
+    /// The IDs of the messages of the fallback language missing from this language, sorted.
+    pub missing: Vec<String>,
+    /// The IDs of the messages only this language defines, sorted.
+    pub extra: Vec<String>,
+    /// The share of the messages of the fallback language this language defines, from `0.0` to `100.0`.
+    pub percentage: f64,
+}
+
+impl CoverageReport {
+    /// Whether every language defines exactly the messages of the fallback language.
+    pub fn is_complete(&self) -> bool {
+        self.locales.iter().all(LocaleCoverage::is_complete)
+    }
+}
+
+impl LocaleCoverage {
+    /// Whether the language defines exactly the messages of the fallback language.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "coverage against {}:", self.reference)?;
+        for locale in self.locales.iter() {
+            write!(f, "{locale}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LocaleCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {:.1}% ({} missing, {} extra)",
+            self.lang,
+            self.percentage,
+            self.missing.len(),
+            self.extra.len()
+        )?;
+        for (sign, items) in [('-', &self.missing), ('+', &self.extra)] {
+            for item in items {
+                writeln!(f, "  {sign} key {item}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Locales {
+    /// Compares the messages of every loaded language against those of the fallback language,
+    /// the runtime counterpart of the `check_keys` option of `load!`.
+    ///
+    /// Message variants such as `signup-cta__variant-b` are optional and thus skipped, see
+    /// `Query::with_variant`. The overlays are not consulted.
+    pub fn coverage(&self) -> CoverageReport {
+        let (locales, fallback_lang) = self.snapshot();
+        let reference = locales
+            .get(&fallback_lang)
+            .map(|locale| coverage_ids(locale))
+            .unwrap_or_default();
+
+        let mut coverage = locales
+            .iter()
+            .filter(|(lang, _)| **lang != fallback_lang)
+            .map(|(lang, locale)| {
+                let ids = coverage_ids(locale);
+                let missing = reference
+                    .difference(&ids)
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>();
+                let percentage = match reference.len() {
+                    0 => 100.0,
+                    len => (len - missing.len()) as f64 * 100.0 / len as f64,
+                };
+                LocaleCoverage {
+                    lang: lang.clone(),
+                    missing,
+                    extra: ids
+                        .difference(&reference)
+                        .map(|id| id.to_string())
+                        .collect(),
+                    percentage,
+                }
+            })
+            .collect::<Vec<_>>();
+        coverage.sort_by_key(|locale| locale.lang.to_string());
+
+        CoverageReport {
+            reference: fallback_lang,
+            locales: coverage,
+        }
+    }
+}
+
+/// Gets the IDs of the messages of `locale` that are expected in every language.
+fn coverage_ids(locale: &Locale) -> BTreeSet<&str> {
+    locale
+        .message_ids()
+        .filter(|id| !id.contains(VARIANT_SEPARATOR))
+        .collect()
+}
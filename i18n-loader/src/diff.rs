@@ -0,0 +1,188 @@
+use crate::{EntryInfo, LanguageIdentifier, Locale, Locales};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The differences between two `Locales` collections, as produced by `Locales::diff`.
+///
+/// Messages and terms are compared by the source text of their patterns, so reformatting a
+/// pattern counts as a change while reordering entries or resources does not.
+/// Terms are listed with their leading `-` and attributes as `key.attribute`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LocalesDiff {
+    /// The languages only present in the new collection.
+    pub added_locales: Vec<LanguageIdentifier>,
+    /// The languages only present in the old collection.
+    pub removed_locales: Vec<LanguageIdentifier>,
+    /// The changes within every language present in both collections, omitting unchanged ones.
+    pub locales: Vec<LocaleDiff>,
+}
+
+/// The differences between the two versions of a single language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LocaleDiff {
+    /// The language that changed.
+    pub lang: LanguageIdentifier,
+    /// The keys only present in the new version.
+    pub added_keys: Vec<String>,
+    /// The keys only present in the old version.
+    pub removed_keys: Vec<String>,
+    /// The keys whose value changed.
+    pub changed_keys: Vec<String>,
+    /// The attributes only present in the new version of a key present in both.
+    pub added_attrs: Vec<String>,
+    /// The attributes only present in the old version of a key present in both.
+    pub removed_attrs: Vec<String>,
+    /// The attributes whose value changed.
+    pub changed_attrs: Vec<String>,
+}
+
+impl LocalesDiff {
+    /// Whether the two collections are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.added_locales.is_empty() && self.removed_locales.is_empty() && self.locales.is_empty()
+    }
+}
+
+impl LocaleDiff {
+    /// Whether the two versions of the language are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.added_keys.is_empty()
+            && self.removed_keys.is_empty()
+            && self.changed_keys.is_empty()
+            && self.added_attrs.is_empty()
+            && self.removed_attrs.is_empty()
+            && self.changed_attrs.is_empty()
+    }
+}
+
+impl fmt::Display for LocalesDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+
+        for lang in self.added_locales.iter() {
+            writeln!(f, "+ locale {lang}")?;
+        }
+        for lang in self.removed_locales.iter() {
+            writeln!(f, "- locale {lang}")?;
+        }
+        for locale in self.locales.iter() {
+            write!(f, "{locale}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LocaleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.lang)?;
+        for (sign, kind, items) in [
+            ('+', "key", &self.added_keys),
+            ('-', "key", &self.removed_keys),
+            ('~', "key", &self.changed_keys),
+            ('+', "attr", &self.added_attrs),
+            ('-', "attr", &self.removed_attrs),
+            ('~', "attr", &self.changed_attrs),
+        ] {
+            for item in items {
+                writeln!(f, "  {sign} {kind} {item}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Locales {
+    /// Computes what changed going from this collection to `other`.
+    pub fn diff(&self, other: &Locales) -> LocalesDiff {
+        let mut diff = LocalesDiff::default();
+        let (locales, other_locales) = (self.locale_map(), other.locale_map());
+        for (lang, old) in locales.iter() {
+            match other_locales.get(lang) {
+                Some(new) => {
+                    let locale = diff_locale(lang, old, new);
+                    if !locale.is_empty() {
+                        diff.locales.push(locale);
+                    }
+                }
+                None => diff.removed_locales.push(lang.clone()),
+            }
+        }
+        diff.added_locales = other_locales
+            .keys()
+            .filter(|lang| !locales.contains_key(*lang))
+            .cloned()
+            .collect();
+
+        diff.added_locales.sort_by_key(ToString::to_string);
+        diff.removed_locales.sort_by_key(ToString::to_string);
+        diff.locales.sort_by_key(|locale| locale.lang.to_string());
+        diff
+    }
+}
+
+/// Computes what changed going from `old` to `new`.
+fn diff_locale(lang: &LanguageIdentifier, old: &Locale, new: &Locale) -> LocaleDiff {
+    let old = keyed_entries(old);
+    let new = keyed_entries(new);
+
+    let mut diff = LocaleDiff {
+        lang: lang.clone(),
+        added_keys: new
+            .keys()
+            .filter(|key| !old.contains_key(*key))
+            .cloned()
+            .collect(),
+        removed_keys: old
+            .keys()
+            .filter(|key| !new.contains_key(*key))
+            .cloned()
+            .collect(),
+        changed_keys: Vec::default(),
+        added_attrs: Vec::default(),
+        removed_attrs: Vec::default(),
+        changed_attrs: Vec::default(),
+    };
+    for (key, old) in old.iter() {
+        let Some(new) = new.get(key) else {
+            continue;
+        };
+
+        if old.pattern != new.pattern {
+            diff.changed_keys.push(key.clone());
+        }
+        for attr in old.attrs.iter() {
+            match new.attr_pattern(attr) {
+                Some(pattern) if Some(pattern) != old.attr_pattern(attr) => {
+                    diff.changed_attrs.push(format!("{key}.{attr}"))
+                }
+                Some(_) => {}
+                None => diff.removed_attrs.push(format!("{key}.{attr}")),
+            }
+        }
+        for attr in new.attrs.iter().filter(|attr| !old.attrs.contains(attr)) {
+            diff.added_attrs.push(format!("{key}.{attr}"));
+        }
+    }
+
+    diff
+}
+
+/// Collects the entries of a locale keyed by their ID, with terms prefixed by `-`.
+fn keyed_entries(locale: &Locale) -> BTreeMap<String, EntryInfo<'_>> {
+    locale
+        .entries()
+        .map(|entry| {
+            let key = match entry.is_term {
+                true => format!("-{}", entry.id),
+                false => entry.id.to_string(),
+            };
+            (key, entry)
+        })
+        .collect()
+}
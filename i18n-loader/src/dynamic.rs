@@ -0,0 +1,112 @@
+use crate::{FluentValue, LanguageIdentifier, Locales, Message, Query, QueryError};
+use serde_json::Value;
+
+/// An error returned by `Locales::query_dynamic`, naming the stage that rejected the input.
+#[derive(Debug, thiserror::Error)]
+pub enum DynamicQueryError {
+    #[error("could not parse langid: {lang}")]
+    InvalidLangid { lang: String },
+    #[error("unknown message id '{id}' for language {lang}")]
+    UnknownKey {
+        id: String,
+        lang: LanguageIdentifier,
+    },
+    #[error("expected the arguments to be an object, found {kind}")]
+    InvalidArgs { kind: &'static str },
+    #[error(
+        "unsupported value for argument ${key}: expected a string, number or bool, found {kind}"
+    )]
+    UnsupportedArg { key: String, kind: &'static str },
+    #[error(transparent)]
+    Fluent(QueryError),
+}
+
+impl Locales {
+    /// Queries for a message described by untrusted input, such as the parameters of an API request.
+    ///
+    /// The language identifier is parsed and canonicalized, the message must exist in either the
+    /// requested or the fallback language, and `args` must be an object (or `null`) of strings,
+    /// numbers and bools. Bools are passed as the strings `"true"` and `"false"`, so that they
+    /// can be selected on. The query falls back to the fallback language.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::Locales;
+    /// use serde_json::json;
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello, { $name }!")])
+    ///     .unwrap();
+    /// let msg = locales.query_dynamic("en-us", "hello", &json!({ "name": "Alex" })).unwrap();
+    /// assert_eq!(msg.value, "Hello, \u{2068}Alex\u{2069}!");
+    /// ```
+    pub fn query_dynamic(
+        &self,
+        lang: &str,
+        id: &str,
+        args: &Value,
+    ) -> Result<Message, DynamicQueryError> {
+        let lang =
+            lang.parse::<LanguageIdentifier>()
+                .map_err(|_| DynamicQueryError::InvalidLangid {
+                    lang: lang.to_string(),
+                })?;
+
+        let mut query = Query::new(id.to_string()).with_fallback(true);
+        match args {
+            Value::Null => {}
+            Value::Object(args) => {
+                for (key, value) in args.iter() {
+                    query = query.with_arg(key.clone(), json_to_fluent(key, value)?);
+                }
+            }
+            other => {
+                return Err(DynamicQueryError::InvalidArgs {
+                    kind: json_kind(other),
+                })
+            }
+        }
+
+        let (locales, overlays) = (self.locale_map(), self.overlays());
+        if self
+            .resolving_locale(&locales, &overlays, &lang, &query)
+            .is_none()
+        {
+            return Err(DynamicQueryError::UnknownKey {
+                id: id.to_string(),
+                lang,
+            });
+        }
+
+        self.query(&lang, &query).map_err(DynamicQueryError::Fluent)
+    }
+}
+
+/// Converts a JSON scalar into the value of the argument `key`.
+fn json_to_fluent(key: &str, value: &Value) -> Result<FluentValue<'static>, DynamicQueryError> {
+    match value {
+        Value::String(s) => Ok(FluentValue::from(s.clone())),
+        Value::Number(n) => match n.as_f64() {
+            Some(n) => Ok(FluentValue::from(n)),
+            None => Ok(FluentValue::from(n.to_string())),
+        },
+        Value::Bool(b) => Ok(FluentValue::from(b.to_string())),
+        other => Err(DynamicQueryError::UnsupportedArg {
+            key: key.to_string(),
+            kind: json_kind(other),
+        }),
+    }
+}
+
+/// Names the kind of a JSON value for error messages.
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a bool",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
@@ -0,0 +1,215 @@
+use fluent_syntax::ast;
+use std::ops::Range;
+
+/// Describes a single message or term as it appears in its originating resource.
+///
+/// Obtained through `Locale::entries`, it borrows directly from the source text of the
+/// resource, which makes it suitable for building editors on top of the loaded translations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryInfo<'a> {
+    /// The identifier of the entry, without the leading `-` for terms.
+    pub id: &'a str,
+    /// Whether the entry is a term (`-brand = ...`) rather than a message.
+    pub is_term: bool,
+    /// The identifiers of all attributes defined on the entry, in source order.
+    pub attrs: Vec<&'a str>,
+    /// The raw source text of the entry's value, if it has one.
+    pub pattern: Option<&'a str>,
+    /// The exact source snippet of the whole entry, including its attributes.
+    pub source: &'a str,
+    /// The byte span of `source` within the originating resource.
+    pub span: Range<usize>,
+    /// The index of the originating resource within the `Locale`.
+    pub resource: usize,
+}
+
+impl<'a> EntryInfo<'a> {
+    /// Builds the entry information of every message and term of a resource parsed from `source`.
+    pub(crate) fn from_resource(
+        resource: usize,
+        source: &'a str,
+        entries: impl IntoIterator<Item = &'a ast::Entry<&'a str>>,
+    ) -> Vec<Self> {
+        let entries = entries.into_iter().collect::<Vec<_>>();
+        // an entry ends where the next one starts, as a select expression may close at column 0
+        let mut ends = Vec::with_capacity(entries.len());
+        let mut end = source.len();
+        for entry in entries.iter().rev() {
+            ends.push(end);
+            if let Some(start) = entry_start(source, entry) {
+                end = start;
+            }
+        }
+
+        entries
+            .into_iter()
+            .zip(ends.into_iter().rev())
+            .filter_map(|(entry, end)| Self::from_ast(resource, source, entry, end))
+            .collect()
+    }
+
+    /// Builds the entry information from an AST entry borrowed from `source`, which ends before
+    /// `end`.
+    ///
+    /// Returns `None` for comments and junk.
+    fn from_ast(
+        resource: usize,
+        source: &'a str,
+        entry: &ast::Entry<&'a str>,
+        end: usize,
+    ) -> Option<Self> {
+        let (id, is_term, attributes) = match entry {
+            ast::Entry::Message(msg) => (msg.id.name, false, &msg.attributes),
+            ast::Entry::Term(term) => (term.id.name, true, &term.attributes),
+            _ => return None,
+        };
+
+        let id_offset = offset_in(source, id);
+        let start = if is_term { id_offset - 1 } else { id_offset };
+        let span = start..start + trim_trailing_comments(&source[start..end]).len();
+        let snippet = &source[span.clone()];
+
+        Some(Self {
+            id,
+            is_term,
+            attrs: attributes.iter().map(|attr| attr.id.name).collect(),
+            pattern: pattern_of(snippet),
+            source: snippet,
+            span,
+            resource,
+        })
+    }
+}
+
+impl<'a> EntryInfo<'a> {
+    /// Gets the raw source text of the value of the attribute `id`, if the entry defines it.
+    pub fn attr_pattern(&self, id: &str) -> Option<&'a str> {
+        let mut start = None;
+        let mut offset = 0;
+        for line in self.source.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+            let is_attr = trimmed.starts_with('.');
+            match start {
+                Some(start) if is_attr => return Some(self.source[start..offset].trim()),
+                None if is_attr => {
+                    let value = trimmed[1..]
+                        .strip_prefix(id)
+                        .map(str::trim_start)
+                        .and_then(|rest| rest.strip_prefix('='));
+                    if let Some(value) = value {
+                        start = Some(offset + line.len() - value.len());
+                    }
+                }
+                _ => {}
+            }
+            offset += line.len();
+        }
+
+        start.map(|start| self.source[start..].trim())
+    }
+}
+
+/// Describes a message loaded into a `Locale`, as yielded by `Locale::messages`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageMeta<'a> {
+    /// The identifier of the message.
+    pub id: &'a str,
+    /// The identifiers of the attributes of the definition in use, in source order.
+    pub attrs: Vec<&'a str>,
+}
+
+/// Describes an unparseable part of a resource that the parser skipped as junk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunkEntry {
+    /// The 1-based line on which the junk starts.
+    pub line: usize,
+    /// The byte span of the junk within its resource.
+    pub span: Range<usize>,
+    /// The offending source text, without trailing whitespace.
+    pub excerpt: String,
+}
+
+impl std::fmt::Display for JunkEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: {}",
+            self.line,
+            self.excerpt.replace('\n', "\n\t  ")
+        )
+    }
+}
+
+/// Collects the junk entries of a resource parsed from `source`.
+pub fn junk_entries<'a>(
+    source: &'a str,
+    entries: impl IntoIterator<Item = &'a ast::Entry<&'a str>>,
+) -> Vec<JunkEntry> {
+    entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            ast::Entry::Junk { content } => {
+                let start = offset_in(source, content);
+                let excerpt = content.trim_end();
+                Some(JunkEntry {
+                    line: source[..start].matches('\n').count() + 1,
+                    span: start..start + excerpt.len(),
+                    excerpt: excerpt.to_string(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Computes the byte offset of `part` within `whole`.
+///
+/// `part` must be a subslice of `whole`, which holds for every string in an AST parsed from it.
+fn offset_in(whole: &str, part: &str) -> usize {
+    part.as_ptr() as usize - whole.as_ptr() as usize
+}
+
+/// Computes the offset of the line on which an entry starts.
+///
+/// Returns `None` for comments, which the runtime parser of `FluentResource` leaves out.
+fn entry_start(source: &str, entry: &ast::Entry<&str>) -> Option<usize> {
+    let anchor = match entry {
+        ast::Entry::Message(msg) => msg.id.name,
+        ast::Entry::Term(term) => term.id.name,
+        ast::Entry::Junk { content } => content,
+        _ => return None,
+    };
+    let offset = offset_in(source, anchor);
+    Some(source[..offset].rfind('\n').map_or(0, |idx| idx + 1))
+}
+
+/// Trims the blank lines and the comments following an entry, up to the start of the next one.
+///
+/// A line starting with `#` ends an entry, while any other line, such as the `}` closing a select
+/// expression, may continue it even when it is not indented.
+fn trim_trailing_comments(snippet: &str) -> &str {
+    let mut end = snippet.len();
+    for line in snippet.trim_end().rsplit('\n') {
+        if !line.starts_with('#') && !line.trim().is_empty() {
+            break;
+        }
+        end = offset_in(snippet, line);
+    }
+
+    snippet[..end].trim_end()
+}
+
+/// Extracts the value of an entry snippet, i.e. everything between the `=` and the first attribute.
+fn pattern_of(snippet: &str) -> Option<&str> {
+    let (_, rest) = snippet.split_once('=')?;
+    let mut len = 0;
+    for (idx, line) in rest.split_inclusive('\n').enumerate() {
+        if idx > 0 && line.trim_start().starts_with('.') {
+            break;
+        }
+        len += line.len();
+    }
+
+    let pattern = rest[..len].trim();
+    (!pattern.is_empty()).then_some(pattern)
+}
@@ -0,0 +1,159 @@
+use crate::Locale;
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+/// A value formatted by `Locales::format_value`, which dereferences to `str`.
+///
+/// A value borrowed from a resource keeps a snapshot of its `Locale` alive instead of copying
+/// the string, so it stays valid even if the locale is replaced or removed in the meantime.
+#[derive(Clone)]
+pub struct FormattedValue<'a> {
+    repr: Repr<'a>,
+}
+
+#[derive(Clone)]
+enum Repr<'a> {
+    /// A slice of the source of one of the resources of `locale`.
+    Resource {
+        locale: Arc<Locale>,
+        resource: usize,
+        range: Range<usize>,
+    },
+    /// A string borrowed from the caller, such as the ID returned by `Locales::format_or_key`.
+    Borrowed(&'a str),
+    /// A string built by formatting the message.
+    Owned(String),
+}
+
+impl<'a> FormattedValue<'a> {
+    /// Wraps a value formatted by `locale`, keeping the snapshot alive if the value borrows from
+    /// one of its resources.
+    pub(crate) fn from_locale(locale: &Arc<Locale>, value: Cow<'_, str>) -> Self {
+        let value = match value {
+            Cow::Borrowed(value) => value,
+            Cow::Owned(value) => return Self::from(value),
+        };
+        let start = value.as_ptr() as usize;
+        let slice = locale
+            .resources
+            .iter()
+            .enumerate()
+            .find_map(|(idx, resource)| {
+                let source = resource.source();
+                let offset = start.checked_sub(source.as_ptr() as usize)?;
+                (offset + value.len() <= source.len()).then_some((idx, offset))
+            });
+        let repr = match slice {
+            Some((resource, offset)) => Repr::Resource {
+                locale: locale.clone(),
+                resource,
+                range: offset..offset + value.len(),
+            },
+            None => Repr::Owned(value.to_string()),
+        };
+        Self { repr }
+    }
+
+    /// Gets the value as a string slice.
+    pub fn as_str(&self) -> &str {
+        match &self.repr {
+            Repr::Resource {
+                locale,
+                resource,
+                range,
+            } => &locale.resources[*resource].source()[range.clone()],
+            Repr::Borrowed(value) => value,
+            Repr::Owned(value) => value,
+        }
+    }
+
+    /// Converts the value into an owned `String`, only allocating if it isn't owned already.
+    pub fn into_string(self) -> String {
+        match self.repr {
+            Repr::Owned(value) => value,
+            _ => self.as_str().to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for FormattedValue<'a> {
+    fn from(value: &'a str) -> Self {
+        Self {
+            repr: Repr::Borrowed(value),
+        }
+    }
+}
+
+impl From<String> for FormattedValue<'_> {
+    fn from(value: String) -> Self {
+        Self {
+            repr: Repr::Owned(value),
+        }
+    }
+}
+
+impl From<FormattedValue<'_>> for String {
+    fn from(value: FormattedValue<'_>) -> Self {
+        value.into_string()
+    }
+}
+
+impl Deref for FormattedValue<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for FormattedValue<'_> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for FormattedValue<'_> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for FormattedValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for FormattedValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for FormattedValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for FormattedValue<'_> {}
+
+impl PartialEq<str> for FormattedValue<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for FormattedValue<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for FormattedValue<'_> {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{LanguageIdentifier, Locale, Locales};
+use fluent_syntax::ast;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The references between the messages and terms of a locale, as produced by `Locale::reference_graph`.
+///
+/// An entry depends on every message and term referenced by its value or attributes, e.g.
+/// `{ other-key }`, `{ other-key.attr }` or `{ -brand }`. Terms are listed with their leading `-`.
+/// References to entries the locale does not define are kept, so that they can be reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReferenceGraph {
+    /// The entries directly referenced by each entry, for every entry of the locale.
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// The entries directly referencing each entry, omitting unreferenced ones.
+    dependents: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ReferenceGraph {
+    /// Gets the entries directly referenced by `id`, sorted.
+    pub fn dependencies_of(&self, id: &str) -> Vec<&str> {
+        Self::neighbors(&self.dependencies, id)
+    }
+
+    /// Gets the entries directly referencing `id`, sorted.
+    pub fn dependents_of(&self, id: &str) -> Vec<&str> {
+        Self::neighbors(&self.dependents, id)
+    }
+
+    /// Gets every entry referencing `id`, directly or through other entries, sorted.
+    ///
+    /// `id` itself is only listed if it is part of a cycle.
+    pub fn transitive_dependents_of(&self, id: &str) -> Vec<&str> {
+        let mut found = BTreeSet::new();
+        let mut pending = self.dependents_of(id);
+        while let Some(dependent) = pending.pop() {
+            if found.insert(dependent) {
+                pending.extend(self.dependents_of(dependent));
+            }
+        }
+
+        found.into_iter().collect()
+    }
+
+    /// Gets the reference cycles, each as the sorted entries taking part in it.
+    ///
+    /// Fluent reports a cyclic reference when formatting any of these entries. The cycles are
+    /// sorted by their first entry.
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        let mut search = CycleSearch {
+            graph: self,
+            index: HashMap::new(),
+            low: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            cycles: Vec::new(),
+        };
+        for id in self.dependencies.keys() {
+            if !search.index.contains_key(id.as_str()) {
+                search.visit(id);
+            }
+        }
+
+        let mut cycles = search.cycles;
+        for cycle in cycles.iter_mut() {
+            cycle.sort();
+        }
+        cycles.sort();
+        cycles
+    }
+
+    /// Whether any entry references itself, directly or through other entries.
+    pub fn has_cycles(&self) -> bool {
+        !self.cycles().is_empty()
+    }
+
+    fn neighbors<'g>(edges: &'g BTreeMap<String, BTreeSet<String>>, id: &str) -> Vec<&'g str> {
+        edges
+            .get(id)
+            .map(|ids| ids.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    fn add_entry(&mut self, id: String, references: BTreeSet<String>) {
+        for reference in references.iter() {
+            self.dependents
+                .entry(reference.clone())
+                .or_default()
+                .insert(id.clone());
+        }
+        self.dependencies.entry(id).or_default().extend(references);
+    }
+}
+
+/// Tarjan's strongly connected components algorithm, collecting the components that form cycles.
+struct CycleSearch<'g> {
+    graph: &'g ReferenceGraph,
+    index: HashMap<&'g str, usize>,
+    low: HashMap<&'g str, usize>,
+    stack: Vec<&'g str>,
+    on_stack: BTreeSet<&'g str>,
+    cycles: Vec<Vec<&'g str>>,
+}
+
+impl<'g> CycleSearch<'g> {
+    fn visit(&mut self, id: &'g str) {
+        let index = self.index.len();
+        self.index.insert(id, index);
+        self.low.insert(id, index);
+        self.stack.push(id);
+        self.on_stack.insert(id);
+
+        for dependency in self.graph.dependencies_of(id) {
+            if !self.index.contains_key(dependency) {
+                self.visit(dependency);
+                let low = self.low[id].min(self.low[dependency]);
+                self.low.insert(id, low);
+            } else if self.on_stack.contains(dependency) {
+                let low = self.low[id].min(self.index[dependency]);
+                self.low.insert(id, low);
+            }
+        }
+
+        if self.low[id] == self.index[id] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            let is_cycle = component.len() > 1 || self.graph.dependencies_of(id).contains(&id);
+            if is_cycle {
+                self.cycles.push(component);
+            }
+        }
+    }
+}
+
+impl Locale {
+    /// Builds the graph of the references between the messages and terms of this locale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, FluentResource, Locale, ResourceConflict};
+    ///
+    /// let resource = FluentResource::try_new("-brand = Acme\nhello = Hello from { -brand }!\nwelcome = { hello }\n".to_string()).unwrap();
+    /// let locale = Locale::new(langid!("en-US"), vec![resource], ResourceConflict::Error).unwrap();
+    /// let graph = locale.reference_graph();
+    /// assert_eq!(graph.dependents_of("-brand"), ["hello"]);
+    /// assert_eq!(graph.transitive_dependents_of("-brand"), ["hello", "welcome"]);
+    /// ```
+    pub fn reference_graph(&self) -> ReferenceGraph {
+        let mut graph = ReferenceGraph::default();
+        for resource in self.resources.iter() {
+            for entry in resource.entries() {
+                let (id, value, attributes) = match entry {
+                    ast::Entry::Message(msg) => {
+                        (msg.id.name.to_string(), msg.value.as_ref(), &msg.attributes)
+                    }
+                    ast::Entry::Term(term) => (
+                        format!("-{}", term.id.name),
+                        Some(&term.value),
+                        &term.attributes,
+                    ),
+                    _ => continue,
+                };
+
+                let mut references = BTreeSet::new();
+                for pattern in value
+                    .into_iter()
+                    .chain(attributes.iter().map(|attr| &attr.value))
+                {
+                    collect_pattern(pattern, &mut references);
+                }
+                graph.add_entry(id, references);
+            }
+        }
+
+        graph
+    }
+}
+
+impl Locales {
+    /// Lists every message of `lang` whose output could change if the message or term `id` is
+    /// edited, i.e. the messages referencing it directly or through other entries, sorted.
+    ///
+    /// Terms are given with their leading `-`, e.g. `-brand`. Returns an empty list if the
+    /// language is not loaded.
+    pub fn impact_of_change(&self, lang: &LanguageIdentifier, id: &str) -> Vec<String> {
+        let Some(locale) = self.locale(lang) else {
+            return Vec::new();
+        };
+
+        locale
+            .reference_graph()
+            .transitive_dependents_of(id)
+            .into_iter()
+            .filter(|dependent| !dependent.starts_with('-') && *dependent != id)
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+fn collect_pattern(pattern: &ast::Pattern<&str>, references: &mut BTreeSet<String>) {
+    for element in pattern.elements.iter() {
+        if let ast::PatternElement::Placeable { expression } = element {
+            collect_expression(expression, references);
+        }
+    }
+}
+
+fn collect_expression(expression: &ast::Expression<&str>, references: &mut BTreeSet<String>) {
+    match expression {
+        ast::Expression::Select { selector, variants } => {
+            collect_inline(selector, references);
+            for variant in variants.iter() {
+                collect_pattern(&variant.value, references);
+            }
+        }
+        ast::Expression::Inline(inline) => collect_inline(inline, references),
+    }
+}
+
+fn collect_inline(inline: &ast::InlineExpression<&str>, references: &mut BTreeSet<String>) {
+    match inline {
+        ast::InlineExpression::MessageReference { id, .. } => {
+            references.insert(id.name.to_string());
+        }
+        ast::InlineExpression::TermReference { id, arguments, .. } => {
+            references.insert(format!("-{}", id.name));
+            if let Some(arguments) = arguments {
+                collect_arguments(arguments, references);
+            }
+        }
+        ast::InlineExpression::FunctionReference { arguments, .. } => {
+            collect_arguments(arguments, references)
+        }
+        ast::InlineExpression::Placeable { expression } => {
+            collect_expression(expression, references)
+        }
+        ast::InlineExpression::StringLiteral { .. }
+        | ast::InlineExpression::NumberLiteral { .. }
+        | ast::InlineExpression::VariableReference { .. } => {}
+    }
+}
+
+fn collect_arguments(arguments: &ast::CallArguments<&str>, references: &mut BTreeSet<String>) {
+    for argument in arguments.positional.iter() {
+        collect_inline(argument, references);
+    }
+    for argument in arguments.named.iter() {
+        collect_inline(&argument.value, references);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{LanguageIdentifier, Locale, Locales};
+use fluent_syntax::ast;
+use std::collections::HashSet;
+
+/// What a locale defines for a message, as returned by `Locales::message_info`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageInfo {
+    /// The ID of the message.
+    pub id: String,
+    /// The language whose locale defines the message.
+    pub lang: LanguageIdentifier,
+    /// Whether the message is missing from the requested language and was found in the fallback language.
+    pub is_fallback: bool,
+    /// Whether the message has a value, rather than only attributes.
+    pub has_value: bool,
+    /// The IDs of the attributes of the message, in source order.
+    pub attrs: Vec<String>,
+    /// The IDs of the attributes that reference a variable, in source order.
+    pub attrs_requiring_args: Vec<String>,
+    /// Whether the value references a variable.
+    pub value_requires_args: bool,
+}
+
+impl MessageInfo {
+    /// Whether the value or any attribute references a variable, i.e. cannot be formatted without arguments.
+    pub fn requires_args(&self) -> bool {
+        self.value_requires_args || !self.attrs_requiring_args.is_empty()
+    }
+}
+
+impl Locales {
+    /// Describes the message `id` without formatting it, e.g. to check whether a language has it.
+    ///
+    /// The message is looked up like a query with fallback enabled, see `Query::with_fallback`:
+    /// in the requested language, then in the fallback language, with the overlays taking
+    /// precedence over both. Returns `None` if neither defines it. Variables referenced through
+    /// other messages count as required arguments, while those of terms do not, as terms only see
+    /// the arguments passed to them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello, { $name }!\n    .title = Greeting")])
+    ///     .unwrap();
+    /// let info = locales.message_info(&langid!("en-US"), "hello").unwrap();
+    /// assert!(info.has_value && info.value_requires_args);
+    /// assert_eq!(info.attrs, ["title"]);
+    /// assert!(locales.message_info(&langid!("en-US"), "missing").is_none());
+    /// ```
+    pub fn message_info(&self, lang: &LanguageIdentifier, id: &str) -> Option<MessageInfo> {
+        let ((locales, fallback_lang), overlays) = (self.snapshot(), self.overlays());
+        let info = [lang, &fallback_lang].into_iter().find_map(|candidate| {
+            let locale = overlays
+                .iter()
+                .rev()
+                .chain(std::iter::once(&locales))
+                .filter_map(|layer| layer.get(candidate))
+                .find(|locale| locale.bundle.has_message(id))?;
+            locale.message_info(id, candidate, candidate != lang)
+        });
+        info
+    }
+}
+
+impl Locale {
+    /// Describes the message `id` as defined by this locale, see `Locales::message_info`.
+    fn message_info(
+        &self,
+        id: &str,
+        lang: &LanguageIdentifier,
+        is_fallback: bool,
+    ) -> Option<MessageInfo> {
+        let msg = self.bundle.get_message(id)?;
+        let requires_args = |pattern: &ast::Pattern<&str>| {
+            let mut visited = HashSet::new();
+            self.pattern_requires_args(pattern, &mut visited)
+        };
+
+        Some(MessageInfo {
+            id: id.to_string(),
+            lang: lang.clone(),
+            is_fallback,
+            has_value: msg.value().is_some(),
+            attrs: msg.attributes().map(|attr| attr.id().to_string()).collect(),
+            attrs_requiring_args: msg
+                .attributes()
+                .filter(|attr| requires_args(attr.value()))
+                .map(|attr| attr.id().to_string())
+                .collect(),
+            value_requires_args: msg.value().is_some_and(requires_args),
+        })
+    }
+
+    /// Whether `pattern` references a variable, directly or through the messages it references.
+    ///
+    /// `visited` holds the referenced messages already searched, guarding against cycles.
+    fn pattern_requires_args<'r>(
+        &'r self,
+        pattern: &ast::Pattern<&'r str>,
+        visited: &mut HashSet<(&'r str, Option<&'r str>)>,
+    ) -> bool {
+        pattern.elements.iter().any(|element| match element {
+            ast::PatternElement::Placeable { expression } => {
+                self.expression_requires_args(expression, visited)
+            }
+            ast::PatternElement::TextElement { .. } => false,
+        })
+    }
+
+    fn expression_requires_args<'r>(
+        &'r self,
+        expression: &ast::Expression<&'r str>,
+        visited: &mut HashSet<(&'r str, Option<&'r str>)>,
+    ) -> bool {
+        match expression {
+            ast::Expression::Select { selector, variants } => {
+                self.inline_requires_args(selector, visited)
+                    || variants
+                        .iter()
+                        .any(|variant| self.pattern_requires_args(&variant.value, visited))
+            }
+            ast::Expression::Inline(inline) => self.inline_requires_args(inline, visited),
+        }
+    }
+
+    fn inline_requires_args<'r>(
+        &'r self,
+        inline: &ast::InlineExpression<&'r str>,
+        visited: &mut HashSet<(&'r str, Option<&'r str>)>,
+    ) -> bool {
+        match inline {
+            ast::InlineExpression::VariableReference { .. } => true,
+            ast::InlineExpression::MessageReference { id, attribute } => {
+                let attribute = attribute.as_ref().map(|attr| attr.name);
+                if !visited.insert((id.name, attribute)) {
+                    return false;
+                }
+                let Some(msg) = self.bundle.get_message(id.name) else {
+                    return false;
+                };
+                let pattern = match attribute {
+                    Some(attribute) => msg.get_attribute(attribute).map(|attr| attr.value()),
+                    None => msg.value(),
+                };
+                pattern.is_some_and(|pattern| self.pattern_requires_args(pattern, visited))
+            }
+            // the variables within a term are its own parameters, only its arguments matter
+            ast::InlineExpression::TermReference { arguments, .. } => arguments
+                .as_ref()
+                .is_some_and(|arguments| self.arguments_require_args(arguments, visited)),
+            ast::InlineExpression::FunctionReference { arguments, .. } => {
+                self.arguments_require_args(arguments, visited)
+            }
+            ast::InlineExpression::Placeable { expression } => {
+                self.expression_requires_args(expression, visited)
+            }
+            ast::InlineExpression::StringLiteral { .. }
+            | ast::InlineExpression::NumberLiteral { .. } => false,
+        }
+    }
+
+    fn arguments_require_args<'r>(
+        &'r self,
+        arguments: &ast::CallArguments<&'r str>,
+        visited: &mut HashSet<(&'r str, Option<&'r str>)>,
+    ) -> bool {
+        arguments
+            .positional
+            .iter()
+            .chain(arguments.named.iter().map(|argument| &argument.value))
+            .any(|argument| self.inline_requires_args(argument, visited))
+    }
+}
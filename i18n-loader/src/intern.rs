@@ -0,0 +1,98 @@
+use crate::{FluentResource, LanguageIdentifier, Locale, Locales, ResourceConflictError};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, Weak};
+
+/// The resources shared by the locales of a collection, keyed by a hash of their source.
+///
+/// Regions that reuse the file of their base language verbatim would otherwise keep one parsed
+/// copy of it per locale; with 40 locales sharing a 10 KB `common.ftl`, that is 400 KB of source
+/// plus its AST, down to a single copy. Entries are weak, so a resource is freed along with the
+/// last locale using it.
+#[derive(Debug, Default)]
+pub(crate) struct ResourceCache {
+    /// The resources whose source hashes to the key, usually one.
+    resources: Mutex<HashMap<u64, Vec<Weak<FluentResource>>>>,
+}
+
+impl ResourceCache {
+    /// Gets the resource already shared with the same source as `resource`, or shares it.
+    fn share(&self, resource: Arc<FluentResource>) -> Arc<FluentResource> {
+        let mut hasher = DefaultHasher::new();
+        resource.source().hash(&mut hasher);
+        let mut cache = self.resources.lock().unwrap_or_else(|err| err.into_inner());
+        let shared = cache.entry(hasher.finish()).or_default();
+        shared.retain(|weak| weak.strong_count() > 0);
+        if let Some(existing) = shared
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|existing| existing.source() == resource.source())
+        {
+            return existing;
+        }
+
+        shared.push(Arc::downgrade(&resource));
+        resource
+    }
+}
+
+impl Locales {
+    /// Adds a new language's localization data like `Locales::add_locale`, from resources that
+    /// may already be shared, e.g. by the code `load!` generates for files embedded by several
+    /// languages.
+    ///
+    /// # Errors
+    /// Returns a `ResourceConflictError` if the resources define the same entry under
+    /// `ResourceConflict::Error`, in which case the locale is not added.
+    ///
+    /// # Panics
+    /// Panics if `lang_str` is not a valid language identifier.
+    pub fn add_shared_locale(
+        &self,
+        lang_str: &str,
+        resources: Vec<Arc<FluentResource>>,
+    ) -> Result<(), ResourceConflictError> {
+        self.add_shared_locale_with_common(lang_str, resources, Vec::new())
+    }
+
+    /// Adds a new language's localization data like `Locales::add_shared_locale`, followed by
+    /// `common` resources shared by every language, e.g. brand names and other terms that must
+    /// not be translated.
+    ///
+    /// The entries of the language's own resources override those of the common resources
+    /// regardless of the `ResourceConflict` policy, which only applies among the former.
+    ///
+    /// # Errors
+    /// Returns a `ResourceConflictError` if the own resources define the same entry under
+    /// `ResourceConflict::Error`, in which case the locale is not added.
+    ///
+    /// # Panics
+    /// Panics if `lang_str` is not a valid language identifier.
+    pub fn add_shared_locale_with_common(
+        &self,
+        lang_str: &str,
+        resources: Vec<Arc<FluentResource>>,
+        common: Vec<Arc<FluentResource>>,
+    ) -> Result<(), ResourceConflictError> {
+        let lang: LanguageIdentifier = lang_str.parse().expect("Language ID should be valid");
+        self.insert_shared_locale(lang, resources, common)
+    }
+
+    /// Builds a locale with the options of the collection, sharing every resource whose source
+    /// is identical to one of a locale built before.
+    pub(crate) fn build_locale(
+        &self,
+        lang: LanguageIdentifier,
+        resources: Vec<Arc<FluentResource>>,
+        common: Vec<Arc<FluentResource>>,
+    ) -> Result<Locale, ResourceConflictError> {
+        let share = |resources: Vec<Arc<FluentResource>>| {
+            resources
+                .into_iter()
+                .map(|resource| self.resource_cache.share(resource))
+                .collect()
+        };
+        Locale::with_shared_resources(lang, share(resources), share(common), &self.options)
+    }
+}
@@ -3,9 +3,96 @@ pub use fluent_bundle::{
     resolver::errors::{ReferenceKind, ResolverError},
     FluentArgs, FluentError, FluentResource, FluentValue,
 };
+pub use i18n_lang::Lang;
 pub use lazy_static;
-use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock, RwLock,
+    },
+};
+
+mod args;
+mod builder;
+#[cfg(feature = "builtins")]
+mod builtins;
+#[cfg(feature = "net")]
+mod cancel;
+#[cfg(feature = "collate")]
+pub mod collate;
+mod conflict;
+mod coverage;
+mod diff;
+#[cfg(feature = "serde")]
+mod dynamic;
+mod entry;
+mod formatted;
+mod graph;
+mod info;
+mod intern;
+mod miss;
+mod normalize;
+mod observe;
+#[cfg(feature = "fetch")]
+mod payload;
+mod pseudo;
+mod query_errors;
+#[cfg(feature = "net")]
+mod refresh;
+mod reload;
+#[cfg(feature = "net")]
+mod remote;
+#[cfg(feature = "net")]
+mod retry;
+mod sanitize;
+mod select;
+mod switcher;
+mod typed;
+#[cfg(feature = "net")]
+mod upload;
+mod usage;
+mod variant;
+pub use args::*;
+pub use builder::*;
+#[cfg(feature = "net")]
+pub use cancel::*;
+pub use conflict::*;
+pub use coverage::*;
+pub use diff::*;
+#[cfg(feature = "serde")]
+pub use dynamic::*;
+pub use entry::*;
+pub use formatted::*;
+pub use graph::*;
+pub use info::*;
+pub use miss::*;
+pub use normalize::*;
+pub use observe::*;
+#[cfg(feature = "fetch")]
+pub use payload::*;
+pub use pseudo::*;
+pub use query_errors::*;
+#[cfg(feature = "net")]
+pub use refresh::*;
+pub use reload::*;
+#[cfg(feature = "net")]
+pub use remote::*;
+#[cfg(feature = "net")]
+pub use retry::*;
+pub use sanitize::*;
+pub use select::*;
+pub use switcher::*;
+pub use typed::*;
 pub use unic_langid::{langid, langids, LanguageIdentifier};
+#[cfg(feature = "net")]
+pub use upload::*;
+pub use usage::*;
+pub use variant::*;
+
+/// The locales of a collection, keyed by their language.
+pub(crate) type LocaleMap = HashMap<LanguageIdentifier, Arc<Locale>>;
 
 /// A thread-safe container for all loaded localization data.
 ///
@@ -14,11 +101,55 @@ pub use unic_langid::{langid, langids, LanguageIdentifier};
 /// fallback logic to a default language if a translation is missing.
 pub struct Locales {
     /// The map from a language identifier to its `Locale`.
-    locales: HashMap<LanguageIdentifier, Locale>,
+    ///
+    /// Mutations swap in a new map, so readers only ever hold the lock to clone the `Arc`.
+    locales: RwLock<Arc<LocaleMap>>,
     /// The language to use as a fallback if a message is not found in the requested language.
-    fallback_lang: LanguageIdentifier,
+    ///
+    /// Only read while holding the lock of `locales`, see `Locales::snapshot`, so that the
+    /// fallback language is always present in the snapshot it is read with.
+    fallback_lang: RwLock<LanguageIdentifier>,
     /// An optional error handler to be called with any localization errors.
-    on_error: Option<fn(&[FluentError])>,
+    on_error: Option<ErrorHandler>,
+    /// An optional handler to be called whenever an inline default replaces a missing message.
+    on_inline_default: Option<fn(&LanguageIdentifier, &str)>,
+    /// The options applied to every `Locale` added to the collection.
+    options: LocaleOptions,
+    /// The Unicode normalization applied to sources reloaded with `Locales::reload_from_dir`.
+    normalization: Normalization,
+    /// Whether sources reloaded with `Locales::reload_from_dir` may contain junk entries.
+    allow_junk: bool,
+    /// The files re-read by `Locales::reload`, see `LocalesBuilder::reload_files`.
+    reload_files: Vec<(String, std::path::PathBuf)>,
+    /// The runtime overlays stacked on top of this collection, the topmost last.
+    ///
+    /// Mutations swap in a new list, so readers only ever hold the lock to clone the `Arc`.
+    overlays: RwLock<Arc<Vec<Arc<Locales>>>>,
+    /// The usage recorder, if usage tracking is enabled.
+    usage: OnceLock<usage::UsageTracker>,
+    /// The sanitizer applied to the string arguments of every query, see `Locales::set_arg_sanitizer`.
+    arg_sanitizer: RwLock<Option<ArgSanitizer>>,
+    /// The reporter notified of every missing message or attribute, see `Locales::set_miss_reporter`.
+    miss_reporter: RwLock<Option<MissReporter>>,
+    /// The observer notified of every resolved query, see `Locales::set_observer`.
+    observer: RwLock<Option<Arc<dyn QueryObserver>>>,
+    /// Whether queries resolved in the fallback language fail, see `Locales::set_strict`.
+    strict: AtomicBool,
+    /// The resources shared by the locales of the collection.
+    resource_cache: intern::ResourceCache,
+    /// How `Locales::ensure_loaded` bounds and retries its requests, see `LocalesBuilder::net_config`.
+    #[cfg(feature = "net")]
+    net: NetConfig,
+    /// The token through which `Locales::ensure_loaded` can be abandoned, see
+    /// `LocalesBuilder::cancel_token`.
+    #[cfg(feature = "net")]
+    cancel_token: Option<CancellationToken>,
+    /// The URL template from which `Locales::ensure_loaded` fetches missing locales.
+    #[cfg(feature = "net")]
+    remote_source: RwLock<Option<String>>,
+    /// The validators of the last response of every URL refreshed with `Locales::refresh_from_url`.
+    #[cfg(feature = "net")]
+    refresh_validators: std::sync::Mutex<HashMap<String, remote::Validators>>,
 }
 
 impl Locales {
@@ -26,13 +157,31 @@ impl Locales {
     ///
     /// # Arguments
     /// * `fallback_lang`: The language identifier to use if a translation is not found in the current language.
+    /// * `on_error`: An optional callback that will be invoked with any errors that occur during message formatting.
+    ///
+    /// This is a shorthand for `LocalesBuilder`, which exposes all of the configuration options.
+    pub fn new(fallback_lang: LanguageIdentifier, on_error: Option<ErrorHandler>) -> Self {
+        Self::builder_with(fallback_lang, on_error).build()
+    }
+
+    /// Creates a new `Locales` collection from FTL sources keyed by their language identifier,
+    /// e.g. fetched through a transport other than `Locales::from_url`.
+    ///
+    /// # Arguments
+    /// * `sources`: The FTL source of every language, keyed by its language identifier.
+    /// * `fallback_lang`: The language identifier to use if a translation is not found in the current language.
     /// * `on_error`: An optional callback function that will be invoked with any errors that occur during message formatting.
-    pub fn new(fallback_lang: LanguageIdentifier, on_error: Option<fn(&[FluentError])>) -> Self {
-        Self {
-            locales: Default::default(),
-            fallback_lang,
-            on_error,
-        }
+    ///
+    /// # Errors
+    /// Returns `LoadError::Parser` with the errors of every invalid language identifier and every
+    /// source that fails to parse, exactly like `Locales::from_url`, or `LoadError::ResourceConflict`
+    /// if a source defines the same entry more than once.
+    pub fn from_ftl_map(
+        sources: HashMap<String, String>,
+        fallback_lang: LanguageIdentifier,
+        on_error: Option<ErrorHandler>,
+    ) -> Result<Self, LoadError> {
+        Self::builder_with(fallback_lang, on_error).build_from_strs(sources)
     }
 
     /// Creates a new `Locales` collection from a network resource.
@@ -45,48 +194,111 @@ impl Locales {
     pub async fn from_url(
         url: &str,
         fallback_lang: LanguageIdentifier,
-        on_error: Option<fn(&[FluentError])>,
+        on_error: Option<ErrorHandler>,
     ) -> Result<Self, NetError> {
-        let https = hyper_tls::HttpsConnector::new();
-        let client = hyper::Client::builder().build::<_, hyper::Body>(https);
+        Self::builder_with(fallback_lang, on_error)
+            .build_from_url(url)
+            .await
+    }
 
-        let uri = url.parse().unwrap();
+    /// Creates a new `Locales` collection from a network resource, loading only the languages
+    /// matching `langs` and the fallback language.
+    ///
+    /// See `LocalesBuilder::langs`.
+    #[cfg(feature = "net")]
+    pub async fn from_url_filtered(
+        url: &str,
+        langs: &[LanguageIdentifier],
+        fallback_lang: LanguageIdentifier,
+        on_error: Option<ErrorHandler>,
+    ) -> Result<Self, NetError> {
+        Self::builder_with(fallback_lang, on_error)
+            .langs(langs.iter().cloned())
+            .build_from_url(url)
+            .await
+    }
 
-        let res = client.get(uri).await.map_err(NetError::ServerError)?;
-        let body = hyper::body::to_bytes(res.into_body())
+    /// Creates a new `Locales` collection from a network resource, returning the metadata of the
+    /// payload along with it.
+    ///
+    /// See `LocalesBuilder::build_from_url_with_meta`.
+    ///
+    /// # Arguments
+    /// * `url`: The URL from which to fetch the translation.
+    /// * `fallback_lang`: The language identifier to use if a translation is not found in the current
+    ///   language, or `None` to use the one suggested by the payload, and `en-US` if it suggests none.
+    /// * `on_error`: An optional callback function that will be invoked with any errors that occur during message formatting.
+    #[cfg(feature = "net")]
+    pub async fn from_url_with_meta(
+        url: &str,
+        fallback_lang: Option<LanguageIdentifier>,
+        on_error: Option<ErrorHandler>,
+    ) -> Result<(Self, RemoteMeta), NetError> {
+        let builder = match fallback_lang {
+            Some(fallback_lang) => Self::builder_with(fallback_lang, on_error),
+            None => match on_error {
+                Some(on_error) => LocalesBuilder::new().on_error(move |errs| on_error(errs)),
+                None => LocalesBuilder::new(),
+            },
+        };
+        builder.build_from_url_with_meta(url).await
+    }
+
+    /// Creates a new `Locales` collection from a resource fetched by `fetch`, with the HTTP client
+    /// of the application or from any other transport.
+    ///
+    /// See `LocalesBuilder::build_from_fetcher`.
+    ///
+    /// # Arguments
+    /// * `url`: The URL passed to `fetch`.
+    /// * `fetch`: The function fetching the body of the resource.
+    /// * `fallback_lang`: The language identifier to use if a translation is not found in the current language.
+    /// * `on_error`: An optional callback function that will be invoked with any errors that occur during message formatting.
+    #[cfg(feature = "fetch")]
+    pub async fn from_fetcher<F, Fut, E>(
+        url: &str,
+        fetch: F,
+        fallback_lang: LanguageIdentifier,
+        on_error: Option<ErrorHandler>,
+    ) -> Result<Self, FetchError<E>>
+    where
+        F: FnOnce(&str) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<u8>, E>>,
+    {
+        Self::builder_with(fallback_lang, on_error)
+            .build_from_fetcher(url, fetch)
             .await
-            .map_err(NetError::ServerError)?;
-        let definitions: HashMap<String, String> =
-            serde_json::from_slice(&body).map_err(NetError::InvalidFormat)?;
-        let mut parser_errors: Vec<ParserError> = Vec::default();
-        let mut locales: HashMap<LanguageIdentifier, Locale> = HashMap::default();
-        for (langid, definition) in definitions.into_iter() {
-            let langid = match langid.parse::<LanguageIdentifier>() {
-                Ok(langid) => langid,
-                Err(_) => {
-                    parser_errors.push(ParserError::InvalidLangid { langid });
-                    continue;
-                }
-            };
-            let resource = match FluentResource::try_new(definition) {
-                Ok(resource) => resource,
-                Err((_, errors)) => {
-                    parser_errors.push(ParserError::ParserError { langid, errors });
-                    continue;
-                }
-            };
-            locales.insert(langid.clone(), Locale::new(langid, vec![resource]));
-        }
+    }
 
-        if !parser_errors.is_empty() {
-            return Err(NetError::ParserError(parser_errors));
-        }
+    /// Creates a new `Locales` collection from a network resource, skipping the locales that fail to parse.
+    ///
+    /// See `LocalesBuilder::build_from_url_lenient`.
+    #[cfg(feature = "net")]
+    pub async fn from_url_lenient(
+        url: &str,
+        fallback_lang: LanguageIdentifier,
+        on_error: Option<ErrorHandler>,
+    ) -> Result<PartialLoad, NetError> {
+        Self::builder_with(fallback_lang, on_error)
+            .build_from_url_lenient(url)
+            .await
+    }
 
-        Ok(Self {
-            locales,
-            fallback_lang,
-            on_error,
-        })
+    /// Creates a new `LocalesBuilder` for configuring a `Locales` collection.
+    pub fn builder() -> LocalesBuilder {
+        LocalesBuilder::new()
+    }
+
+    /// Creates a new `LocalesBuilder` from the arguments of the legacy constructors.
+    fn builder_with(
+        fallback_lang: LanguageIdentifier,
+        on_error: Option<ErrorHandler>,
+    ) -> LocalesBuilder {
+        let builder = LocalesBuilder::new().fallback_lang(fallback_lang);
+        match on_error {
+            Some(on_error) => builder.on_error(move |errs| on_error(errs)),
+            None => builder,
+        }
     }
 
     /// Adds a new language's localization data to the collection.
@@ -95,52 +307,759 @@ impl Locales {
     /// * `lang_str`: A string slice representing the language identifier (e.g., "en-US", "de").
     /// * `resources`: A vector of `FluentResource`s containing the translation data for this language.
     ///
+    /// If the resources define no messages, the observer is notified through
+    /// `QueryObserver::on_empty_locale`, since every query for the language would silently fall back.
+    ///
+    /// Entries defined by more than one resource are resolved as configured with
+    /// `LocalesBuilder::resource_conflict`.
+    ///
+    /// # Errors
+    /// Returns a `ResourceConflictError` if the resources define the same entry under `ResourceConflict::Error`,
+    /// in which case the locale is not added.
+    ///
+    /// A locale already loaded for the language is replaced. Queries running concurrently keep
+    /// using the locales they started with.
+    ///
     /// # Panics
     /// Panics if `lang_str` is not a valid language identifier.
-    pub fn add_locale(&mut self, lang_str: &str, resources: Vec<FluentResource>) {
+    pub fn add_locale(
+        &self,
+        lang_str: &str,
+        resources: Vec<FluentResource>,
+    ) -> Result<(), ResourceConflictError> {
         let lang_id: LanguageIdentifier = lang_str.parse().expect("Language ID should be valid");
-        let locale = Locale::new(lang_id.clone(), resources);
-        self.locales.insert(lang_id, locale);
+        self.insert_locale(lang_id, resources)
+    }
+
+    /// Adds a new language's localization data like `Locales::add_locale`, rejecting invalid input
+    /// instead of panicking, e.g. for languages read from a configuration file.
+    ///
+    /// A locale already loaded for the language is only replaced if `overwrite` is set.
+    ///
+    /// # Errors
+    /// Returns an `AddLocaleError` if `lang_str` is not a valid language identifier, if
+    /// `resources` is empty, if a locale is already loaded for the language and `overwrite` is not
+    /// set, or if the resources conflict. The collection is left unchanged in every case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{AddLocaleError, FluentResource, Locales};
+    ///
+    /// let locales = Locales::builder().build_from_strs([("en-US", "hello = Hello")]).unwrap();
+    /// let resource = || vec![FluentResource::try_new("hello = Bok".to_string()).unwrap()];
+    /// assert!(locales.try_add_locale("hr-HR", resource(), false).is_ok());
+    /// assert!(matches!(
+    ///     locales.try_add_locale("hr-HR", resource(), false),
+    ///     Err(AddLocaleError::AlreadyLoaded { .. })
+    /// ));
+    /// ```
+    pub fn try_add_locale(
+        &self,
+        lang_str: &str,
+        resources: Vec<FluentResource>,
+        overwrite: bool,
+    ) -> Result<(), AddLocaleError> {
+        let lang: LanguageIdentifier =
+            lang_str
+                .parse()
+                .map_err(|source| AddLocaleError::InvalidLangid {
+                    langid: lang_str.to_string(),
+                    source,
+                })?;
+        if resources.is_empty() {
+            return Err(AddLocaleError::NoResources { lang });
+        }
+        if !overwrite && self.locale_map().contains_key(&lang) {
+            return Err(AddLocaleError::AlreadyLoaded { lang });
+        }
+
+        let resources = resources.into_iter().map(Arc::new).collect();
+        let locale = self.build_locale(lang.clone(), resources, Vec::new())?;
+        let mut result = Ok(());
+        // checked again, as the locale may have been added in the meantime
+        self.update_locales(|locales| match locales.contains_key(&lang) && !overwrite {
+            true => result = Err(AddLocaleError::AlreadyLoaded { lang }),
+            false => _ = locales.insert(lang, Arc::new(locale)),
+        });
+        result
+    }
+
+    /// Adds a new language's localization data to the collection, applying the configured `LocaleOptions`.
+    pub(crate) fn insert_locale(
+        &self,
+        lang: LanguageIdentifier,
+        resources: Vec<FluentResource>,
+    ) -> Result<(), ResourceConflictError> {
+        let resources = resources.into_iter().map(Arc::new).collect();
+        self.insert_shared_locale(lang, resources, Vec::new())
+    }
+
+    /// Adds a new language's localization data like `Locales::insert_locale`, from resources that
+    /// may already be shared, followed by the `common` resources shared by every language.
+    pub(crate) fn insert_shared_locale(
+        &self,
+        lang: LanguageIdentifier,
+        resources: Vec<Arc<FluentResource>>,
+        common: Vec<Arc<FluentResource>>,
+    ) -> Result<(), ResourceConflictError> {
+        let has_messages = resources.iter().chain(common.iter()).any(|resource| {
+            resource
+                .entries()
+                .any(|entry| matches!(entry, fluent_syntax::ast::Entry::Message(_)))
+        });
+        if let Some(observer) = self.observer().filter(|_| !has_messages) {
+            observer.on_empty_locale(&lang);
+        }
+
+        let locale = self.build_locale(lang.clone(), resources, common)?;
+        self.update_locales(|locales| {
+            locales.insert(lang, Arc::new(locale));
+        });
+        Ok(())
+    }
+
+    /// Removes the locale of the language `lang`, returning it.
+    ///
+    /// Returns `None` if the language is not loaded or is the fallback language, whose locale
+    /// every missing locale falls back to. Queries running concurrently keep using the locales
+    /// they started with.
+    pub fn remove_locale(&self, lang: &LanguageIdentifier) -> Option<Arc<Locale>> {
+        let mut removed = None;
+        self.update_locales(|locales| {
+            if *lang != self.fallback_lang() {
+                removed = locales.remove(lang);
+            }
+        });
+        removed
     }
 
     /// Queries for a message in a specific language, applying fallback logic if the language is not found.
     ///
     /// It first attempts to find the `Locale` for the requested language. If the entire `Locale` is missing,
     /// it will automatically retry the query using the configured fallback language.
+    ///
+    /// If the query has fallback enabled (see `Query::with_fallback`), a message missing from the requested
+    /// language is resolved in the fallback language instead, and any attributes missing from the requested
+    /// language's version of the message are overlaid from the fallback language's version. Overlaid
+    /// attributes are marked with `AttrCache::is_fallback`.
     #[track_caller]
-    pub fn query(
+    pub fn query(&self, lang: &LanguageIdentifier, query: &Query) -> Result<Message, QueryError> {
+        let ((locales, fallback_lang), overlays) = (self.snapshot(), self.overlays());
+        self.record_usage(lang, split_attr_id(&query.id).0);
+        let variant = self.variant_query(&locales, &overlays, lang, query);
+        let query = variant.as_ref().unwrap_or(query);
+        let sanitized = self.sanitized_query(query);
+        let query = sanitized.as_ref().unwrap_or(query);
+        let query_result = self.resolve(
+            &overlays,
+            lang,
+            locales.get(lang).map(Arc::as_ref),
+            locales.get(&fallback_lang).map(Arc::as_ref),
+            query,
+        );
+
+        // inspect the errors if on_error exists
+        if let (Some(on_error), Err(errs)) = (&self.on_error, &query_result) {
+            on_error(errs);
+        }
+        query_result
+    }
+
+    /// Queries for a message like `Locales::query`, substituting `default` as its value if the
+    /// message is missing from both the requested and the fallback language.
+    ///
+    /// This is what `tr!(.., default = "..")` expands to. The missing message is still reported
+    /// through `on_error`, followed by the `on_inline_default` handler. Any other error, such as a
+    /// missing argument, is returned as is and the default is not used.
+    #[track_caller]
+    pub fn query_or_default(
         &self,
         lang: &LanguageIdentifier,
         query: &Query,
-    ) -> Result<Message, Vec<FluentError>> {
-        let query_result = match self.locales.get(lang) {
-            Some(locale) => locale.query(query),
-            None => {
-                let fallback_locale = self
-                    .locales
-                    .get(&self.fallback_lang)
-                    .expect("a fallback language should *always* exist and be present as a locale");
-                fallback_locale.query(query)
+        default: &str,
+    ) -> Result<Message, QueryError> {
+        match self.query(lang, query) {
+            Err(errs)
+                if is_missing_message(&errs, &query.id) && !self.has_message(lang, &query.id) =>
+            {
+                if let Some(on_inline_default) = self.on_inline_default {
+                    on_inline_default(lang, &query.id);
+                }
+                Ok(Message {
+                    id: query.id.to_string(),
+                    value: default.to_string(),
+                    attrs: Default::default(),
+                    resolved_lang: LanguageIdentifier::default(),
+                })
             }
+            result => result,
+        }
+    }
+
+    /// Whether a query for the message `id` in the language `lang` would find it, without
+    /// formatting it or invoking `on_error`.
+    ///
+    /// The message is looked up like a query with fallback enabled, see `Query::with_fallback`:
+    /// in the requested language, then in the fallback language, with the overlays taking
+    /// precedence over both. Use `Locales::defines_message` to skip the fallback language.
+    pub fn has_message(&self, lang: &LanguageIdentifier, id: &str) -> bool {
+        self.defines_message(lang, id) || self.defines_message(&self.fallback_lang(), id)
+    }
+
+    /// Whether the message `id` is defined in the language `lang`, by this collection or any of its overlays.
+    ///
+    /// The fallback language is not consulted.
+    pub fn defines_message(&self, lang: &LanguageIdentifier, id: &str) -> bool {
+        let overlays = self.overlays();
+        std::iter::once(&self.locale_map())
+            .chain(overlays.iter())
+            .filter_map(|layer| layer.get(lang))
+            .any(|locale| locale.has_message(id))
+    }
+
+    /// Queries for multiple messages in a specific language at once.
+    ///
+    /// This behaves exactly like calling `Locales::query` for each query, except that the locale
+    /// and its fallback are looked up only once. The `on_error` handler is invoked once per failed
+    /// query, in the order of the queries. The result at each index corresponds to the query at the
+    /// same index.
+    #[track_caller]
+    pub fn query_batch(
+        &self,
+        lang: &LanguageIdentifier,
+        queries: &[Query],
+    ) -> Vec<Result<Message, QueryError>> {
+        let ((locales, fallback_lang), overlays) = (self.snapshot(), self.overlays());
+        let locale = locales.get(lang).map(Arc::as_ref);
+        let fallback_locale = locales.get(&fallback_lang).map(Arc::as_ref);
+        let results = queries
+            .iter()
+            .map(|query| {
+                self.record_usage(lang, split_attr_id(&query.id).0);
+                let variant = self.variant_query(&locales, &overlays, lang, query);
+                let query = variant.as_ref().unwrap_or(query);
+                let sanitized = self.sanitized_query(query);
+                let query = sanitized.as_ref().unwrap_or(query);
+                self.resolve(&overlays, lang, locale, fallback_locale, query)
+            })
+            .collect::<Vec<_>>();
+
+        // inspect the errors of each failed query if on_error exists
+        if let Some(on_error) = &self.on_error {
+            for errs in results.iter().filter_map(|result| result.as_ref().err()) {
+                on_error(errs);
+            }
+        }
+        results
+    }
+
+    /// Resolves a query in `locale`, falling back to `fallback_locale` as configured by the query.
+    ///
+    /// The overlays take precedence over both, on a per-message basis.
+    #[track_caller]
+    fn resolve(
+        &self,
+        overlays: &[Arc<LocaleMap>],
+        lang: &LanguageIdentifier,
+        locale: Option<&Locale>,
+        fallback_locale: Option<&Locale>,
+        query: &Query,
+    ) -> Result<Message, QueryError> {
+        let observer = self
+            .observer()
+            .map(|observer| (observer, std::time::Instant::now()));
+        let (resolving, fallback_attempted) =
+            self.select_locale(overlays, lang, locale, fallback_locale, query);
+        let mut query_result = match self.strict_error(lang, query, fallback_attempted) {
+            Some(err) => Err(err),
+            None => resolving.query(query).map_err(|errors| QueryError {
+                lang: lang.clone(),
+                message_id: query.id.to_string(),
+                fallback_attempted,
+                errors,
+            }),
         };
 
-        // inspect the errors if on_error exists
-        if let (Some(on_error), Err(errs)) = (&self.on_error, &query_result) {
-            on_error(errs);
+        // overlay the attributes missing in the requested language
+        if let (true, false, Ok(msg), Some(fallback_locale)) = (
+            query.with_fallback,
+            self.is_strict(),
+            &mut query_result,
+            fallback_locale,
+        ) {
+            fallback_locale.overlay_attrs(query, msg);
         }
+        Self::observe_query(observer, lang, query, fallback_attempted, &query_result);
+        self.report_misses(lang, query, fallback_attempted, &mut query_result);
         query_result
     }
 
-    /// If an `on_error` handler is configured, this method invokes it with the provided slice of `FluentError`s.
-    pub fn call_on_error(&self, errors: &[FluentError]) {
-        if let Some(on_error) = self.on_error {
+    /// Enables or disables strict mode, e.g. in tests, so that untranslated messages cannot
+    /// silently slip through the fallback language. Disabled by default.
+    ///
+    /// In strict mode, a query that would be resolved in the fallback language, because the
+    /// requested language is not loaded or does not define the message, fails instead, with an
+    /// unknown message error reported for the requested language. No attributes are overlaid from
+    /// the fallback language either. Querying the fallback language itself is unaffected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, Query};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello"), ("hr-HR", "bye = Bok")])
+    ///     .unwrap();
+    /// let query = Query::new("hello").with_fallback(true);
+    /// locales.set_strict(true);
+    /// assert!(locales.query(&langid!("hr-HR"), &query).is_err());
+    /// ```
+    pub fn set_strict(&self, strict: bool) {
+        self.strict.store(strict, Ordering::Relaxed);
+    }
+
+    /// Whether strict mode is enabled, see `Locales::set_strict`.
+    pub fn is_strict(&self) -> bool {
+        self.strict.load(Ordering::Relaxed)
+    }
+
+    /// Builds the error of a query that strict mode rejects, see `Locales::set_strict`.
+    fn strict_error(
+        &self,
+        lang: &LanguageIdentifier,
+        query: &Query,
+        fallback_attempted: bool,
+    ) -> Option<QueryError> {
+        if !fallback_attempted || !self.is_strict() {
+            return None;
+        }
+
+        let (id, attr) = split_attr_id(&query.id);
+        let missing =
+            FluentError::ResolverError(ResolverError::Reference(ReferenceKind::Message {
+                id: id.to_string(),
+                attribute: attr.map(ToString::to_string),
+            }));
+        Some(QueryError {
+            lang: lang.clone(),
+            message_id: query.id.to_string(),
+            fallback_attempted,
+            errors: QueryErrors::from(vec![missing]),
+        })
+    }
+
+    /// Selects the locale that formats a query, see `Locales::resolve`, along with whether it is
+    /// the one of the fallback language.
+    fn select_locale<'s>(
+        &'s self,
+        overlays: &'s [Arc<LocaleMap>],
+        lang: &LanguageIdentifier,
+        locale: Option<&'s Locale>,
+        fallback_locale: Option<&'s Locale>,
+        query: &Query,
+    ) -> (&'s Locale, bool) {
+        let overlaid = |lang: &LanguageIdentifier| {
+            overlays
+                .iter()
+                .rev()
+                .filter_map(|overlay| overlay.get(lang).map(Arc::as_ref))
+                .find(|locale| locale.defines(&query.id))
+        };
+        let locale = overlaid(lang)
+            .or_else(|| locale.filter(|locale| !query.with_fallback || locale.defines(&query.id)));
+        match locale {
+            Some(locale) => (locale, false),
+            None => (
+                overlaid(&self.fallback_lang())
+                    .or(fallback_locale)
+                    .expect("a fallback language should *always* exist and be present as a locale"),
+                true,
+            ),
+        }
+    }
+
+    /// Queries for the value of a message like `Locales::query`, without formatting its attributes.
+    ///
+    /// Since the attributes are skipped, so are their errors, and no attribute of the fallback
+    /// language is overlaid. The errors of the value are reported through `on_error` as usual.
+    #[track_caller]
+    pub fn try_query_value(
+        &self,
+        lang: &LanguageIdentifier,
+        query: &Query,
+    ) -> Result<String, QueryError> {
+        let ((locales, fallback_lang), overlays) = (self.snapshot(), self.overlays());
+        self.resolve_value(&locales, &fallback_lang, &overlays, lang, query)
+    }
+
+    /// Queries for the values of multiple messages in a specific language at once, returning the
+    /// message ID of any that fails, as with `Locales::query_value`.
+    ///
+    /// Like `Locales::query_batch`, the locale and its fallback are looked up only once, and the
+    /// value at each index corresponds to the ID at the same index. The `on_error` handler is
+    /// invoked once per failed message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "save = Save\ncancel = Cancel")])
+    ///     .unwrap();
+    /// let values = locales.query_values(&langid!("en-US"), &["save", "cancel", "missing"]);
+    /// assert_eq!(values, ["Save", "Cancel", "missing"]);
+    /// ```
+    #[track_caller]
+    pub fn query_values(&self, lang: &LanguageIdentifier, ids: &[&str]) -> Vec<String> {
+        let ((locales, fallback_lang), overlays) = (self.snapshot(), self.overlays());
+        ids.iter()
+            .map(|id| {
+                self.resolve_value(&locales, &fallback_lang, &overlays, lang, &Query::new(*id))
+                    .unwrap_or_else(|_| id.to_string())
+            })
+            .collect()
+    }
+
+    /// Resolves the value of a query against a snapshot of the locales, see
+    /// `Locales::try_query_value`.
+    #[track_caller]
+    fn resolve_value(
+        &self,
+        locales: &LocaleMap,
+        fallback_lang: &LanguageIdentifier,
+        overlays: &[Arc<LocaleMap>],
+        lang: &LanguageIdentifier,
+        query: &Query,
+    ) -> Result<String, QueryError> {
+        self.record_usage(lang, split_attr_id(&query.id).0);
+        let variant = self.variant_query(locales, overlays, lang, query);
+        let query = variant.as_ref().unwrap_or(query);
+        let sanitized = self.sanitized_query(query);
+        let query = sanitized.as_ref().unwrap_or(query);
+        let (locale, fallback_attempted) = self.select_locale(
+            overlays,
+            lang,
+            locales.get(lang).map(Arc::as_ref),
+            locales.get(fallback_lang).map(Arc::as_ref),
+            query,
+        );
+        let result = match self.strict_error(lang, query, fallback_attempted) {
+            Some(err) => Err(err),
+            None => locale
+                .format_value(&query.id, Some(&query.args))
+                .map(Cow::into_owned)
+                .map_err(|errs| QueryError {
+                    lang: lang.clone(),
+                    message_id: query.id.to_string(),
+                    fallback_attempted,
+                    errors: QueryErrors::from(errs),
+                }),
+        };
+
+        if let (Some(on_error), Err(errs)) = (&self.on_error, &result) {
+            on_error(errs);
+        }
+        result
+    }
+
+    /// Queries for the value of a message like `Locales::try_query_value`, returning the message
+    /// ID on any error.
+    #[track_caller]
+    pub fn query_value(&self, lang: &LanguageIdentifier, query: &Query) -> String {
+        self.try_query_value(lang, query)
+            .unwrap_or_else(|_| query.id.to_string())
+    }
+
+    /// Builds the message `tr!` substitutes if querying the message `id` in the language `lang` fails.
+    ///
+    /// Its value is the ID, unless the message is defined without a value, in which case it is the
+    /// placeholder configured with `LocalesBuilder::missing_value`. The ID is used under
+    /// `MissingValuePolicy::Error` as well, since `tr!` always produces a message.
+    pub fn placeholder_message(&self, lang: &LanguageIdentifier, id: &str) -> Message {
+        let (locales, overlays) = (self.locale_map(), self.overlays());
+        let query = Query::new(id);
+        let has_no_value = self
+            .resolving_locale(&locales, &overlays, lang, &query)
+            .and_then(|locale| locale.bundle.get_message(id))
+            .is_some_and(|msg| msg.value().is_none());
+        let value = match has_no_value {
+            true => self.options.missing_value.placeholder(id),
+            false => None,
+        };
+
+        Message {
+            id: id.to_string(),
+            value: value.unwrap_or_else(|| id.to_string()),
+            attrs: Default::default(),
+            resolved_lang: Default::default(),
+        }
+    }
+
+    /// Stacks a runtime overlay on top of this collection, e.g. to hotfix translations without redeploying.
+    ///
+    /// Queries consult the overlays first, topmost first, and fall through to the lower layers for
+    /// any message the overlay does not define in the requested language. Messages are overlaid as
+    /// a whole, i.e. the attributes of an overlaid message all come from the overlay.
+    ///
+    /// The overlay's own fallback language and error handler are ignored. Readers are never blocked
+    /// for longer than it takes to clone an `Arc`.
+    pub fn push_overlay(&self, overlay: Locales) {
+        self.update_overlays(|overlays| overlays.push(Arc::new(overlay)));
+    }
+
+    /// Removes the topmost overlay, returning it.
+    pub fn pop_overlay(&self) -> Option<Arc<Locales>> {
+        let mut popped = None;
+        self.update_overlays(|overlays| popped = overlays.pop());
+        popped
+    }
+
+    /// Removes all overlays.
+    pub fn clear_overlays(&self) {
+        self.update_overlays(|overlays| overlays.clear());
+    }
+
+    /// Merges the locales of `other` into this collection, e.g. fresher translations loaded from
+    /// the network into the ones embedded with `load!`.
+    ///
+    /// Locales are replaced as a whole: a language present in `other` is served entirely by it,
+    /// so a message only this collection defines in that language falls back as if it were
+    /// missing, while languages absent from `other` are kept. Use `Locales::push_overlay` to merge
+    /// message by message instead. All the locales are swapped in at once. The fallback language,
+    /// error handler and overlays of `other` are ignored.
+    ///
+    /// Returns the languages that were replaced or added, sorted.
+    pub fn merge_locales(&self, other: Locales) -> Vec<LanguageIdentifier> {
+        let other = other.locale_map();
+        let mut merged = other.keys().cloned().collect::<Vec<_>>();
+        merged.sort();
+        self.update_locales(|locales| {
+            locales.extend(
+                other
+                    .iter()
+                    .map(|(lang, locale)| (lang.clone(), locale.clone())),
+            );
+        });
+        merged
+    }
+
+    /// Formats the value of the message `id` in a specific language, borrowing from the resource whenever possible.
+    ///
+    /// This is the allocation-free counterpart of `Locales::query` for plain strings: the message is
+    /// looked up in the overlays, then in the requested language, or in the fallback language if
+    /// the requested language is missing entirely, and formatted like `Locale::format_value`. A
+    /// value without placeables holds a snapshot of the locale that defines it rather than a copy,
+    /// so it stays valid if the locale is replaced or removed while in use.
+    #[track_caller]
+    pub fn format_value(
+        &self,
+        lang: &LanguageIdentifier,
+        id: &str,
+        args: Option<&FluentArgs>,
+    ) -> Result<FormattedValue<'static>, Vec<FluentError>> {
+        let ((locales, fallback_lang), overlays) = (self.snapshot(), self.overlays());
+        let overlaid = overlays
+            .iter()
+            .rev()
+            .filter_map(|overlay| overlay.get(lang))
+            .find(|locale| locale.bundle.has_message(id));
+        let locale = overlaid
+            .or_else(|| locales.get(lang))
+            .or_else(|| locales.get(&fallback_lang))
+            .expect("a fallback language should *always* exist and be present as a locale");
+        let result = locale
+            .format_value(id, args)
+            .map(|value| FormattedValue::from_locale(locale, value));
+        self.record_usage(lang, split_attr_id(id).0);
+
+        if let (Some(on_error), Err(errs)) = (&self.on_error, &result) {
+            on_error(&QueryError {
+                lang: lang.clone(),
+                message_id: id.to_string(),
+                fallback_attempted: overlaid.is_none() && !locales.contains_key(lang),
+                errors: QueryErrors::from(errs.clone()),
+            });
+        }
+        result
+    }
+
+    /// Formats the value of the message `id` like `Locales::format_value`, returning the ID itself on any error.
+    #[track_caller]
+    pub fn format_or_key<'b>(
+        &'b self,
+        lang: &LanguageIdentifier,
+        id: &'b str,
+        args: Option<&FluentArgs>,
+    ) -> FormattedValue<'b> {
+        self.format_value(lang, id, args)
+            .unwrap_or_else(|_| FormattedValue::from(id))
+    }
+
+    /// Gets the layer that defines the message `id` in the language `lang`, without applying any fallback.
+    ///
+    /// The base collection is layer `0` and every overlay is numbered in the order it was pushed,
+    /// starting at `1`.
+    pub fn layer_of(&self, lang: &LanguageIdentifier, id: &str) -> Option<usize> {
+        let (locales, overlays) = (self.locale_map(), self.overlays());
+        let layers = std::iter::once(&locales).chain(overlays.iter());
+        layers
+            .enumerate()
+            .filter(|(_, layer)| {
+                let locale = layer.get(lang);
+                locale.is_some_and(|locale| locale.bundle.has_message(id))
+            })
+            .last()
+            .map(|(idx, _)| idx)
+    }
+
+    /// Takes a snapshot of the locales of the current overlays.
+    pub(crate) fn overlays(&self) -> Vec<Arc<LocaleMap>> {
+        self.overlays
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .map(|overlay| overlay.locale_map())
+            .collect()
+    }
+
+    /// Takes a snapshot of the current locales.
+    pub(crate) fn locale_map(&self) -> Arc<LocaleMap> {
+        self.locales
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Gets a snapshot of the locales together with the fallback language, which is always
+    /// present among them.
+    pub(crate) fn snapshot(&self) -> (Arc<LocaleMap>, LanguageIdentifier) {
+        let locales = self.locales.read().unwrap_or_else(|err| err.into_inner());
+        (locales.clone(), self.fallback_lang())
+    }
+
+    /// Swaps in an updated copy of the locales.
+    fn update_locales(&self, update: impl FnOnce(&mut LocaleMap)) {
+        let mut locales = self.locales.write().unwrap_or_else(|err| err.into_inner());
+        let mut updated = LocaleMap::clone(&locales);
+        update(&mut updated);
+        *locales = Arc::new(updated);
+    }
+
+    /// Swaps in an updated copy of the overlays.
+    fn update_overlays(&self, update: impl FnOnce(&mut Vec<Arc<Locales>>)) {
+        let mut overlays = self.overlays.write().unwrap_or_else(|err| err.into_inner());
+        let mut updated = Vec::clone(&overlays);
+        update(&mut updated);
+        *overlays = Arc::new(updated);
+    }
+
+    /// If an `on_error` handler is configured, this method invokes it with the provided `QueryError`.
+    pub fn call_on_error(&self, errors: &QueryError) {
+        if let Some(on_error) = &self.on_error {
             on_error(errors);
         }
     }
 
+    /// Gets the language used as a fallback if a message or locale is missing.
+    pub fn fallback_lang(&self) -> LanguageIdentifier {
+        self.fallback_lang
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Changes the fallback language, e.g. for region-specific deployments falling back to `es`.
+    ///
+    /// Every query resolves through the new fallback from then on, while queries running
+    /// concurrently finish with the fallback they started with. The language must be loaded in
+    /// this collection, overlays aside, since every missing locale falls back to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello"), ("es", "hello = Hola")])
+    ///     .unwrap();
+    /// locales.set_fallback_lang(langid!("es")).unwrap();
+    /// assert_eq!(locales.fallback_lang(), langid!("es"));
+    /// assert!(locales.set_fallback_lang(langid!("de")).is_err());
+    /// ```
+    pub fn set_fallback_lang(&self, lang: LanguageIdentifier) -> Result<(), UnknownLocale> {
+        // the locales can't change while their lock is held, see `Locales::snapshot`
+        let locales = self.locales.read().unwrap_or_else(|err| err.into_inner());
+        if !locales.contains_key(&lang) {
+            return Err(UnknownLocale { lang });
+        }
+
+        *self
+            .fallback_lang
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = lang;
+        Ok(())
+    }
+
+    /// Gets the `Locale` registered for the given language, without applying any fallback.
+    ///
+    /// The locale stays usable even if it is replaced or removed in the meantime.
+    pub fn locale(&self, lang: &LanguageIdentifier) -> Option<Arc<Locale>> {
+        self.locale_map().get(lang).cloned()
+    }
+
+    /// Exports the FTL source of every registered locale, see `Locale::to_ftl`, keyed by its
+    /// language identifier. The overlays are not included.
+    ///
+    /// The map has the shape consumed by `Locales::from_url` and `Locales::from_ftl_map`, so that
+    /// the loaded translations can be saved and restored later.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, Query};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello")])
+    ///     .unwrap();
+    /// let restored = Locales::from_ftl_map(locales.export(), langid!("en-US"), None).unwrap();
+    /// let msg = restored.query(&langid!("en-US"), &Query::new("hello")).unwrap();
+    /// assert_eq!(msg.value, "Hello");
+    /// ```
+    pub fn export(&self) -> HashMap<String, String> {
+        self.locale_map()
+            .iter()
+            .map(|(lang, locale)| (lang.to_string(), locale.to_ftl()))
+            .collect()
+    }
+
+    /// Iterates over the registered locales, in no particular order. The overlays are not included.
+    ///
+    /// The locales are those registered when the iterator is created; adding or removing locales
+    /// in the meantime does not affect it. The locales are shared and cannot be mutated through
+    /// the `Arc`; use `Locales::add_locale` to replace one.
+    pub fn iter(&self) -> impl Iterator<Item = (LanguageIdentifier, Arc<Locale>)> {
+        let locales = self.locale_map();
+        locales
+            .iter()
+            .map(|(lang, locale)| (lang.clone(), locale.clone()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Gets the IDs of all messages loaded for the given language, see `Locale::message_ids`.
+    ///
+    /// Returns `None` if the language is not loaded. Neither the fallback language nor the
+    /// overlays are consulted.
+    pub fn message_ids(&self, lang: &LanguageIdentifier) -> Option<Vec<String>> {
+        let locale = self.locale(lang)?;
+        Some(locale.message_ids().map(str::to_string).collect())
+    }
+
     /// Gets the languages used in the locales collection.
     pub fn langs(&self) -> Vec<i18n_lang::Lang> {
-        self.locales
+        self.locale_map()
             .keys()
             .cloned()
             .map(i18n_lang::Lang::from)
@@ -148,6 +1067,153 @@ impl Locales {
     }
 }
 
+/// Parses FTL sources keyed by their language identifier into Fluent resources.
+///
+/// A language may appear multiple times, in which case all of its resources are collected in
+/// source order. Invalid language identifiers and sources that fail to parse are skipped and
+/// reported in the returned errors, so that callers can decide whether a partial result is acceptable.
+/// A source containing any junk entry counts as failing to parse.
+pub fn parse_locale_sources(
+    sources: impl IntoIterator<Item = (String, String)>,
+) -> (
+    HashMap<LanguageIdentifier, Vec<FluentResource>>,
+    Vec<ParserError>,
+) {
+    parse_sources(sources, false)
+}
+
+/// Parses FTL sources like `parse_locale_sources`, optionally keeping sources that contain junk.
+///
+/// With `allow_junk`, the junk entries are dropped and the rest of the source is kept.
+pub(crate) fn parse_sources(
+    sources: impl IntoIterator<Item = (String, String)>,
+    allow_junk: bool,
+) -> (
+    HashMap<LanguageIdentifier, Vec<FluentResource>>,
+    Vec<ParserError>,
+) {
+    let mut parser_errors: Vec<ParserError> = Vec::default();
+    let mut resources: HashMap<LanguageIdentifier, Vec<FluentResource>> = HashMap::default();
+    for (langid, definition) in sources.into_iter() {
+        let langid = match langid.parse::<LanguageIdentifier>() {
+            Ok(langid) => langid,
+            Err(_) => {
+                parser_errors.push(ParserError::InvalidLangid { langid });
+                continue;
+            }
+        };
+        let (resource, errors) = match FluentResource::try_new(definition) {
+            Ok(resource) => (resource, Vec::default()),
+            Err((resource, errors)) => (resource, errors),
+        };
+        let junk = junk_entries(resource.source(), resource.entries());
+        if !allow_junk && (!errors.is_empty() || !junk.is_empty()) {
+            parser_errors.push(ParserError::ParserError {
+                langid,
+                errors,
+                junk,
+            });
+            continue;
+        }
+        resources.entry(langid).or_default().push(resource);
+    }
+
+    (resources, parser_errors)
+}
+
+/// Checks whether `key` is one of `keys` in a const context, for the key validation of `tr!`.
+#[doc(hidden)]
+pub const fn __contains_key(keys: &[&str], key: &str) -> bool {
+    let mut idx = 0;
+    while idx < keys.len() {
+        let candidate = keys[idx].as_bytes();
+        let key = key.as_bytes();
+        if candidate.len() == key.len() {
+            let mut pos = 0;
+            while pos < key.len() && candidate[pos] == key[pos] {
+                pos += 1;
+            }
+            if pos == key.len() {
+                return true;
+            }
+        }
+        idx += 1;
+    }
+
+    false
+}
+
+/// A custom function callable from Fluent patterns.
+pub type FluentFunction =
+    Arc<dyn for<'a> Fn(&[FluentValue<'a>], &FluentArgs) -> FluentValue<'a> + Send + Sync>;
+
+/// What a message that only has attributes, such as `login-btn = .title = Log in`, formats to
+/// when its value is queried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingValuePolicy {
+    /// Formats to the ID in angle brackets, e.g. `<login-btn>`.
+    #[default]
+    AngleBrackets,
+    /// Formats to an empty string.
+    Empty,
+    /// Formats to the ID, e.g. `login-btn`.
+    Id,
+    /// Rejects the query with `ResolverError::NoValue`.
+    Error,
+}
+
+impl MissingValuePolicy {
+    /// Gets the placeholder the message `id` formats to, or `None` under `MissingValuePolicy::Error`.
+    pub fn placeholder(self, id: &str) -> Option<String> {
+        match self {
+            Self::AngleBrackets => Some(format!("<{id}>")),
+            Self::Empty => Some(String::new()),
+            Self::Id => Some(id.to_string()),
+            Self::Error => None,
+        }
+    }
+}
+
+impl std::str::FromStr for MissingValuePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "angle_brackets" => Ok(Self::AngleBrackets),
+            "empty" => Ok(Self::Empty),
+            "id" => Ok(Self::Id),
+            "error" => Ok(Self::Error),
+            _ => Err(format!(
+                "unknown missing value policy '{s}', expected 'angle_brackets', 'empty', 'id' or 'error'"
+            )),
+        }
+    }
+}
+
+/// Configures how the bundle of a `Locale` formats messages.
+#[derive(Clone)]
+pub struct LocaleOptions {
+    /// Whether placeables are wrapped in Unicode bidi isolation characters.
+    pub use_isolating: bool,
+    /// Custom functions callable from Fluent patterns, keyed by their name.
+    pub functions: Vec<(String, FluentFunction)>,
+    /// How entries defined by more than one resource are resolved.
+    pub resource_conflict: ResourceConflict,
+    /// What a message without a value formats to.
+    pub missing_value: MissingValuePolicy,
+}
+
+impl Default for LocaleOptions {
+    fn default() -> Self {
+        Self {
+            use_isolating: true,
+            functions: Vec::default(),
+            resource_conflict: ResourceConflict::default(),
+            missing_value: MissingValuePolicy::default(),
+        }
+    }
+}
+
 /// Manages Fluent localization resources for a specific locale.
 ///
 /// A `Locale` holds a collection of `FluentResource` objects, which contain the
@@ -158,24 +1224,220 @@ pub struct Locale {
     /// The underlying `FluentBundle` that manages the collection of resources
     /// and handles the formatting of messages.
     bundle: Arc<FluentBundle<Arc<FluentResource>>>,
+    /// The resources making up the bundle, retained for source-level access.
+    resources: Vec<Arc<FluentResource>>,
+    /// How many of the last `resources` are shared by every locale, whose entries the others
+    /// override, see `Locales::add_shared_locale_with_common`.
+    common: usize,
+    /// The variant names of every message with variants, see `Query::with_variant`.
+    variants: HashMap<String, Vec<String>>,
+    /// How entries defined by more than one resource were resolved.
+    resource_conflict: ResourceConflict,
+    /// What a message without a value formats to.
+    missing_value: MissingValuePolicy,
 }
 
 impl Locale {
     /// Creates a new `Locale` for a given language and its resources.
     ///
-    /// # Arguments
-    /// * `lang`: The `LanguageIdentifier` for this locale.
-    /// * `resources`: A vector of `FluentResource`s containing the translation data.
-    pub fn new(lang: LanguageIdentifier, resources: Vec<FluentResource>) -> Self {
-        let mut bundle = FluentBundle::new_concurrent(vec![lang.clone()]);
-        for resource in resources.into_iter() {
-            bundle
-                .add_resource(Arc::new(resource))
-                .expect("resource should never be overriding another; consider this a bug if it happens and open an issue at https://github.com/Instant-Reactive-Systems/i18n/issues");
+    /// # Arguments
+    /// * `lang`: The `LanguageIdentifier` for this locale.
+    /// * `resources`: A vector of `FluentResource`s containing the translation data.
+    /// * `conflict`: How entries defined by more than one resource are resolved.
+    ///
+    /// # Errors
+    /// Returns a `ResourceConflictError` if the resources define the same entry under `ResourceConflict::Error`.
+    pub fn new(
+        lang: LanguageIdentifier,
+        resources: Vec<FluentResource>,
+        conflict: ResourceConflict,
+    ) -> Result<Self, ResourceConflictError> {
+        let options = LocaleOptions {
+            resource_conflict: conflict,
+            ..Default::default()
+        };
+        Self::with_options(lang, resources, &options)
+    }
+
+    /// Creates a new `Locale` for a given language and its resources, configured with the given options.
+    ///
+    /// # Errors
+    /// Returns a `ResourceConflictError` if the resources define the same entry under `ResourceConflict::Error`.
+    pub fn with_options(
+        lang: LanguageIdentifier,
+        resources: Vec<FluentResource>,
+        options: &LocaleOptions,
+    ) -> Result<Self, ResourceConflictError> {
+        let resources = resources.into_iter().map(Arc::new).collect();
+        Self::with_shared_resources(lang, resources, Vec::new(), options)
+    }
+
+    /// Creates a new `Locale` like `Locale::with_options`, from resources that may be shared with
+    /// other locales, followed by `common` resources whose entries the others override.
+    pub(crate) fn with_shared_resources(
+        lang: LanguageIdentifier,
+        mut resources: Vec<Arc<FluentResource>>,
+        common: Vec<Arc<FluentResource>>,
+        options: &LocaleOptions,
+    ) -> Result<Self, ResourceConflictError> {
+        let mut bundle = FluentBundle::new_concurrent(vec![lang.clone()]);
+        bundle.set_use_isolating(options.use_isolating);
+        #[cfg(feature = "builtins")]
+        builtins::register(&mut bundle, &lang, |id| {
+            options.functions.iter().any(|(custom, _)| custom == id)
+        });
+        for (id, func) in options.functions.iter() {
+            let func = func.clone();
+            bundle
+                .add_function(id, move |positional, named| func(positional, named))
+                .expect("function ids are unique");
+        }
+        conflict::add_resources(&mut bundle, &lang, &resources, options.resource_conflict)?;
+        conflict::add_common_resources(&mut bundle, &common);
+        let common_count = common.len();
+        resources.extend(common);
+        let bundle = Arc::new(bundle);
+        let variants = variant::collect_variants(resources.iter().flat_map(|resource| {
+            resource.entries().filter_map(|entry| match entry {
+                fluent_syntax::ast::Entry::Message(msg) => Some(msg.id.name),
+                _ => None,
+            })
+        }));
+
+        Ok(Self {
+            bundle,
+            resources,
+            common: common_count,
+            variants,
+            resource_conflict: options.resource_conflict,
+            missing_value: options.missing_value,
+        })
+    }
+
+    /// Gets the underlying `FluentBundle`, e.g. for custom formatting or other Fluent tooling.
+    ///
+    /// The bundle is shared with the attributes of the messages queried from this locale, so it
+    /// cannot be mutated; build a new `Locale` instead.
+    pub fn bundle(&self) -> &FluentBundle<Arc<FluentResource>> {
+        &self.bundle
+    }
+
+    /// Gets the resources of this locale, in the order they were added.
+    ///
+    /// Resources with the same source are shared by the locales of a `Locales` collection.
+    pub fn resources(&self) -> &[Arc<FluentResource>] {
+        &self.resources
+    }
+
+    /// Gets the resources of this locale that are not shared by every locale, see
+    /// `Locales::add_shared_locale_with_common`.
+    pub fn own_resources(&self) -> &[Arc<FluentResource>] {
+        &self.resources[..self.resources.len() - self.common]
+    }
+
+    /// Gets the resources shared by every locale, which come last in `Locale::resources`.
+    pub fn common_resources(&self) -> &[Arc<FluentResource>] {
+        &self.resources[self.resources.len() - self.common..]
+    }
+
+    /// Gets the language of this locale.
+    pub fn lang(&self) -> &LanguageIdentifier {
+        &self.bundle.locales[0]
+    }
+
+    /// Iterates over all messages and terms of this locale, in resource and source order.
+    ///
+    /// Comments and junk entries are skipped.
+    pub fn entries(&self) -> impl Iterator<Item = EntryInfo<'_>> {
+        self.resources
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, resource)| {
+                EntryInfo::from_resource(idx, resource.source(), resource.entries())
+            })
+    }
+
+    /// Whether this locale defines the message `id`.
+    pub fn has_message(&self, id: &str) -> bool {
+        self.bundle.has_message(id)
+    }
+
+    /// Whether this locale defines what a query for `id` resolves to, i.e. the message or, for a
+    /// dotted ID, the attribute.
+    pub(crate) fn defines(&self, id: &str) -> bool {
+        match split_attr_id(id) {
+            (id, Some(attr)) => self.has_attribute(id, attr),
+            (id, None) => self.has_message(id),
+        }
+    }
+
+    /// Whether this locale defines the message `id` with the attribute `attr`.
+    pub fn has_attribute(&self, id: &str, attr: &str) -> bool {
+        self.bundle
+            .get_message(id)
+            .is_some_and(|msg| msg.get_attribute(attr).is_some())
+    }
+
+    /// Iterates over the IDs of all messages of this locale, across all of its resources.
+    ///
+    /// The IDs are yielded in resource and source order, each only once, even if several resources
+    /// define the message. Terms are skipped.
+    pub fn message_ids(&self) -> impl Iterator<Item = &str> {
+        let mut seen = HashSet::new();
+        self.entries()
+            .filter(|entry| !entry.is_term)
+            .map(|entry| entry.id)
+            .filter(move |id| seen.insert(*id))
+    }
+
+    /// Iterates over all messages of this locale like `Locale::message_ids`, along with their attributes.
+    ///
+    /// If several resources define a message, the attributes of the definition in use are listed,
+    /// see `ResourceConflict`.
+    pub fn messages(&self) -> impl Iterator<Item = MessageMeta<'_>> {
+        self.message_ids().filter_map(|id| {
+            let msg = self.bundle.get_message(id)?;
+            Some(MessageMeta {
+                id,
+                attrs: msg.attributes().map(|attr| attr.id()).collect(),
+            })
+        })
+    }
+
+    /// Returns the exact FTL snippet defining the message `id`, including its attributes.
+    ///
+    /// Terms are not considered; use `Locale::entries` to inspect them. If several resources define
+    /// the message, the snippet of the definition in use is returned, see `ResourceConflict`.
+    pub fn source_of(&self, id: &str) -> Option<&str> {
+        let mut definitions = self
+            .entries()
+            .filter(|entry| !entry.is_term && entry.id == id);
+        let definition = match self.resource_conflict {
+            ResourceConflict::Override => definitions.last(),
+            ResourceConflict::Error | ResourceConflict::KeepFirst => definitions.next(),
+        };
+        definition.map(|entry| entry.source)
+    }
+
+    /// Concatenates the sources of the resources of this locale, in order, into a single FTL source.
+    ///
+    /// Every source is terminated by a line break, so that the last entry of one resource does
+    /// not run into the first entry of the next. Entries defined by more than one resource stay
+    /// duplicated, so the source only loads back with the same `ResourceConflict` policy.
+    pub fn to_ftl(&self) -> String {
+        let mut ftl = String::with_capacity(
+            self.resources
+                .iter()
+                .map(|resource| resource.source().len() + 1)
+                .sum(),
+        );
+        for resource in self.resources.iter() {
+            ftl.push_str(resource.source());
+            if !ftl.is_empty() && !ftl.ends_with('\n') {
+                ftl.push('\n');
+            }
         }
-        let bundle = Arc::new(bundle);
-
-        Self { bundle }
+        ftl
     }
 
     /// Resolves a `Query` into a fully formatted `Message`.
@@ -183,96 +1445,184 @@ impl Locale {
     /// This method takes a `Query` which specifies a message ID and any
     /// arguments, and attempts to format it into a `Message` struct.
     /// If the message ID is not found, or if any errors occur during formatting,
-    /// an `Err` containing a vector of `FluentError`s is returned.
+    /// an `Err` containing the `QueryErrors` of the value and of each attribute is returned.
+    ///
+    /// A dotted ID such as `login-btn.aria-label` resolves to the attribute alone, formatted with
+    /// the main arguments of the query as the `Message::value`, without formatting the rest of
+    /// the message.
     #[track_caller]
-    pub fn query(&self, query: &Query) -> Result<Message, Vec<FluentError>> {
-        let mut errors = Vec::default();
+    pub fn query(&self, query: &Query) -> Result<Message, QueryErrors> {
+        if let (id, Some(attr)) = split_attr_id(&query.id) {
+            // the attribute is formatted as the value, with the main arguments
+            let value =
+                self.format_value(&query.id, Some(&query.args))
+                    .map_err(|errs| match self.bundle.has_message(id) {
+                        true => QueryErrors::for_attr(attr, errs),
+                        false => QueryErrors::from(errs),
+                    })?;
+            return Ok(Message {
+                id: query.id.to_string(),
+                value: value.into_owned(),
+                attrs: HashMap::default(),
+                resolved_lang: self.lang().clone(),
+            });
+        }
+
         let msg = match self.bundle.get_message(&query.id) {
             Some(msg) => msg,
             None => {
-                errors.push(FluentError::ResolverError(ResolverError::Reference(
-                    ReferenceKind::Message {
+                return Err(QueryErrors::from(vec![FluentError::ResolverError(
+                    ResolverError::Reference(ReferenceKind::Message {
                         id: query.id.to_string(),
                         attribute: None,
-                    },
-                )));
-                return Err(errors);
+                    }),
+                )]));
             }
         };
 
+        let mut errors = Vec::default();
         let value = match msg.value() {
             Some(pattern) => self
                 .bundle
                 .format_pattern(pattern, Some(&query.args), &mut errors)
                 .to_string(),
-            None => format!("<{}>", query.id),
+            None => self.missing_value(&query.id, &mut errors),
         };
+        let mut query_errors = QueryErrors::from(errors);
 
         let mut attrs = HashMap::default();
         for attr in msg.attributes() {
-            let mut local_errors = Vec::default();
-            let pattern = attr.value();
-            let attr_cache = match query.attr_args.get(attr.id()) {
-                Some(args) => {
-                    let value = self
-                        .bundle
-                        .format_pattern(pattern, Some(args), &mut local_errors);
-
-                    AttrCache {
-                        entry_id: query.id.to_string(),
-                        attr_id: attr.id().to_string(),
-                        value: Some(value.to_string()),
-                        bundle: self.bundle.clone(),
-                    }
-                }
-                None => {
-                    let mut even_more_local_errors = Vec::default();
-                    let value =
-                        self.bundle
-                            .format_pattern(pattern, None, &mut even_more_local_errors);
-
-                    let value = if !even_more_local_errors.is_empty() {
-                        let only_missing_attr_args = even_more_local_errors.iter().all(|err| {
-                            matches!(
-                                err,
-                                FluentError::ResolverError(ResolverError::Reference(
-                                    ReferenceKind::Variable { .. }
-                                ))
-                            )
-                        });
-
-                        // only consider errors other than a missing placeable as an actual error
-                        if !only_missing_attr_args {
-                            local_errors.extend(even_more_local_errors.into_iter());
-                        }
-
-                        None
-                    } else {
-                        Some(value.to_string())
-                    };
-
-                    AttrCache {
-                        entry_id: query.id.to_string(),
-                        attr_id: attr.id().to_string(),
-                        value,
-                        bundle: self.bundle.clone(),
-                    }
-                }
-            };
-
-            attrs.insert(attr.id().to_string(), attr_cache);
+            let attr_id = attr.id().to_string();
+            let mut errors = Vec::default();
+            attrs.insert(
+                attr_id.clone(),
+                self.attr_cache(query, attr, false, &mut errors),
+            );
+            query_errors.extend_attr(attr_id, errors);
         }
 
-        if !errors.is_empty() {
-            return Err(errors);
+        if !query_errors.is_empty() {
+            return Err(query_errors);
         }
 
         Ok(Message {
             id: query.id.to_string(),
             value: value.to_string(),
             attrs,
+            resolved_lang: self.lang().clone(),
+        })
+    }
+
+    /// Formats the value of the message `id`, borrowing from the resource whenever possible.
+    ///
+    /// A message without placeables is returned as a borrow of its source, without allocating.
+    /// Unlike `Locale::query`, the attributes of the message are not formatted. A dotted ID such
+    /// as `login-btn.aria-label` formats the attribute instead of the value.
+    pub fn format_value<'b>(
+        &'b self,
+        id: &str,
+        args: Option<&FluentArgs>,
+    ) -> Result<Cow<'b, str>, Vec<FluentError>> {
+        let bundle: &'b FluentBundle<Arc<FluentResource>> = &self.bundle;
+        let (msg_id, attr) = split_attr_id(id);
+        let missing = |attribute: Option<&str>| {
+            vec![FluentError::ResolverError(ResolverError::Reference(
+                ReferenceKind::Message {
+                    id: msg_id.to_string(),
+                    attribute: attribute.map(str::to_string),
+                },
+            ))]
+        };
+        let Some(msg) = bundle.get_message(msg_id) else {
+            return Err(missing(None));
+        };
+
+        let mut errors = Vec::default();
+        let value = match (attr, msg.value()) {
+            (Some(attr), _) => match msg.get_attribute(attr) {
+                Some(attr) => bundle.format_pattern(attr.value(), args, &mut errors),
+                None => return Err(missing(Some(attr))),
+            },
+            (None, Some(pattern)) => bundle.format_pattern(pattern, args, &mut errors),
+            (None, None) => Cow::Owned(self.missing_value(id, &mut errors)),
+        };
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(value)
+    }
+
+    /// Formats the value of the message `id`, which has none, as configured by `MissingValuePolicy`.
+    fn missing_value(&self, id: &str, errors: &mut Vec<FluentError>) -> String {
+        self.missing_value.placeholder(id).unwrap_or_else(|| {
+            errors.push(FluentError::ResolverError(ResolverError::NoValue(
+                id.to_string(),
+            )));
+            String::new()
         })
     }
+
+    /// Adds this locale's version of any attribute of the queried message that is missing from `msg`.
+    ///
+    /// The added attributes are marked as originating from a fallback.
+    fn overlay_attrs(&self, query: &Query, msg: &mut Message) {
+        let Some(fallback_msg) = self.bundle.get_message(&query.id) else {
+            return;
+        };
+
+        for attr in fallback_msg.attributes() {
+            if !msg.attrs.contains_key(attr.id()) {
+                let attr_id = attr.id().to_string();
+                // the requested language is served regardless of the errors of the fallback
+                let attr_cache = self.attr_cache(query, attr, true, &mut Vec::default());
+                msg.attrs.insert(attr_id, attr_cache);
+            }
+        }
+    }
+
+    /// Formats an attribute of the queried message into an `AttrCache`.
+    ///
+    /// If formatting the attribute with the arguments held by the query fails only because of missing
+    /// arguments, the value is left to be lazily resolved with the remaining arguments. Any other
+    /// error is pushed to `attr_errors`.
+    fn attr_cache(
+        &self,
+        query: &Query,
+        attr: fluent_bundle::FluentAttribute<'_>,
+        is_fallback: bool,
+        attr_errors: &mut Vec<FluentError>,
+    ) -> AttrCache {
+        let mut errors = Vec::default();
+        let propagated = query.propagated_attr_args(attr.id());
+        let args = propagated
+            .as_ref()
+            .or_else(|| query.attr_args.get(attr.id()));
+        let value = self.bundle.format_pattern(attr.value(), args, &mut errors);
+        // a missing placeable is not an error, as the argument can still be supplied lazily
+        let is_missing_arg = |err: &FluentError| {
+            matches!(
+                err,
+                FluentError::ResolverError(ResolverError::Reference(
+                    ReferenceKind::Variable { .. }
+                ))
+            )
+        };
+        let only_missing_attr_args = errors.iter().all(is_missing_arg);
+        let value = (errors.is_empty() || (args.is_some() && !only_missing_attr_args))
+            .then(|| value.to_string());
+        attr_errors.extend(errors.into_iter().filter(|err| !is_missing_arg(err)));
+
+        AttrCache {
+            entry_id: query.id.to_string(),
+            attr_id: attr.id().to_string(),
+            value,
+            bundle: self.bundle.clone(),
+            is_fallback,
+            args: args.map(owned_args).unwrap_or_default(),
+            miss_reporter: None,
+        }
+    }
 }
 
 /// Represents a localized message with its ID, value, and attributes.
@@ -284,6 +1634,29 @@ pub struct Message {
     pub value: String,
     /// A map of associated attributes for the message, such as `aria-label`.
     pub attrs: HashMap<String, AttrCache>,
+    /// The language whose locale resolved the value, which differs from the requested language if
+    /// the query fell back, e.g. to mark machine-fallback strings in the UI.
+    ///
+    /// It is the undetermined language `und` if the value was not resolved from any locale, such as
+    /// an inline default or the message ID substituted by `tr!` on error.
+    pub resolved_lang: LanguageIdentifier,
+}
+
+impl Message {
+    /// Returns the value of the message with the given casing applied, respecting the rules of `lang`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Message};
+    /// use i18n_lang::Case;
+    ///
+    /// let msg = Message { value: "istanbul".to_string(), ..Default::default() };
+    /// assert_eq!(msg.value_cased(Case::Upper, &langid!("tr-TR")), "İSTANBUL");
+    /// ```
+    pub fn value_cased(&self, case: i18n_lang::Case, lang: &LanguageIdentifier) -> String {
+        i18n_lang::to_case(lang.language.as_str(), &self.value, case)
+    }
 }
 
 /// Represents a request to format a localized message, including its ID and arguments.
@@ -297,21 +1670,62 @@ pub struct Query<'a> {
     attr_args: HashMap<Cow<'a, str>, FluentArgs<'a>>,
     /// A flag to indicate whether to use the fallback language if the message is not found.
     with_fallback: bool,
+    /// The select arguments to validate before formatting, see `Query::with_select_arg`.
+    select_args: Vec<SelectArg>,
+    /// The selector picking a variant of the message, see `Query::with_variant`.
+    variant_selector: Option<VariantSelector<'a>>,
+    /// Whether `Locales::try_query` rejects the query if any argument was set more than once.
+    strict_args: bool,
+    /// The arguments that were set more than once, in the order they were overwritten.
+    duplicate_args: Vec<DuplicateArg>,
+    /// The arguments of the main message value exempt from sanitization, see `Query::with_raw_arg`.
+    raw_args: Vec<String>,
+    /// Whether the main arguments are passed to the attributes as well, see `Query::propagate_args`.
+    propagate_args: bool,
 }
 
 impl<'a> Query<'a> {
     /// Creates a new `Query` for a given message ID.
+    ///
+    /// A dotted ID such as `login-btn.aria-label` queries the attribute alone, see `Locale::query`.
     pub fn new(id: impl Into<Cow<'a, str>>) -> Self {
         Self {
             id: id.into(),
             args: Default::default(),
             attr_args: Default::default(),
             with_fallback: false,
+            select_args: Default::default(),
+            variant_selector: None,
+            strict_args: false,
+            duplicate_args: Vec::default(),
+            raw_args: Vec::default(),
+            propagate_args: false,
         }
     }
 
+    /// Creates a new `Query` for the attribute `attr` of the message `id`, resolving to the
+    /// formatted attribute as the `Message::value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, Query};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "login-btn = Login\n    .aria-label = Log in")])
+    ///     .unwrap();
+    /// let query = Query::attr("login-btn", "aria-label");
+    /// assert_eq!(locales.query(&langid!("en-US"), &query).unwrap().value, "Log in");
+    /// ```
+    pub fn attr(id: &str, attr: &str) -> Self {
+        Self::new(format!("{id}.{attr}"))
+    }
+
     /// Adds an argument for the main message value.
     ///
+    /// Setting an argument that is already set replaces its value, i.e. the last write wins.
+    /// The replaced arguments are listed by `Query::duplicate_args`, see `Query::strict_args`.
+    ///
     /// # Example
     ///
     /// ```
@@ -324,12 +1738,45 @@ impl<'a> Query<'a> {
         I: Into<Cow<'a, str>>,
         V: Into<FluentValue<'a>>,
     {
-        self.args.set(id.into(), value.into());
+        let id = id.into();
+        self.raw_args.retain(|raw| *raw != id);
+        set_arg(
+            &mut self.args,
+            None,
+            id,
+            value.into(),
+            &mut self.duplicate_args,
+        );
         self
     }
 
+    /// Adds an argument for the main message value that is exempt from the sanitizer set with
+    /// `Locales::set_arg_sanitizer`, e.g. for trusted markup or preformatted text.
+    ///
+    /// Like with `Query::with_arg`, the last write wins, including whether the argument is raw.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::Query;
+    ///
+    /// let query = Query::new("log-entry").with_raw_arg("details", "line one\nline two");
+    /// ```
+    pub fn with_raw_arg<I, V>(self, id: I, value: V) -> Self
+    where
+        I: Into<Cow<'a, str>>,
+        V: Into<FluentValue<'a>>,
+    {
+        let id = id.into();
+        let mut query = self.with_arg(id.clone(), value);
+        query.raw_args.push(id.into_owned());
+        query
+    }
+
     /// Adds an argument for a specific attribute of the message.
     ///
+    /// Like with `Query::with_arg`, the last write wins.
+    ///
     /// # Example
     ///
     /// ```
@@ -343,10 +1790,183 @@ impl<'a> Query<'a> {
         I: Into<Cow<'a, str>>,
         V: Into<FluentValue<'a>>,
     {
-        self.attr_args
-            .entry(attr.into())
-            .or_default()
-            .set(id.into(), value.into());
+        let attr = attr.into();
+        let args = self.attr_args.entry(attr.clone()).or_default();
+        set_arg(
+            args,
+            Some(&attr),
+            id.into(),
+            value.into(),
+            &mut self.duplicate_args,
+        );
+        self
+    }
+
+    /// Adds arguments for the main message value, e.g. from a map assembled elsewhere.
+    ///
+    /// The arguments are added in iteration order, as with `Query::with_arg`, so a later
+    /// duplicate wins over an earlier one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::Query;
+    /// use std::collections::HashMap;
+    ///
+    /// let args = HashMap::from([("userName".to_string(), "Alex".to_string())]);
+    /// let query = Query::new("hello-user").with_args(args);
+    /// ```
+    pub fn with_args<I, K, V>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: Into<FluentValue<'a>>,
+    {
+        self.extend(args);
+        self
+    }
+
+    /// Adds arguments for a specific attribute of the message, in iteration order as with
+    /// `Query::with_attr_arg`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::Query;
+    ///
+    /// let query = Query::new("user-tooltip").with_attr_args("aria-label", [("userName", "Alex")]);
+    /// ```
+    pub fn with_attr_args<A, I, K, V>(self, attr: A, args: I) -> Self
+    where
+        A: Into<Cow<'a, str>>,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: Into<FluentValue<'a>>,
+    {
+        let attr = attr.into();
+        args.into_iter().fold(self, |query, (id, value)| {
+            query.with_attr_arg(attr.clone(), id, value)
+        })
+    }
+
+    /// Adds an argument for the main message value that selects a variant, such as `$gender`.
+    ///
+    /// `Locales::try_query` rejects the query with `InvalidSelectValue` unless `value` is one of
+    /// `allowed` or the message's select expression on `key` has a variant for it, which catches
+    /// values that would otherwise silently fall into the default variant. `Locales::query` formats
+    /// the argument like any other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::Query;
+    ///
+    /// let query = Query::new("user-invited").with_select_arg("gender", "female", &["male", "female", "other"]);
+    /// ```
+    pub fn with_select_arg<I>(mut self, key: I, value: &str, allowed: &[&str]) -> Self
+    where
+        I: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        self.select_args.push(SelectArg {
+            key: key.to_string(),
+            value: value.to_string(),
+            allowed: allowed.iter().map(ToString::to_string).collect(),
+        });
+        // sanitizing the value would keep it from matching its variant
+        self.raw_args.push(key.to_string());
+        set_arg(
+            &mut self.args,
+            None,
+            key,
+            FluentValue::from(value.to_string()),
+            &mut self.duplicate_args,
+        );
+        self
+    }
+
+    /// Makes `Locales::try_query` reject the query with `TryQueryError::DuplicateArg` if any of its
+    /// arguments was set more than once, e.g. through a copy-pasted `with_arg`.
+    ///
+    /// Disabled by default, in which case the last write silently wins.
+    pub fn strict_args(mut self, strict: bool) -> Self {
+        self.strict_args = strict;
+        self
+    }
+
+    /// Gets the arguments that were set more than once, in the order they were overwritten.
+    pub fn duplicate_args(&self) -> &[DuplicateArg] {
+        &self.duplicate_args
+    }
+
+    /// Passes the main arguments to every attribute of the message as well, so that an attribute
+    /// using the same variable as the value does not need it repeated with `Query::with_attr_arg`.
+    ///
+    /// The arguments set with `Query::with_attr_arg` override the main ones of the same name.
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, Query};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "user = { $name }\n    .title = Profile of { $name }")])
+    ///     .unwrap();
+    /// let query = Query::new("user").with_arg("name", "Alex").propagate_args(true);
+    /// let mut msg = locales.query(&langid!("en-US"), &query).unwrap();
+    /// let title = msg.attrs.get_mut("title").unwrap().query(None, false).unwrap();
+    /// assert_eq!(title, "Profile of \u{2068}Alex\u{2069}");
+    /// ```
+    pub fn propagate_args(mut self, propagate: bool) -> Self {
+        self.propagate_args = propagate;
+        self
+    }
+
+    /// Merges the main arguments with those of the attribute `attr`, if they are propagated.
+    fn propagated_attr_args(&self, attr: &str) -> Option<FluentArgs<'_>> {
+        if !self.propagate_args || self.args.iter().next().is_none() {
+            return None;
+        }
+
+        let mut args = variant::borrow_args(&self.args);
+        for (key, value) in self
+            .attr_args
+            .get(attr)
+            .into_iter()
+            .flat_map(FluentArgs::iter)
+        {
+            args.set(key, value.clone());
+        }
+        Some(args)
+    }
+
+    /// Lets `selector` pick a variant of the message, e.g. to A/B test its copy.
+    ///
+    /// Variants are messages named after the queried one followed by `VARIANT_SEPARATOR` and the
+    /// variant name, such as `signup-cta__variant-b` for `signup-cta`. The selector is handed the
+    /// sorted variant names found across every language and returns the index of the one to
+    /// serve, e.g. based on a hash of the user ID. The base message is served instead when the
+    /// selector declines, or when the locale resolving the message does not define the variant.
+    ///
+    /// The ID of the served message, variant or not, is reported as `Message::id` and recorded by
+    /// usage tracking, so that analytics can be joined on it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::Query;
+    ///
+    /// let user_id: u64 = 42;
+    /// let query = Query::new("signup-cta").with_variant(move |variants| {
+    ///     Some((user_id % (variants.len() as u64 + 1)) as usize).filter(|idx| *idx < variants.len())
+    /// });
+    /// ```
+    pub fn with_variant<F>(mut self, selector: F) -> Self
+    where
+        F: Fn(&[&str]) -> Option<usize> + Send + Sync + 'a,
+    {
+        self.variant_selector = Some(VariantSelector(Arc::new(selector)));
         self
     }
 
@@ -360,6 +1980,188 @@ impl<'a> Query<'a> {
     }
 }
 
+/// A query that owns its ID and arguments, e.g. to be stored and resolved later, see
+/// `Query::into_owned`.
+pub type OwnedQuery = Query<'static>;
+
+impl Query<'_> {
+    /// Copies the ID and arguments of the query into owned data, so that it can outlive them,
+    /// e.g. to be stored and resolved later.
+    ///
+    /// The selector set with `Query::with_variant` may borrow as well, so it is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, OwnedQuery, Query};
+    ///
+    /// fn greeting(name: &str) -> OwnedQuery {
+    ///     Query::new("hello-user").with_arg("name", name).into_owned()
+    /// }
+    ///
+    /// let locales = Locales::builder()
+    ///     .use_isolating(false)
+    ///     .build_from_strs([("en-US", "hello-user = Hello, { $name }!")])
+    ///     .unwrap();
+    /// let name = String::from("Alex");
+    /// let query = greeting(&name);
+    /// drop(name);
+    /// assert_eq!(locales.query(&langid!("en-US"), &query).unwrap().value, "Hello, Alex!");
+    /// ```
+    pub fn into_owned(self) -> OwnedQuery {
+        Query {
+            id: Cow::Owned(self.id.into_owned()),
+            args: owned_args(&self.args),
+            attr_args: self
+                .attr_args
+                .iter()
+                .map(|(attr, args)| (Cow::Owned(attr.to_string()), owned_args(args)))
+                .collect(),
+            with_fallback: self.with_fallback,
+            select_args: self.select_args,
+            variant_selector: None,
+            strict_args: self.strict_args,
+            duplicate_args: self.duplicate_args,
+            raw_args: self.raw_args,
+            propagate_args: self.propagate_args,
+        }
+    }
+}
+
+impl Clone for Query<'_> {
+    fn clone(&self) -> Self {
+        Query {
+            id: self.id.clone(),
+            args: clone_args(&self.args),
+            attr_args: self
+                .attr_args
+                .iter()
+                .map(|(attr, args)| (attr.clone(), clone_args(args)))
+                .collect(),
+            with_fallback: self.with_fallback,
+            select_args: self.select_args.clone(),
+            variant_selector: self.variant_selector.clone(),
+            strict_args: self.strict_args,
+            duplicate_args: self.duplicate_args.clone(),
+            raw_args: self.raw_args.clone(),
+            propagate_args: self.propagate_args,
+        }
+    }
+}
+
+/// Adds arguments for the main message value, as with `Query::with_args`.
+impl<'a, K, V> Extend<(K, V)> for Query<'a>
+where
+    K: Into<Cow<'a, str>>,
+    V: Into<FluentValue<'a>>,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, args: I) {
+        for (id, value) in args {
+            *self = std::mem::take(self).with_arg(id, value);
+        }
+    }
+}
+
+/// Splits a dotted ID such as `login-btn.aria-label` into the message ID and the attribute.
+///
+/// Message IDs cannot contain a `.`, so anything after the first one names an attribute.
+fn split_attr_id(id: &str) -> (&str, Option<&str>) {
+    match id.split_once('.') {
+        Some((id, attr)) => (id, Some(attr)),
+        None => (id, None),
+    }
+}
+
+/// Whether `errs` only report that the message `id` itself is missing.
+fn is_missing_message(errs: &QueryError, id: &str) -> bool {
+    let (id, _) = split_attr_id(id);
+    !errs.is_empty()
+        && errs.iter().all(|err| {
+            matches!(
+                err,
+                FluentError::ResolverError(ResolverError::Reference(ReferenceKind::Message {
+                    id: missing,
+                    attribute: None,
+                })) if missing == id
+            )
+        })
+}
+
+/// Sets an argument, recording it in `duplicates` if it was already set.
+fn set_arg<'a>(
+    args: &mut FluentArgs<'a>,
+    attr: Option<&str>,
+    key: Cow<'a, str>,
+    value: FluentValue<'a>,
+    duplicates: &mut Vec<DuplicateArg>,
+) {
+    if args.iter().any(|(existing, _)| existing == key) {
+        duplicates.push(DuplicateArg {
+            attr: attr.map(ToString::to_string),
+            key: key.to_string(),
+        });
+    }
+    args.set(key, value);
+}
+
+/// The error returned by `Locales::set_fallback_lang` and `Locales::add_pseudo_locale` for a
+/// language that is not loaded.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("language {lang} is not loaded")]
+pub struct UnknownLocale {
+    /// The rejected language.
+    pub lang: LanguageIdentifier,
+}
+
+/// The error returned by `Locales::try_add_locale`.
+#[derive(Debug, thiserror::Error)]
+pub enum AddLocaleError {
+    /// The language identifier could not be parsed.
+    #[error("invalid language identifier {langid:?}: {source}")]
+    InvalidLangid {
+        /// The rejected language identifier.
+        langid: String,
+        source: unic_langid::LanguageIdentifierError,
+    },
+    /// No resources were given for the language.
+    #[error("no resources were given for {lang}")]
+    NoResources {
+        /// The language of the locale.
+        lang: LanguageIdentifier,
+    },
+    /// A locale is already loaded for the language, and overwriting it was not requested.
+    #[error("a locale is already loaded for {lang}")]
+    AlreadyLoaded {
+        /// The language of the locale.
+        lang: LanguageIdentifier,
+    },
+    /// The resources define the same entry more than once, see `ResourceConflict`.
+    #[error(transparent)]
+    ResourceConflict(#[from] ResourceConflictError),
+}
+
+/// An argument of a `Query` that was set more than once.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("argument ${key} was set more than once{}", attr.as_ref().map(|attr| format!(" for attribute {attr}")).unwrap_or_default())]
+pub struct DuplicateArg {
+    /// The attribute the argument is for, or `None` for the main message value.
+    pub attr: Option<String>,
+    /// The name of the argument.
+    pub key: String,
+}
+
+impl<'a> From<&'a str> for Query<'a> {
+    fn from(id: &'a str) -> Self {
+        Query::new(id)
+    }
+}
+
+impl From<String> for Query<'static> {
+    fn from(id: String) -> Self {
+        Query::new(id)
+    }
+}
+
 /// Provides a cache for the value of a specific attribute from a localization entry.
 ///
 /// This struct is designed for lazy evaluation. It stores the identifiers for a
@@ -377,15 +2179,22 @@ pub struct AttrCache {
     /// The underlying `FluentBundle` that manages the collection of resources
     /// and handles the formatting of messages.
     pub bundle: Arc<FluentBundle<Arc<FluentResource>>>,
+    /// Whether the attribute is missing in the requested language and was taken from the fallback language.
+    pub is_fallback: bool,
+    /// The arguments given for the attribute when the message was queried, see `AttrCache::query`.
+    pub args: FluentArgs<'static>,
+    /// The reporter notified if the attribute turns out to be missing, see `Locales::set_miss_reporter`.
+    pub miss_reporter: Option<MissReporter>,
 }
 
 impl AttrCache {
     /// Queries the cached attribute, formatting it with the given arguments.
     ///
-    /// If the attribute value is already cached, it will be returned immediately.
-    /// Otherwise, it will be formatted using the provided `args`. If `args` are
-    /// not provided, any arguments required by the attribute will be missing,
-    /// potentially resulting in a formatting error.
+    /// If the attribute value is already cached and `replace` is not set, it will be returned immediately,
+    /// regardless of `args`. Otherwise, it will be formatted using the arguments given for the attribute
+    /// when the message was queried, overlaid with the provided `args`, with `args` winning on the same key.
+    /// Any arguments required by the attribute that are in neither will be missing, resulting in a
+    /// formatting error.
     ///
     /// # Errors
     /// Returns a `Vec<FluentError>` if any errors occur during formatting, such as
@@ -406,6 +2215,7 @@ impl AttrCache {
         let msg = match self.bundle.get_message(&self.entry_id) {
             Some(msg) => msg,
             None => {
+                self.report_miss();
                 errors.push(FluentError::ResolverError(ResolverError::Reference(
                     ReferenceKind::Message {
                         id: self.entry_id.to_string(),
@@ -417,6 +2227,7 @@ impl AttrCache {
         };
 
         let Some(this_attr) = msg.attributes().find(|attr| attr.id() == self.attr_id) else {
+            self.report_miss();
             errors.push(FluentError::ResolverError(ResolverError::Reference(
                 ReferenceKind::Message {
                     id: self.entry_id.to_string(),
@@ -426,8 +2237,15 @@ impl AttrCache {
             return Err(errors);
         };
 
+        let mut merged = owned_args(&self.args);
+        for (key, value) in args.into_iter().flat_map(FluentArgs::iter) {
+            merged.set(key.to_string(), value.into_owned());
+        }
+
         let pattern = this_attr.value();
-        let value = self.bundle.format_pattern(pattern, args, &mut errors);
+        let value = self
+            .bundle
+            .format_pattern(pattern, Some(&merged), &mut errors);
 
         if !errors.is_empty() {
             return Err(errors);
@@ -458,28 +2276,119 @@ impl std::fmt::Debug for AttrCache {
             .field("entry_id", &self.entry_id)
             .field("attr_id", &self.attr_id)
             .field("value", &self.value)
+            .field("is_fallback", &self.is_fallback)
+            .field("args", &self.args)
             .finish()
     }
 }
 
-#[cfg(feature = "net")]
+/// Copies arguments, owning their names.
+fn clone_args<'a>(args: &FluentArgs<'a>) -> FluentArgs<'a> {
+    // `FluentArgs::iter` shortens the lifetime of the values, unlike `FluentArgs::get`
+    args.iter()
+        .filter_map(|(key, _)| {
+            let value = args.get(key.to_string())?.clone();
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Copies the arguments into ones that own all of their values.
+fn owned_args(args: &FluentArgs) -> FluentArgs<'static> {
+    args.iter()
+        .map(|(key, value)| (key.to_string(), value.into_owned()))
+        .collect()
+}
+
+/// An error that occurred while loading localization resources at runtime.
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("could not read {path:?}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid UTF-8 in {path:?} at byte offset {offset}")]
+    InvalidUtf8 {
+        path: std::path::PathBuf,
+        offset: usize,
+    },
+    #[error("errors occurred during parsing:\n{}", {
+        _0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+    })]
+    Parser(Vec<ParserError>),
+    #[error(transparent)]
+    ResourceConflict(#[from] ResourceConflictError),
+    #[error("the fallback language {} is not loaded", .0.lang)]
+    MissingFallback(#[from] UnknownLocale),
+    #[error("both {:?} and {:?} define the locale {lang}", paths[0], paths[1])]
+    DuplicateLocale {
+        lang: String,
+        paths: [std::path::PathBuf; 2],
+    },
+}
+
+/// An error that occurred while parsing the localization resources of a language.
 #[derive(Debug, thiserror::Error)]
 pub enum ParserError {
     #[error("could not parse langid: {langid}")]
     InvalidLangid { langid: String },
     #[error("errors occurred during parsing of {}", {
-        use itertools::Itertools;
         format!(
             "{langid}:\n{}",
-            errors.iter().map(|err| format!("\t- {err:?}")).join("\n")
+            junk.iter()
+                .map(|junk| format!("\t- junk at {junk}"))
+                .chain(errors.iter().map(|err| format!("\t- {err:?}")))
+                .collect::<Vec<_>>()
+                .join("\n")
         )
     })]
     ParserError {
         langid: LanguageIdentifier,
         errors: Vec<fluent_syntax::parser::ParserError>,
+        /// The unparseable entries, with their location and source excerpt.
+        junk: Vec<JunkEntry>,
     },
 }
 
+/// An error that occurred while loading localization resources through a fetcher, see
+/// `LocalesBuilder::build_from_fetcher`.
+#[cfg(feature = "fetch")]
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError<E> {
+    /// The fetcher failed.
+    #[error("fetching the resource failed: {0}")]
+    Fetch(E),
+    #[error("errors occurred during parsing:\n{}", {
+        _0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+    })]
+    Parser(Vec<ParserError>),
+    #[error(
+        "invalid format received, expected {{'lang-id': 'fluent-definitions', ..}}; errors: {0}"
+    )]
+    InvalidFormat(serde_json::Error),
+    #[error("unsupported payload version {0}, expected 2")]
+    UnsupportedVersion(u64),
+    #[error(transparent)]
+    ResourceConflict(#[from] ResourceConflictError),
+    #[error("the fallback language {} is not loaded", .0.lang)]
+    MissingFallback(#[from] UnknownLocale),
+}
+
+#[cfg(feature = "net")]
+impl From<FetchError<NetError>> for NetError {
+    fn from(err: FetchError<NetError>) -> Self {
+        match err {
+            FetchError::Fetch(err) => err,
+            FetchError::Parser(errs) => NetError::ParserError(errs),
+            FetchError::InvalidFormat(err) => NetError::InvalidFormat(err),
+            FetchError::UnsupportedVersion(version) => NetError::UnsupportedVersion(version),
+            FetchError::ResourceConflict(err) => NetError::ResourceConflict(err),
+            FetchError::MissingFallback(err) => NetError::MissingFallback(err),
+        }
+    }
+}
+
 #[cfg(feature = "net")]
 #[derive(Debug, thiserror::Error)]
 pub enum NetError {
@@ -492,4 +2401,58 @@ pub enum NetError {
     ParserError(Vec<ParserError>),
     #[error("invalid format received from server, expected {{'lang-id': 'fluent-definitions', ..}}; errors: {0}")]
     InvalidFormat(#[from] serde_json::Error),
+    #[error("timed out after {elapsed:?}")]
+    Timeout { elapsed: std::time::Duration },
+    #[error("gave up after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<NetError>,
+    },
+    #[error("the load was cancelled")]
+    Cancelled,
+    #[error(transparent)]
+    ResourceConflict(#[from] ResourceConflictError),
+    #[error("the fallback language {} is not loaded", .0.lang)]
+    MissingFallback(#[from] UnknownLocale),
+    #[error("no remote source is set, see `Locales::set_remote_source`")]
+    NoRemoteSource,
+    #[error("invalid URL {url}: {reason}")]
+    InvalidUrl { url: String, reason: String },
+    #[error("{url} responded with status {status}{}", match body_snippet.is_empty() {
+        true => String::new(),
+        false => format!(": {body_snippet}"),
+    })]
+    HttpStatus {
+        url: String,
+        status: u16,
+        body_snippet: String,
+    },
+    #[error("{url} responded without a body")]
+    EmptyBody { url: String },
+    #[error("invalid UTF-8 in the response of {url} at byte offset {offset}")]
+    InvalidUtf8 { url: String, offset: usize },
+    #[error("unsupported payload version {0}, expected 2")]
+    UnsupportedVersion(u64),
+    #[error("unsupported content encoding '{0}', expected 'gzip' or 'deflate'")]
+    UnsupportedEncoding(String),
+    #[error("could not decompress the response of {url}: {source}")]
+    Decompress {
+        url: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("the language of the FTL source at {url} is unknown, set it or respond with a Content-Language header")]
+    MissingContentLanguage { url: String },
+    #[error("the FTL source at {url} is for {found}, expected {}", expected.as_ref().map_or("a valid language".to_string(), |lang| lang.to_string()))]
+    ContentLanguageMismatch {
+        url: String,
+        expected: Option<LanguageIdentifier>,
+        found: String,
+    },
+    #[error("{url} has no source for {lang}")]
+    MissingLocale {
+        url: String,
+        lang: LanguageIdentifier,
+    },
 }
@@ -0,0 +1,228 @@
+use crate::{split_attr_id, AttrCache, FluentError, ReferenceKind, ResolverError};
+use crate::{LanguageIdentifier, Locales, Message, Query, QueryError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A callback notified of every miss, see `Locales::set_miss_reporter`.
+pub type MissReporter = Arc<dyn Fn(&MissEvent) + Send + Sync>;
+
+/// A message or attribute that was requested but missing from the requested language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissEvent {
+    /// The requested language.
+    pub lang: LanguageIdentifier,
+    /// The ID of the message.
+    pub id: String,
+    /// The attribute, if the miss concerns an attribute of the message.
+    pub attr: Option<String>,
+    /// Whether the fallback language provided the message or attribute instead.
+    pub fallback_hit: bool,
+}
+
+/// The misses of a message or attribute in a language, as summarized by `MissCollector::summary`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissSummary {
+    /// The requested language.
+    pub lang: LanguageIdentifier,
+    /// The ID of the message.
+    pub id: String,
+    /// The attribute, if the misses concern an attribute of the message.
+    pub attr: Option<String>,
+    /// The number of times neither the requested nor the fallback language provided it.
+    pub misses: u64,
+    /// The number of times the fallback language provided it instead.
+    pub fallback_hits: u64,
+}
+
+/// The miss and fallback hit counters of a message or attribute, keyed by language, message ID
+/// and attribute.
+type MissCounts = HashMap<(LanguageIdentifier, String, Option<String>), (u64, u64)>;
+
+/// Collects misses in memory, e.g. to export a report of the missing translations.
+///
+/// See `Locales::collect_misses`.
+#[derive(Debug, Default)]
+pub struct MissCollector {
+    /// The miss and fallback hit counters recorded so far.
+    counts: Mutex<MissCounts>,
+}
+
+impl MissCollector {
+    /// Records a single miss.
+    pub fn record(&self, event: &MissEvent) {
+        let mut counts = self.counts.lock().unwrap_or_else(|err| err.into_inner());
+        let (misses, fallback_hits) = counts
+            .entry((event.lang.clone(), event.id.clone(), event.attr.clone()))
+            .or_default();
+        match event.fallback_hit {
+            true => *fallback_hits += 1,
+            false => *misses += 1,
+        }
+    }
+
+    /// Returns the recorded misses, sorted by language, message ID and attribute.
+    pub fn summary(&self) -> Vec<MissSummary> {
+        let counts = self.counts.lock().unwrap_or_else(|err| err.into_inner());
+        let mut summary = counts
+            .iter()
+            .map(|((lang, id, attr), (misses, fallback_hits))| MissSummary {
+                lang: lang.clone(),
+                id: id.clone(),
+                attr: attr.clone(),
+                misses: *misses,
+                fallback_hits: *fallback_hits,
+            })
+            .collect::<Vec<_>>();
+        summary.sort_by(|a, b| {
+            (a.lang.to_string(), &a.id, &a.attr).cmp(&(b.lang.to_string(), &b.id, &b.attr))
+        });
+
+        summary
+    }
+
+    /// Clears the recorded misses, e.g. after they have been exported.
+    pub fn reset(&self) {
+        self.counts
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+    }
+}
+
+impl Locales {
+    /// Sets a reporter notified whenever a queried message or attribute is missing from the
+    /// requested language. Replaces any previous reporter.
+    ///
+    /// Unlike `on_error`, a message resolved in the fallback language is reported as well, with
+    /// `MissEvent::fallback_hit` set, as are the attributes overlaid from the fallback language.
+    /// A message missing from both languages is reported without it. The attributes of a queried
+    /// message carry the reporter along, so that `AttrCache::query` reports a missing attribute.
+    pub fn set_miss_reporter<F>(&self, reporter: F)
+    where
+        F: Fn(&MissEvent) + Send + Sync + 'static,
+    {
+        *self
+            .miss_reporter
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(reporter));
+    }
+
+    /// Removes the reporter set with `Locales::set_miss_reporter`.
+    pub fn clear_miss_reporter(&self) {
+        *self
+            .miss_reporter
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    /// Sets a `MissCollector` as the miss reporter, returning it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, Query};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello"), ("hr-HR", "bye = Bok")])
+    ///     .unwrap();
+    /// let misses = locales.collect_misses();
+    ///
+    /// let query = Query::new("hello").with_fallback(true);
+    /// locales.query(&langid!("hr-HR"), &query).unwrap();
+    /// let summary = misses.summary();
+    /// assert_eq!((summary[0].id.as_str(), summary[0].fallback_hits), ("hello", 1));
+    /// ```
+    pub fn collect_misses(&self) -> Arc<MissCollector> {
+        let collector = Arc::new(MissCollector::default());
+        let recorder = collector.clone();
+        self.set_miss_reporter(move |event| recorder.record(event));
+        collector
+    }
+
+    /// Reports the misses of a resolved query, if a miss reporter is set.
+    pub(crate) fn report_misses(
+        &self,
+        lang: &LanguageIdentifier,
+        query: &Query,
+        fallback_attempted: bool,
+        result: &mut Result<Message, QueryError>,
+    ) {
+        let reporter = self
+            .miss_reporter
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        let Some(reporter) = reporter else {
+            return;
+        };
+
+        let (id, attr) = split_attr_id(&query.id);
+        let report = |attr: Option<&str>, fallback_hit| {
+            reporter(&MissEvent {
+                lang: lang.clone(),
+                id: id.to_string(),
+                attr: attr.map(ToString::to_string),
+                fallback_hit,
+            })
+        };
+        match result {
+            Ok(msg) => {
+                if fallback_attempted {
+                    report(attr, true);
+                }
+                if msg.attrs.is_empty() {
+                    return;
+                }
+
+                // the bundle of an attribute can be the one of the fallback language
+                let requested = lang.clone();
+                let reporter = reporter.clone();
+                let attr_reporter: MissReporter = Arc::new(move |event| {
+                    reporter(&MissEvent {
+                        lang: requested.clone(),
+                        ..event.clone()
+                    })
+                });
+                for attr_cache in msg.attrs.values_mut() {
+                    if attr_cache.is_fallback && !fallback_attempted {
+                        report(Some(&attr_cache.attr_id), true);
+                    }
+                    attr_cache.miss_reporter = Some(attr_reporter.clone());
+                }
+            }
+            Err(errs) if is_missing_entry(errs, id, attr) => report(attr, false),
+            Err(_) => {}
+        }
+    }
+}
+
+impl AttrCache {
+    /// Reports the attribute as missing, if a miss reporter is set.
+    ///
+    /// The event carries the language of the bundle, which the reporters set by `Locales::query`
+    /// replace with the requested language.
+    pub(crate) fn report_miss(&self) {
+        if let (Some(reporter), Some(lang)) = (&self.miss_reporter, self.bundle.locales.first()) {
+            reporter(&MissEvent {
+                lang: lang.clone(),
+                id: self.entry_id.clone(),
+                attr: Some(self.attr_id.clone()),
+                fallback_hit: false,
+            });
+        }
+    }
+}
+
+/// Whether a query failed only because the message `id`, or its attribute `attr`, is missing.
+pub(crate) fn is_missing_entry(errs: &QueryError, id: &str, attr: Option<&str>) -> bool {
+    !errs.is_empty()
+        && errs.iter().all(|err| {
+            matches!(
+                err,
+                FluentError::ResolverError(ResolverError::Reference(ReferenceKind::Message {
+                    id: missing,
+                    attribute,
+                })) if missing == id && (attribute.is_none() || attribute.as_deref() == attr)
+            )
+        })
+}
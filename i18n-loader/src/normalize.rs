@@ -0,0 +1,70 @@
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// The Unicode normalization form applied to FTL sources when loading them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// Leaves the text as is.
+    #[default]
+    None,
+    /// Normalizes the text to the canonical composition form (NFC).
+    Nfc,
+}
+
+impl std::str::FromStr for Normalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "nfc" => Ok(Self::Nfc),
+            _ => Err(format!(
+                "unknown normalization form '{s}', expected 'none' or 'nfc'"
+            )),
+        }
+    }
+}
+
+/// A source that was normalized before parsing, along with the normalizations that were applied to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedSource {
+    /// The normalized source text.
+    pub source: String,
+    /// A short description of every normalization that changed the source (e.g. `"BOM"`, `"CRLF"`, `"NFC"`).
+    pub changes: Vec<&'static str>,
+}
+
+/// Decodes raw FTL bytes and normalizes them, see `normalize_source`.
+///
+/// # Errors
+/// Returns the `Utf8Error` if the bytes are not valid UTF-8; its `valid_up_to` is the byte offset
+/// of the first invalid sequence.
+pub fn normalize_bytes(
+    bytes: Vec<u8>,
+    normalization: Normalization,
+) -> Result<NormalizedSource, std::str::Utf8Error> {
+    let source = String::from_utf8(bytes).map_err(|err| err.utf8_error())?;
+    Ok(normalize_source(source, normalization))
+}
+
+/// Normalizes an FTL source before parsing.
+///
+/// A leading UTF-8 byte order mark is stripped and line endings are normalized to `\n`. If
+/// requested, the text is additionally normalized to the given Unicode normalization form.
+pub fn normalize_source(mut source: String, normalization: Normalization) -> NormalizedSource {
+    let mut changes = Vec::default();
+
+    if source.starts_with('\u{feff}') {
+        source.drain(..'\u{feff}'.len_utf8());
+        changes.push("BOM");
+    }
+    if source.contains('\r') {
+        source = source.replace("\r\n", "\n").replace('\r', "\n");
+        changes.push("CRLF");
+    }
+    if normalization == Normalization::Nfc && !is_nfc(&source) {
+        source = source.nfc().collect();
+        changes.push("NFC");
+    }
+
+    NormalizedSource { source, changes }
+}
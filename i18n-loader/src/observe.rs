@@ -0,0 +1,222 @@
+use crate::miss::is_missing_entry;
+use crate::{split_attr_id, LanguageIdentifier, Locales, Message, Query, QueryError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Observes every query resolved by `Locales::query` and `Locales::query_batch`, e.g. to find the
+/// hot translation keys or to time formatting. See `Locales::set_observer`.
+///
+/// The observer is also notified of locales added without any message, see `on_empty_locale`.
+pub trait QueryObserver: Send + Sync {
+    /// Called once a query for the message `id` in the language `lang` is resolved, with the time
+    /// it took to format the message.
+    fn on_query(
+        &self,
+        lang: &LanguageIdentifier,
+        id: &str,
+        duration: Duration,
+        outcome: QueryOutcome,
+    );
+
+    /// Called when a locale that defines no messages is added for the language `lang`, since
+    /// every query for the language silently falls back. The locale is added nonetheless.
+    fn on_empty_locale(&self, lang: &LanguageIdentifier) {
+        _ = lang;
+    }
+}
+
+/// How a query reported to a `QueryObserver` was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum QueryOutcome {
+    /// The requested language resolved the message.
+    Hit,
+    /// The fallback language resolved the message.
+    Fallback,
+    /// The message is missing from the requested and the fallback language.
+    Miss,
+    /// The message was found but failed to format, e.g. because of a missing argument.
+    Error,
+}
+
+impl QueryOutcome {
+    /// Classifies the result of a query.
+    fn of(result: &Result<Message, QueryError>, query: &Query, fallback_attempted: bool) -> Self {
+        let (id, attr) = split_attr_id(&query.id);
+        match result {
+            Ok(_) if fallback_attempted => Self::Fallback,
+            Ok(_) => Self::Hit,
+            Err(errs) if is_missing_entry(errs, id, attr) => Self::Miss,
+            Err(_) => Self::Error,
+        }
+    }
+}
+
+/// A `QueryObserver` counting the queries of every message and their outcomes in memory.
+///
+/// # Example
+///
+/// ```
+/// use i18n_loader::{langid, CountingObserver, Locales, Query, QueryOutcome};
+/// use std::sync::Arc;
+///
+/// let locales = Locales::builder()
+///     .build_from_strs([("en-US", "hello = Hello")])
+///     .unwrap();
+/// let observer = Arc::new(CountingObserver::default());
+/// locales.set_observer(observer.clone());
+///
+/// locales.query(&langid!("en-US"), &Query::new("hello")).unwrap();
+/// assert_eq!(observer.queries_of("hello"), 1);
+/// assert_eq!(observer.outcomes(QueryOutcome::Hit), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct CountingObserver {
+    /// The number of queries of every message ID.
+    queries: Mutex<HashMap<String, u64>>,
+    /// The number of queries of every outcome.
+    outcomes: Mutex<HashMap<QueryOutcome, u64>>,
+    /// The time spent formatting all of the queries.
+    duration: Mutex<Duration>,
+}
+
+impl CountingObserver {
+    /// Gets the number of queries of the message `id`, across every language.
+    pub fn queries_of(&self, id: &str) -> u64 {
+        let queries = self.queries.lock().unwrap_or_else(|err| err.into_inner());
+        queries.get(id).copied().unwrap_or_default()
+    }
+
+    /// Gets the number of queries that were resolved with `outcome`.
+    pub fn outcomes(&self, outcome: QueryOutcome) -> u64 {
+        let outcomes = self.outcomes.lock().unwrap_or_else(|err| err.into_inner());
+        outcomes.get(&outcome).copied().unwrap_or_default()
+    }
+
+    /// Gets the queried message IDs along with their number of queries, the most queried first.
+    pub fn hot_keys(&self) -> Vec<(String, u64)> {
+        let queries = self.queries.lock().unwrap_or_else(|err| err.into_inner());
+        let mut keys = queries
+            .iter()
+            .map(|(id, count)| (id.clone(), *count))
+            .collect::<Vec<_>>();
+        keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        keys
+    }
+
+    /// Gets the time spent formatting all of the observed queries.
+    pub fn total_duration(&self) -> Duration {
+        *self.duration.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Clears the counters, e.g. after they have been exported.
+    pub fn reset(&self) {
+        self.queries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+        self.outcomes
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+        *self.duration.lock().unwrap_or_else(|err| err.into_inner()) = Duration::ZERO;
+    }
+}
+
+impl QueryObserver for CountingObserver {
+    fn on_query(
+        &self,
+        _: &LanguageIdentifier,
+        id: &str,
+        duration: Duration,
+        outcome: QueryOutcome,
+    ) {
+        *self
+            .queries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(id.to_string())
+            .or_default() += 1;
+        *self
+            .outcomes
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(outcome)
+            .or_default() += 1;
+        *self.duration.lock().unwrap_or_else(|err| err.into_inner()) += duration;
+    }
+}
+
+/// A `QueryObserver` emitting a `tracing` event for every query, under the `i18n` target.
+///
+/// Misses are emitted at the `DEBUG` level and every other outcome at the `TRACE` level.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingObserver;
+
+#[cfg(feature = "tracing")]
+impl QueryObserver for TracingObserver {
+    fn on_query(
+        &self,
+        lang: &LanguageIdentifier,
+        id: &str,
+        duration: Duration,
+        outcome: QueryOutcome,
+    ) {
+        let duration_us = duration.as_micros() as u64;
+        match outcome {
+            QueryOutcome::Miss => tracing::debug!(
+                target: "i18n",
+                %lang, id, duration_us, ?outcome,
+                "missing translation"
+            ),
+            _ => tracing::trace!(
+                target: "i18n",
+                %lang, id, duration_us, ?outcome,
+                "translation queried"
+            ),
+        }
+    }
+
+    fn on_empty_locale(&self, lang: &LanguageIdentifier) {
+        tracing::warn!(target: "i18n", %lang, "locale defines no messages");
+    }
+}
+
+impl Locales {
+    /// Sets an observer notified of every query resolved by `Locales::query` and
+    /// `Locales::query_batch`. Replaces any previous observer.
+    ///
+    /// Queries are only timed while an observer is set.
+    pub fn set_observer(&self, observer: Arc<dyn QueryObserver>) {
+        *self.observer.write().unwrap_or_else(|err| err.into_inner()) = Some(observer);
+    }
+
+    /// Removes the observer set with `Locales::set_observer`.
+    pub fn clear_observer(&self) {
+        *self.observer.write().unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    /// Gets the observer, if one is set.
+    pub(crate) fn observer(&self) -> Option<Arc<dyn QueryObserver>> {
+        self.observer
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Notifies the observer of a resolved query, if one is set.
+    pub(crate) fn observe_query(
+        observer: Option<(Arc<dyn QueryObserver>, std::time::Instant)>,
+        lang: &LanguageIdentifier,
+        query: &Query,
+        fallback_attempted: bool,
+        result: &Result<Message, QueryError>,
+    ) {
+        if let Some((observer, start)) = observer {
+            let outcome = QueryOutcome::of(result, query, fallback_attempted);
+            observer.on_query(lang, &query.id, start.elapsed(), outcome);
+        }
+    }
+}
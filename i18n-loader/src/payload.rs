@@ -0,0 +1,106 @@
+use crate::{FetchError, LanguageIdentifier, ParserError};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The latest version of the remote payload envelope.
+const LATEST_VERSION: u64 = 2;
+
+/// The metadata of a remote payload, see `Locales::from_url_with_meta`.
+///
+/// A legacy payload, i.e. a bare JSON object mapping language identifiers to FTL sources, and a
+/// single FTL source are reported as version `1` without metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteMeta {
+    /// The version of the payload format.
+    pub version: u64,
+    /// When the payload was generated, as given by the server.
+    pub generated_at: Option<String>,
+    /// The fallback language suggested by the server.
+    pub fallback: Option<LanguageIdentifier>,
+}
+
+impl Default for RemoteMeta {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            generated_at: None,
+            fallback: None,
+        }
+    }
+}
+
+/// The FTL sources of a remote payload keyed by their language identifier, along with its
+/// metadata.
+pub(crate) struct Payload {
+    pub(crate) sources: HashMap<String, String>,
+    pub(crate) meta: RemoteMeta,
+}
+
+/// An error that occurred while decoding a remote payload.
+pub(crate) enum DecodeError {
+    InvalidFormat(serde_json::Error),
+    UnsupportedVersion(u64),
+    InvalidFallback(String),
+}
+
+impl From<serde_json::Error> for DecodeError {
+    fn from(err: serde_json::Error) -> Self {
+        DecodeError::InvalidFormat(err)
+    }
+}
+
+impl<E> From<DecodeError> for FetchError<E> {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::InvalidFormat(err) => FetchError::InvalidFormat(err),
+            DecodeError::UnsupportedVersion(version) => FetchError::UnsupportedVersion(version),
+            DecodeError::InvalidFallback(langid) => {
+                FetchError::Parser(vec![ParserError::InvalidLangid { langid }])
+            }
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<DecodeError> for crate::NetError {
+    fn from(err: DecodeError) -> Self {
+        FetchError::<crate::NetError>::from(err).into()
+    }
+}
+
+/// Decodes a remote JSON payload, ignoring a leading BOM.
+///
+/// A payload with a `version` field is an envelope of the shape
+/// `{"version": 2, "generated_at": "..", "fallback": "en-US", "locales": {"en-US": "..", ..}}`,
+/// any other is a bare object mapping language identifiers to FTL sources.
+pub(crate) fn decode_payload(body: &[u8]) -> Result<Payload, DecodeError> {
+    let body = body.strip_prefix("\u{feff}".as_bytes()).unwrap_or(body);
+    let mut value = serde_json::from_slice::<Value>(body)?;
+    let Some(version) = value.as_object_mut().and_then(|obj| obj.remove("version")) else {
+        return Ok(Payload {
+            sources: serde_json::from_value(value)?,
+            meta: RemoteMeta::default(),
+        });
+    };
+
+    let version = serde_json::from_value::<u64>(version)?;
+    if version != LATEST_VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+    let mut field = |name| value.get_mut(name).map(Value::take).unwrap_or_default();
+    let fallback = serde_json::from_value::<Option<String>>(field("fallback"))?
+        .map(|langid| {
+            langid
+                .parse()
+                .map_err(|_| DecodeError::InvalidFallback(langid))
+        })
+        .transpose()?;
+    Ok(Payload {
+        meta: RemoteMeta {
+            version,
+            generated_at: serde_json::from_value(field("generated_at"))?,
+            fallback,
+        },
+        sources: serde_json::from_value(field("locales"))?,
+    })
+}
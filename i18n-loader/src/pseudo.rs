@@ -0,0 +1,189 @@
+use crate::{FluentResource, LanguageIdentifier, Locales, UnknownLocale};
+use fluent_syntax::ast;
+use std::sync::Arc;
+
+/// How `Locales::add_pseudo_locale` transforms the text of every message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoStyle {
+    /// Replaces letters with accented lookalikes, pads every message by about 30% and wraps it in
+    /// brackets, e.g. `[Ŵéļçöɱé ƀåçķ~~~]`, exposing hardcoded, truncated and concatenated strings.
+    Accented,
+    /// Wraps every text in right-to-left override marks, exposing layouts that break with
+    /// right-to-left languages.
+    Bidi,
+}
+
+impl PseudoStyle {
+    /// Transforms a text portion of a pattern.
+    fn transform(self, text: &str) -> String {
+        match self {
+            Self::Accented => text.chars().map(accent).collect(),
+            Self::Bidi if text.trim().is_empty() => text.to_string(),
+            Self::Bidi => format!("\u{202E}{text}\u{202C}"),
+        }
+    }
+}
+
+impl Locales {
+    /// Adds a pseudo-locale `pseudo_id`, e.g. `en-XA`, whose messages are those of `base`
+    /// transformed as configured by `style`.
+    ///
+    /// Only the text of patterns is transformed, so placeables such as `{ $var }` and references
+    /// to other messages still resolve. Terms are transformed without padding or brackets, since
+    /// they are embedded in messages. Like `Locales::add_locale`, a locale already loaded for
+    /// `pseudo_id` is replaced.
+    ///
+    /// # Errors
+    /// Returns an `UnknownLocale` error if `base` is not loaded.
+    ///
+    /// # Panics
+    /// Panics if `pseudo_id` is not a valid language identifier.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, Locales, PseudoStyle, Query};
+    ///
+    /// let locales = Locales::builder()
+    ///     .build_from_strs([("en-US", "hello = Hello")])
+    ///     .unwrap();
+    /// locales
+    ///     .add_pseudo_locale(&langid!("en-US"), "en-XA", PseudoStyle::Accented)
+    ///     .unwrap();
+    /// let msg = locales.query(&langid!("en-XA"), &Query::new("hello")).unwrap();
+    /// assert_eq!(msg.value, "[Ĥéļļö~~]");
+    /// ```
+    pub fn add_pseudo_locale(
+        &self,
+        base: &LanguageIdentifier,
+        pseudo_id: &str,
+        style: PseudoStyle,
+    ) -> Result<(), UnknownLocale> {
+        let Some(locale) = self.locale(base) else {
+            return Err(UnknownLocale { lang: base.clone() });
+        };
+        let lang: LanguageIdentifier = pseudo_id.parse().expect("Language ID should be valid");
+
+        let pseudo = |resources: &[Arc<FluentResource>]| {
+            resources
+                .iter()
+                .map(|resource| {
+                    Arc::new(
+                        FluentResource::try_new(pseudo_source(resource, style))
+                            .expect("a serialized resource should parse"),
+                    )
+                })
+                .collect()
+        };
+        self.insert_shared_locale(
+            lang,
+            pseudo(locale.own_resources()),
+            pseudo(locale.common_resources()),
+        )
+        .expect("the base locale was built with the same options");
+        Ok(())
+    }
+}
+
+/// Serializes `resource` with the text of its patterns transformed as configured by `style`.
+fn pseudo_source(resource: &FluentResource, style: PseudoStyle) -> String {
+    // the entries borrow the transformed text, so it is collected from a copy beforehand
+    let mut texts = Vec::default();
+    for_each_pattern(
+        &mut resource.entries().cloned().collect::<Vec<_>>(),
+        |pattern, is_message| {
+            let mut len = 0;
+            for_each_text(pattern, &mut |text| {
+                len += text.chars().count();
+                texts.push(style.transform(text));
+            });
+            if is_message && style == PseudoStyle::Accented {
+                texts.push(format!("{}]", "~".repeat((len * 3).div_ceil(10))));
+            }
+        },
+    );
+
+    let mut texts = texts.iter().map(String::as_str);
+    let mut entries = resource.entries().cloned().collect::<Vec<_>>();
+    for_each_pattern(&mut entries, |pattern, is_message| {
+        for_each_text(pattern, &mut |text| *text = texts.next().unwrap());
+        if is_message && style == PseudoStyle::Accented {
+            // the brackets are string literals, since the lines of a multiline pattern
+            // cannot start with `[`
+            let literal = |value| ast::PatternElement::Placeable {
+                expression: ast::Expression::Inline(ast::InlineExpression::StringLiteral { value }),
+            };
+            pattern.elements.insert(0, literal("["));
+            pattern.elements.push(literal(texts.next().unwrap()));
+        }
+    });
+
+    fluent_syntax::serializer::serialize(&ast::Resource { body: entries })
+}
+
+/// Invokes `f` with the value and attribute patterns of every entry, along with whether the
+/// entry is a message.
+fn for_each_pattern<'s>(
+    entries: &mut [ast::Entry<&'s str>],
+    mut f: impl FnMut(&mut ast::Pattern<&'s str>, bool),
+) {
+    for entry in entries.iter_mut() {
+        let (value, attributes, is_message) = match entry {
+            ast::Entry::Message(msg) => (msg.value.as_mut(), &mut msg.attributes, true),
+            ast::Entry::Term(term) => (Some(&mut term.value), &mut term.attributes, false),
+            _ => continue,
+        };
+        for pattern in value
+            .into_iter()
+            .chain(attributes.iter_mut().map(|attr| &mut attr.value))
+        {
+            f(pattern, is_message);
+        }
+    }
+}
+
+/// Invokes `f` with every text element of `pattern`, including those of its select variants.
+fn for_each_text<'s>(pattern: &mut ast::Pattern<&'s str>, f: &mut impl FnMut(&mut &'s str)) {
+    for element in pattern.elements.iter_mut() {
+        match element {
+            ast::PatternElement::TextElement { value } => f(value),
+            ast::PatternElement::Placeable { expression } => {
+                for_each_expression_text(expression, f)
+            }
+        }
+    }
+}
+
+fn for_each_expression_text<'s>(
+    expression: &mut ast::Expression<&'s str>,
+    f: &mut impl FnMut(&mut &'s str),
+) {
+    match expression {
+        ast::Expression::Select { variants, .. } => {
+            for variant in variants.iter_mut() {
+                for_each_text(&mut variant.value, f);
+            }
+        }
+        ast::Expression::Inline(ast::InlineExpression::Placeable { expression }) => {
+            for_each_expression_text(expression, f)
+        }
+        ast::Expression::Inline(_) => {}
+    }
+}
+
+/// Replaces an ASCII letter with an accented lookalike.
+fn accent(ch: char) -> char {
+    const UPPER: [char; 26] = [
+        'Å', 'Ɓ', 'Ç', 'Ð', 'É', 'Ƒ', 'Ĝ', 'Ĥ', 'Î', 'Ĵ', 'Ķ', 'Ļ', 'Ṁ', 'Ñ', 'Ö', 'Þ', 'Ǫ', 'Ŕ',
+        'Š', 'Ţ', 'Û', 'Ṽ', 'Ŵ', 'Ẋ', 'Ý', 'Ž',
+    ];
+    const LOWER: [char; 26] = [
+        'å', 'ƀ', 'ç', 'ð', 'é', 'ƒ', 'ĝ', 'ĥ', 'î', 'ĵ', 'ķ', 'ļ', 'ɱ', 'ñ', 'ö', 'þ', 'ǫ', 'ŕ',
+        'š', 'ţ', 'û', 'ṽ', 'ŵ', 'ẋ', 'ý', 'ž',
+    ];
+    match ch {
+        'A'..='Z' => UPPER[(ch as u8 - b'A') as usize],
+        'a'..='z' => LOWER[(ch as u8 - b'a') as usize],
+        _ => ch,
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{FluentError, LanguageIdentifier};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A callback invoked with the errors of every failed query, see `LocalesBuilder::on_error`.
+pub type ErrorHandler = Arc<dyn Fn(&QueryError) + Send + Sync>;
+
+/// The errors of a query, grouped by the part of the message they occurred in.
+///
+/// Each group lists its errors in the order they occurred, without duplicates. Iterating yields
+/// the errors of the value first, followed by those of the attributes in alphabetical order, with
+/// the errors already reported for an earlier group skipped. A missing message is reported as an
+/// error of the value.
+///
+/// Attributes only report the errors that cannot be fixed by supplying their arguments lazily, see
+/// `AttrCache::query`, so a missing variable is never reported once per attribute.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryErrors {
+    /// The errors of the message value.
+    pub value: Vec<FluentError>,
+    /// The errors of each attribute, keyed by the attribute name.
+    pub attrs: BTreeMap<String, Vec<FluentError>>,
+}
+
+impl QueryErrors {
+    /// Creates the errors of the attribute `attr`, e.g. as returned by `AttrCache::query`.
+    pub fn for_attr(attr: impl Into<String>, errors: Vec<FluentError>) -> Self {
+        let mut query_errors = Self::default();
+        query_errors.extend_attr(attr, errors);
+        query_errors
+    }
+
+    /// Whether no error occurred.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty() && self.attrs.values().all(Vec::is_empty)
+    }
+
+    /// Gets the number of distinct errors, i.e. the number of errors yielded by `QueryErrors::iter`.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Iterates over the distinct errors, those of the value first and then those of the
+    /// attributes in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = &FluentError> {
+        let mut seen = Vec::new();
+        self.value
+            .iter()
+            .chain(self.attrs.values().flatten())
+            .filter(move |err| {
+                let is_new = !seen.contains(err);
+                if is_new {
+                    seen.push(*err);
+                }
+                is_new
+            })
+    }
+
+    /// Adds errors of the value, skipping those it already lists.
+    pub fn extend_value(&mut self, errors: impl IntoIterator<Item = FluentError>) {
+        dedup_extend(&mut self.value, errors);
+    }
+
+    /// Adds errors of the attribute `attr`, skipping those it already lists.
+    pub fn extend_attr(
+        &mut self,
+        attr: impl Into<String>,
+        errors: impl IntoIterator<Item = FluentError>,
+    ) {
+        let mut errors = errors.into_iter().peekable();
+        if errors.peek().is_some() {
+            dedup_extend(self.attrs.entry(attr.into()).or_default(), errors);
+        }
+    }
+
+    /// Moves the errors of `other` into this one, group by group.
+    pub fn append(&mut self, other: QueryErrors) {
+        self.extend_value(other.value);
+        for (attr, errors) in other.attrs {
+            self.extend_attr(attr, errors);
+        }
+    }
+}
+
+fn dedup_extend(errors: &mut Vec<FluentError>, new: impl IntoIterator<Item = FluentError>) {
+    for err in new {
+        if !errors.contains(&err) {
+            errors.push(err);
+        }
+    }
+}
+
+impl From<Vec<FluentError>> for QueryErrors {
+    /// Creates the errors of the message value.
+    fn from(errors: Vec<FluentError>) -> Self {
+        let mut query_errors = Self::default();
+        query_errors.extend_value(errors);
+        query_errors
+    }
+}
+
+impl From<QueryErrors> for Vec<FluentError> {
+    /// Flattens the errors in the order of `QueryErrors::iter`.
+    fn from(errors: QueryErrors) -> Self {
+        errors.iter().cloned().collect()
+    }
+}
+
+impl<'e> IntoIterator for &'e QueryErrors {
+    type Item = &'e FluentError;
+    type IntoIter = Box<dyn Iterator<Item = &'e FluentError> + 'e>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// The errors of a query along with its context, as returned by `Locales::query` and passed to
+/// the `on_error` handler.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("errors occurred while querying {message_id} in {lang}:\n{}", {
+    errors.iter().map(|err| format!("\t- {err}")).collect::<Vec<_>>().join("\n")
+})]
+pub struct QueryError {
+    /// The requested language.
+    pub lang: LanguageIdentifier,
+    /// The ID of the queried message, or an empty string for the errors concerning a whole locale,
+    /// such as one that defines no messages.
+    pub message_id: String,
+    /// Whether the message was looked up in the fallback language, because the requested language
+    /// is not loaded or, with `Query::with_fallback`, does not define the message.
+    pub fallback_attempted: bool,
+    /// The errors, grouped by the part of the message they occurred in.
+    pub errors: QueryErrors,
+}
+
+impl QueryError {
+    /// Iterates over the distinct errors, see `QueryErrors::iter`.
+    pub fn iter(&self) -> impl Iterator<Item = &FluentError> {
+        self.errors.iter()
+    }
+
+    /// Gets the number of distinct errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Whether no error occurred.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl From<QueryError> for QueryErrors {
+    fn from(err: QueryError) -> Self {
+        err.errors
+    }
+}
+
+impl From<QueryError> for Vec<FluentError> {
+    /// Flattens the errors in the order of `QueryErrors::iter`, dropping the context.
+    fn from(err: QueryError) -> Self {
+        err.errors.into()
+    }
+}
+
+impl<'e> IntoIterator for &'e QueryError {
+    type Item = &'e FluentError;
+    type IntoIter = Box<dyn Iterator<Item = &'e FluentError> + 'e>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
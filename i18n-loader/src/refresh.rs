@@ -0,0 +1,105 @@
+use crate::{CancellationToken, Locales, NetError, RefreshOutcome};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+impl Locales {
+    /// Spawns a task on the current `tokio` runtime that refreshes the collection from `url`
+    /// every `interval` with `Locales::refresh_from_url`, so that translations update without a
+    /// redeploy.
+    ///
+    /// The first refresh happens one `interval` after spawning. `on_refresh` is invoked with the
+    /// outcome of every successful refresh. A failed refresh leaves the locales as they are and
+    /// is reported through `RefreshHandle::last_error`; the task keeps running and tries again on
+    /// the next tick. Every refresh is bounded by the timeout set with `LocalesBuilder::timeout`,
+    /// so that a request that hangs fails with `NetError::Timeout` rather than stalling the task. The task stops once the returned `RefreshHandle` is stopped or dropped.
+    ///
+    /// # Panics
+    /// Panics if called outside of a `tokio` runtime.
+    pub fn spawn_auto_refresh<F>(
+        self: Arc<Self>,
+        url: String,
+        interval: Duration,
+        on_refresh: F,
+    ) -> RefreshHandle
+    where
+        F: Fn(&RefreshOutcome) + Send + 'static,
+    {
+        let status = Arc::new(Mutex::new(RefreshStatus::default()));
+        let stop = CancellationToken::new();
+        tokio::spawn({
+            let status = status.clone();
+            let stop = stop.clone();
+            async move {
+                loop {
+                    let result = tokio::select! {
+                        _ = stop.cancelled() => break,
+                        result = async {
+                            tokio::time::sleep(interval).await;
+                            self.refresh_from_url(&url).await
+                        } => result,
+                    };
+
+                    let mut status = status.lock().unwrap_or_else(|err| err.into_inner());
+                    match result {
+                        Ok(outcome) => {
+                            status.last_refresh = Some(SystemTime::now());
+                            status.last_error = None;
+                            drop(status);
+                            on_refresh(&outcome);
+                        }
+                        Err(err) => status.last_error = Some(Arc::new(err)),
+                    }
+                }
+            }
+        });
+
+        RefreshHandle { status, stop }
+    }
+}
+
+/// The state of an auto-refresh task, as of its latest refresh.
+#[derive(Default)]
+struct RefreshStatus {
+    /// When the latest successful refresh completed.
+    last_refresh: Option<SystemTime>,
+    /// The error of the latest refresh, unless it succeeded.
+    last_error: Option<Arc<NetError>>,
+}
+
+/// A handle to the task started by `Locales::spawn_auto_refresh`, which stops once dropped.
+pub struct RefreshHandle {
+    status: Arc<Mutex<RefreshStatus>>,
+    stop: CancellationToken,
+}
+
+impl RefreshHandle {
+    /// Stops the task. A refresh in progress is abandoned without changing any locale.
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+
+    /// Whether the task was stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.stop.is_cancelled()
+    }
+
+    /// When the latest successful refresh completed, whether or not it changed any locale.
+    pub fn last_refresh(&self) -> Option<SystemTime> {
+        self.status().last_refresh
+    }
+
+    /// The error of the latest refresh, or `None` if it succeeded or none happened yet.
+    pub fn last_error(&self) -> Option<Arc<NetError>> {
+        self.status().last_error.clone()
+    }
+
+    fn status(&self) -> std::sync::MutexGuard<'_, RefreshStatus> {
+        self.status.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for RefreshHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
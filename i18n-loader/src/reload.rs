@@ -0,0 +1,226 @@
+use crate::builder::{read_dir_sources, read_source};
+use crate::{normalize_source, parse_sources};
+use crate::{
+    FluentResource, LanguageIdentifier, LoadError, Locale, Locales, ParserError,
+    ResourceConflictError,
+};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The outcome of `Locales::reload_from_dir`.
+#[derive(Debug, Default)]
+pub struct ReloadReport {
+    /// The languages loaded for the first time, sorted.
+    pub added: Vec<LanguageIdentifier>,
+    /// The languages whose sources changed and were swapped in, sorted.
+    pub changed: Vec<LanguageIdentifier>,
+    /// The errors of the languages that failed to parse, which keep their previous locale.
+    pub errors: Vec<ParserError>,
+    /// The resource conflicts of the languages that keep their previous locale, see
+    /// `LocalesBuilder::resource_conflict`.
+    pub conflicts: Vec<ResourceConflictError>,
+}
+
+impl ReloadReport {
+    /// Whether any locale was added or swapped in.
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.changed.is_empty()
+    }
+
+    /// Whether any language failed to reload.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty() || !self.conflicts.is_empty()
+    }
+}
+
+impl Locales {
+    /// Re-reads a directory laid out as for `LocalesBuilder::build_from_dir`, swapping in the
+    /// locales whose sources changed.
+    ///
+    /// Sources are normalized and checked for junk as configured on the builder. A language that
+    /// fails to parse, or whose resources conflict, keeps its previous locale and is listed in the
+    /// report instead. Languages missing from the directory are left as they are; use
+    /// `Locales::remove_locale` to drop them. The resources shared by every language, such as
+    /// those of a `_common` directory, are not reloaded. All the updated locales are swapped in
+    /// at once, and queries running concurrently keep using the locales they started with.
+    ///
+    /// # Errors
+    /// Returns a `LoadError` if the directory or one of its files cannot be read, in which case
+    /// no locale is changed.
+    pub fn reload_from_dir(&self, path: impl AsRef<Path>) -> Result<ReloadReport, LoadError> {
+        Ok(self.reload_sources(read_dir_sources(path.as_ref())?.sources))
+    }
+
+    /// Re-reads the given `.ftl` files, each paired with the language identifier it belongs to,
+    /// swapping in the locales whose sources changed, as `Locales::reload_from_dir` does.
+    ///
+    /// The files of a language make up all of its resources, in the given order. This is what
+    /// the statics generated by `load!` with `dev_reload = true` reload their embedded files with.
+    ///
+    /// # Errors
+    /// Returns a `LoadError` if one of the files cannot be read, in which case no locale is
+    /// changed.
+    pub fn reload_from_files<L, P>(
+        &self,
+        files: impl IntoIterator<Item = (L, P)>,
+    ) -> Result<ReloadReport, LoadError>
+    where
+        L: Into<String>,
+        P: AsRef<Path>,
+    {
+        let sources = files
+            .into_iter()
+            .map(|(langid, path)| Ok((langid.into(), read_source(path.as_ref())?)))
+            .collect::<Result<Vec<_>, LoadError>>()?;
+
+        Ok(self.reload_sources(sources))
+    }
+
+    /// Re-reads the files set with `LocalesBuilder::reload_files` like
+    /// `Locales::reload_from_files`, returning an empty report if there are none.
+    ///
+    /// # Errors
+    /// Returns a `LoadError` if one of the files cannot be read, in which case no locale is
+    /// changed.
+    pub fn reload(&self) -> Result<ReloadReport, LoadError> {
+        if self.reload_files.is_empty() {
+            return Ok(ReloadReport::default());
+        }
+
+        self.reload_from_files(
+            self.reload_files
+                .iter()
+                .map(|(lang, path)| (lang.as_str(), path)),
+        )
+    }
+
+    /// Parses the sources of every language, swapping in the ones that changed.
+    fn reload_sources(&self, sources: Vec<(String, String)>) -> ReloadReport {
+        let normalization = self.normalization;
+        let (resources, errors) = parse_sources(
+            sources
+                .into_iter()
+                .map(|(langid, source)| (langid, normalize_source(source, normalization).source)),
+            self.allow_junk,
+        );
+        let failed = errors
+            .iter()
+            .filter_map(|err| match err {
+                ParserError::ParserError { langid, .. } => Some(langid.clone()),
+                ParserError::InvalidLangid { .. } => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let mut report = ReloadReport {
+            errors,
+            ..Default::default()
+        };
+        self.swap_in_locales(
+            resources
+                .into_iter()
+                .filter(|(lang, _)| !failed.contains(lang)),
+            &mut report,
+        );
+
+        report
+    }
+
+    /// Swaps in the locales of `resources` whose sources changed all at once, recording the added
+    /// and changed languages and the resource conflicts in `report`.
+    pub(crate) fn swap_in_locales(
+        &self,
+        resources: impl IntoIterator<Item = (LanguageIdentifier, Vec<FluentResource>)>,
+        report: &mut ReloadReport,
+    ) {
+        let (current, fallback_lang) = self.snapshot();
+        let mut reloaded = Vec::default();
+        for (lang, resources) in resources {
+            if current
+                .get(&lang)
+                .is_some_and(|locale| locale.has_sources(&resources))
+            {
+                continue;
+            }
+
+            let resources = resources.into_iter().map(Arc::new).collect();
+            // the resources shared by every locale are not reloaded, and an added locale shares
+            // those of the fallback language
+            let common = current
+                .get(&lang)
+                .or_else(|| current.get(&fallback_lang))
+                .map(|locale| locale.common_resources().to_vec())
+                .unwrap_or_default();
+            match self.build_locale(lang.clone(), resources, common) {
+                Ok(locale) => reloaded.push((lang, locale)),
+                Err(err) => report.conflicts.push(err),
+            }
+        }
+
+        if !reloaded.is_empty() {
+            self.update_locales(|locales| {
+                for (lang, locale) in reloaded {
+                    match locales.insert(lang.clone(), Arc::new(locale)) {
+                        Some(_) => report.changed.push(lang),
+                        None => report.added.push(lang),
+                    }
+                }
+            });
+        }
+        report.added.sort();
+        report.changed.sort();
+        report.conflicts.sort_by(|a, b| a.lang.cmp(&b.lang));
+    }
+
+    /// Watches the directory at `path`, reloading it with `Locales::reload_from_dir` whenever
+    /// anything within it changes.
+    ///
+    /// `on_reload` is invoked with the outcome of every reload. The collection is borrowed for
+    /// `'static`, as for the statics generated by `load!`. Watching stops once the returned
+    /// `LocalesWatcher` is dropped.
+    ///
+    /// # Errors
+    /// Returns a `notify::Error` if the directory cannot be watched.
+    #[cfg(feature = "watch")]
+    pub fn watch_dir<F>(
+        &'static self,
+        path: impl AsRef<Path>,
+        on_reload: F,
+    ) -> Result<LocalesWatcher, notify::Error>
+    where
+        F: Fn(Result<ReloadReport, LoadError>) + Send + 'static,
+    {
+        use notify::Watcher;
+
+        let path = path.as_ref().to_path_buf();
+        let dir = path.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if event.is_ok_and(|event| !event.kind.is_access()) {
+                    on_reload(self.reload_from_dir(&dir));
+                }
+            })?;
+        watcher.watch(&path, notify::RecursiveMode::Recursive)?;
+
+        Ok(LocalesWatcher { _watcher: watcher })
+    }
+}
+
+/// A handle to the directory watch started by `Locales::watch_dir`, which stops once dropped.
+#[cfg(feature = "watch")]
+pub struct LocalesWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl Locale {
+    /// Whether the locale was built from exactly the sources of `resources` in order, besides
+    /// the resources shared by every locale.
+    fn has_sources(&self, resources: &[FluentResource]) -> bool {
+        self.own_resources().len() == resources.len()
+            && self
+                .own_resources()
+                .iter()
+                .zip(resources)
+                .all(|(current, new)| current.source() == new.source())
+    }
+}
@@ -0,0 +1,469 @@
+use crate::payload::{decode_payload, Payload};
+use crate::{normalize_bytes, normalize_source, parse_sources};
+use crate::{LanguageIdentifier, Locales, NetError, Normalization, ReloadReport, RemoteMeta};
+use std::collections::HashMap;
+
+/// The placeholder of a remote source URL replaced with the language identifier.
+const LANG_PLACEHOLDER: &str = "{lang}";
+
+/// The number of characters of an error response kept in `NetError::HttpStatus`.
+const BODY_SNIPPET_LEN: usize = 200;
+
+impl Locales {
+    /// Sets the URL from which `Locales::ensure_loaded` fetches the FTL source of a language,
+    /// such as `https://cdn.example.com/i18n/{lang}.ftl`, where `{lang}` is replaced with the
+    /// language identifier.
+    ///
+    /// Replaces any previous remote source.
+    pub fn set_remote_source(&self, url_template: impl Into<String>) {
+        *self
+            .remote_source
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = Some(url_template.into());
+    }
+
+    /// Fetches, parses and adds the locale of `lang` from the remote source set with
+    /// `Locales::set_remote_source`, unless it is already loaded.
+    ///
+    /// Queries stay synchronous, so call this before querying a language that may not be loaded
+    /// yet, e.g. when the user selects it. Until it succeeds, queries for the language fall back
+    /// as usual. Sources are normalized and checked for junk as configured on the builder.
+    /// Concurrent calls for the same language may each fetch it. The request is bounded and
+    /// retried as configured by `LocalesBuilder::net_config`, and abandoned once the token set
+    /// with `LocalesBuilder::cancel_token` is cancelled.
+    ///
+    /// # Errors
+    /// Returns `NetError::NoRemoteSource` if no remote source is set, `NetError::HttpStatus` if the
+    /// server responds with an error status such as `404 Not Found`, `NetError::Timeout` or
+    /// `NetError::Cancelled` if the request is abandoned, and the other `NetError`s if the source
+    /// cannot be fetched or parsed. The collection is left unchanged in every case.
+    pub async fn ensure_loaded(&self, lang: &LanguageIdentifier) -> Result<(), NetError> {
+        if self.locale_map().contains_key(lang) {
+            return Ok(());
+        }
+        let template = self
+            .remote_source
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+            .ok_or(NetError::NoRemoteSource)?;
+        let url = template.replace(LANG_PLACEHOLDER, &lang.to_string());
+
+        self.add_locale_from_url(&url, lang).await
+    }
+
+    /// Fetches, parses and adds the locale of `lang` from a single network resource, replacing
+    /// any locale already loaded for it.
+    ///
+    /// The resource is decoded according to the `Content-Type` of the response: `application/json`
+    /// is a JSON object mapping language identifiers to FTL sources, of which only `lang` is
+    /// loaded, and anything else is the FTL source of `lang`. A `Content-Language` header, if
+    /// present, must name `lang`. Sources are normalized and checked for junk as configured on
+    /// the builder. The request is bounded, retried and abandoned as for `Locales::ensure_loaded`.
+    ///
+    /// # Errors
+    /// Returns `NetError::ContentLanguageMismatch` if the response is for another language,
+    /// `NetError::MissingLocale` if a JSON map has no source for `lang`, `NetError::HttpStatus` if the
+    /// server responds with an error status, `NetError::Timeout` or `NetError::Cancelled` if the
+    /// request is abandoned, and the other `NetError`s if the source cannot be fetched or parsed. The collection is left unchanged in every case.
+    pub async fn add_locale_from_url(
+        &self,
+        url: &str,
+        lang: &LanguageIdentifier,
+    ) -> Result<(), NetError> {
+        let mut sources = self
+            .fetch_bounded(url)
+            .await?
+            .into_payload(url, Some(lang), BodyFormat::Ftl)?
+            .sources;
+        let source = sources
+            .remove(&lang.to_string())
+            .ok_or_else(|| NetError::MissingLocale {
+                url: url.to_string(),
+                lang: lang.clone(),
+            })?;
+
+        let (mut resources, errors) = parse_sources(
+            [(
+                lang.to_string(),
+                normalize_source(source, self.normalization).source,
+            )],
+            self.allow_junk,
+        );
+        if !errors.is_empty() {
+            return Err(NetError::ParserError(errors));
+        }
+        let resources = resources.remove(lang).unwrap_or_default();
+        self.insert_locale(lang.clone(), resources)?;
+        Ok(())
+    }
+
+    /// Loads a network resource as for `Locales::from_url` over the `embedded` collection, e.g. one
+    /// built from translations compiled into the application, keeping the embedded translations
+    /// if the resource cannot be loaded.
+    ///
+    /// The locales of the resource replace the embedded ones per language, as for
+    /// `Locales::merge_locales`, while the languages absent from the resource remain available
+    /// from the embedded collection. Sources are parsed with the options of `embedded`, and the
+    /// resource is loaded as a whole or not at all, see `Locales::refresh_from_url`. The request
+    /// is bounded, retried and abandoned as configured on the builder of `embedded`, so that a
+    /// server that hangs falls back to the embedded translations once the timeout elapses. For a
+    /// collection that cannot be moved, such as the static generated by `load!`, call
+    /// `Locales::refresh_from_url` on it directly.
+    ///
+    /// Returns the merged collection along with the error that prevented loading the resource,
+    /// if any, e.g. to log it.
+    pub async fn from_url_or(url: &str, embedded: Locales) -> (Locales, Option<NetError>) {
+        let error = embedded.refresh_from_url(url).await.err();
+        (embedded, error)
+    }
+
+    /// Re-fetches a network resource loaded as for `Locales::from_url`, swapping in the locales
+    /// whose sources changed, see `Locales::reload_from_dir`.
+    ///
+    /// The `ETag` and `Last-Modified` headers of the previous refresh of `url` are sent back, so
+    /// that a server responding with `304 Not Modified` spares downloading and parsing a resource
+    /// that did not change. The first refresh of a URL always downloads it. Sources are normalized and checked for junk as configured on the
+    /// builder. Languages missing from the resource are left as they are. The request is bounded,
+    /// retried and abandoned as for `Locales::ensure_loaded`.
+    ///
+    /// # Errors
+    /// Returns `NetError::ParserError` if any source fails to parse, `NetError::Timeout` or
+    /// `NetError::Cancelled` if the request is abandoned, and the other `NetError`s if the
+    /// resource cannot be fetched or decoded. No locale is changed in either case, and the
+    /// next refresh downloads the resource again.
+    pub async fn refresh_from_url(&self, url: &str) -> Result<RefreshOutcome, NetError> {
+        let validators = self
+            .refresh_validators
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(url)
+            .cloned()
+            .unwrap_or_default();
+        let response = crate::retry::bounded(
+            &self.net,
+            self.cancel_token.as_ref(),
+            crate::retry::with_retries(&self.net, || fetch_if_modified(url, &validators)),
+        )
+        .await?;
+        let Some(response) = response else {
+            return Ok(RefreshOutcome::Unchanged);
+        };
+
+        let validators = response.validators.clone();
+        let sources = response.into_payload(url, None, BodyFormat::Json)?.sources;
+        let normalization = self.normalization;
+        let (resources, errors) = parse_sources(
+            sources
+                .into_iter()
+                .map(|(langid, source)| (langid, normalize_source(source, normalization).source)),
+            self.allow_junk,
+        );
+        if !errors.is_empty() {
+            return Err(NetError::ParserError(errors));
+        }
+
+        let mut report = ReloadReport::default();
+        self.swap_in_locales(resources, &mut report);
+        self.refresh_validators
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(url.to_string(), validators);
+        Ok(RefreshOutcome::Reloaded(report))
+    }
+}
+
+impl Locales {
+    /// Fetches a network resource as configured by `LocalesBuilder::net_config`, giving up once
+    /// the token set with `LocalesBuilder::cancel_token` is cancelled.
+    async fn fetch_bounded(&self, url: &str) -> Result<Response, NetError> {
+        crate::retry::bounded(
+            &self.net,
+            self.cancel_token.as_ref(),
+            crate::retry::fetch_with_retries(url, &self.net),
+        )
+        .await
+    }
+}
+
+/// The outcome of `Locales::refresh_from_url`.
+#[derive(Debug)]
+pub enum RefreshOutcome {
+    /// The server reported the resource as unchanged, so nothing was downloaded or parsed.
+    Unchanged,
+    /// The resource was downloaded and the locales whose sources changed were swapped in.
+    Reloaded(ReloadReport),
+}
+
+/// The format of a fetched body.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BodyFormat {
+    /// A JSON object mapping language identifiers to FTL sources.
+    Json,
+    /// The FTL source of a single language.
+    Ftl,
+}
+
+impl BodyFormat {
+    /// Returns the format named by a `Content-Type` header, or `default` if it names neither.
+    fn from_content_type(content_type: Option<&str>, default: BodyFormat) -> BodyFormat {
+        let Some(content_type) = content_type else {
+            return default;
+        };
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "application/json" => BodyFormat::Json,
+            "text/plain" | "application/fluent" => BodyFormat::Ftl,
+            _ => default,
+        }
+    }
+}
+
+/// A successful response fetched with `hyper`.
+pub(crate) struct Response {
+    body: Vec<u8>,
+    content_type: Option<String>,
+    content_language: Option<String>,
+    validators: Validators,
+}
+
+/// The `ETag` and `Last-Modified` headers of a response, with which a later request asks the
+/// server whether the resource changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Response {
+    /// Decodes the FTL sources of the response, keyed by their language identifier, along with
+    /// the metadata of a JSON payload.
+    ///
+    /// The format is chosen by the `Content-Type` header, falling back to `default`. The language
+    /// of an FTL body is `lang` or the one named by the `Content-Language` header.
+    /// An FTL body that is not valid UTF-8 fails with `NetError::InvalidUtf8`.
+    pub(crate) fn into_payload(
+        self,
+        url: &str,
+        lang: Option<&LanguageIdentifier>,
+        default: BodyFormat,
+    ) -> Result<Payload, NetError> {
+        if BodyFormat::from_content_type(self.content_type.as_deref(), default) == BodyFormat::Json
+        {
+            return Ok(decode_payload(&self.body)?);
+        }
+
+        let content_language = self
+            .content_language
+            .map(|value| {
+                value.trim().parse::<LanguageIdentifier>().map_err(|_| {
+                    NetError::ContentLanguageMismatch {
+                        url: url.to_string(),
+                        expected: lang.cloned(),
+                        found: value,
+                    }
+                })
+            })
+            .transpose()?;
+        let lang = match (lang, content_language) {
+            (Some(lang), Some(found)) if *lang != found => {
+                return Err(NetError::ContentLanguageMismatch {
+                    url: url.to_string(),
+                    expected: Some(lang.clone()),
+                    found: found.to_string(),
+                })
+            }
+            (Some(lang), _) => lang.clone(),
+            (None, Some(found)) => found,
+            (None, None) => {
+                return Err(NetError::MissingContentLanguage {
+                    url: url.to_string(),
+                })
+            }
+        };
+        let source = normalize_bytes(self.body, Normalization::None)
+            .map_err(|err| NetError::InvalidUtf8 {
+                url: url.to_string(),
+                offset: err.valid_up_to(),
+            })?
+            .source;
+        Ok(Payload {
+            sources: HashMap::from([(lang.to_string(), source)]),
+            meta: RemoteMeta::default(),
+        })
+    }
+}
+
+/// Fetches a network resource with `hyper`.
+///
+/// # Errors
+/// Returns `NetError::InvalidUrl` if `url` cannot be parsed, `NetError::HttpStatus` if the server
+/// responds with an error status and `NetError::EmptyBody` if it responds without a body.
+pub(crate) async fn fetch(url: &str) -> Result<Response, NetError> {
+    let response = fetch_if_modified(url, &Validators::default()).await?;
+    // without validators, the server has no reason to respond with `304 Not Modified`
+    response.ok_or_else(|| NetError::HttpStatus {
+        url: url.to_string(),
+        status: hyper::StatusCode::NOT_MODIFIED.as_u16(),
+        body_snippet: String::new(),
+    })
+}
+
+/// Fetches a network resource with `hyper` unless it matches `validators`, in which case the
+/// server responds with `304 Not Modified` and `None` is returned.
+///
+/// # Errors
+/// Returns `NetError::InvalidUrl` if `url` cannot be parsed, `NetError::HttpStatus` if the server
+/// responds with an error status and `NetError::EmptyBody` if it responds without a body.
+pub(crate) async fn fetch_if_modified(
+    url: &str,
+    validators: &Validators,
+) -> Result<Option<Response>, NetError> {
+    let https = hyper_tls::HttpsConnector::new();
+    let client = hyper::Client::builder().build::<_, hyper::Body>(https);
+    let uri = url
+        .parse::<hyper::Uri>()
+        .map_err(|err| NetError::InvalidUrl {
+            url: url.to_string(),
+            reason: err.to_string(),
+        })?;
+    let mut request =
+        hyper::Request::get(uri).header(hyper::header::ACCEPT_ENCODING, "gzip, deflate");
+    if let Some(etag) = &validators.etag {
+        request = request.header(hyper::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(hyper::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let request = request
+        .body(hyper::Body::empty())
+        .map_err(|err| NetError::InvalidUrl {
+            url: url.to_string(),
+            reason: err.to_string(),
+        })?;
+
+    let res = client.request(request).await?;
+    if res.status() == hyper::StatusCode::NOT_MODIFIED && *validators != Validators::default() {
+        return Ok(None);
+    }
+    if !res.status().is_success() {
+        let status = res.status().as_u16();
+        let body = hyper::body::to_bytes(res.into_body())
+            .await
+            .unwrap_or_default();
+        return Err(NetError::HttpStatus {
+            url: url.to_string(),
+            status,
+            body_snippet: body_snippet(&body),
+        });
+    }
+
+    let header = |name| {
+        res.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let content_type = header(hyper::header::CONTENT_TYPE);
+    let content_language = header(hyper::header::CONTENT_LANGUAGE);
+    let content_encoding = header(hyper::header::CONTENT_ENCODING);
+    let validators = Validators {
+        etag: header(hyper::header::ETAG),
+        last_modified: header(hyper::header::LAST_MODIFIED),
+    };
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let body = decode_body(url, content_encoding.as_deref(), body.to_vec())?;
+    if body.is_empty() {
+        return Err(NetError::EmptyBody {
+            url: url.to_string(),
+        });
+    }
+    Ok(Some(Response {
+        body,
+        content_type,
+        content_language,
+        validators,
+    }))
+}
+
+/// Decodes a body according to the `Content-Encoding` header of its response, whose codings
+/// are listed in the order they were applied.
+///
+/// # Errors
+/// Returns `NetError::UnsupportedEncoding` for a coding other than `gzip`, `deflate` and
+/// `identity`, and `NetError::Decompress` if the body is not encoded as advertised.
+fn decode_body(url: &str, encoding: Option<&str>, mut body: Vec<u8>) -> Result<Vec<u8>, NetError> {
+    use std::io::Read;
+
+    let codings = encoding
+        .into_iter()
+        .flat_map(|encoding| encoding.split(','));
+    for coding in codings.rev() {
+        let mut decoded = Vec::default();
+        let result = match coding.trim().to_ascii_lowercase().as_str() {
+            "" | "identity" => continue,
+            "gzip" | "x-gzip" => {
+                flate2::read::MultiGzDecoder::new(&body[..]).read_to_end(&mut decoded)
+            }
+            "deflate" => flate2::read::ZlibDecoder::new(&body[..]).read_to_end(&mut decoded),
+            _ => return Err(NetError::UnsupportedEncoding(coding.trim().to_string())),
+        };
+        result.map_err(|source| NetError::Decompress {
+            url: url.to_string(),
+            source,
+        })?;
+        body = decoded;
+    }
+
+    Ok(body)
+}
+
+/// Posts a JSON body to a network resource with `hyper`.
+///
+/// # Errors
+/// Returns `NetError::InvalidUrl` if `url` cannot be parsed and `NetError::HttpStatus` if the
+/// server responds with an error status.
+pub(crate) async fn post_json(url: &str, body: Vec<u8>) -> Result<(), NetError> {
+    let https = hyper_tls::HttpsConnector::new();
+    let client = hyper::Client::builder().build::<_, hyper::Body>(https);
+    let invalid_url = |reason: String| NetError::InvalidUrl {
+        url: url.to_string(),
+        reason,
+    };
+    let uri = url
+        .parse::<hyper::Uri>()
+        .map_err(|err| invalid_url(err.to_string()))?;
+    let request = hyper::Request::post(uri)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(hyper::Body::from(body))
+        .map_err(|err| invalid_url(err.to_string()))?;
+
+    let res = client.request(request).await?;
+    if !res.status().is_success() {
+        let status = res.status().as_u16();
+        let body = hyper::body::to_bytes(res.into_body())
+            .await
+            .unwrap_or_default();
+        return Err(NetError::HttpStatus {
+            url: url.to_string(),
+            status,
+            body_snippet: body_snippet(&body),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the start of the body of an error response, for `NetError::HttpStatus`.
+fn body_snippet(body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    let body = body.trim();
+    match body.char_indices().nth(BODY_SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
@@ -0,0 +1,144 @@
+use crate::remote::{fetch, Response};
+use crate::{CancellationToken, NetError};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// How a network load bounds and retries its requests, see `LocalesBuilder::net_config`.
+///
+/// Transient failures, i.e. connection errors and `5xx` responses, are retried with an
+/// exponential backoff. Any other error, such as a `4xx` response or an invalid body, fails
+/// the load right away.
+///
+/// # Example
+///
+/// ```
+/// use i18n_loader::NetConfig;
+/// use std::time::Duration;
+///
+/// let config = NetConfig::new()
+///     .timeout(Duration::from_secs(10))
+///     .retries(5)
+///     .backoff(Duration::from_millis(100));
+/// assert_eq!(config.retries, 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetConfig {
+    /// The time after which the whole load, retries included, gives up with `NetError::Timeout`.
+    pub timeout: Duration,
+    /// How many times a transient failure is retried after the first attempt.
+    pub retries: u32,
+    /// The delay before the first retry, doubled before every further one.
+    pub backoff: Duration,
+}
+
+impl Default for NetConfig {
+    /// A 30 second timeout and 3 retries, starting with a 250 millisecond backoff.
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+impl NetConfig {
+    /// Creates the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time after which the load gives up.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times a transient failure is retried.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns the delay before the retry following the attempt at index `attempt`.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << attempt.min(16))
+    }
+}
+
+/// Whether a failed request may succeed when retried.
+fn is_transient(err: &NetError) -> bool {
+    match err {
+        NetError::ServerError(err) => err.is_connect(),
+        NetError::HttpStatus { status, .. } => (500..600).contains(status),
+        _ => false,
+    }
+}
+
+/// Fetches a network resource, retrying transient failures as configured by `config`.
+///
+/// # Errors
+/// Returns `NetError::RetriesExhausted` with the last error if every attempt failed
+/// transiently, or the first error that is not transient.
+pub(crate) async fn fetch_with_retries(
+    url: &str,
+    config: &NetConfig,
+) -> Result<Response, NetError> {
+    with_retries(config, || fetch(url)).await
+}
+
+/// Runs the request made by `request`, retrying transient failures as configured by `config`,
+/// see `fetch_with_retries`.
+pub(crate) async fn with_retries<T, F, Fut>(
+    config: &NetConfig,
+    mut request: F,
+) -> Result<T, NetError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, NetError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(err) if is_transient(&err) && attempt < config.retries => {
+                tokio::time::sleep(config.delay(attempt)).await;
+                attempt += 1;
+            }
+            Err(err) if is_transient(&err) && attempt > 0 => {
+                return Err(NetError::RetriesExhausted {
+                    attempts: attempt + 1,
+                    source: Box::new(err),
+                });
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Runs a network load, giving up with `NetError::Timeout` once the timeout of `config` elapses
+/// or with `NetError::Cancelled` once `cancel_token` is cancelled.
+pub(crate) async fn bounded<T>(
+    config: &NetConfig,
+    cancel_token: Option<&CancellationToken>,
+    load: impl Future<Output = Result<T, NetError>>,
+) -> Result<T, NetError> {
+    let start = Instant::now();
+    let cancelled = async {
+        match cancel_token {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        result = tokio::time::timeout(config.timeout, load) => {
+            result.map_err(|_| NetError::Timeout { elapsed: start.elapsed() })?
+        }
+        _ = cancelled => Err(NetError::Cancelled),
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{FluentArgs, FluentValue, Locales, Query};
+use std::borrow::Cow;
+use std::sync::Arc;
+use unicode_normalization::char::is_combining_mark;
+
+/// A callback rewriting the string arguments of every query, see `Locales::set_arg_sanitizer`.
+pub type ArgSanitizer = Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>;
+
+impl Locales {
+    /// Sets a sanitizer applied to every string argument before formatting, e.g. to keep user
+    /// input from wrecking log lines and layouts. Replaces any previous sanitizer.
+    ///
+    /// The sanitizer sees the raw argument, before it is interpolated and wrapped in bidi isolation
+    /// characters, so any escaping of the formatted message (e.g. for HTML) applies after it.
+    /// Numbers are not sanitized, and neither are arguments set with `Query::with_raw_arg` or
+    /// `Query::with_select_arg`, nor those given lazily to `AttrCache::query`. See
+    /// `strip_control_chars` and `truncate` for the built-ins, which compose by feeding the output
+    /// of one into the other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use i18n_loader::{langid, strip_control_chars, truncate, Locales, Query};
+    /// use std::borrow::Cow;
+    ///
+    /// let locales = Locales::builder()
+    ///     .use_isolating(false)
+    ///     .build_from_strs([("en-US", "hello = Hello, { $name }!")])
+    ///     .unwrap();
+    /// let truncate = truncate(8);
+    /// locales.set_arg_sanitizer(move |value| {
+    ///     Cow::Owned(truncate(&strip_control_chars(value)).into_owned())
+    /// });
+    ///
+    /// let query = Query::new("hello").with_arg("name", "\x1b[31mAlexander\x1b[0m");
+    /// assert_eq!(locales.query(&langid!("en-US"), &query).unwrap().value, "Hello, Alexand…!");
+    /// ```
+    pub fn set_arg_sanitizer<F>(&self, sanitizer: F)
+    where
+        F: Fn(&str) -> Cow<'_, str> + Send + Sync + 'static,
+    {
+        *self
+            .arg_sanitizer
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(sanitizer));
+    }
+
+    /// Removes the sanitizer set with `Locales::set_arg_sanitizer`.
+    pub fn clear_arg_sanitizer(&self) {
+        *self
+            .arg_sanitizer
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    /// Copies the query with its arguments sanitized, if a sanitizer is set.
+    pub(crate) fn sanitized_query<'q>(&self, query: &'q Query) -> Option<Query<'q>> {
+        let sanitizer = self
+            .arg_sanitizer
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()?;
+        let sanitize = |args: &'q FluentArgs, raw: &[String]| {
+            let mut sanitized = FluentArgs::with_capacity(args.iter().count());
+            for (key, value) in args.iter() {
+                let value = match value {
+                    FluentValue::String(s) if !raw.iter().any(|raw| raw == key) => {
+                        FluentValue::String(Cow::Owned(sanitizer(s).into_owned()))
+                    }
+                    value => value.clone(),
+                };
+                sanitized.set(key, value);
+            }
+            sanitized
+        };
+
+        Some(Query {
+            id: query.id.as_ref().into(),
+            args: sanitize(&query.args, &query.raw_args),
+            attr_args: query
+                .attr_args
+                .iter()
+                .map(|(attr, args)| (attr.as_ref().into(), sanitize(args, &[])))
+                .collect(),
+            with_fallback: query.with_fallback,
+            select_args: query.select_args.clone(),
+            variant_selector: None,
+            strict_args: query.strict_args,
+            duplicate_args: query.duplicate_args.clone(),
+            raw_args: query.raw_args.clone(),
+            propagate_args: query.propagate_args,
+        })
+    }
+}
+
+/// Removes the control characters of `value`, along with the ANSI escape sequences they start.
+///
+/// C0 and C1 controls and `DEL` are removed, except for tabs and line breaks. CSI sequences,
+/// such as the `\x1b[31m` setting the text color, are removed as a whole.
+///
+/// # Example
+///
+/// ```
+/// use i18n_loader::strip_control_chars;
+///
+/// assert_eq!(strip_control_chars("\x1b[1mbold\x1b[0m\x07 text\n"), "bold text\n");
+/// ```
+pub fn strip_control_chars(value: &str) -> Cow<'_, str> {
+    let is_removed = |c: char| c.is_control() && !matches!(c, '\t' | '\n' | '\r');
+    if !value.chars().any(is_removed) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut stripped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        let is_csi = match c {
+            '\u{1b}' => chars.next_if_eq(&'[').is_some(),
+            '\u{9b}' => true,
+            _ => false,
+        };
+        if is_csi {
+            // parameter and intermediate bytes, followed by a single final byte
+            while chars
+                .next_if(|c| ('\u{20}'..='\u{3f}').contains(c))
+                .is_some()
+            {}
+            chars.next_if(|c| ('\u{40}'..='\u{7e}').contains(c));
+        } else if !is_removed(c) {
+            stripped.push(c);
+        }
+    }
+
+    Cow::Owned(stripped)
+}
+
+/// Creates a sanitizer truncating values longer than `max_chars` characters, ending them with `…`.
+///
+/// The result, ellipsis included, is at most `max_chars` characters long. Values are only cut
+/// between user-perceived characters, so combining marks stay with their base character and
+/// emoji sequences, such as flags or families joined with `U+200D`, are kept or dropped whole.
+///
+/// # Example
+///
+/// ```
+/// use i18n_loader::truncate;
+///
+/// let truncate = truncate(6);
+/// assert_eq!(truncate("Alex"), "Alex");
+/// assert_eq!(truncate("Alexander"), "Alexa…");
+/// ```
+pub fn truncate(max_chars: usize) -> impl Fn(&str) -> Cow<'_, str> + Clone + Send + Sync {
+    move |value| {
+        if value.chars().count() <= max_chars {
+            return Cow::Borrowed(value);
+        }
+        if max_chars == 0 {
+            return Cow::Borrowed("");
+        }
+
+        // the end of the last user-perceived character that fits along with the ellipsis
+        let mut end = 0;
+        let mut prev = None;
+        let mut regional_indicators = 0;
+        for (idx, (offset, c)) in value.char_indices().enumerate() {
+            let is_regional_indicator = ('\u{1f1e6}'..='\u{1f1ff}').contains(&c);
+            let extends = is_extending(c)
+                || prev == Some('\u{200d}')
+                || (is_regional_indicator && regional_indicators % 2 == 1);
+            if !extends {
+                if idx > max_chars - 1 {
+                    break;
+                }
+                end = offset;
+            }
+            regional_indicators = if is_regional_indicator {
+                regional_indicators + 1
+            } else {
+                0
+            };
+            prev = Some(c);
+        }
+
+        Cow::Owned(format!("{}…", &value[..end]))
+    }
+}
+
+/// Whether `c` extends the user-perceived character before it.
+fn is_extending(c: char) -> bool {
+    is_combining_mark(c)
+        || matches!(c,
+            '\u{200d}' // zero width joiner
+            | '\u{fe00}'..='\u{fe0f}' // variation selectors
+            | '\u{1f3fb}'..='\u{1f3ff}' // skin tone modifiers
+            | '\u{e0020}'..='\u{e007f}' // tags
+        )
+}
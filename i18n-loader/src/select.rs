@@ -0,0 +1,181 @@
+use crate::{
+    DuplicateArg, LanguageIdentifier, Locale, LocaleMap, Locales, Message, Query, QueryError,
+};
+use fluent_syntax::ast;
+use std::sync::Arc;
+
+/// A select argument of a `Query` along with the values it is allowed to take.
+#[derive(Debug, Clone)]
+pub(crate) struct SelectArg {
+    /// The name of the argument.
+    pub(crate) key: String,
+    /// The value passed for the argument.
+    pub(crate) value: String,
+    /// The values explicitly allowed by the caller.
+    pub(crate) allowed: Vec<String>,
+}
+
+/// A select argument whose value matches neither the allowed values nor any variant of the message.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid value '{value}' for select argument ${key}, expected one of: {}", variants.join(", "))]
+pub struct InvalidSelectValue {
+    /// The name of the argument.
+    pub key: String,
+    /// The rejected value.
+    pub value: String,
+    /// The allowed values followed by the variant keys of the message's select expressions on the argument.
+    pub variants: Vec<String>,
+}
+
+/// An error returned by `Locales::try_query`.
+#[derive(Debug, thiserror::Error)]
+pub enum TryQueryError {
+    #[error(transparent)]
+    InvalidSelectValue(#[from] InvalidSelectValue),
+    #[error(transparent)]
+    DuplicateArg(#[from] DuplicateArg),
+    #[error(transparent)]
+    Fluent(QueryError),
+}
+
+impl Locales {
+    /// Queries for a message like `Locales::query`, first validating the select arguments of the query.
+    ///
+    /// The select arguments are validated against the message as found in the locale that would
+    /// format it. See `Query::with_select_arg`. A query with strict arguments is also rejected if
+    /// any of its arguments was set more than once, see `Query::strict_args`.
+    #[track_caller]
+    pub fn try_query(
+        &self,
+        lang: &LanguageIdentifier,
+        query: &Query,
+    ) -> Result<Message, TryQueryError> {
+        if let (true, Some(duplicate)) = (query.strict_args, query.duplicate_args.first()) {
+            return Err(duplicate.clone().into());
+        }
+        if !query.select_args.is_empty() {
+            let (locales, overlays) = (self.locale_map(), self.overlays());
+            if let Some(locale) = self.resolving_locale(&locales, &overlays, lang, query) {
+                locale.validate_select_args(query)?;
+            }
+        }
+
+        self.query(lang, query).map_err(TryQueryError::Fluent)
+    }
+
+    /// Finds the locale that defines the queried message, consulting the overlays first and
+    /// the fallback language last.
+    pub(crate) fn resolving_locale<'s>(
+        &self,
+        locales: &'s LocaleMap,
+        overlays: &'s [Arc<LocaleMap>],
+        lang: &LanguageIdentifier,
+        query: &Query,
+    ) -> Option<&'s Locale> {
+        [lang, &self.fallback_lang()].into_iter().find_map(|lang| {
+            overlays
+                .iter()
+                .rev()
+                .filter_map(|overlay| overlay.get(lang))
+                .chain(locales.get(lang))
+                .map(Arc::as_ref)
+                .find(|locale| locale.defines(&query.id))
+        })
+    }
+}
+
+impl Locale {
+    /// Gets the keys of the variants of every select expression on the variable `variable` in the
+    /// message `id`, in source order and without duplicates.
+    ///
+    /// Both the value and the attributes of the message are searched, including nested selects.
+    /// Returns `None` if the message does not exist or never selects on the variable.
+    pub fn select_variants(&self, id: &str, variable: &str) -> Option<Vec<String>> {
+        let msg = self.bundle.get_message(id)?;
+        let mut variants = Vec::default();
+        let mut found = false;
+        let patterns = msg
+            .value()
+            .into_iter()
+            .chain(msg.attributes().map(|attr| attr.value()));
+        for pattern in patterns {
+            found |= collect_variants(pattern, variable, &mut variants);
+        }
+
+        found.then_some(variants)
+    }
+
+    /// Validates the select arguments of the query against the queried message.
+    fn validate_select_args(&self, query: &Query) -> Result<(), InvalidSelectValue> {
+        for arg in query.select_args.iter() {
+            if arg.allowed.contains(&arg.value) {
+                continue;
+            }
+
+            let variants = self
+                .select_variants(&query.id, &arg.key)
+                .unwrap_or_default();
+            if variants.contains(&arg.value) {
+                continue;
+            }
+
+            let mut expected = arg.allowed.clone();
+            expected.extend(variants.into_iter().filter(|v| !arg.allowed.contains(v)));
+            return Err(InvalidSelectValue {
+                key: arg.key.clone(),
+                value: arg.value.clone(),
+                variants: expected,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the variant keys of the select expressions on `variable` within `pattern`.
+///
+/// Returns whether any such select expression was found.
+fn collect_variants(pattern: &ast::Pattern<&str>, variable: &str, out: &mut Vec<String>) -> bool {
+    let mut found = false;
+    for element in pattern.elements.iter() {
+        if let ast::PatternElement::Placeable { expression } = element {
+            found |= collect_expression_variants(expression, variable, out);
+        }
+    }
+
+    found
+}
+
+/// Collects the variant keys of the select expressions on `variable` within `expression`.
+fn collect_expression_variants(
+    expression: &ast::Expression<&str>,
+    variable: &str,
+    out: &mut Vec<String>,
+) -> bool {
+    match expression {
+        ast::Expression::Select { selector, variants } => {
+            let selects = matches!(
+                selector,
+                ast::InlineExpression::VariableReference { id } if id.name == variable
+            );
+            let mut found = selects;
+            for variant in variants.iter() {
+                if selects {
+                    let key = match &variant.key {
+                        ast::VariantKey::Identifier { name } => name.to_string(),
+                        ast::VariantKey::NumberLiteral { value } => value.to_string(),
+                    };
+                    if !out.contains(&key) {
+                        out.push(key);
+                    }
+                }
+                found |= collect_variants(&variant.value, variable, out);
+            }
+            found
+        }
+        ast::Expression::Inline(ast::InlineExpression::Placeable { expression }) => {
+            collect_expression_variants(expression, variable, out)
+        }
+        ast::Expression::Inline(_) => false,
+    }
+}
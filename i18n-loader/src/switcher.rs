@@ -0,0 +1,207 @@
+use crate::{LanguageIdentifier, Locales};
+use i18n_lang::Lang;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A callback invoked with the newly selected language.
+type ChangeCallback = Arc<dyn Fn(&LanguageIdentifier) + Send + Sync>;
+
+/// The identifier of a callback registered with `LanguageSwitcher::on_change`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+/// An error that occurred while selecting a language.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SwitchError {
+    #[error("could not parse langid: {lang}")]
+    InvalidLangid { lang: String },
+    #[error("language {lang} is not available")]
+    Unknown { lang: LanguageIdentifier },
+    #[error("no languages are available")]
+    Empty,
+}
+
+/// Holds the globally selected language, out of a fixed set of available languages.
+///
+/// The selection can be read from any thread without ever waiting for longer than it takes to
+/// clone an `Arc`. It is typically built once from the output of `langs!` and kept in a static:
+///
+/// ```
+/// use i18n_loader::{langid, LanguageSwitcher};
+/// use std::sync::OnceLock;
+///
+/// static LANG: OnceLock<LanguageSwitcher> = OnceLock::new();
+///
+/// let switcher = LANG.get_or_init(|| {
+///     LanguageSwitcher::new([langid!("en-US"), langid!("hr-HR")]).unwrap()
+/// });
+/// switcher.set("hr-HR").unwrap();
+/// assert_eq!(switcher.current(), langid!("hr-HR"));
+/// ```
+pub struct LanguageSwitcher {
+    /// The available languages, in cycling order.
+    available: Vec<LanguageIdentifier>,
+    /// The selected language along with its index within `available`.
+    current: RwLock<Arc<(usize, LanguageIdentifier)>>,
+    /// The callbacks invoked when the selection changes.
+    subscribers: RwLock<Vec<(SubscriptionId, ChangeCallback)>>,
+    /// The identifier of the next registered callback.
+    next_subscription: AtomicUsize,
+    /// Held while a change is made and its callbacks are invoked, so that the callbacks observe
+    /// the changes in the order they were made.
+    notifying: Mutex<()>,
+}
+
+impl LanguageSwitcher {
+    /// Creates a switcher over the given languages, selecting the first one.
+    ///
+    /// Duplicate languages are ignored, keeping the first occurrence.
+    pub fn new(
+        available: impl IntoIterator<Item = LanguageIdentifier>,
+    ) -> Result<Self, SwitchError> {
+        let mut langs: Vec<LanguageIdentifier> = Vec::default();
+        for lang in available {
+            if !langs.contains(&lang) {
+                langs.push(lang);
+            }
+        }
+        let first = langs.first().cloned().ok_or(SwitchError::Empty)?;
+
+        Ok(Self {
+            available: langs,
+            current: RwLock::new(Arc::new((0, first))),
+            subscribers: RwLock::default(),
+            next_subscription: AtomicUsize::new(0),
+            notifying: Mutex::default(),
+        })
+    }
+
+    /// Creates a switcher over the languages listed by `langs!` or `Locales::langs`, selecting the first one.
+    pub fn from_langs(langs: impl IntoIterator<Item = Lang>) -> Result<Self, SwitchError> {
+        let langs = langs
+            .into_iter()
+            .map(|lang| parse_lang(&lang.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(langs)
+    }
+
+    /// Gets the available languages, in cycling order.
+    pub fn available(&self) -> &[LanguageIdentifier] {
+        &self.available
+    }
+
+    /// Gets the selected language.
+    pub fn current(&self) -> LanguageIdentifier {
+        self.snapshot().1.clone()
+    }
+
+    /// Selects a language, given either as a `LanguageIdentifier` or as a string.
+    ///
+    /// The callbacks are invoked only if the selection actually changed.
+    pub fn set(&self, lang: impl ToString) -> Result<(), SwitchError> {
+        let lang = parse_lang(&lang.to_string())?;
+        let idx = self
+            .available
+            .iter()
+            .position(|available| *available == lang)
+            .ok_or(SwitchError::Unknown { lang })?;
+        self.select(|_| idx);
+        Ok(())
+    }
+
+    /// Selects the next available language, wrapping around, and returns it.
+    pub fn next(&self) -> LanguageIdentifier {
+        let len = self.available.len();
+        self.select(|idx| (idx + 1) % len)
+    }
+
+    /// Selects the previous available language, wrapping around, and returns it.
+    pub fn prev(&self) -> LanguageIdentifier {
+        let len = self.available.len();
+        self.select(|idx| (idx + len - 1) % len)
+    }
+
+    /// Registers a callback invoked with the newly selected language whenever the selection changes.
+    ///
+    /// Callbacks are invoked on the thread that changed the selection, in registration order.
+    /// Changes made concurrently are serialized, so every callback sees them in the order they
+    /// were made and the last one it sees is the selected language. The selection itself can
+    /// still be read while callbacks run, but a callback must not change it, which would deadlock.
+    pub fn on_change<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&LanguageIdentifier) + Send + Sync + 'static,
+    {
+        let id = SubscriptionId(self.next_subscription.fetch_add(1, Ordering::Relaxed));
+        self.subscribers
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    /// Removes a callback registered with `LanguageSwitcher::on_change`.
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.subscribers
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .retain(|(existing, _)| *existing != id);
+    }
+
+    /// Gets the selected language along with its index.
+    fn snapshot(&self) -> Arc<(usize, LanguageIdentifier)> {
+        self.current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Selects the language at the index computed from the selected index, notifying the subscribers on change.
+    fn select(&self, f: impl FnOnce(usize) -> usize) -> LanguageIdentifier {
+        let _notifying = self.notifying.lock().unwrap_or_else(|err| err.into_inner());
+        let (changed, lang) = {
+            let mut current = self.current.write().unwrap_or_else(|err| err.into_inner());
+            let idx = f(current.0);
+            let changed = idx != current.0;
+            if changed {
+                *current = Arc::new((idx, self.available[idx].clone()));
+            }
+            (changed, current.1.clone())
+        };
+
+        if changed {
+            // invoke the callbacks outside of the locks of the selection and the subscribers, so
+            // that they may read the selection and register other callbacks
+            let subscribers = self
+                .subscribers
+                .read()
+                .unwrap_or_else(|err| err.into_inner())
+                .iter()
+                .map(|(_, callback)| callback.clone())
+                .collect::<Vec<_>>();
+            for callback in subscribers {
+                callback(&lang);
+            }
+        }
+        lang
+    }
+}
+
+impl Locales {
+    /// Creates a `LanguageSwitcher` over the languages of this collection, selecting the fallback language.
+    ///
+    /// The languages are sorted by their identifier, so that the cycling order is stable.
+    pub fn language_switcher(&self) -> Result<LanguageSwitcher, SwitchError> {
+        let mut langs = self.locale_map().keys().cloned().collect::<Vec<_>>();
+        langs.sort_by_key(ToString::to_string);
+        let switcher = LanguageSwitcher::new(langs)?;
+        switcher.set(self.fallback_lang())?;
+        Ok(switcher)
+    }
+}
+
+/// Parses a language identifier for the switcher.
+fn parse_lang(lang: &str) -> Result<LanguageIdentifier, SwitchError> {
+    lang.parse().map_err(|_| SwitchError::InvalidLangid {
+        lang: lang.to_string(),
+    })
+}
@@ -0,0 +1,36 @@
+use crate::{LanguageIdentifier, Locales, Message, Query, QueryError};
+
+/// A message known at compile time, implemented by the handles `load!` generates with
+/// `typed = pub mod messages`.
+///
+/// A handle is a struct named after the message, such as `messages::WelcomeBack` for
+/// `welcome-back`, with one field per argument of the message value. Messages without arguments
+/// get a unit struct. Building a handle therefore requires every argument, and renaming or
+/// removing a message breaks the code using it.
+pub trait MessageKey {
+    /// The ID of the message, e.g. `welcome-back`.
+    const ID: &'static str;
+    /// The names of the attributes of the message, across every embedded language.
+    const ATTRS: &'static [&'static str];
+    /// The names of the arguments of the message value, across every embedded language.
+    const ARGS: &'static [&'static str];
+
+    /// Converts the handle into a query for the message, with its fields as the arguments.
+    fn into_query<'a>(self) -> Query<'a>
+    where
+        Self: 'a;
+}
+
+impl Locales {
+    /// Queries for the message of a typed handle generated by `load!`, see `MessageKey`.
+    ///
+    /// The handle is converted with `MessageKey::into_query`, and the query is resolved like with
+    /// `Locales::query`.
+    pub fn query_typed<M: MessageKey>(
+        &self,
+        lang: &LanguageIdentifier,
+        args: M,
+    ) -> Result<Message, QueryError> {
+        self.query(lang, &args.into_query())
+    }
+}
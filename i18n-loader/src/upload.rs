@@ -0,0 +1,109 @@
+use crate::{Locales, MissEvent, NetError};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+/// The number of buffered misses that triggers an upload before `flush_every` elapses.
+const UPLOAD_THRESHOLD: usize = 256;
+
+/// A miss buffered for upload, along with when it happened.
+struct BufferedMiss {
+    event: MissEvent,
+    time: SystemTime,
+}
+
+/// Uploads misses to a server in batches, see `Locales::set_miss_uploader`.
+pub struct MissUploader {
+    /// The URL the batches are posted to.
+    url: String,
+    /// The misses recorded since the last upload.
+    buffer: Mutex<Vec<BufferedMiss>>,
+    /// Wakes the upload task up once the buffer reaches `UPLOAD_THRESHOLD`.
+    threshold_reached: Notify,
+}
+
+impl MissUploader {
+    /// Buffers a single miss, waking the upload task up if the buffer is full.
+    pub fn record(&self, event: &MissEvent) {
+        let mut buffer = self.buffer.lock().unwrap_or_else(|err| err.into_inner());
+        buffer.push(BufferedMiss {
+            event: event.clone(),
+            time: SystemTime::now(),
+        });
+        if buffer.len() >= UPLOAD_THRESHOLD {
+            self.threshold_reached.notify_one();
+        }
+    }
+
+    /// Uploads the buffered misses right away, e.g. on shutdown.
+    ///
+    /// # Errors
+    /// Returns a `NetError` if the upload fails, in which case the misses are dropped.
+    pub async fn flush(&self) -> Result<(), NetError> {
+        let batch = std::mem::take(&mut *self.buffer.lock().unwrap_or_else(|err| err.into_inner()));
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let body = serde_json::Value::Array(
+            batch
+                .into_iter()
+                .map(|miss| {
+                    let ts = miss
+                        .time
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as u64;
+                    serde_json::json!({
+                        "lang": miss.event.lang.to_string(),
+                        "id": miss.event.id,
+                        "attr": miss.event.attr,
+                        "ts": ts,
+                    })
+                })
+                .collect(),
+        );
+        crate::remote::post_json(&self.url, body.to_string().into_bytes()).await
+    }
+}
+
+impl Locales {
+    /// Sets a `MissUploader` as the miss reporter, which posts the misses to `url` in batches,
+    /// returning it.
+    ///
+    /// Every batch is a JSON array of `{"lang", "id", "attr", "ts"}` objects, `attr` being `null`
+    /// for a message and `ts` the time of the miss in milliseconds since the Unix epoch. A batch
+    /// is posted every `flush_every`, or sooner once enough misses are buffered, by a task spawned
+    /// on the current `tokio` runtime. Queries only ever buffer the miss, so a slow or failing
+    /// server never affects them; a failed upload drops its batch. Call `MissUploader::flush` on
+    /// shutdown to upload the misses buffered since the last batch.
+    ///
+    /// Replaces any previous miss reporter. The task stops once the uploader is dropped, i.e.
+    /// after `Locales::clear_miss_reporter` and dropping the returned handle.
+    ///
+    /// # Panics
+    /// Panics if called outside of a `tokio` runtime.
+    pub fn set_miss_uploader(&self, url: String, flush_every: Duration) -> Arc<MissUploader> {
+        let uploader = Arc::new(MissUploader {
+            url,
+            buffer: Default::default(),
+            threshold_reached: Notify::new(),
+        });
+        tokio::spawn(upload_periodically(Arc::downgrade(&uploader), flush_every));
+
+        let recorder = uploader.clone();
+        self.set_miss_reporter(move |event| recorder.record(event));
+        uploader
+    }
+}
+
+/// Flushes the uploader every `flush_every` or once its buffer is full, until it is dropped.
+async fn upload_periodically(uploader: Weak<MissUploader>, flush_every: Duration) {
+    while let Some(uploader) = uploader.upgrade() {
+        tokio::select! {
+            _ = tokio::time::sleep(flush_every) => {}
+            _ = uploader.threshold_reached.notified() => {}
+        }
+        _ = uploader.flush().await;
+    }
+}
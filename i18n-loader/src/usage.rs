@@ -0,0 +1,107 @@
+use crate::{AttrCache, LanguageIdentifier, Locales};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+/// The number of times a message was queried in a language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageEntry {
+    /// The language the message was queried in.
+    pub lang: LanguageIdentifier,
+    /// The ID of the message.
+    pub id: String,
+    /// The number of times the message was queried.
+    pub hits: u64,
+}
+
+/// Records how often every message is queried.
+///
+/// Once a message has been seen, recording it only takes a shared lock and an atomic increment.
+#[derive(Default)]
+pub(crate) struct UsageTracker {
+    /// The hit counters, keyed by language and message ID.
+    hits: RwLock<HashMap<LanguageIdentifier, HashMap<String, AtomicU64>>>,
+}
+
+impl UsageTracker {
+    /// Records a single query of the message `id` in the language `lang`.
+    pub(crate) fn record(&self, lang: &LanguageIdentifier, id: &str) {
+        {
+            let hits = self.hits.read().unwrap_or_else(|err| err.into_inner());
+            if let Some(counter) = hits.get(lang).and_then(|ids| ids.get(id)) {
+                counter.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        }
+
+        let mut hits = self.hits.write().unwrap_or_else(|err| err.into_inner());
+        hits.entry(lang.clone())
+            .or_default()
+            .entry(id.to_string())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Locales {
+    /// Enables recording how often every message is queried, per language.
+    ///
+    /// Usage tracking is cheap enough to be left on in production. Calling this more than once has no effect.
+    pub fn enable_usage_tracking(&self) {
+        self.usage.get_or_init(UsageTracker::default);
+    }
+
+    /// Returns the recorded usage, sorted by language and message ID.
+    ///
+    /// Returns an empty list if usage tracking is not enabled.
+    pub fn usage_snapshot(&self) -> Vec<UsageEntry> {
+        let Some(usage) = self.usage.get() else {
+            return Vec::default();
+        };
+
+        let hits = usage.hits.read().unwrap_or_else(|err| err.into_inner());
+        let mut entries = hits
+            .iter()
+            .flat_map(|(lang, ids)| {
+                ids.iter().map(|(id, counter)| UsageEntry {
+                    lang: lang.clone(),
+                    id: id.clone(),
+                    hits: counter.load(Ordering::Relaxed),
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| (a.lang.to_string(), &a.id).cmp(&(b.lang.to_string(), &b.id)));
+
+        entries
+    }
+
+    /// Clears the recorded usage, e.g. after it has been exported.
+    pub fn reset_usage(&self) {
+        if let Some(usage) = self.usage.get() {
+            usage
+                .hits
+                .write()
+                .unwrap_or_else(|err| err.into_inner())
+                .clear();
+        }
+    }
+
+    /// Records a lazily resolved attribute query as a use of its parent message.
+    ///
+    /// The usage is attributed to the language of the bundle the attribute is resolved against.
+    pub fn record_attr_usage(&self, attr: &AttrCache) {
+        if let (Some(usage), Some(lang)) = (self.usage.get(), attr.bundle.locales.first()) {
+            usage.record(lang, &attr.entry_id);
+        }
+    }
+
+    /// Records a query of the message `id` in the language `lang`, if usage tracking is enabled.
+    ///
+    /// `id` is the message as queried, before a variant or an attribute is picked, so that every
+    /// query is attributed to the message it names.
+    pub(crate) fn record_usage(&self, lang: &LanguageIdentifier, id: &str) {
+        if let Some(usage) = self.usage.get() {
+            usage.record(lang, id);
+        }
+    }
+}
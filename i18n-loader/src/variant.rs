@@ -0,0 +1,117 @@
+use crate::{FluentArgs, LanguageIdentifier, Locale, LocaleMap, Locales, Query};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// The separator between the ID of a message and the name of one of its variants, as in `signup-cta__variant-b`.
+///
+/// Fluent identifiers may only contain letters, digits, `-` and `_`, so the separator is a double underscore.
+pub const VARIANT_SEPARATOR: &str = "__";
+
+/// A callback picking one of the variant names of a message by its index, or declining with `None`.
+type SelectorFn<'a> = Arc<dyn Fn(&[&str]) -> Option<usize> + Send + Sync + 'a>;
+
+/// The variant selector of a `Query`, shared by its clones.
+#[derive(Clone)]
+pub(crate) struct VariantSelector<'a>(pub(crate) SelectorFn<'a>);
+
+impl fmt::Debug for VariantSelector<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("VariantSelector")
+    }
+}
+
+impl Locales {
+    /// Gets the variant names of the message `id`, across every language and layer, sorted and without duplicates.
+    pub fn variants_of(&self, id: &str) -> Vec<String> {
+        let overlays = self.overlays();
+        let mut variants = std::iter::once(&self.locale_map())
+            .chain(overlays.iter())
+            .flat_map(|layer| layer.values())
+            .filter_map(|locale| locale.variants.get(id))
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        variants.sort();
+        variants.dedup();
+        variants
+    }
+
+    /// Retargets the query to the variant picked by its selector, if the locale resolving the
+    /// message defines that variant.
+    pub(crate) fn variant_query<'q>(
+        &self,
+        locales: &LocaleMap,
+        overlays: &[Arc<LocaleMap>],
+        lang: &LanguageIdentifier,
+        query: &'q Query,
+    ) -> Option<Query<'q>> {
+        let selector = query.variant_selector.as_ref()?;
+        let variants = self.variants_of(&query.id);
+        if variants.is_empty() {
+            return None;
+        }
+
+        let names = variants.iter().map(String::as_str).collect::<Vec<_>>();
+        let name = names.get((selector.0)(&names)?)?;
+        let id = format!("{}{VARIANT_SEPARATOR}{name}", query.id);
+        self.resolving_locale(locales, overlays, lang, query)
+            .filter(|locale| locale.bundle.has_message(&id))
+            .map(|_| query.retarget(id))
+    }
+}
+
+impl<'a> Query<'a> {
+    /// Copies this query for the message `id`, borrowing its arguments.
+    fn retarget(&self, id: String) -> Query<'_> {
+        Query {
+            id: id.into(),
+            args: borrow_args(&self.args),
+            attr_args: self
+                .attr_args
+                .iter()
+                .map(|(attr, args)| (attr.as_ref().into(), borrow_args(args)))
+                .collect(),
+            with_fallback: self.with_fallback,
+            select_args: self.select_args.clone(),
+            variant_selector: None,
+            strict_args: self.strict_args,
+            duplicate_args: self.duplicate_args.clone(),
+            raw_args: self.raw_args.clone(),
+            propagate_args: self.propagate_args,
+        }
+    }
+}
+
+/// Copies arguments, borrowing their names.
+pub(crate) fn borrow_args<'b>(args: &'b FluentArgs) -> FluentArgs<'b> {
+    let mut borrowed = FluentArgs::with_capacity(args.iter().count());
+    for (key, value) in args.iter() {
+        borrowed.set(key, value.clone());
+    }
+    borrowed
+}
+
+/// Collects the variant names of every message defined by a locale, keyed by the message ID.
+pub(crate) fn collect_variants<'e>(
+    ids: impl Iterator<Item = &'e str>,
+) -> HashMap<String, Vec<String>> {
+    let mut variants: HashMap<String, Vec<String>> = HashMap::new();
+    for id in ids {
+        if let Some((base, name)) = id.split_once(VARIANT_SEPARATOR) {
+            variants
+                .entry(base.to_string())
+                .or_default()
+                .push(name.to_string());
+        }
+    }
+
+    variants
+}
+
+impl Locale {
+    /// Gets the variant names of the message `id` defined by this locale, in source order.
+    pub fn variants(&self, id: &str) -> &[String] {
+        self.variants.get(id).map(Vec::as_slice).unwrap_or_default()
+    }
+}
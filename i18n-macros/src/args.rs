@@ -0,0 +1,87 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Path, Result};
+
+use crate::crate_path::default_crate_path;
+
+pub fn derive_into_query_args_impl(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let mut krate = default_crate_path();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("arg"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate_path") {
+                krate = meta.value()?.parse::<Path>()?;
+                Ok(())
+            } else {
+                Err(meta.error("Unexpected attribute. Expected `#[arg(crate_path = PATH)]`."))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => &Default::default(),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`IntoQueryArgs` can only be derived for structs with named fields.",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`IntoQueryArgs` can only be derived for structs with named fields.",
+            ))
+        }
+    };
+
+    let mut args = Vec::default();
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().expect("named fields have an ident");
+        let mut name = ident.to_string().trim_start_matches("r#").replace('_', "-");
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("arg"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("Unexpected attribute. Expected `#[arg(rename = \"name\")]`."))
+                }
+            })?;
+        }
+        args.push(quote! {
+            if let ::core::option::Option::Some(value) = #krate::ArgValue::arg_value(&self.#ident) {
+                args.set(#name, value);
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let capacity = args.len();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::IntoQueryArgs for #ident #ty_generics #where_clause {
+            fn into_args(&self) -> #krate::FluentArgs<'_> {
+                let mut args = #krate::FluentArgs::with_capacity(#capacity);
+                #(#args)*
+                args
+            }
+        }
+    })
+}
@@ -4,16 +4,28 @@ use quote::quote;
 use std::collections::HashMap;
 use syn::{
     parse::{Parse, ParseStream, Result},
-    Expr, Ident, LitStr, Token,
+    Expr, Ident, LitStr, Path, Token,
 };
 
+use crate::crate_path::default_crate_path;
+use crate::tr::duplicate_arg;
+
 // Simplified representation of arguments
 type Args = HashMap<String, Expr>;
 
+// The optional arguments of the macro
+struct OptionalArgs {
+    args: Args,
+    locales: Path,
+    krate: Path,
+    replace: bool,
+}
+
 // A helper function to parse the optional arguments, including `locales`.
-fn parse_optional_args(input: ParseStream) -> Result<(Args, Ident, bool)> {
+fn parse_optional_args(input: ParseStream) -> Result<OptionalArgs> {
     let mut args = Args::new();
     let mut locales_var = None;
+    let mut krate = default_crate_path();
     let mut replace_var = false;
 
     while !input.is_empty() {
@@ -29,8 +41,12 @@ fn parse_optional_args(input: ParseStream) -> Result<(Args, Ident, bool)> {
                 input.parse::<Token![=]>()?;
                 locales_var = Some(input.parse()?);
                 continue;
+            } else if key == "crate_path" {
+                input.parse::<Token![=]>()?;
+                krate = input.parse()?;
+                continue;
             } else {
-                return Err(syn::Error::new(key.span(), "Unexpected identifier. Only `locales` and `replace` are supported as keyword arguments."));
+                return Err(syn::Error::new(key.span(), "Unexpected identifier. Only `locales`, `crate_path` and `replace` are supported as keyword arguments."));
             }
         } else if input.peek(Ident) { // Check for `replace` keyword
             let key: Ident = input.parse()?;
@@ -38,7 +54,7 @@ fn parse_optional_args(input: ParseStream) -> Result<(Args, Ident, bool)> {
                 replace_var = true;
                 continue;
             } else {
-                return Err(syn::Error::new(key.span(), "Unexpected identifier. Only `locales` and `replace` are supported as keyword arguments."));
+                return Err(syn::Error::new(key.span(), "Unexpected identifier. Only `locales`, `crate_path` and `replace` are supported as keyword arguments."));
             }
         }
 
@@ -46,18 +62,21 @@ fn parse_optional_args(input: ParseStream) -> Result<(Args, Ident, bool)> {
         let key: LitStr = input.parse()?;
         input.parse::<Token![=]>()?;
         let value: Expr = input.parse()?;
-        args.insert(key.value(), value);
+        if args.insert(key.value(), value).is_some() {
+            return Err(duplicate_arg(&key));
+        }
     }
     
-    let locales = locales_var.unwrap_or_else(|| Ident::new("LOCALES", Span::call_site()));
-    Ok((args, locales, replace_var))
+    let locales = locales_var.unwrap_or_else(|| Path::from(Ident::new("LOCALES", Span::call_site())));
+    Ok(OptionalArgs { args, locales, krate, replace: replace_var })
 }
 
 pub struct AttrMacroInput {
     from: Expr,
     attr: LitStr,
     args: Args,
-    locales: Ident,
+    locales: Path,
+    krate: Path,
     replace: bool,
 }
 
@@ -67,14 +86,14 @@ impl Parse for AttrMacroInput {
         input.parse::<Token![,]>()?;
         let attr: LitStr = input.parse()?;
 
-        let (args, locales, replace) = parse_optional_args(input)?;
+        let OptionalArgs { args, locales, krate, replace } = parse_optional_args(input)?;
 
-        Ok(AttrMacroInput { from, attr, args, locales, replace })
+        Ok(AttrMacroInput { from, attr, args, locales, krate, replace })
     }
 }
 
 pub fn attr_impl(input: TokenStream) -> TokenStream {
-    let AttrMacroInput { from, attr, args, locales, replace } = match syn::parse(input) {
+    let AttrMacroInput { from, attr, args, locales, krate, replace } = match syn::parse(input) {
         Ok(input) => input,
         Err(err) => return err.to_compile_error().into(),
     };
@@ -82,9 +101,9 @@ pub fn attr_impl(input: TokenStream) -> TokenStream {
     let (args_creation, args_variable) = if args.is_empty() {
         (quote! {}, quote! { None })
     } else {
-        let mut stmts = quote! { let mut fluent_args = i18n::FluentArgs::new(); };
+        let mut stmts = quote! { let mut fluent_args = #krate::FluentArgs::new(); };
         for (key, value) in args.into_iter() {
-            stmts.extend(quote! { fluent_args.set(#key, i18n::FluentValue::from(#value)); });
+            stmts.extend(quote! { fluent_args.set(#key, #krate::FluentValue::from(#value)); });
         }
         (stmts, quote! { Some(&fluent_args) })
     };
@@ -94,9 +113,12 @@ pub fn attr_impl(input: TokenStream) -> TokenStream {
             #args_creation
             let args = #args_variable;
             let query_result = match #from.attrs.get_mut(#attr) {
-                Some(attr_cache) => attr_cache.query(args, #replace),
-                None => Err(vec![i18n::FluentError::ResolverError(
-                    i18n::ResolverError::Reference(i18n::ReferenceKind::Message {
+                Some(attr_cache) => {
+                    #locales.record_attr_usage(attr_cache);
+                    attr_cache.query(args, #replace)
+                }
+                None => Err(vec![#krate::FluentError::ResolverError(
+                    #krate::ResolverError::Reference(#krate::ReferenceKind::Message {
                         id: #from.id.clone(),
                         attribute: Some(#attr.to_string()),
                     }),
@@ -105,7 +127,12 @@ pub fn attr_impl(input: TokenStream) -> TokenStream {
             match query_result {
                 Ok(s) => s,
                 Err(errs) => {
-                    #locales.call_on_error(&errs);
+                    #locales.call_on_error(&#krate::QueryError {
+                        lang: #from.resolved_lang.clone(),
+                        message_id: #from.id.clone(),
+                        fallback_attempted: false,
+                        errors: #krate::QueryErrors::for_attr(#attr, errs),
+                    });
                     #attr.to_string()
                 }
             }
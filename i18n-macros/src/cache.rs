@@ -0,0 +1,311 @@
+use fluent_syntax::ast::Entry;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::typed::{collect_pattern, PatternRefs};
+
+/// The version of the cache format, bumped whenever the information in `FileSummary` changes.
+const FORMAT_VERSION: u32 = 4;
+
+/// What `load!` needs to know about a parsed `.ftl` file, short of its AST.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileSummary {
+    /// The IDs of the messages, in source order.
+    pub messages: Vec<String>,
+    /// The IDs of the terms, without the leading `-`, in source order.
+    pub terms: Vec<String>,
+    /// The variables referenced by the value and by every attribute of the messages, in source
+    /// order, keyed by the message ID or by a dotted ID such as `login-btn.aria-label`.
+    pub vars: Vec<(String, Vec<String>)>,
+    /// The attributes of every message, in source order.
+    pub attrs: Vec<(String, Vec<String>)>,
+    /// The messages and terms referenced by every message and term, in source order, keyed by
+    /// the ID (`-brand` for a term) and referenced as `welcome`, `welcome.title` or `-brand`.
+    pub refs: Vec<(String, Vec<String>)>,
+    /// The junk entries, formatted for the compile error.
+    pub junk: Vec<String>,
+    /// The parser errors, formatted for the compile error.
+    pub parse_errors: Vec<String>,
+}
+
+impl FileSummary {
+    /// Parses `source` and summarizes it.
+    pub fn parse(source: &str) -> Self {
+        let (resource, parse_errors) = match fluent_syntax::parser::parse(source) {
+            Ok(resource) => (resource, Vec::default()),
+            Err((resource, errs)) => (resource, errs),
+        };
+        let mut summary = FileSummary {
+            junk: i18n_loader::junk_entries(source, resource.body.iter())
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            parse_errors: parse_errors.iter().map(|err| format!("{err:?}")).collect(),
+            ..Default::default()
+        };
+        for entry in resource.body.iter() {
+            match entry {
+                Entry::Message(msg) => {
+                    let id = msg.id.name;
+                    let patterns = msg.value.iter().map(|value| (id.to_string(), value)).chain(
+                        msg.attributes
+                            .iter()
+                            .map(|attr| (format!("{id}.{}", attr.id.name), &attr.value)),
+                    );
+                    let mut entry_refs = Vec::default();
+                    for (key, pattern) in patterns {
+                        let mut refs = PatternRefs::default();
+                        collect_pattern(pattern, &mut refs);
+                        summary.vars.push((key, refs.vars.into_iter().collect()));
+                        entry_refs.extend(format_refs(refs.messages, refs.terms));
+                    }
+                    summary.refs.push((id.to_string(), entry_refs));
+                    summary.messages.push(id.to_string());
+                    summary.attrs.push((
+                        id.to_string(),
+                        msg.attributes
+                            .iter()
+                            .map(|attr| attr.id.name.to_string())
+                            .collect(),
+                    ));
+                }
+                Entry::Term(term) => {
+                    let mut entry_refs = Vec::default();
+                    let patterns = std::iter::once(&term.value)
+                        .chain(term.attributes.iter().map(|attr| &attr.value));
+                    for pattern in patterns {
+                        let mut refs = PatternRefs::default();
+                        collect_pattern(pattern, &mut refs);
+                        entry_refs.extend(format_refs(refs.messages, refs.terms));
+                    }
+                    summary
+                        .refs
+                        .push((format!("-{}", term.id.name), entry_refs));
+                    summary.terms.push(term.id.name.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        summary
+    }
+}
+
+/// Formats the references of a pattern as listed by `FileSummary::refs`.
+fn format_refs(
+    messages: BTreeSet<(String, Option<String>)>,
+    terms: BTreeSet<String>,
+) -> impl Iterator<Item = String> {
+    let messages = messages.into_iter().map(|(id, attr)| match attr {
+        Some(attr) => format!("{id}.{attr}"),
+        None => id,
+    });
+    messages.chain(terms.into_iter().map(|id| format!("-{id}")))
+}
+
+/// An on-disk cache of `FileSummary`s shared by every `load!` invocation of a build, so that
+/// several crates loading the same directory only parse each file once.
+///
+/// Entries are keyed by the absolute path of the file along with its modification time and size,
+/// and additionally verified against a hash of the source, so an edit is always picked up.
+/// The cache is invalidated whenever the version of this crate or of the format changes.
+pub struct ParseCache {
+    dir: PathBuf,
+}
+
+impl ParseCache {
+    /// Opens the cache in `i18n-macro-cache` within the target directory of the build.
+    ///
+    /// The target directory is `CARGO_TARGET_DIR` if set, or else the closest `target` directory
+    /// above the crate created by cargo. Falls back to `OUT_DIR`, which is only shared by the
+    /// invocations within a single crate. Returns `None` if no directory can be found or created.
+    pub fn open() -> Option<Self> {
+        let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .or_else(|| {
+                let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?);
+                manifest_dir
+                    .ancestors()
+                    .map(|dir| dir.join("target"))
+                    .find(|dir| dir.join("CACHEDIR.TAG").is_file())
+            })
+            .or_else(|| std::env::var_os("OUT_DIR").map(PathBuf::from))?;
+
+        Self::in_dir(target_dir.join("i18n-macro-cache"))
+    }
+
+    /// Opens the cache in `dir`, creating it if needed.
+    pub fn in_dir(dir: PathBuf) -> Option<Self> {
+        std::fs::create_dir_all(&dir).ok()?;
+        Some(ParseCache { dir })
+    }
+
+    /// Gets the summary of the file at `path` with the contents `source`, parsing it on a miss.
+    pub fn summarize(&self, path: &Path, source: &str) -> FileSummary {
+        let key = CacheKey::new(path, source);
+        if let Some(summary) = key.as_ref().and_then(|key| self.get(key)) {
+            return summary;
+        }
+
+        let summary = FileSummary::parse(source);
+        if let Some(key) = key {
+            // a failure to write only costs a parse in the next invocation
+            _ = self.put(&key, &summary);
+        }
+        summary
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}", fnv1a(key.path.as_bytes())))
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<FileSummary> {
+        let contents = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        let mut lines = contents.lines();
+        if lines.next()? != key.header() {
+            return None;
+        }
+
+        let mut summary = FileSummary::default();
+        for line in lines {
+            let (kind, value) = line.split_once('\t')?;
+            if let Some(lists) = match kind {
+                "vars" => Some(&mut summary.vars),
+                "attrs" => Some(&mut summary.attrs),
+                "refs" => Some(&mut summary.refs),
+                _ => None,
+            } {
+                let mut values = value.split('\t').map(unescape);
+                lists.push((values.next()?, values.collect()));
+                continue;
+            }
+            let value = unescape(value);
+            match kind {
+                "message" => summary.messages.push(value),
+                "term" => summary.terms.push(value),
+                "junk" => summary.junk.push(value),
+                "error" => summary.parse_errors.push(value),
+                _ => return None,
+            }
+        }
+
+        Some(summary)
+    }
+
+    fn put(&self, key: &CacheKey, summary: &FileSummary) -> std::io::Result<()> {
+        let mut contents = key.header();
+        let lines = [
+            ("message", &summary.messages),
+            ("term", &summary.terms),
+            ("junk", &summary.junk),
+            ("error", &summary.parse_errors),
+        ];
+        for (kind, values) in lines {
+            for value in values {
+                contents.push('\n');
+                contents.push_str(kind);
+                contents.push('\t');
+                contents.push_str(&escape(value));
+            }
+        }
+        let lists = [
+            ("vars", &summary.vars),
+            ("attrs", &summary.attrs),
+            ("refs", &summary.refs),
+        ];
+        for (kind, lists) in lists {
+            for (id, values) in lists {
+                contents.push('\n');
+                contents.push_str(kind);
+                contents.push('\t');
+                contents.push_str(&escape(id));
+                for value in values {
+                    contents.push('\t');
+                    contents.push_str(&escape(value));
+                }
+            }
+        }
+
+        // write to a temporary file first, as other crates may be reading the entry concurrently
+        let path = self.entry_path(key);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, &path)
+    }
+}
+
+/// Identifies a version of a file.
+struct CacheKey {
+    path: String,
+    modified: u128,
+    size: u64,
+    hash: u64,
+}
+
+impl CacheKey {
+    fn new(path: &Path, source: &str) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+
+        Some(CacheKey {
+            path: path.to_str()?.to_string(),
+            modified,
+            size: metadata.len(),
+            hash: fnv1a(source.as_bytes()),
+        })
+    }
+
+    /// The first line of the entry, which must match for the entry to be used.
+    fn header(&self) -> String {
+        format!(
+            "i18n-macros {} {FORMAT_VERSION}\t{}\t{}\t{}\t{:016x}",
+            env!("CARGO_PKG_VERSION"),
+            escape(&self.path),
+            self.modified,
+            self.size,
+            self.hash
+        )
+    }
+}
+
+/// The 64-bit FNV-1a hash, which is stable across toolchains unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    unescaped
+}
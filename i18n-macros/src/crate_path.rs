@@ -0,0 +1,9 @@
+use syn::Path;
+
+/// The path through which the generated code names the items of the facade crate.
+///
+/// Overridden with `crate_path = ::my_i18n` when the dependency is renamed, or with
+/// `crate_path = ::i18n_loader` when depending on `i18n-loader` and `i18n-macros` directly.
+pub fn default_crate_path() -> Path {
+    syn::parse_quote!(::i18n)
+}
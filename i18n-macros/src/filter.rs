@@ -0,0 +1,135 @@
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse::{ParseStream, Result};
+use syn::{bracketed, punctuated::Punctuated, LitStr, Token};
+use unic_langid::LanguageIdentifier;
+
+/// An allow-list of the locales to embed, shared by the macros that scan locale directories.
+///
+/// The list is given either inline (`locales = ["en-US", "hr-HR"]`) or through an environment
+/// variable read at compile time (`locales_env = "APP_LOCALES"`), which takes precedence when set.
+#[derive(Default)]
+pub struct LocaleFilter {
+    /// The inline allow-list.
+    locales: Option<(Span, Vec<LitStr>)>,
+    /// The name of the environment variable holding a comma-separated allow-list.
+    locales_env: Option<LitStr>,
+}
+
+/// A resolved `LocaleFilter`.
+pub struct ResolvedFilter {
+    /// The allowed languages and the span to report errors about them at, or `None` if every locale is allowed.
+    allowed: Option<(Span, Vec<LanguageIdentifier>)>,
+    /// The name of the environment variable the allow-list was read from, if any.
+    env: Option<String>,
+}
+
+impl LocaleFilter {
+    /// Parses the value of the `key` parameter if it belongs to the filter, returning whether it did.
+    pub fn parse_param(&mut self, key: &str, input: ParseStream) -> Result<bool> {
+        match key {
+            "locales" => {
+                let content;
+                let bracket = bracketed!(content in input);
+                let locales = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                self.locales = Some((bracket.span.join(), locales.into_iter().collect()));
+            }
+            "locales_env" => self.locales_env = Some(input.parse()?),
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Resolves the allow-list, reading the environment variable if one is configured.
+    pub fn resolve(&self) -> Result<ResolvedFilter> {
+        let from_env = self.locales_env.as_ref().and_then(|name| {
+            let value = std::env::var(name.value()).ok()?;
+            let locales = value
+                .split(',')
+                .map(str::trim)
+                .filter(|lang| !lang.is_empty())
+                .map(|lang| LitStr::new(lang, name.span()))
+                .collect::<Vec<_>>();
+            (!locales.is_empty()).then_some((name.span(), locales))
+        });
+
+        let allowed = match from_env.as_ref().or(self.locales.as_ref()) {
+            Some((span, locales)) => {
+                let langs = locales
+                    .iter()
+                    .map(|lang| {
+                        lang.value().parse::<LanguageIdentifier>().map_err(|err| {
+                            syn::Error::new(
+                                lang.span(),
+                                format!("Invalid language identifier '{}': {err}", lang.value()),
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Some((*span, langs))
+            }
+            None => None,
+        };
+
+        Ok(ResolvedFilter {
+            allowed,
+            env: self.locales_env.as_ref().map(LitStr::value),
+        })
+    }
+}
+
+impl ResolvedFilter {
+    /// Whether the locale in the directory `dir_name` should be embedded.
+    pub fn includes(&self, dir_name: &str) -> bool {
+        let Some((_, allowed)) = &self.allowed else {
+            return true;
+        };
+
+        dir_name
+            .parse::<LanguageIdentifier>()
+            .is_ok_and(|lang| allowed.contains(&lang))
+    }
+
+    /// Ensures that every allowed language exists among `dir_names` and that `required` is allowed.
+    pub fn verify(&self, dir_names: &[String], required: Option<&str>) -> Result<()> {
+        let Some((span, allowed)) = &self.allowed else {
+            return Ok(());
+        };
+
+        let found = dir_names
+            .iter()
+            .filter_map(|name| name.parse::<LanguageIdentifier>().ok())
+            .collect::<Vec<_>>();
+        let missing = allowed
+            .iter()
+            .filter(|lang| !found.contains(lang))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(syn::Error::new(
+                *span,
+                format!("Unknown locales in the allow-list: {}", missing.join(", ")),
+            ));
+        }
+
+        if let Some(required) = required.filter(|lang| !self.includes(lang)) {
+            return Err(syn::Error::new(
+                *span,
+                format!("The fallback language '{required}' must be included in the allow-list"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Makes the compiler rebuild the crate whenever the allow-list environment variable changes.
+    pub fn env_tracker(&self) -> proc_macro2::TokenStream {
+        match &self.env {
+            Some(env) => quote! {
+                const _: Option<&str> = option_env!(#env);
+            },
+            None => quote! {},
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use quote::quote;
+use syn::{LitStr, Path};
+
+/// Emits a compile-time check that the message `id` exists in the locales static at `locales`.
+///
+/// `load!` attaches the known message IDs as associated constants of the static's type, so the
+/// check resolves through any path or `use` alias of the static, including across crates.
+/// The check only fails if the static was generated with `validate_keys = true`. With `warn_only`,
+/// an unknown ID is reported as a compile-time warning instead of an error.
+pub fn key_check(
+    krate: &Path,
+    locales: &Path,
+    id: &LitStr,
+    warn_only: bool,
+) -> proc_macro2::TokenStream {
+    let locales_str = quote!(#locales).to_string().replace(' ', "");
+    let msg = format!(
+        "i18n: unknown message id '{}' in `{locales_str}`",
+        id.value()
+    );
+    if warn_only {
+        // the deprecated method is only selected if the condition evaluates to `true`
+        return quote! {
+            const _: () = {
+                struct Check<const UNKNOWN: bool>;
+                impl Check<true> {
+                    #[deprecated(note = #msg)]
+                    const fn warn() {}
+                }
+                impl Check<false> {
+                    const fn warn() {}
+                }
+                Check::<{
+                    #locales::__I18N_VALIDATE_KEYS && !#krate::__contains_key(#locales::__I18N_KEYS, #id)
+                }>::warn();
+            };
+        };
+    }
+
+    quote! {
+        const _: () = {
+            if #locales::__I18N_VALIDATE_KEYS && !#krate::__contains_key(#locales::__I18N_KEYS, #id) {
+                panic!(#msg);
+            }
+        };
+    }
+}
@@ -1,6 +1,68 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::LitStr;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Ident, LitBool, LitStr, Token};
+use unic_langid::LanguageIdentifier;
+
+use crate::crate_path::default_crate_path;
+
+use crate::filter::LocaleFilter;
+use crate::load::DEFAULT_COMMON_NAME;
+use crate::paths::LocalesPath;
+use crate::scan::DirScanner;
+
+struct LangsMacroInput {
+    path: LitStr,
+    follow_symlinks: bool,
+    from_workspace_root: bool,
+    filter: LocaleFilter,
+    krate: syn::Path,
+}
+
+impl Parse for LangsMacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path: LitStr = input.parse().map_err(|err| {
+            let msg = "Expected a path to the locales directory, relative to your crate root (where Cargo.toml is).";
+            syn::Error::new(err.span(), msg)
+        })?;
+
+        let mut follow_symlinks = false;
+        let mut from_workspace_root = false;
+        let mut filter = LocaleFilter::default();
+        let mut krate = default_crate_path();
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let key_str = key.to_string();
+            if filter.parse_param(&key_str, input)? {
+                continue;
+            }
+
+            match key_str.as_str() {
+                "follow_symlinks" => follow_symlinks = input.parse::<LitBool>()?.value(),
+                "from_workspace_root" => from_workspace_root = input.parse::<LitBool>()?.value(),
+                "crate_path" => krate = input.parse()?,
+                _ => return Err(syn::Error::new(
+                    key.span(),
+                    "Unexpected parameter, expected 'follow_symlinks', 'from_workspace_root', 'locales', 'locales_env', or 'crate_path'",
+                )),
+            }
+        }
+
+        Ok(LangsMacroInput {
+            path,
+            follow_symlinks,
+            from_workspace_root,
+            filter,
+            krate,
+        })
+    }
+}
 
 /// Extracts all used languages from the given locale path.
 pub fn langs_impl(input: TokenStream) -> TokenStream {
@@ -11,70 +73,106 @@ pub fn langs_impl(input: TokenStream) -> TokenStream {
             .into();
     }
 
-    let input_path: LitStr = match syn::parse(input) {
+    let LangsMacroInput {
+        path: input_path,
+        follow_symlinks,
+        from_workspace_root,
+        filter,
+        krate,
+    } = match syn::parse(input) {
         Ok(input) => input,
-        Err(err) => {
-            let msg = "Expected a path to the locales directory, relative to your crate root (where Cargo.toml is).";
-            return syn::Error::new(err.span(), msg).to_compile_error().into();
-        }
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let filter = match filter.resolve() {
+        Ok(filter) => filter,
+        Err(err) => return err.to_compile_error().into(),
     };
     let path = input_path.value();
 
-    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-    let mut absolute_path = std::path::PathBuf::from(manifest_dir);
-    absolute_path.push(&path);
+    let locales_path = match LocalesPath::resolve(&input_path, from_workspace_root) {
+        Ok(locales_path) => locales_path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let mut scanner = DirScanner::new(&locales_path.root, follow_symlinks);
+    let absolute_path = &locales_path.absolute;
 
     // Read directories in the specified path
-    let langs = std::fs::read_dir(&absolute_path)
-        .expect("Failed to read directory")
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-
-            // Check if it's a directory
-            if !path.is_dir() {
-                return None;
-            }
+    let entries = match scanner.read_dir(absolute_path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            return syn::Error::new(
+                input_path.span(),
+                format!("Expected directory '{path}' ({absolute_path:?}): {err}"),
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+    let locale_names = entries
+        .iter()
+        .filter(|entry| entry.locale_name() != Some(DEFAULT_COMMON_NAME))
+        .filter_map(|entry| entry.canonical_locale())
+        .collect::<std::result::Result<Vec<_>, _>>();
+    let mut locale_names = match locale_names {
+        Ok(locale_names) => locale_names,
+        Err(msg) => {
+            return syn::Error::new(input_path.span(), format!("{msg} (in '{path}')"))
+                .to_compile_error()
+                .into()
+        }
+    };
+    // a locale directory and a flat file of the same locale are adjacent once sorted
+    locale_names.sort();
+    locale_names.dedup();
+    if let Err(err) = filter.verify(&locale_names, None) {
+        return err.to_compile_error().into();
+    }
 
-            // Extract language ID from directory name
-            let dir_name = path.file_name()?.to_str()?.to_string();
-            let splitter = if dir_name.contains('_') {
-                "_".to_string()
-            } else if dir_name.contains('-') {
-                "-".to_string()
-            } else {
-                dir_name.clone()
-            };
-            let mut parts = dir_name.split(&splitter);
-            let langid = parts
-                .next()
-                .map(str::to_lowercase)
-                .expect("should always be present");
-            let region = parts.next().map(str::to_uppercase);
-            let full_langid = if let Some(region) = &region {
-                format!("{}-{}", langid, region)
-            } else {
-                langid.clone()
-            };
-            let name = i18n_lang::langid_to_name(&langid);
-            let flag = region.map(|region| i18n_lang::langid_to_flag(&region));
-            let dir = i18n_lang::langid_to_dir(&langid);
-
-            Some(quote! {
-                i18n::Lang {
-                    id: #full_langid.to_string(),
-                    name: #name,
-                    flag: #flag,
-                    dir: #dir,
-                }
-            })
-        })
+    let langs = locale_names
+        .into_iter()
+        // Check if it's an included locale
+        .filter(|dir_name| filter.includes(dir_name))
+        .map(|dir_name| lang_expr(&krate, &dir_name).1)
         .collect::<Vec<_>>();
 
     // Generate the token stream representing the array of Lang instances
+    let skipped_note = scanner.skipped_note();
+    let env_tracker = filter.env_tracker();
+    let path_env_tracker = locales_path.env_tracker();
     let expanded = quote! {
-        [#(#langs),*]
+        {
+            #skipped_note
+            #env_tracker
+            #path_env_tracker
+            [#(#langs),*]
+        }
     };
 
     TokenStream::from(expanded)
 }
+
+/// The `Lang` of the locale named `locale`, as an expression building it along with its ID.
+pub fn lang_expr(krate: &syn::Path, locale: &str) -> (String, proc_macro2::TokenStream) {
+    // the locale is canonical, e.g. `hr-HR` or `sr-Latn-RS`
+    let full_langid = locale.to_string();
+    let lang = locale
+        .parse::<LanguageIdentifier>()
+        .expect("should be a canonical locale");
+    let langid = lang.language.as_str();
+    let name = i18n_lang::langid_to_name(langid);
+    let flag = lang
+        .region
+        .and_then(|region| i18n_lang::langid_to_flag(region.as_str()))
+        .unwrap_or_default();
+    let dir = i18n_lang::langid_to_dir(langid);
+
+    let expr = quote! {
+        #krate::Lang {
+            id: #full_langid.to_string(),
+            name: #name,
+            flag: #flag,
+            dir: #dir,
+        }
+    };
+    (full_langid, expr)
+}
@@ -1,17 +1,40 @@
+mod args;
 mod attr;
+mod cache;
+mod crate_path;
+mod filter;
+mod keys;
 mod langs;
 mod load;
+mod paths;
+mod report;
+mod scan;
 mod tr;
+mod tr_batch;
+mod tr_typed;
+mod typed;
 
 use proc_macro::TokenStream;
 
 /// Extracts language information from a specified directory.
 ///
-/// This macro reads the subdirectories of the given path, treating each subdirectory
-/// as a language ID. It then generates a `[i18n::Lang; ...]` array containing
+/// This macro reads the subdirectories and `.ftl` files of the given path, treating the name of
+/// each subdirectory and the stem of each file as a language ID, as laid out for `load!`. It then
+/// generates a `[i18n::Lang; ...]` array containing
 /// metadata for each found language (ID, name, flag, direction).
 ///
-/// The path should be relative to your crate root (where Cargo.toml is).
+/// The path should be relative to your crate root (where Cargo.toml is), or to the workspace
+/// root with `from_workspace_root = true`. It may reference environment variables as in `load!`.
+///
+/// Hidden entries, non-UTF-8 names and symlinks are skipped and reported in a single
+/// compile-time warning. Pass `follow_symlinks = true` to follow symlinks that stay within
+/// the crate root.
+///
+/// Pass `locales = ["en-US", "hr-HR"]` to only list some of the languages, or
+/// `locales_env = "APP_LOCALES"` to read a comma-separated allow-list from an environment
+/// variable at compile time, which takes precedence over `locales` when set.
+///
+/// Pass `crate_path = ::my_i18n` if the `i18n` dependency is renamed, see `load!`.
 ///
 /// # Usage
 ///
@@ -34,41 +57,226 @@ pub fn langs(input: TokenStream) -> TokenStream {
 /// lazily-initialized static instance of `i18n::Locales`.
 ///
 /// This macro reads `.ftl` files from subdirectories of the given path (each
-/// subdirectory representing a locale). It parses these files at compile time
-/// and embeds their content into your binary.
+/// subdirectory representing a locale) and any of their nested directories, as well as flat
+/// `<locale>.ftl` files directly within it.
+/// A locale with both a subdirectory and a flat file merges them, the flat file being its last
+/// resource. Locale names must be language identifiers, and are canonicalized, so a `hr-hr`
+/// directory is embedded as `hr-HR` and found by `langid!("hr-HR")`; any other name, such as
+/// `english`, is a compile error.
+/// It parses these files at compile time and embeds their content into your binary.
 ///
 /// The generated static instance provides methods to query for localized messages.
 ///
 /// # Syntax
 ///
-/// `load!(path: LitStr [, fallback_lang: LitStr] [, check_keys: bool] [, name: Ident] [, on_error: Expr])`
+/// `load!(path: LitStr [, fallback_lang: LitStr] [, check_keys: bool | LitStr] [, allow_missing: [LitStr]] [, check_keys_per_file: bool] [, check_args: bool] [, check_attrs: bool | LitStr] [, check_refs: bool] [, name: Ident] [, vis: Visibility] [, langs_name: Ident] [, on_error: Expr] [, on_inline_default: Expr] [, follow_symlinks: bool] [, from_workspace_root: bool] [, normalize: LitStr] [, allow_junk: bool] [, allow_empty: bool] [, locales: [LitStr]] [, locales_env: LitStr] [, include: [LitStr]] [, exclude: [LitStr]] [, common: LitStr] [, check_excluded: bool] [, validate_keys: bool] [, crate_path: Path] [, typed: Visibility mod Ident] [, keys_mod: Ident] [, keys_enum: Ident] [, cache: bool] [, dev_reload: bool] [, legacy_lazy_static: bool] [, report: LitStr] [, manifest: bool] [, resource_conflict: LitStr] [, use_isolating: bool] [, missing_value: LitStr])`
 ///
 /// # Arguments
 ///
 /// - `path`: A string literal representing the path to the locales directory.
-///   This path should be relative to your crate root (where `Cargo.toml` is).
+///   This path should be relative to your crate root (where `Cargo.toml` is). It may reference
+///   environment variables as `$VAR` or `${VAR}`, which are read when the macro expands and
+///   must be set. `${WORKSPACE_ROOT}` falls back to the root of the workspace when unset, e.g.
+///   `"${WORKSPACE_ROOT}/i18n"`. Write `$$` for a literal `$`.
+///
+/// - `from_workspace_root` (optional): A boolean literal. If `true`, the path is relative to
+///   the root of the workspace instead, found by walking up from the crate root to the closest
+///   `Cargo.toml` with a `[workspace]` table. Useful when several member crates share the same
+///   translations. Defaults to `false`.
 ///
 /// - `fallback_lang` (optional): A string literal representing the language identifier
 ///   (e.g., "en-US") to use as a fallback if a message is not found in the
-///   requested language. Defaults to `"en-US"`.
-///
-/// - `check_keys` (optional): A boolean literal (`true` or `false`). If `true`
-///   (default), the macro will perform a compile-time check to ensure all
-///   locale files have a consistent set of message keys. If `false`, this
-///   check is skipped.
+///   requested language. Defaults to `"en-US"`. It is a compile error if no locale matches it,
+///   compared regardless of case as locale directories are, or if no locale is found at all.
+///
+/// - `check_keys` (optional): `true` (default), `false` or `"warn"`. If `true`,
+///   the macro will perform a compile-time check to ensure all
+///   locales define the same message keys, across all of their files, naming the files that
+///   define a missing key in the other locales. If `false`, this
+///   check is skipped. Message variants such as `signup-cta__variant-b` are optional,
+///   see `Query::with_variant`. With `"warn"`, the missing keys are reported as a
+///   compile-time warning instead.
+///
+/// - `check_keys_per_file` (optional): A boolean literal. If `true`, `check_keys` compares the
+///   keys of every file instead, matching files across locales by their path relative to the
+///   locale directory, e.g. `pages/settings.ftl`, and the flat `<locale>.ftl` files with each
+///   other. Defaults to `false`.
+///
+/// - `allow_missing` (optional): An array of string literals listing the locales exempt from
+///   `check_keys`, e.g. `["hr-HR"]`, for translations that lag behind. Their missing keys fall
+///   back to the fallback language at runtime, while the other locales stay strict.
+///
+/// - `check_args` (optional): A boolean literal, defaulting to the value of `check_keys`. If
+///   `true`, the macro checks that the value and every attribute of a message reference the same
+///   variables in every locale defining them, including within selectors, so that a misspelled
+///   `{ $usrname }` is a compile error rather than a formatting error in a single language.
+///
+/// - `check_attrs` (optional): `true`, `false` (default) or `"warn"`. If `true`, the macro checks
+///   that a message defines the same attributes in every locale defining it within the same file,
+///   naming the message, the attribute and the locale missing it. With `"warn"`, the missing
+///   attributes are reported as a compile-time warning instead, as they still fall back to the
+///   fallback language at runtime.
+///
+/// - `check_refs` (optional): A boolean literal. If `true` (default), the macro checks that every
+///   message, attribute and term referenced by a message or term, such as `{ -brand }` or
+///   `{ menu.label }`, is defined by the same locale across its files, naming the entry, the
+///   file and the dangling reference. Such a typo would otherwise only fail at runtime.
 ///
 /// - `name` (optional): An identifier to use as the name for the generated
-///   `lazy_static` variable. Defaults to `LOCALES`.
+///   `static` variable, a `std::sync::LazyLock<i18n::Locales>`. Defaults to `LOCALES`.
+///
+/// - `vis` (optional): The visibility of the generated statics, e.g. `vis = pub(crate)` to keep
+///   the locales of a library out of its public API. Defaults to `pub`.
+///
+/// - `langs_name` (optional): An identifier, e.g. `langs_name = AVAILABLE_LANGS`. If set, a
+///   `std::sync::LazyLock<[i18n::Lang; N]>` of that name is generated alongside the locales, listing
+///   exactly the embedded locales sorted by their ID, as `langs!` would describe them. This
+///   saves scanning the directory again with `langs!`.
 ///
 /// - `on_error` (optional): An expression that evaluates to a function or closure
 ///   to be called when an error occurs during localization (e.g., missing message).
-///   The function should have the signature `fn(errors: &[i18n::FluentError])`.
+///   The function should have the signature `fn(error: &i18n::QueryError)`, and receives the
+///   errors along with the language, the message ID and whether the fallback language was used.
+///
+/// - `on_inline_default` (optional): An expression that evaluates to a function to be called
+///   whenever the `default` of a `tr!` replaces a missing message. The function should have the
+///   signature `fn(lang: &i18n::LanguageIdentifier, id: &str)`.
+///
+/// - `follow_symlinks` (optional): A boolean literal. If `true`, symlinks within the
+///   locales directory are followed as long as they resolve to a path within the crate root,
+///   or the workspace root if the path is resolved from it.
+///   Defaults to `false`, in which case symlinks are skipped.
+///
+/// - `normalize` (optional): A string literal naming the Unicode normalization form applied to
+///   the sources, either `"none"` (default) or `"nfc"`. A leading UTF-8 BOM is stripped and
+///   CRLF line endings are converted to `\n` either way.
+///
+/// - `allow_junk` (optional): A boolean literal. By default, any entry that is not valid Fluent
+///   syntax (junk) is a compile error pointing at its line and source. If `true`, junk entries
+///   are silently dropped and the valid entries around them are kept.
+///
+/// - `allow_empty` (optional): A boolean literal. By default, a locale directory that defines no
+///   messages (for example one holding only comments or no `.ftl` files at all) and a `.ftl` file
+///   that defines no entries are compile errors, since every query for them would silently fall
+///   back. If `true`, they are reported as warnings instead. Blank files, such as zero-byte
+///   placeholders, are always skipped with a warning naming them.
+///
+/// - `locales` (optional): An array of string literals listing the locales to embed, e.g.
+///   `["en-US", "hr-HR"]`. Defaults to every locale in the directory. Every listed locale must
+///   exist, and the fallback language must be listed. Querying an excluded locale at runtime
+///   resolves through the fallback language.
+///
+/// - `locales_env` (optional): A string literal naming an environment variable that holds a
+///   comma-separated list of the locales to embed. It is read at compile time and takes
+///   precedence over `locales` when set and non-empty, which lets builds vary it per target.
+///
+/// - `exclude` (optional): An array of glob patterns, e.g. `["_drafts/**", "*.draft.ftl"]`.
+///   Locale directories, nested directories and files matching any of them are skipped entirely:
+///   they are neither embedded, checked, nor tracked for rebuilds. Patterns are matched against
+///   the `/`-separated path relative to the locales directory, such as
+///   `en-US/_drafts/notes.ftl`, and against every trailing part of it, so `*.draft.ftl` matches
+///   a draft at any depth. A `*` never matches a `/`, while `**` matches any number of
+///   directories.
+///
+/// - `include` (optional): An array of glob patterns matched like `exclude`. If set, only the
+///   `.ftl` files matching any of them are loaded, unless they are excluded.
+///
+/// - `common` (optional): A string literal naming the directory or flat `.ftl` file within the
+///   locales directory whose resources are shared by every locale, e.g. for brand names that
+///   must be the same in every language. Defaults to `"_common"`, which is used if it exists.
+///   The common resources come after the ones of every locale, whose definitions override them
+///   regardless of `resource_conflict`, and their keys count as defined in every locale for
+///   `check_keys`. `dev_reload` does not re-read them, and `langs!` skips `_common`.
+///
+/// - `check_excluded` (optional): A boolean literal. If `true`, locales excluded from embedding
+///   are still parsed and included in the key-consistency check. Defaults to `false`.
+///
+/// - `validate_keys` (optional): A boolean literal. If `true`, `tr!` and `tr_batch!` fail to
+///   compile when querying a message ID that none of the embedded locales define. The check
+///   resolves through the path passed as `locales`, so it also works across crates and through
+///   `use` aliases. Defaults to `false`.
+///
+/// - `crate_path` (optional): The path through which the generated code names the items of the
+///   `i18n` crate. Defaults to `::i18n`. Set it when the dependency is renamed in `Cargo.toml`
+///   (`crate_path = ::my_i18n`), or to `::i18n_loader` when depending on `i18n-loader` and
+///   `i18n-macros` directly. `tr!`, `tr_batch!`, `tr_typed!`, `attr!` and `langs!` accept it as well.
+///
+/// - `typed` (optional): A module declaration, e.g. `typed = pub mod messages`. If set, a typed
+///   handle implementing `i18n::MessageKey` is generated in that module for every message ID,
+///   named after the ID in `UpperCamelCase` (`welcome-back` becomes `messages::WelcomeBack`).
+///   A handle has one `i18n::FluentValue` field per variable of the message value, named after
+///   the variable in `snake_case` (`$userName` becomes `user_name`), and is a unit struct for
+///   messages without variables. Fields that would be keywords are raw identifiers (`r#type`),
+///   or get a trailing underscore if they cannot be (`self_`). IDs or variables that map to the
+///   same name are a compile error. Message variants do not get handles of their own. See
+///   `tr_typed!` and `Locales::query_typed`.
+///
+/// - `keys_mod` (optional): An identifier, e.g. `keys_mod = my_keys`. If set, a `pub mod` of that
+///   name is generated with a `&'static str` constant for every message ID, named after the ID in
+///   `SCREAMING_SNAKE_CASE` (`login-btn` becomes `my_keys::LOGIN_BTN`), so that a mistyped ID
+///   passed to `Query::new` or `tr!` fails to resolve at compile time. IDs that map to the same
+///   name are a compile error. Message variants do not get constants of their own.
+///
+/// - `keys_enum` (optional): An identifier, e.g. `keys_enum = TranslationKey`. If set, a `pub enum`
+///   of that name is generated with a variant for every message ID, named after the ID in
+///   `UpperCamelCase` (`login-btn` becomes `TranslationKey::LoginBtn`), so that code can match on
+///   the keys exhaustively. `id()` returns the message ID, `attrs()` the attributes of the message
+///   in any locale, and `TranslationKey::ALL` lists every variant sorted by message ID. The enum
+///   converts into an `i18n::Query`. IDs that map to the same name are a compile error. Message
+///   variants do not get variants of their own.
+///
+/// - `cache` (optional): A boolean literal. By default, the keys and parse errors of every file
+///   are cached in `i18n-macro-cache` within the target directory, so that the invocations of
+///   `load!` across the crates of a workspace only parse each file once. Entries are keyed by the
+///   path, modification time and size of the file, verified against a hash of its contents, and
+///   discarded when the version of `i18n-macros` changes. If `false`, every file is parsed.
+///
+/// - `dev_reload` (optional): A boolean literal. If `true`, debug builds re-read the embedded
+///   files from their absolute paths when the static is first accessed, and on every call to
+///   `LOCALES.reload()`, so translations can be edited without recompiling, see
+///   `i18n::LocalesBuilder::reload_files`. A locale keeps its embedded content if a file is
+///   missing, as for a deployed binary, or fails to parse. Release builds only embed the files,
+///   and `reload` returns an empty report. Defaults to `false`.
+///
+/// - `legacy_lazy_static` (optional): A boolean literal. If `true`, the statics are generated
+///   with `lazy_static!` as in previous releases, instead of `std::sync::LazyLock`. Deprecated,
+///   and to be removed in the next release. Defaults to `false`.
+///
+/// - `report` (optional): A path relative to your crate root, e.g.
+///   `report = "target/i18n-report.json"`. If set, a JSON summary of the embedded messages is
+///   written there at compile time: the fallback language and, for every locale, the attributes
+///   of each message and the files defining it, relative to the locales directory. Failing to
+///   write it is a compile-time warning rather than an error.
+///
+/// - `manifest` (optional): A boolean literal. If `true`, the same JSON is embedded as a
+///   `static I18N_MANIFEST: &str` alongside the locales. Defaults to `false`.
+///
+/// - `resource_conflict` (optional): A string literal naming how a message or term defined by
+///   more than one file of a locale is resolved, see `i18n::ResourceConflict`. `"error"`
+///   (default) makes it a compile error, `"keep_first"` keeps the definition of the first file
+///   and `"override"` the one of the last file, in file name order.
+///
+/// - `use_isolating` (optional): A boolean literal. If `false`, placeables are not wrapped in
+///   Unicode bidi isolation characters (`\u{2068}`, `\u{2069}`), e.g. for emails, logs and CLIs.
+///   Defaults to `true`.
+///
+/// - `missing_value` (optional): A string literal naming what a message without a value, such as
+///   `login-btn = .title = Log in`, formats to, see `i18n::MissingValuePolicy`. `"angle_brackets"`
+///   (default) formats it to `<login-btn>`, `"empty"` to an empty string, `"id"` to `login-btn`,
+///   and `"error"` rejects the query, in which case `tr!` falls back to the ID.
+///
+/// A leading byte order mark is always stripped and line endings are always normalized to `\n`
+/// before parsing; files that required normalization are reported in a compile-time warning.
+/// Files that are not valid UTF-8 are rejected with the byte offset of the first invalid sequence.
+///
+/// Hidden entries (such as `.git`), non-UTF-8 names and skipped symlinks are reported in a
+/// single compile-time warning, and symlink cycles are detected and broken.
 ///
 /// # Usage
 ///
 /// ```ignore
 /// // Basic usage with default values.
-/// i18n::load!("../tests/i18n", name = LOCALES_DEFAULT);
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], name = LOCALES_DEFAULT);
 ///
 /// // With a custom fallback language, disabled key checking, and a custom name.
 /// i18n::load!(
@@ -79,12 +287,12 @@ pub fn langs(input: TokenStream) -> TokenStream {
 /// );
 ///
 /// // With an error handler.
-/// fn on_error(errors: &[i18n::FluentError]) {
+/// fn on_error(error: &i18n::QueryError) {
 ///     // Log the error, send it to a monitoring service, etc.
-///     println!("Localization errors: {:?}", errors);
+///     println!("Localization errors for {} in {}: {:?}", error.message_id, error.lang, error.errors);
 /// }
 ///
-/// i18n::load!("../tests/i18n", on_error = on_error, name = LOCALES_WITH_ERROR_HANDLER);
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], on_error = on_error, name = LOCALES_WITH_ERROR_HANDLER);
 ///
 /// // Example of accessing the generated data (assuming default name `LOCALES_DEFAULT`).
 /// let lang_en = i18n::langid!("en-US");
@@ -110,16 +318,28 @@ pub fn load(input: TokenStream) -> TokenStream {
 ///
 /// # Syntax
 ///
-/// `tr!(lang: Expr, id: LitStr [, locales = VAR_NAME] [, key = value]* [, .attribute_name(key = value)* ])`
+/// `tr!(lang: Expr, id: LitStr | Path [, locales = PATH] [, crate_path = PATH] [, key = value]* [, .attribute_name(key = value)* ])`
 ///
 /// - `lang`: A Rust expression that evaluates to a `&LanguageIdentifier` (e.g., `langid!("en-US")` or a variable). This is the language to query for.
-/// - `id`: A string literal representing the ID of the Fluent message.
-/// - `locales` (optional): An identifier for the `i18n::Locales` static variable to use. Defaults to `LOCALES`.
+/// - `id`: A string literal representing the ID of the Fluent message. A dotted ID such as
+///   `"login-btn.aria-label"` resolves to the attribute alone, formatted with the main arguments
+///   as the value of the returned message. The ID may also be the path of a key constant generated
+///   by `load!` with `keys_mod`, e.g. `my_keys::LOGIN_BTN`, which is then checked by resolving it.
+/// - `locales` (optional): A path to the `i18n::Locales` static generated by `load!`, e.g. `LOCALES`,
+///   `app_i18n::LOCALES` or a `use` alias of it. Defaults to `LOCALES`.
+/// - `crate_path` (optional): The path of the `i18n` crate, see `load!`. Defaults to `::i18n`.
+/// - `default` (optional): An expression evaluating to a `&str` to use as the value of the message
+///   if it is missing from both the requested and the fallback language, e.g. because the
+///   translations lag behind the code. Formatting errors still fall back to the message ID. See
+///   `Locales::query_or_default`. With `validate_keys`, an unknown message ID with a default is
+///   only a compile-time warning.
 /// - `key = value`: Optional key-value pairs for arguments to the main message.
 ///   `key` must be an string literal, and `value` can be any Rust expression.
 /// - `attr(attr_id, key = value)`: Optional arguments for a specific attribute
 ///   of the message. `attr_id` is a string literal representing the attribute ID (e.g., "aria-label" or "attr-arg").
 ///
+/// Giving the same argument twice, for the message or for the same attribute, is a compile error.
+///
 /// # Returns
 ///
 /// An `i18n::Message` instance.
@@ -128,7 +348,7 @@ pub fn load(input: TokenStream) -> TokenStream {
 ///
 /// ```ignore
 /// // Load the localization data.
-/// i18n::load!("../tests/i18n", fallback_lang = "en-US", name = TR_LOCALES);
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], fallback_lang = "en-US", name = TR_LOCALES);
 ///
 /// let lang_en = i18n::langid!("en-US");
 ///
@@ -145,7 +365,7 @@ pub fn load(input: TokenStream) -> TokenStream {
 /// assert_eq!(msg3.attrs.get("attr-arg"), Some(&"This is an attribute argument with arbitrary text: \u{2068}some text\u{2069}".to_string()));
 ///
 /// // With a custom locales variable:
-/// i18n::load!("../tests/i18n", fallback_lang = "en-US", name = MY_APP_LOCALES);
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], fallback_lang = "en-US", name = MY_APP_LOCALES);
 /// let msg4 = i18n::tr!(lang_en, "foo-b", locales = MY_APP_LOCALES);
 /// assert_eq!(msg4.value, "English B".to_string());
 /// ```
@@ -154,6 +374,80 @@ pub fn tr(input: TokenStream) -> TokenStream {
     tr::tr_impl(input)
 }
 
+/// Queries for multiple localized messages at once, returning an array of `i18n::Message` instances.
+///
+/// This macro is the batched counterpart of `tr!`, built on `Locales::query_batch`. The locale is
+/// looked up only once, and the `on_error` handler is invoked once per failed message.
+///
+/// # Syntax
+///
+/// `tr_batch!(lang: Expr, [id: LitStr, ...] [, locales = PATH] [, crate_path = PATH])`
+///
+/// - `lang`: A Rust expression that evaluates to a `&LanguageIdentifier`.
+/// - `[id, ...]`: An array of string literals representing the IDs of the Fluent messages.
+/// - `locales` (optional): A path to the `i18n::Locales` static generated by `load!`, e.g. `LOCALES`,
+///   `app_i18n::LOCALES` or a `use` alias of it. Defaults to `LOCALES`.
+/// - `crate_path` (optional): The path of the `i18n` crate, see `load!`. Defaults to `::i18n`.
+///
+/// # Returns
+///
+/// An array of `i18n::Message` instances, one per ID and in the same order. A message that
+/// fails to resolve falls back to its ID, like with `tr!`.
+///
+/// # Example
+///
+/// ```ignore
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], name = BATCH_LOCALES);
+/// let lang = i18n::langid!("en-US");
+///
+/// let [a, b] = i18n::tr_batch!(lang, ["foo-a", "foo-b"], locales = BATCH_LOCALES);
+/// assert_eq!(a.value, "English A");
+/// assert_eq!(b.value, "English B");
+/// ```
+#[proc_macro]
+pub fn tr_batch(input: TokenStream) -> TokenStream {
+    tr_batch::tr_batch_impl(input)
+}
+
+/// Queries for a localized message through a typed handle generated by `load!` with `typed`,
+/// returning an `i18n::Message` instance.
+///
+/// Unlike `tr!`, the message and its arguments are checked by the compiler: a renamed message
+/// or a missing argument fails to compile. Argument values are converted with `Into`, like with
+/// `tr!`. Arguments of attributes are still given lazily, through `attr!`.
+///
+/// # Syntax
+///
+/// `tr_typed!(lang: Expr, handle: Expr [, locales = PATH] [, crate_path = PATH])`
+///
+/// - `lang`: A Rust expression that evaluates to a `LanguageIdentifier`.
+/// - `handle`: A struct expression of a typed handle, e.g. `WelcomeBack { username: "Alice" }`,
+///   or the path of a handle without arguments, e.g. `LoginBtn`.
+/// - `locales` (optional): A path to the `i18n::Locales` static generated by `load!`. Defaults to `LOCALES`.
+/// - `crate_path` (optional): The path of the `i18n` crate, see `load!`. Defaults to `::i18n`.
+///
+/// # Returns
+///
+/// An `i18n::Message` instance. A message that fails to resolve falls back to its ID, like with `tr!`.
+///
+/// # Example
+///
+/// ```ignore
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], name = TYPED_LOCALES, typed = mod messages);
+/// use messages::*;
+/// let lang = i18n::langid!("en-US");
+///
+/// let msg = i18n::tr_typed!(lang, WelcomeBack { username: "Alice" }, locales = TYPED_LOCALES);
+/// assert_eq!(msg.value, "Welcome back, \u{2068}Alice\u{2069}!");
+///
+/// let msg = i18n::tr_typed!(lang, FooA, locales = TYPED_LOCALES);
+/// assert_eq!(msg.value, "English A");
+/// ```
+#[proc_macro]
+pub fn tr_typed(input: TokenStream) -> TokenStream {
+    tr_typed::tr_typed_impl(input)
+}
+
 /// Queries an attribute from an existing `i18n::Message` instance.
 ///
 /// This macro provides a convenient way to lazily query for an attribute's
@@ -162,14 +456,15 @@ pub fn tr(input: TokenStream) -> TokenStream {
 ///
 /// # Syntax
 ///
-/// `attr!(message_expr, attr_id: LitStr [, locales = VAR_NAME] [, replace] [, key = value]*)`
+/// `attr!(message_expr, attr_id: LitStr [, locales = PATH] [, crate_path = PATH] [, replace] [, key = value]*)`
 ///
 /// - `message_expr`: An expression that evaluates to a `&mut i18n::Message`.
 /// - `attr_id`: A string literal representing the ID of the attribute to query.
-/// - `locales` (optional): The `i18n::Locales` instance to use for the error callback. Defaults to `LOCALES`.
+/// - `locales` (optional): The `i18n::Locales` instance to use for the error callback and usage tracking. Defaults to `LOCALES`.
+/// - `crate_path` (optional): The path of the `i18n` crate, see `load!`. Defaults to `::i18n`.
 /// - `replace` (optional): A flag that, if present, forces the attribute to be re-queried from the Fluent bundle,
 ///   bypassing any cached value. Defaults to `false` (cached value is used if available).
-/// - `key = value`: Optional key-value pairs for arguments to the attribute.
+/// - `key = value`: Optional key-value pairs for arguments to the attribute. Giving the same key twice is a compile error.
 ///
 /// # Returns
 ///
@@ -179,7 +474,7 @@ pub fn tr(input: TokenStream) -> TokenStream {
 /// # Example
 ///
 /// ```ignore
-/// i18n::load!("../tests/i18n", name = ATTR_MACRO_LOCALES);
+/// i18n::load!("../tests/i18n", allow_missing = ["hr-hr"], name = ATTR_MACRO_LOCALES);
 /// let lang = i18n::langid!("en-US");
 ///
 /// let mut msg = i18n::tr!(lang, "login-btn", locales = ATTR_MACRO_LOCALES);
@@ -196,3 +491,35 @@ pub fn tr(input: TokenStream) -> TokenStream {
 pub fn attr(input: TokenStream) -> TokenStream {
     attr::attr_impl(input)
 }
+
+/// Derives `i18n::IntoQueryArgs` for a struct, so that its fields can be passed to queries at once
+/// with `Query::with_arg_struct`, without typing their names as strings.
+///
+/// Every field becomes an argument named after the field in kebab case, e.g. `user_name` becomes
+/// `$user-name`. Fields can be numbers, `String`, `&str`, `Cow<str>`, or an `Option` of these,
+/// in which case `None` omits the argument.
+///
+/// # Attributes
+///
+/// - `#[arg(rename = "userName")]` on a field: Names the argument explicitly.
+/// - `#[arg(crate_path = PATH)]` on the struct: The path of the `i18n` crate, see `load!`.
+///   Defaults to `::i18n`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(i18n::IntoQueryArgs)]
+/// struct Account<'a> {
+///     #[arg(rename = "username")]
+///     name: &'a str,
+///     count: u32,
+///     plan: Option<String>,
+/// }
+///
+/// let account = Account { name: "Alice", count: 3, plan: None };
+/// let query = i18n::Query::new("account-summary").with_arg_struct(&account);
+/// ```
+#[proc_macro_derive(IntoQueryArgs, attributes(arg))]
+pub fn derive_into_query_args(input: TokenStream) -> TokenStream {
+    args::derive_into_query_args_impl(input)
+}
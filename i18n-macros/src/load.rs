@@ -1,19 +1,60 @@
-use fluent_syntax::ast::Entry;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use quote::{quote, quote_spanned};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use syn::parse::{Parse, ParseStream, Result};
-use syn::{Expr, Ident, LitBool, LitStr, Token};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{bracketed, Expr, Ident, LitBool, LitStr, Token, Visibility};
 use unic_langid::LanguageIdentifier;
 
+use crate::cache::{FileSummary, ParseCache};
+use crate::crate_path::default_crate_path;
+use crate::filter::LocaleFilter;
+use crate::langs::lang_expr;
+use crate::paths::{LocalesPath, PathFilter};
+use crate::report::BuildReport;
+use crate::scan::{warning, DirScanner, ScannedEntry};
+use crate::typed::{expand_keys, TypedMessages, TypedModule};
+use i18n_loader::{MissingValuePolicy, Normalization, ResourceConflict};
+
 struct LoadMacroInput {
     path: LitStr,
     fallback_lang: Option<LitStr>,
-    check_keys: bool,
+    check_keys: CheckMode,
+    allow_missing: Vec<LanguageIdentifier>,
+    check_keys_per_file: bool,
+    check_args: Option<bool>,
+    check_attrs: CheckMode,
+    check_refs: bool,
     name: Ident,
+    vis: Visibility,
+    langs_name: Option<Ident>,
     on_error: Option<Expr>,
+    on_inline_default: Option<Expr>,
+    follow_symlinks: bool,
+    from_workspace_root: bool,
+    normalize: Normalization,
+    allow_junk: bool,
+    allow_empty: bool,
+    filter: LocaleFilter,
+    paths: PathFilter,
+    common: Option<LitStr>,
+    check_excluded: bool,
+    validate_keys: bool,
+    krate: syn::Path,
+    typed: Option<TypedModule>,
+    keys_mod: Option<Ident>,
+    keys_enum: Option<Ident>,
+    cache: bool,
+    dev_reload: bool,
+    legacy_lazy_static: bool,
+    report: Option<LitStr>,
+    manifest: bool,
+    resource_conflict: ResourceConflict,
+    use_isolating: bool,
+    missing_value: MissingValuePolicy,
 }
 
 impl Parse for LoadMacroInput {
@@ -21,7 +62,7 @@ impl Parse for LoadMacroInput {
         if input.is_empty() {
             return Err(syn::Error::new(
                 input.span(),
-                "Usage: load!(\"i18n\")\nOptional parameters: `fallback_lang`, `check_keys`, `name`, `on_error`.\nThe path should be relative to your crate root (where Cargo.toml is).",
+                "Usage: load!(\"i18n\")\nOptional parameters: `fallback_lang`, `check_keys`, `allow_missing`, `check_keys_per_file`, `check_args`, `check_attrs`, `check_refs`, `name`, `vis`, `langs_name`, `on_error`, `on_inline_default`, `follow_symlinks`, `from_workspace_root`, `normalize`, `allow_junk`, `allow_empty`, `locales`, `locales_env`, `include`, `exclude`, `common`, `check_excluded`, `validate_keys`, `crate_path`, `typed`, `keys_mod`, `keys_enum`, `cache`, `dev_reload`, `legacy_lazy_static`, `report`, `manifest`, `resource_conflict`, `use_isolating`, `missing_value`.\nThe path should be relative to your crate root (where Cargo.toml is).",
             ));
         }
 
@@ -30,9 +71,39 @@ impl Parse for LoadMacroInput {
         })?;
 
         let mut fallback_lang = None;
-        let mut check_keys = true;
+        let mut check_keys = CheckMode::Error;
+        let mut allow_missing = Vec::default();
+        let mut check_keys_per_file = false;
+        let mut check_args = None;
+        let mut check_attrs = CheckMode::Off;
+        let mut check_refs = true;
         let mut name = Ident::new("LOCALES", Span::call_site());
+        let mut vis: Visibility = syn::parse_quote!(pub);
+        let mut langs_name = None;
         let mut on_error = None;
+        let mut on_inline_default = None;
+        let mut follow_symlinks = false;
+        let mut from_workspace_root = false;
+        let mut normalize = Normalization::None;
+        let mut allow_junk = false;
+        let mut allow_empty = false;
+        let mut filter = LocaleFilter::default();
+        let mut paths = PathFilter::default();
+        let mut common = None;
+        let mut check_excluded = false;
+        let mut validate_keys = false;
+        let mut krate = default_crate_path();
+        let mut typed = None;
+        let mut keys_mod = None;
+        let mut keys_enum = None;
+        let mut cache = true;
+        let mut dev_reload = false;
+        let mut legacy_lazy_static = false;
+        let mut report = None;
+        let mut manifest = false;
+        let mut resource_conflict = ResourceConflict::Error;
+        let mut use_isolating = true;
+        let mut missing_value = MissingValuePolicy::default();
 
         while input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
@@ -43,15 +114,87 @@ impl Parse for LoadMacroInput {
             let key: Ident = input.parse()?;
             input.parse::<Token![=]>()?;
 
-            match key.to_string().as_str() {
+            let key_str = key.to_string();
+            if filter.parse_param(&key_str, input)? || paths.parse_param(&key_str, input)? {
+                continue;
+            }
+
+            match key_str.as_str() {
                 "fallback_lang" => fallback_lang = Some(input.parse()?),
-                "check_keys" => check_keys = input.parse::<LitBool>()?.value(),
+                "check_keys" => check_keys = input.parse()?,
+                "allow_missing" => {
+                    let content;
+                    bracketed!(content in input);
+                    allow_missing = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .map(|lang| {
+                            lang.value().parse().map_err(|err| {
+                                syn::Error::new(
+                                    lang.span(),
+                                    format!(
+                                        "Invalid language identifier '{}': {err}",
+                                        lang.value()
+                                    ),
+                                )
+                            })
+                        })
+                        .collect::<Result<_>>()?;
+                }
+                "check_keys_per_file" => check_keys_per_file = input.parse::<LitBool>()?.value(),
+                "check_args" => check_args = Some(input.parse::<LitBool>()?.value()),
+                "check_attrs" => check_attrs = input.parse()?,
+                "check_refs" => check_refs = input.parse::<LitBool>()?.value(),
                 "name" => name = input.parse::<Ident>()?,
+                "vis" => vis = input.parse()?,
+                "langs_name" => langs_name = Some(input.parse::<Ident>()?),
                 "on_error" => on_error = Some(input.parse::<Expr>()?),
+                "on_inline_default" => on_inline_default = Some(input.parse::<Expr>()?),
+                "follow_symlinks" => follow_symlinks = input.parse::<LitBool>()?.value(),
+                "from_workspace_root" => {
+                    from_workspace_root = input.parse::<LitBool>()?.value()
+                }
+                "normalize" => {
+                    let form = input.parse::<LitStr>()?;
+                    normalize = form
+                        .value()
+                        .parse()
+                        .map_err(|err| syn::Error::new(form.span(), err))?;
+                }
+                "allow_junk" => allow_junk = input.parse::<LitBool>()?.value(),
+                "allow_empty" => allow_empty = input.parse::<LitBool>()?.value(),
+                "common" => common = Some(input.parse()?),
+                "check_excluded" => check_excluded = input.parse::<LitBool>()?.value(),
+                "validate_keys" => validate_keys = input.parse::<LitBool>()?.value(),
+                "crate_path" => krate = input.parse()?,
+                "typed" => typed = Some(input.parse()?),
+                "keys_mod" => keys_mod = Some(input.parse::<Ident>()?),
+                "keys_enum" => keys_enum = Some(input.parse::<Ident>()?),
+                "cache" => cache = input.parse::<LitBool>()?.value(),
+                "dev_reload" => dev_reload = input.parse::<LitBool>()?.value(),
+                "legacy_lazy_static" => {
+                    legacy_lazy_static = input.parse::<LitBool>()?.value()
+                }
+                "report" => report = Some(input.parse()?),
+                "manifest" => manifest = input.parse::<LitBool>()?.value(),
+                "use_isolating" => use_isolating = input.parse::<LitBool>()?.value(),
+                "resource_conflict" => {
+                    let policy = input.parse::<LitStr>()?;
+                    resource_conflict = policy
+                        .value()
+                        .parse()
+                        .map_err(|err| syn::Error::new(policy.span(), err))?;
+                }
+                "missing_value" => {
+                    let policy = input.parse::<LitStr>()?;
+                    missing_value = policy
+                        .value()
+                        .parse()
+                        .map_err(|err| syn::Error::new(policy.span(), err))?;
+                }
                 _ => {
                     return Err(syn::Error::new(
                         key.span(),
-                        "Unexpected parameter, expected 'fallback_lang', 'check_keys', 'name', or 'on_error'",
+                        "Unexpected parameter, expected 'fallback_lang', 'check_keys', 'allow_missing', 'check_keys_per_file', 'check_args', 'check_attrs', 'check_refs', 'name', 'vis', 'langs_name', 'on_error', 'on_inline_default', 'follow_symlinks', 'from_workspace_root', 'normalize', 'allow_junk', 'allow_empty', 'locales', 'locales_env', 'include', 'exclude', 'common', 'check_excluded', 'validate_keys', 'crate_path', 'typed', 'keys_mod', 'keys_enum', 'cache', 'dev_reload', 'legacy_lazy_static', 'report', 'manifest', 'resource_conflict', 'use_isolating', or 'missing_value'",
                     ))
                 }
             }
@@ -61,49 +204,154 @@ impl Parse for LoadMacroInput {
             path,
             fallback_lang,
             check_keys,
+            allow_missing,
+            check_keys_per_file,
+            check_args,
+            check_attrs,
+            check_refs,
             name,
+            vis,
+            langs_name,
             on_error,
+            on_inline_default,
+            follow_symlinks,
+            from_workspace_root,
+            normalize,
+            allow_junk,
+            allow_empty,
+            filter,
+            paths,
+            common,
+            check_excluded,
+            validate_keys,
+            krate,
+            typed,
+            keys_mod,
+            keys_enum,
+            cache,
+            dev_reload,
+            legacy_lazy_static,
+            report,
+            manifest,
+            resource_conflict,
+            use_isolating,
+            missing_value,
         })
     }
 }
 
+/// How `load!` reports the findings of a consistency check across locales, given as `true`,
+/// `false` or `"warn"`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CheckMode {
+    Off,
+    Warn,
+    Error,
+}
+
+impl Parse for CheckMode {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(LitBool) {
+            return Ok(match input.parse::<LitBool>()?.value() {
+                true => CheckMode::Error,
+                false => CheckMode::Off,
+            });
+        }
+        let mode = input.parse::<LitStr>()?;
+        match mode.value().as_str() {
+            "warn" => Ok(CheckMode::Warn),
+            _ => Err(syn::Error::new(
+                mode.span(),
+                "Expected `true`, `false` or `\"warn\"`",
+            )),
+        }
+    }
+}
+
 pub fn load_impl(input: TokenStream) -> TokenStream {
+    expand(input.into(), ParseCache::open).into()
+}
+
+/// Expands `load!`, opening the parse cache with `open_cache` unless it is disabled.
+fn expand(
+    input: proc_macro2::TokenStream,
+    open_cache: impl FnOnce() -> Option<ParseCache>,
+) -> proc_macro2::TokenStream {
     let LoadMacroInput {
         path: path_lit,
         fallback_lang,
         check_keys,
+        allow_missing,
+        check_keys_per_file,
+        check_args,
+        check_attrs,
+        check_refs,
         name,
+        vis,
+        langs_name,
         on_error,
-    } = match syn::parse(input) {
+        on_inline_default,
+        follow_symlinks,
+        from_workspace_root,
+        normalize,
+        allow_junk,
+        allow_empty,
+        filter,
+        paths,
+        common,
+        check_excluded,
+        validate_keys,
+        krate,
+        typed,
+        keys_mod,
+        keys_enum,
+        cache,
+        dev_reload,
+        legacy_lazy_static,
+        report,
+        manifest,
+        resource_conflict,
+        use_isolating,
+        missing_value,
+    } = match syn::parse2(input) {
         Ok(input) => input,
-        Err(err) => return err.to_compile_error().into(),
+        Err(err) => return err.to_compile_error(),
+    };
+    let check_args = check_args.unwrap_or(check_keys != CheckMode::Off);
+    let filter = match filter.resolve() {
+        Ok(filter) => filter,
+        Err(err) => return err.to_compile_error(),
     };
 
+    let fallback_span = fallback_lang.as_ref().map_or(path_lit.span(), LitStr::span);
     let fallback_lang = match fallback_lang {
         Some(lang) => {
             // Verify the fallback language identifier at compile time.
-            if let Err(err) = lang.value().parse::<LanguageIdentifier>() {
-                return syn::Error::new(
-                    lang.span(),
-                    format!("Invalid fallback language identifier: {}", err),
-                )
-                .to_compile_error()
-                .into();
+            match lang.value().parse::<LanguageIdentifier>() {
+                Ok(lang) => lang.to_string(),
+                Err(err) => {
+                    return syn::Error::new(
+                        lang.span(),
+                        format!("Invalid fallback language identifier: {}", err),
+                    )
+                    .to_compile_error()
+                }
             }
-            let lang_str = lang.value();
-            quote! { #lang_str }
         }
-        None => quote! { "en-US" },
+        None => "en-US".to_string(),
     };
 
     let path = path_lit.value();
     let path = Path::new(&path);
 
-    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-    let mut absolute_path = std::path::PathBuf::from(manifest_dir);
-    absolute_path.push(path);
+    let locales_path = match LocalesPath::resolve(&path_lit, from_workspace_root) {
+        Ok(locales_path) => locales_path,
+        Err(err) => return err.to_compile_error(),
+    };
+    let mut scanner = DirScanner::new(&locales_path.root, follow_symlinks);
+    let absolute_path = &locales_path.absolute;
 
-    let entries = match std::fs::read_dir(&absolute_path) {
+    let entries = match scanner.read_dir(absolute_path) {
         Ok(entries) => entries,
         Err(err) => {
             return syn::Error::new(
@@ -111,139 +359,1200 @@ pub fn load_impl(input: TokenStream) -> TokenStream {
                 format!("Expected directory '{path:?}' ({absolute_path:?}): {err}"),
             )
             .to_compile_error()
-            .into()
         }
     };
 
+    // a locale is a subdirectory of `.ftl` files, a flat `<locale>.ftl` file, or both, in which
+    // case the flat file is merged in as the last resource of the locale
+    let mut locale_entries: BTreeMap<String, Vec<ScannedEntry>> = BTreeMap::new();
+    // the directory or file of resources shared by every locale, named like a locale
+    let common_name = common
+        .as_ref()
+        .map_or(DEFAULT_COMMON_NAME.to_string(), LitStr::value);
+    let mut common_entries = Vec::default();
+    for entry in entries {
+        let included = match entry.is_dir {
+            true => !paths.excludes_dir(&entry.name),
+            false => paths.includes_file(&entry.name),
+        };
+        if !included {
+            continue;
+        }
+        if entry.locale_name() == Some(common_name.as_str()) {
+            common_entries.push(entry);
+        } else if let Some(locale) = entry.canonical_locale() {
+            let locale = match locale {
+                Ok(locale) => locale,
+                Err(msg) => {
+                    return syn::Error::new(
+                        path_lit.span(),
+                        format!("{msg} (in '{}')", path.display()),
+                    )
+                    .to_compile_error()
+                }
+            };
+            let entries = locale_entries.entry(locale.clone()).or_default();
+            // `hr-hr` and `hr-HR` would otherwise both be merged into the locale `hr-HR`
+            if let Some(other) = entries.iter().find(|other| other.is_dir == entry.is_dir) {
+                return syn::Error::new(
+                    path_lit.span(),
+                    format!(
+                        "Both '{}' and '{}' in '{}' define the locale {locale}",
+                        other.name,
+                        entry.name,
+                        path.display()
+                    ),
+                )
+                .to_compile_error();
+            }
+            entries.push(entry);
+        }
+    }
+    let locale_names = locale_entries.keys().cloned().collect::<Vec<_>>();
+    if let Err(err) = filter.verify(&locale_names, Some(&fallback_lang)) {
+        return err.to_compile_error();
+    }
+    if let Some(common) = common.as_ref().filter(|_| common_entries.is_empty()) {
+        return syn::Error::new(
+            common.span(),
+            format!(
+                "Expected a directory or a `.ftl` file named '{common_name}' in '{}'",
+                path.display()
+            ),
+        )
+        .to_compile_error();
+    }
+
     let mut errors = Vec::new();
     let mut locale_contents: HashMap<String, Vec<String>> = HashMap::new();
+    let mut common_contents: Vec<String> = Vec::default();
+    // the keys of the common resources, which every locale defines
+    let mut common_keys: HashSet<String> = HashSet::default();
     let mut file_keys: HashMap<String, HashMap<String, HashSet<String>>> = HashMap::new();
-    let mut all_absolute_file_paths: Vec<String> = Vec::default();
+    // the variables of every message value and attribute, keyed by its ID and then by the locale
+    let mut message_vars: BTreeMap<String, BTreeMap<String, BTreeSet<String>>> = BTreeMap::new();
+    // the attributes of every message, keyed by the file, then by the message ID and the locale
+    let mut file_attrs: BTreeMap<String, BTreeMap<String, BTreeMap<String, BTreeSet<String>>>> =
+        BTreeMap::new();
+    let mut tracked_files: Vec<PathBuf> = Vec::default();
+    // the embedded files along with their locale, which `dev_reload` re-reads at runtime
+    let mut embedded_files: Vec<(String, String)> = Vec::default();
+    let mut normalized_files: Vec<String> = Vec::default();
+    let mut message_ids: BTreeSet<String> = BTreeSet::default();
+    let mut empty: Vec<String> = Vec::default();
+    // the files holding nothing but whitespace, such as placeholders, which are skipped
+    let mut blank_files: Vec<String> = Vec::default();
+    let mut typed_messages = TypedMessages::default();
+    // the file first defining each entry of each locale, as messages and terms share a namespace
+    let mut definitions: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut conflicts: Vec<String> = Vec::default();
+    // the messages, attributes and terms each embedded locale defines, as they are referenced
+    let mut defined_refs: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    // the references of each embedded locale, along with the entry and the file making them
+    let mut entry_refs: BTreeMap<String, Vec<(String, String, String)>> = BTreeMap::new();
+    let mut build_report = BuildReport::default();
+    let cache = cache.then(open_cache).flatten();
 
-    for entry in entries {
-        let Ok(entry) = entry else { continue };
-        if !entry.path().is_dir() {
+    let common_entries =
+        (!common_entries.is_empty()).then(|| (common_name.clone(), common_entries));
+    'locales: for (locale, entries) in common_entries.into_iter().chain(locale_entries) {
+        let is_common = locale == common_name;
+        let embed = is_common || filter.includes(&locale);
+        if !embed && !check_excluded {
             continue;
         }
-
-        let locale = entry.file_name().to_string_lossy().to_string();
-        let locale_path = entry.path();
-        let Ok(files) = std::fs::read_dir(&locale_path) else {
-            continue;
-        };
-        for file in files {
-            let Ok(file) = file else { continue };
-            let file_path = file.path();
-            if file_path.extension().and_then(|ext| ext.to_str()) != Some("ftl") {
+        // every file along with how it is displayed and the name it is compared by `check_keys`
+        let mut files = Vec::default();
+        for entry in entries {
+            if !entry.is_dir {
+                files.push((entry.name.clone(), FLAT_FILE_NAME.to_string(), entry));
                 continue;
             }
+            match scanner.read_ftl_tree(&entry.path, &entry.name, &paths) {
+                Ok(dir_files) => files.extend(
+                    dir_files
+                        .into_iter()
+                        .map(|(name, file)| (format!("{}/{name}", entry.name), name, file)),
+                ),
+                Err(err) => {
+                    errors.push(format!("Failed to read {locale}: {err}"));
+                    continue 'locales;
+                }
+            }
+        }
+        let mut message_count = 0;
+        for (file_display, file_name, file) in files {
+            let file_path = file.path;
 
             // track the file using `include_str!`
-            if let Some(path) = file_path.to_str() {
-                all_absolute_file_paths.push(path.to_string());
-            }
+            tracked_files.push(file_path.clone());
 
-            let file_name = file.file_name().to_string_lossy().to_string();
-            let content = match std::fs::read_to_string(&file_path) {
-                Ok(content) => content,
+            let bytes = match std::fs::read(&file_path) {
+                Ok(bytes) => bytes,
                 Err(err) => {
-                    errors.push(format!("Failed to read {locale}/{file_name}: {err}"));
+                    errors.push(format!("Failed to read {file_display}: {err}"));
                     continue;
                 }
             };
-
-            let resource = match fluent_syntax::parser::parse(content.as_str()) {
-                Ok(resource) => resource,
-                Err((_, errs)) => {
-                    let msgs = errs.iter().map(|e| format!("{e:?}")).collect::<Vec<_>>();
+            let content = match i18n_loader::normalize_bytes(bytes, normalize) {
+                Ok(normalized) => {
+                    if !normalized.changes.is_empty() {
+                        normalized_files.push(format!(
+                            "\t- {file_display}: {}",
+                            normalized.changes.join(", ")
+                        ));
+                    }
+                    normalized.source
+                }
+                Err(err) => {
                     errors.push(format!(
-                        "Failed to parse {locale}/{file_name}: {}",
-                        msgs.join("\n")
+                        "Invalid UTF-8 in {file_display} at byte offset {}",
+                        err.valid_up_to()
                     ));
                     continue;
                 }
             };
 
-            let mut keys = HashSet::new();
-            for entry in resource.body.iter() {
-                match entry {
-                    Entry::Message(msg) => _ = keys.insert(msg.id.name.to_string()),
-                    Entry::Term(term) => _ = keys.insert(term.id.name.to_string()),
-                    _ => {}
+            // a placeholder would otherwise be embedded as a resource missing every key
+            if content.trim().is_empty() {
+                blank_files.push(format!("\t- {file_display}"));
+                continue;
+            }
+
+            let summary = match &cache {
+                Some(cache) => cache.summarize(&file_path, &content),
+                None => FileSummary::parse(&content),
+            };
+            if !allow_junk && (!summary.junk.is_empty() || !summary.parse_errors.is_empty()) {
+                let msgs = summary
+                    .junk
+                    .iter()
+                    .map(|junk| format!("\t- junk at {junk}"))
+                    .chain(summary.parse_errors.iter().map(|err| format!("\t- {err}")))
+                    .collect::<Vec<_>>();
+                errors.push(format!(
+                    "Failed to parse {file_display} (set `allow_junk = true` to skip invalid entries):\n{}",
+                    msgs.join("\n")
+                ));
+                continue;
+            }
+
+            message_count += summary.messages.len();
+            let keys = summary
+                .messages
+                .iter()
+                .chain(summary.terms.iter())
+                .cloned()
+                .collect::<HashSet<_>>();
+            if embed && keys.is_empty() {
+                empty.push(format!("\t- {file_display}: the file defines no entries"));
+            }
+
+            if is_common {
+                common_keys.extend(keys);
+            } else {
+                file_keys
+                    .entry(file_name.clone())
+                    .or_default()
+                    .insert(locale.clone(), keys);
+            }
+            let message_attrs = file_attrs.entry(file_name.clone()).or_default();
+            // common messages are the same in every locale
+            for (id, attrs) in summary.attrs.iter().filter(|_| !is_common) {
+                message_attrs
+                    .entry(id.clone())
+                    .or_default()
+                    .entry(locale.clone())
+                    .or_default()
+                    .extend(attrs.iter().cloned());
+            }
+            for (id, vars) in summary.vars.iter().filter(|_| !is_common) {
+                message_vars
+                    .entry(id.clone())
+                    .or_default()
+                    .entry(locale.clone())
+                    .or_default()
+                    .extend(vars.iter().cloned());
+            }
+            if embed && resource_conflict == ResourceConflict::Error {
+                let defined = definitions.entry(locale.clone()).or_default();
+                let entries = summary
+                    .messages
+                    .iter()
+                    .map(|id| (id.clone(), id.clone()))
+                    .chain(
+                        summary
+                            .terms
+                            .iter()
+                            .map(|id| (id.clone(), format!("-{id}"))),
+                    );
+                for (key, id) in entries {
+                    match defined.get(&key) {
+                        Some(first) => conflicts.push(format!(
+                            "\t- {id} in {file_display}, first defined in {first}"
+                        )),
+                        None => _ = defined.insert(key, file_display.clone()),
+                    }
+                }
+            }
+            if embed && check_refs {
+                let defined = defined_refs.entry(locale.clone()).or_default();
+                defined.extend(summary.messages.iter().cloned());
+                defined.extend(summary.terms.iter().map(|id| format!("-{id}")));
+                for (id, attrs) in &summary.attrs {
+                    defined.extend(attrs.iter().map(|attr| format!("{id}.{attr}")));
+                }
+                let refs = entry_refs.entry(locale.clone()).or_default();
+                for (id, references) in &summary.refs {
+                    refs.extend(
+                        references
+                            .iter()
+                            .map(|reference| (id.clone(), reference.clone(), file_display.clone())),
+                    );
                 }
             }
+            if embed {
+                let report_locale = (!is_common).then_some(locale.as_str());
+                build_report.add_file(report_locale, &file_display, &summary);
+                message_ids.extend(summary.messages.iter().cloned());
+                if typed.is_some() || keys_enum.is_some() {
+                    // the handles and the attributes of the keys need the AST, which is not cached
+                    let resource = fluent_syntax::parser::parse(content.as_str())
+                        .unwrap_or_else(|(resource, _)| resource);
+                    typed_messages.add_resource(&locale, &resource);
+                }
+                if is_common {
+                    common_contents.push(content);
+                } else {
+                    locale_contents
+                        .entry(locale.clone())
+                        .or_default()
+                        .push(content);
+                    if let Some(path) = file_path.to_str() {
+                        embedded_files.push((locale.clone(), path.to_string()));
+                    }
+                }
+            }
+        }
 
-            file_keys
-                .entry(file_name.clone())
-                .or_default()
-                .insert(locale.clone(), keys);
-            locale_contents
-                .entry(locale.clone())
-                .or_default()
-                .push(content);
+        // the common resources may only define terms
+        if embed && !is_common && message_count == 0 {
+            empty.push(format!(
+                "\t- {}: the locale defines no messages",
+                path.join(&locale).display()
+            ));
         }
     }
 
-    if check_keys {
-        for (file_name, locale_keysets) in &file_keys {
-            let all_keys: HashSet<String> = locale_keysets
-                .values()
-                .flat_map(|s| s.iter())
-                .cloned()
-                .collect();
-            for (locale, keys) in locale_keysets {
-                let missing: Vec<String> = all_keys
-                    .iter()
-                    .filter(|k| !keys.contains(*k))
+    // a locale is only found once one of its files is embedded
+    if errors.is_empty() && locale_contents.is_empty() {
+        return syn::Error::new(
+            path_lit.span(),
+            format!(
+                "Found no locales in '{}' ({absolute_path:?}): expected a directory or a `.ftl` file named after each language identifier, such as `en-US/main.ftl` or `en-US.ftl`",
+                path.display()
+            ),
+        )
+        .to_compile_error();
+    }
+    let fallback_id = fallback_lang
+        .parse::<LanguageIdentifier>()
+        .expect("verified above");
+    if errors.is_empty()
+        && !locale_contents
+            .keys()
+            .any(|locale| locale.parse::<LanguageIdentifier>().as_ref() == Ok(&fallback_id))
+    {
+        let mut found = locale_contents.keys().cloned().collect::<Vec<_>>();
+        found.sort();
+        return syn::Error::new(
+            fallback_span,
+            format!(
+                "The fallback language '{fallback_lang}' has no locale in '{}', found: {} (set `fallback_lang` to one of them)",
+                path.display(),
+                found.join(", ")
+            ),
+        )
+        .to_compile_error();
+    }
+
+    if !allow_empty && !empty.is_empty() {
+        errors.push(format!(
+            "Found empty locales (set `allow_empty = true` to embed them anyway):\n{}",
+            empty.join("\n")
+        ));
+    }
+
+    if !conflicts.is_empty() {
+        errors.push(format!(
+            "Found entries defined more than once (set `resource_conflict = \"keep_first\"` or `\"override\"` to allow it):\n{}",
+            conflicts.join("\n")
+        ));
+    }
+
+    let mut missing_keys_note = None;
+    if check_keys != CheckMode::Off {
+        let is_allowed = |locale: &str| {
+            locale
+                .parse::<LanguageIdentifier>()
+                .is_ok_and(|lang| allow_missing.contains(&lang))
+        };
+        let mut missing_keys = Vec::default();
+        if check_keys_per_file {
+            for (file_name, locale_keysets) in &file_keys {
+                // message variants are optional, as they fall back to their base message
+                let all_keys: HashSet<String> = locale_keysets
+                    .values()
+                    .flat_map(|s| s.iter())
+                    .filter(|k| !k.contains(i18n_loader::VARIANT_SEPARATOR))
                     .cloned()
                     .collect();
+                for (locale, keys) in locale_keysets {
+                    if is_allowed(locale) {
+                        continue;
+                    }
+                    let missing: Vec<String> = all_keys
+                        .iter()
+                        .filter(|k| !keys.contains(*k) && !common_keys.contains(*k))
+                        .cloned()
+                        .collect();
+                    if !missing.is_empty() {
+                        missing_keys.push(format!(
+                            "Missing keys in {}: {}",
+                            display_file(locale, file_name),
+                            missing.join(", ")
+                        ));
+                    }
+                }
+            }
+        } else {
+            // the files defining every key, keyed by the key and then by the locale
+            let mut key_files: BTreeMap<&str, BTreeMap<&str, BTreeSet<String>>> = BTreeMap::new();
+            let mut locales: BTreeSet<&str> = BTreeSet::new();
+            for (file_name, locale_keysets) in &file_keys {
+                for (locale, keys) in locale_keysets {
+                    locales.insert(locale);
+                    // message variants are optional, as they fall back to their base message
+                    for key in keys.iter().filter(|k| {
+                        !k.contains(i18n_loader::VARIANT_SEPARATOR) && !common_keys.contains(*k)
+                    }) {
+                        key_files
+                            .entry(key)
+                            .or_default()
+                            .entry(locale)
+                            .or_default()
+                            .insert(display_file(locale, file_name));
+                    }
+                }
+            }
+            for locale in locales.into_iter().filter(|locale| !is_allowed(locale)) {
+                let missing = key_files
+                    .iter()
+                    .filter(|(_, files)| !files.contains_key(locale))
+                    .map(|(key, files)| {
+                        let files = files.values().flatten().cloned().collect::<Vec<_>>();
+                        format!("{key} (defined in {})", files.join(", "))
+                    })
+                    .collect::<Vec<_>>();
                 if !missing.is_empty() {
-                    errors.push(format!(
-                        "Missing keys in {locale}/{file_name}: {}",
-                        missing.join(", ")
+                    missing_keys.push(format!("Missing keys in {locale}: {}", missing.join(", ")));
+                }
+            }
+        }
+        if check_keys == CheckMode::Error {
+            errors.extend(missing_keys);
+        } else if !missing_keys.is_empty() {
+            missing_keys_note = Some(warning(&format!(
+                "i18n: found missing keys in {} locales or files:\n{}",
+                missing_keys.len(),
+                missing_keys
+                    .iter()
+                    .map(|missing| format!("\t- {missing}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )));
+        }
+    }
+
+    if check_args {
+        let mismatches = message_vars
+            .iter()
+            .filter_map(|(id, locale_vars)| {
+                let all_vars = locale_vars.values().flatten().collect::<BTreeSet<_>>();
+                let differing = all_vars
+                    .into_iter()
+                    .filter(|var| !locale_vars.values().all(|vars| vars.contains(*var)))
+                    .map(|var| format!("${var}"))
+                    .collect::<Vec<_>>();
+                if differing.is_empty() {
+                    return None;
+                }
+                let locales = locale_vars
+                    .iter()
+                    .map(|(locale, vars)| {
+                        let vars = vars.iter().map(|var| format!("${var}")).collect::<Vec<_>>();
+                        match vars.is_empty() {
+                            true => format!("{locale} uses none"),
+                            false => format!("{locale} uses {}", vars.join(", ")),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                Some(format!(
+                    "\t- {id} ({}): {}",
+                    differing.join(", "),
+                    locales.join("; ")
+                ))
+            })
+            .collect::<Vec<_>>();
+        if !mismatches.is_empty() {
+            errors.push(format!(
+                "Found messages using different variables across locales (set `check_args = false` to skip this check):\n{}",
+                mismatches.join("\n")
+            ));
+        }
+    }
+
+    if check_refs {
+        // the common resources are checked along with every locale
+        let common_defined = defined_refs.remove(&common_name).unwrap_or_default();
+        let common_refs = entry_refs.remove(&common_name).unwrap_or_default();
+        let mut dangling = Vec::default();
+        for (locale, defined) in &defined_refs {
+            let refs = entry_refs[locale].iter().chain(common_refs.iter());
+            for (id, reference, file_display) in refs {
+                if !defined.contains(reference) && !common_defined.contains(reference) {
+                    dangling.push(format!(
+                        "\t- {id} in {file_display} references {reference}, which {locale} does not define"
                     ));
                 }
             }
         }
+        if !dangling.is_empty() {
+            errors.push(format!(
+                "Found references to undefined messages or terms (set `check_refs = false` to skip this check):\n{}",
+                dangling.join("\n")
+            ));
+        }
+    }
+
+    let mut attrs_note = None;
+    if check_attrs != CheckMode::Off {
+        let mut missing = Vec::default();
+        for (file_name, messages) in &file_attrs {
+            for (id, locale_attrs) in messages {
+                let all_attrs = locale_attrs.values().flatten().collect::<BTreeSet<_>>();
+                for attr in all_attrs {
+                    for (locale, attrs) in locale_attrs {
+                        if !attrs.contains(attr) {
+                            missing.push(format!(
+                                "\t- {id}.{attr} is missing in {}",
+                                display_file(locale, file_name)
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        if !missing.is_empty() && check_attrs == CheckMode::Error {
+            errors.push(format!(
+                "Found attributes missing in some locales (set `check_attrs = \"warn\"` to only warn):\n{}",
+                missing.join("\n")
+            ));
+        } else if !missing.is_empty() {
+            attrs_note = Some(warning(&format!(
+                "i18n: found {} attributes missing in some locales:\n{}",
+                missing.len(),
+                missing.join("\n")
+            )));
+        }
     }
 
+    let typed_module = match &typed {
+        Some(module) => match typed_messages.expand(&krate, &name, module) {
+            Ok(expansion) => Some(expansion),
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let keys_module = match &keys_mod {
+        Some(module) => match expand_keys(message_ids.iter(), &name, module) {
+            Ok(expansion) => Some(expansion),
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let keys_enum = match &keys_enum {
+        Some(enum_name) => match typed_messages.expand_keys_enum(&krate, &name, enum_name) {
+            Ok(expansion) => Some(expansion),
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        },
+        None => None,
+    };
+
     if !errors.is_empty() {
         let err_quotes = errors.iter().map(|msg| quote! { compile_error!(#msg); });
-        return quote! { #(#err_quotes)* }.into();
+        return quote! { #(#err_quotes)* };
     }
 
     let locales: Vec<String> = locale_contents.keys().cloned().collect();
-    let add_locale = locales.into_iter().map(|locale| {
-        let contents = locale_contents.get(&locale).unwrap();
-        let create_fluent_resources = contents.iter().map(|content| quote! {
-                i18n::FluentResource::try_new(#content.to_string()).expect("parsed at compile time")
-            }).collect::<Vec<_>>();
+    let unwrap_resource = if allow_junk {
+        quote! { .unwrap_or_else(|(resource, _)| resource) }
+    } else {
+        quote! { .expect("parsed at compile time") }
+    };
+    // files embedded verbatim by several languages are parsed once and shared by their bundles
+    let mut shared_contents: Vec<&String> = Vec::new();
+    let mut share = |content| {
+        let idx = match shared_contents.iter().position(|shared| *shared == content) {
+            Some(idx) => idx,
+            None => {
+                shared_contents.push(content);
+                shared_contents.len() - 1
+            }
+        };
+        let resource = shared_resource_ident(idx);
+        quote! { ::std::sync::Arc::clone(&#resource) }
+    };
+    let common_resources = common_contents.iter().map(&mut share).collect::<Vec<_>>();
+    let add_locale = locales
+        .iter()
+        .map(|locale| {
+            let resources = locale_contents[locale]
+                .iter()
+                .map(&mut share)
+                .collect::<Vec<_>>();
 
+            match common_resources.is_empty() {
+                true => quote! {
+                    locales
+                        .add_shared_locale(#locale, vec![ #(#resources),* ])
+                        .expect("conflicts are checked at compile time");
+                },
+                false => quote! {
+                    locales
+                        .add_shared_locale_with_common(
+                            #locale,
+                            vec![ #(#resources),* ],
+                            vec![ #(#common_resources),* ],
+                        )
+                        .expect("conflicts are checked at compile time");
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+    let create_fluent_resources = shared_contents.iter().enumerate().map(|(idx, content)| {
+        let resource = shared_resource_ident(idx);
         quote! {
-            locales.add_locale(#locale, vec![ #(#create_fluent_resources),* ]);
+            let #resource = ::std::sync::Arc::new(
+                #krate::FluentResource::try_new(#content.to_string()) #unwrap_resource
+            );
         }
     });
 
-    let on_error = on_error.map_or_else(|| quote! { None }, |expr| quote! { Some(#expr) });
-
-    let trackers = all_absolute_file_paths.iter().enumerate().map(|(i, path)| {
-        let const_name = quote::format_ident!("_I18N_TRACKER_{}", i);
+    let skipped_note = scanner.skipped_note();
+    let normalized_note = (!normalized_files.is_empty()).then(|| {
+        warning(&format!(
+            "i18n: normalized {} files before parsing:\n{}",
+            normalized_files.len(),
+            normalized_files.join("\n")
+        ))
+    });
+    let empty_note = (!empty.is_empty()).then(|| {
+        warning(&format!(
+            "i18n: found {} empty locales or files:\n{}",
+            empty.len(),
+            empty.join("\n")
+        ))
+    });
+    let blank_note = (!blank_files.is_empty()).then(|| {
+        warning(&format!(
+            "i18n: skipped {} blank files, which define nothing:\n{}",
+            blank_files.len(),
+            blank_files.join("\n")
+        ))
+    });
+    // spanned, so that a handler of the wrong type is reported on it
+    let on_error = on_error.map(|expr| quote_spanned! {expr.span()=> .on_error(#expr) });
+    let resource_conflict = match resource_conflict {
+        ResourceConflict::Error => quote! { Error },
+        ResourceConflict::KeepFirst => quote! { KeepFirst },
+        ResourceConflict::Override => quote! { Override },
+    };
+    let missing_value = match missing_value {
+        MissingValuePolicy::AngleBrackets => quote! { AngleBrackets },
+        MissingValuePolicy::Empty => quote! { Empty },
+        MissingValuePolicy::Id => quote! { Id },
+        MissingValuePolicy::Error => quote! { Error },
+    };
+    let on_inline_default = on_inline_default.map(|expr| quote! { .on_inline_default(#expr) });
+    let dev_reload_options = dev_reload.then(|| {
+        let normalize = match normalize {
+            Normalization::None => quote! { None },
+            Normalization::Nfc => quote! { Nfc },
+        };
         quote! {
+            .normalize(#krate::Normalization::#normalize)
+            .allow_junk(#allow_junk)
+        }
+    });
+    // release builds leave the files unset, so that `Locales::reload` keeps the embedded content
+    let dev_reload_files = dev_reload.then(|| {
+        let (file_locales, file_paths): (Vec<_>, Vec<_>) = embedded_files.iter().cloned().unzip();
+        quote! {
+            #[cfg(debug_assertions)]
+            let builder = builder.reload_files([#((#file_locales, #file_paths)),*]);
+        }
+    });
+    let dev_reload_init = dev_reload.then(|| {
+        quote! {
+            // the files may have been edited since, or be missing in a deployed binary
+            #[cfg(debug_assertions)]
+            {
+                _ = locales.reload();
+            }
+        }
+    });
+
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut untracked = Vec::default();
+    let mut trackers = Vec::default();
+    for path in &tracked_files {
+        let Some(tracker_path) = tracker_path(path, &manifest_dir) else {
+            untracked.push(format!("\t- {}", path.display()));
+            continue;
+        };
+        let const_name = quote::format_ident!("_I18N_TRACKER_{}", trackers.len());
+        trackers.push(quote! {
             // This const is never used, but it makes the compiler track changes to the file.
             #[allow(dead_code)]
-            const #const_name: &str = include_str!(#path);
+            const #const_name: &str = include_str!(#tracker_path);
+        });
+    }
+    let untracked_note = (!untracked.is_empty()).then(|| {
+        warning(&format!(
+            "i18n: cannot track changes to {} files with non-UTF-8 paths, which are only re-read once the crate is rebuilt:\n{}",
+            untracked.len(),
+            untracked.join("\n")
+        ))
+    });
+
+    let available_langs = langs_name.map(|langs_name| {
+        let mut langs = locales
+            .iter()
+            .map(|locale| lang_expr(&krate, locale))
+            .collect::<Vec<_>>();
+        langs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let count = langs.len();
+        let langs = langs.into_iter().map(|(_, lang)| lang);
+        match legacy_lazy_static {
+            true => quote! {
+                #krate::lazy_static::lazy_static! {
+                    /// The languages embedded by `load!`, sorted by their ID.
+                    #vis static ref #langs_name: [#krate::Lang; #count] = [#(#langs),*];
+                }
+            },
+            false => quote! {
+                /// The languages embedded by `load!`, sorted by their ID.
+                #vis static #langs_name: ::std::sync::LazyLock<[#krate::Lang; #count]> =
+                    ::std::sync::LazyLock::new(|| [#(#langs),*]);
+            },
+        }
+    });
+
+    let init = quote! {
+        #(#trackers)*
+        let builder = #krate::LocalesBuilder::new()
+            .fallback(#fallback_lang)
+            #on_error
+            #on_inline_default
+            .resource_conflict(#krate::ResourceConflict::#resource_conflict)
+            .use_isolating(#use_isolating)
+            .missing_value(#krate::MissingValuePolicy::#missing_value)
+            #dev_reload_options;
+        #dev_reload_files
+        let locales = builder.build();
+        #(#create_fluent_resources)*
+        #(#add_locale)*
+        #dev_reload_init
+        locales
+    };
+    let static_item = match legacy_lazy_static {
+        true => quote! {
+            #krate::lazy_static::lazy_static! {
+                #vis static ref #name: #krate::Locales = { #init };
+            }
+        },
+        // the struct of the same name holds the constants `tr!` reads through `#name::`, as
+        // the struct generated by `lazy_static!` does
+        false => quote! {
+            #vis static #name: ::std::sync::LazyLock<#krate::Locales> =
+                ::std::sync::LazyLock::new(|| { #init });
+
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            #vis struct #name {}
+        },
+    };
+
+    let report_json = (report.is_some() || manifest).then(|| build_report.to_json(&fallback_lang));
+    let report_note = report
+        .as_ref()
+        .zip(report_json.as_ref())
+        .and_then(|(path, json)| BuildReport::write(json, path));
+    let manifest_item = report_json.filter(|_| manifest).map(|json| {
+        quote! {
+            /// The messages embedded by `load!` along with their attributes and files, as JSON.
+            #vis static I18N_MANIFEST: &str = #json;
         }
     });
 
+    let env_tracker = filter.env_tracker();
+    let path_env_tracker = locales_path.env_tracker();
+    let message_ids = message_ids.iter();
+
     quote! {
-        i18n::lazy_static::lazy_static! {
-            pub static ref #name: i18n::Locales = {
-                #(#trackers)*
-                let mut locales = i18n::Locales::new(#fallback_lang.parse().expect("compile time verified"), #on_error);
-                #(#add_locale)*
-                locales
-            };
+        #skipped_note
+        #normalized_note
+        #empty_note
+        #blank_note
+        #untracked_note
+        #missing_keys_note
+        #attrs_note
+        #report_note
+        #env_tracker
+        #path_env_tracker
+        #static_item
+        #available_langs
+        #manifest_item
+
+        impl #name {
+            /// The IDs of every embedded message, checked by `tr!` if `validate_keys` is enabled.
+            #[doc(hidden)]
+            pub const __I18N_KEYS: &'static [&'static str] = &[#(#message_ids),*];
+            #[doc(hidden)]
+            pub const __I18N_VALIDATE_KEYS: bool = #validate_keys;
         }
+
+        #typed_module
+        #keys_module
+        #keys_enum
+    }
+}
+
+/// The path of the file at `path` for `include_str!`, relative to the crate root with `/`
+/// separators, so that the expansion holds no absolute path of the machine it was built on.
+/// `None` if the path isn't UTF-8.
+fn tracker_path(path: &Path, manifest_dir: &Path) -> Option<proc_macro2::TokenStream> {
+    let common = path
+        .components()
+        .zip(manifest_dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    // a file on another drive than the crate has no relative path
+    if common == 0 {
+        let path = path.to_str()?.replace(std::path::MAIN_SEPARATOR, "/");
+        return Some(quote! { #path });
+    }
+
+    let parents = manifest_dir
+        .components()
+        .skip(common)
+        .map(|_| Component::ParentDir);
+    let relative = parents
+        .chain(path.components().skip(common))
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?
+        .join("/");
+    Some(quote! { concat!(env!("CARGO_MANIFEST_DIR"), "/", #relative) })
+}
+
+/// The name under which `check_keys` compares the flat `<locale>.ftl` files of the locales, which
+/// cannot clash with the name of a file within a locale directory.
+const FLAT_FILE_NAME: &str = "";
+
+/// Displays the file named `file_name` within `locale` as `check_keys` does, e.g. `en-US/main.ftl`.
+fn display_file(locale: &str, file_name: &str) -> String {
+    match file_name {
+        FLAT_FILE_NAME => format!("{locale}.ftl"),
+        file_name => format!("{locale}/{file_name}"),
+    }
+}
+
+/// The name of the directory or `.ftl` file of resources shared by every locale, unless set
+/// with `common`.
+pub const DEFAULT_COMMON_NAME: &str = "_common";
+
+/// The hygienic name of the `idx`th resource shared by the locales of a `load!` expansion.
+fn shared_resource_ident(idx: usize) -> Ident {
+    Ident::new(&format!("resource_{idx}"), Span::mixed_site())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    /// Creates an empty directory for a test, removing any leftovers of a previous run.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("i18n-macros-{name}-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn load(locales: &Path, cache: &Path, params: proc_macro2::TokenStream) -> String {
+        let locales = locales.to_str().unwrap();
+        expand(quote! { #locales #params }, || {
+            ParseCache::in_dir(cache.to_path_buf())
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_if_changed_files_are_reparsed() {
+        let dir = test_dir("reparse");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        let file = locales.join("en-US/main.ftl");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+
+        std::fs::write(&file, "hello = Hello\n").unwrap();
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("\"hello\""));
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 1);
+
+        std::fs::write(&file, "hello = Hello\ngoodbye = Bye\n").unwrap();
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("\"goodbye\""));
+
+        // the same size and modification time, which the source hash still tells apart
+        let modified = std::fs::metadata(&file).unwrap().modified().unwrap();
+        std::fs::write(&file, "hello = Hello\nfarewell = By\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("\"farewell\""));
+        assert!(!expansion.contains("\"goodbye\""));
+
+        std::fs::write(&file, "hello = Hello\nbroken\n").unwrap();
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("compile_error"));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_unchanged_files_are_read_from_the_cache() {
+        let dir = test_dir("hit");
+        let file = dir.join("main.ftl");
+        let source = "-brand = Acme\nhello = Hello from { -brand }, { $name }\n    .title = Hi\n";
+        std::fs::write(&file, source).unwrap();
+        let cache = ParseCache::in_dir(dir.join("cache")).unwrap();
+
+        let summary = cache.summarize(&file, source);
+        assert_eq!(summary, FileSummary::parse(source));
+        assert_eq!(summary.messages, ["hello"]);
+        assert_eq!(summary.terms, ["brand"]);
+        assert_eq!(
+            summary.vars,
+            [
+                ("hello".to_string(), vec!["name".to_string()]),
+                ("hello.title".to_string(), vec![])
+            ]
+        );
+        assert_eq!(
+            summary.attrs,
+            [("hello".to_string(), vec!["title".to_string()])]
+        );
+        assert_eq!(
+            summary.refs,
+            [
+                ("-brand".to_string(), vec![]),
+                ("hello".to_string(), vec!["-brand".to_string()])
+            ]
+        );
+
+        // tamper with the entry to tell a hit apart from a parse
+        let entry = std::fs::read_dir(dir.join("cache"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let tampered = std::fs::read_to_string(&entry).unwrap() + "\nmessage\tcached";
+        std::fs::write(&entry, tampered).unwrap();
+        assert_eq!(cache.summarize(&file, source).messages, ["hello", "cached"]);
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_flat_files_are_checked_against_each_other() {
+        let dir = test_dir("flat");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        std::fs::create_dir_all(locales.join("de-DE")).unwrap();
+        std::fs::write(locales.join("en-US.ftl"), "hello = Hello\nbye = Bye").unwrap();
+        std::fs::write(locales.join("hr-HR.ftl"), "hello = Bok").unwrap();
+        // a file within a locale directory is not compared to the flat files
+        std::fs::write(locales.join("de-DE/main.ftl"), "hello = Hallo").unwrap();
+
+        let expansion = load(&locales, &cache, quote!(, check_keys_per_file = true));
+        assert!(expansion.contains("Missing keys in hr-HR.ftl: bye"));
+        assert!(!expansion.contains("de-DE"));
+
+        std::fs::write(locales.join("hr-HR.ftl"), "hello = Bok\nbye = Bok bok").unwrap();
+        let expansion = load(&locales, &cache, quote!(, check_keys_per_file = true));
+        assert!(!expansion.contains("compile_error"));
+        assert!(expansion.contains("add_shared_locale (\"hr-HR\""));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_nested_files_are_checked_by_relative_path() {
+        let dir = test_dir("nested");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        std::fs::create_dir_all(locales.join("en-US/pages")).unwrap();
+        std::fs::create_dir_all(locales.join("hr-HR/pages")).unwrap();
+        std::fs::write(locales.join("en-US/main.ftl"), "hello = Hello").unwrap();
+        std::fs::write(locales.join("hr-HR/main.ftl"), "hello = Bok").unwrap();
+        std::fs::write(
+            locales.join("en-US/pages/settings.ftl"),
+            "settings-title = Settings\nsettings-save = Save",
+        )
+        .unwrap();
+        std::fs::write(
+            locales.join("hr-HR/pages/settings.ftl"),
+            "settings-title = Postavke",
+        )
+        .unwrap();
+
+        let expansion = load(&locales, &cache, quote!(, check_keys_per_file = true));
+        assert!(expansion.contains("Missing keys in hr-HR/pages/settings.ftl: settings-save"));
+
+        std::fs::write(
+            locales.join("hr-HR/pages/settings.ftl"),
+            "settings-title = Postavke\nsettings-save = Spremi",
+        )
+        .unwrap();
+        let expansion = load(&locales, &cache, quote!(, check_keys_per_file = true));
+        assert!(!expansion.contains("compile_error"));
+        assert!(expansion.contains("pages/settings.ftl"));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_colliding_key_constants_are_reported() {
+        let dir = test_dir("keys");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        std::fs::create_dir_all(locales.join("en-US")).unwrap();
+        std::fs::write(
+            locales.join("en-US/main.ftl"),
+            "login-btn = Log in\nlogin_btn = Log in\nlogout = Log out",
+        )
+        .unwrap();
+
+        let expansion = load(&locales, &cache, quote!(, keys_mod = keys));
+        assert!(expansion.contains("`login-btn`, `login_btn` all map to `LOGIN_BTN`"));
+
+        std::fs::write(locales.join("en-US/main.ftl"), "login-btn = Log in").unwrap();
+        let expansion = load(&locales, &cache, quote!(, keys_mod = keys));
+        assert!(!expansion.contains("compile_error"));
+        assert!(expansion.contains("pub const LOGIN_BTN : & str = \"login-btn\""));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_keys_are_compared_across_the_files_of_a_locale() {
+        let dir = test_dir("union");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        std::fs::create_dir_all(locales.join("en-US")).unwrap();
+        std::fs::create_dir_all(locales.join("hr-HR")).unwrap();
+        std::fs::write(locales.join("en-US/main.ftl"), "hello = Hello").unwrap();
+        std::fs::write(
+            locales.join("en-US/buttons.ftl"),
+            "save = Save\ncancel = Cancel",
+        )
+        .unwrap();
+        std::fs::write(locales.join("hr-HR/main.ftl"), "hello = Bok\nsave = Spremi").unwrap();
+
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("Missing keys in hr-HR: cancel (defined in en-US/buttons.ftl)"));
+        assert!(!expansion.contains("save ("));
+
+        let expansion = load(&locales, &cache, quote!(, check_keys_per_file = true));
+        assert!(expansion.contains("Missing keys in en-US/main.ftl: save"));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_excluded_files_are_neither_checked_nor_tracked() {
+        let dir = test_dir("exclude");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        std::fs::create_dir_all(locales.join("en-US/_drafts")).unwrap();
+        std::fs::create_dir_all(locales.join("hr-HR")).unwrap();
+        std::fs::write(locales.join("en-US/main.ftl"), "hello = Hello").unwrap();
+        std::fs::write(locales.join("en-US/_drafts/new.ftl"), "draft = Draft").unwrap();
+        std::fs::write(locales.join("en-US/promo.draft.ftl"), "promo = Promo").unwrap();
+        std::fs::write(locales.join("hr-HR/main.ftl"), "hello = Bok").unwrap();
+
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("Missing keys in hr-HR"));
+
+        let expansion = load(
+            &locales,
+            &cache,
+            quote!(, exclude = ["_drafts/**", "*.draft.ftl"]),
+        );
+        assert!(!expansion.contains("compile_error"));
+        assert!(!expansion.contains("_drafts"));
+        assert!(!expansion.contains("promo.draft.ftl"));
+
+        let expansion = load(&locales, &cache, quote!(, exclude = ["[invalid"]));
+        assert!(expansion.contains("Invalid glob pattern '[invalid'"));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_conflicting_entries_follow_the_policy() {
+        let dir = test_dir("conflict");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        std::fs::create_dir_all(locales.join("en-US")).unwrap();
+        std::fs::write(
+            locales.join("en-US/app.ftl"),
+            "hello = Hello from the app\n-brand = App",
+        )
+        .unwrap();
+        std::fs::write(
+            locales.join("en-US/common.ftl"),
+            "hello = Hello\n-brand = Acme\nbye = Bye",
+        )
+        .unwrap();
+
+        let expansion = load(&locales, &cache, quote!());
+        assert!(expansion.contains("compile_error"));
+        assert!(expansion.contains("hello in en-US/common.ftl, first defined in en-US/app.ftl"));
+        assert!(expansion.contains("-brand in en-US/common.ftl"));
+        assert!(!expansion.contains("bye in"));
+
+        for policy in ["keep_first", "override"] {
+            let expansion = load(&locales, &cache, quote!(, resource_conflict = #policy));
+            assert!(!expansion.contains("compile_error"), "{policy}");
+        }
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Compares cold and warm invocations on 500 files, run with `cargo test -p i18n-macros -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_cached_invocations() {
+        let dir = test_dir("bench");
+        let (locales, cache) = (dir.join("i18n"), dir.join("cache"));
+        for locale in ["en-US", "hr-HR"] {
+            std::fs::create_dir_all(locales.join(locale)).unwrap();
+            for file in 0..250 {
+                let source = (0..40)
+                    .map(|msg| {
+                        format!(
+                            "msg-{file}-{msg} = {{ $count ->\n    [one] One {locale} item\n   *[other] {{ $count }} {locale} items\n}}\n    .title = Title {msg}\n"
+                        )
+                    })
+                    .collect::<String>();
+                std::fs::write(
+                    locales.join(locale).join(format!("file-{file}.ftl")),
+                    source,
+                )
+                .unwrap();
+            }
+        }
+
+        let locales_path = locales.to_str().unwrap();
+        let time = |params: proc_macro2::TokenStream| {
+            let start = Instant::now();
+            for _ in 0..5 {
+                expand(quote! { #locales_path #params }, || {
+                    ParseCache::in_dir(cache.clone())
+                });
+            }
+            start.elapsed() / 5
+        };
+        let sources = walkdir::WalkDir::new(&locales)
+            .into_iter()
+            .map(|entry| entry.unwrap())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| std::fs::read_to_string(entry.path()).unwrap())
+            .collect::<Vec<_>>();
+        let start = Instant::now();
+        sources
+            .iter()
+            .for_each(|source| _ = FileSummary::parse(source));
+        let parsing = start.elapsed();
+
+        let uncached = time(quote!(, cache = false));
+        load(&locales, &cache, quote!());
+        let cached = time(quote!());
+        println!(
+            "500 files: {uncached:?} uncached, {cached:?} cached ({:.1}x), of which {parsing:?} is parsing",
+            uncached.as_secs_f64() / cached.as_secs_f64()
+        );
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 500);
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_trackers_are_relative_to_the_crate_root() {
+        let dir = test_dir("trackers");
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        let workspace_dir = manifest_dir.parent().unwrap();
+        let locales = workspace_dir.join("tests/i18n");
+
+        let expansion = load(&locales, &dir, quote!(, allow_missing = ["hr-hr"]));
+        assert!(expansion.contains("include_str ! (concat ! (env ! (\"CARGO_MANIFEST_DIR\") , \"/\" , \"../tests/i18n/en-US/main.ftl\"))"));
+        assert!(!expansion.contains(workspace_dir.to_str().unwrap()));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_if_tracker_paths_leave_the_crate_root() {
+        let manifest_dir = Path::new("/work/app");
+        let path = Path::new("/work/shared/i18n/en-US/main.ftl");
+        let tracker = tracker_path(path, manifest_dir).unwrap().to_string();
+        assert!(tracker.ends_with("\"../shared/i18n/en-US/main.ftl\")"));
+
+        let path = Path::new("/work/app/../shared/i18n/en-US/main.ftl");
+        let tracker = tracker_path(path, manifest_dir).unwrap().to_string();
+        assert!(tracker.ends_with("\"../shared/i18n/en-US/main.ftl\")"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_if_non_utf8_paths_are_not_tracked() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let manifest_dir = Path::new("/work/app");
+        let path = Path::new("/work/app/i18n").join(std::ffi::OsStr::from_bytes(b"\xff.ftl"));
+        assert!(tracker_path(&path, manifest_dir).is_none());
     }
-    .into()
 }
@@ -0,0 +1,221 @@
+use glob::{MatchOptions, Pattern};
+use quote::quote;
+use std::path::{Path, PathBuf};
+use syn::parse::{ParseStream, Result};
+use syn::{bracketed, punctuated::Punctuated, LitStr, Token};
+
+/// The options glob patterns are matched with, so that `*` never crosses a `/`.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Glob patterns selecting the entries of a locales directory to embed, as in
+/// `exclude = ["_drafts/**", "*.draft.ftl"]` and `include = ["**/*.ftl"]`.
+///
+/// Patterns are matched against the `/`-separated path of an entry relative to the locales
+/// directory, e.g. `en-US/_drafts/notes.ftl`, and against every trailing part of it, so that
+/// `*.draft.ftl` matches a draft at any depth while `en-US/_drafts/**` only matches within `en-US`.
+#[derive(Default)]
+pub struct PathFilter {
+    /// The patterns a file must match one of, unless empty.
+    include: Vec<Pattern>,
+    /// The patterns excluding files, along with the pattern excluding whole directories for the
+    /// ones ending with `/**`.
+    exclude: Vec<(Pattern, Option<Pattern>)>,
+}
+
+impl PathFilter {
+    /// Parses the value of the `key` parameter if it belongs to the filter, returning whether it did.
+    pub fn parse_param(&mut self, key: &str, input: ParseStream) -> Result<bool> {
+        match key {
+            "include" => self.include = parse_patterns(input)?,
+            "exclude" => {
+                self.exclude = parse_patterns(input)?
+                    .into_iter()
+                    .map(|pattern| {
+                        let dir = pattern
+                            .as_str()
+                            .strip_suffix("/**")
+                            .and_then(|dir| Pattern::new(dir).ok());
+                        (pattern, dir)
+                    })
+                    .collect();
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Whether the directory at `path` should be skipped along with everything within it.
+    pub fn excludes_dir(&self, path: &str) -> bool {
+        self.exclude.iter().any(|(pattern, dir)| {
+            matches(pattern, path) || dir.as_ref().is_some_and(|dir| matches(dir, path))
+        })
+    }
+
+    /// Whether the file at `path` should be embedded.
+    pub fn includes_file(&self, path: &str) -> bool {
+        let included =
+            self.include.is_empty() || self.include.iter().any(|pattern| matches(pattern, path));
+        included
+            && !self
+                .exclude
+                .iter()
+                .any(|(pattern, _)| matches(pattern, path))
+    }
+}
+
+/// Parses an array of glob literals, reporting an invalid pattern at its literal.
+fn parse_patterns(input: ParseStream) -> Result<Vec<Pattern>> {
+    let content;
+    bracketed!(content in input);
+    Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+        .into_iter()
+        .map(|pattern| {
+            Pattern::new(&pattern.value()).map_err(|err| {
+                syn::Error::new(
+                    pattern.span(),
+                    format!("Invalid glob pattern '{}': {err}", pattern.value()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Whether `pattern` matches `path` or any of its trailing parts.
+fn matches(pattern: &Pattern, path: &str) -> bool {
+    std::iter::once(path)
+        .chain(path.match_indices('/').map(|(idx, _)| &path[idx + 1..]))
+        .any(|tail| pattern.matches_with(tail, MATCH_OPTIONS))
+}
+
+/// The variable naming the root of the workspace, found by walking up from the crate root when it
+/// is not set in the environment.
+const WORKSPACE_ROOT: &str = "WORKSPACE_ROOT";
+
+/// The locales directory given to `load!` or `langs!`, resolved at expansion time.
+pub struct LocalesPath {
+    /// The directory followed symlinks must not escape.
+    pub root: PathBuf,
+    /// The absolute path of the locales directory.
+    pub absolute: PathBuf,
+    /// The environment variables interpolated into the path, tracked for rebuilds.
+    env: Vec<String>,
+}
+
+impl LocalesPath {
+    /// Resolves `path` against the crate root, or against the workspace root if
+    /// `from_workspace_root` is set, after interpolating the `$VAR` and `${VAR}` environment
+    /// variables it references.
+    pub fn resolve(path: &LitStr, from_workspace_root: bool) -> Result<Self> {
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        let mut env = Vec::default();
+        let mut uses_workspace_root = from_workspace_root;
+        let interpolated = interpolate(&path.value(), |name| {
+            if let Ok(value) = std::env::var(name) {
+                env.push(name.to_string());
+                return Ok(value);
+            }
+            if name == WORKSPACE_ROOT {
+                uses_workspace_root = true;
+                return Ok(workspace_root(&manifest_dir).display().to_string());
+            }
+            Err(format!(
+                "Environment variable `{name}` referenced by the locales path is not set"
+            ))
+        })
+        .map_err(|msg| syn::Error::new(path.span(), msg))?;
+
+        let base = match uses_workspace_root {
+            true => workspace_root(&manifest_dir),
+            false => manifest_dir,
+        };
+        let absolute = base.join(interpolated);
+        let within_base = match (absolute.canonicalize(), base.canonicalize()) {
+            (Ok(absolute), Ok(base)) => absolute.starts_with(base),
+            _ => absolute.starts_with(&base),
+        };
+        // a path leading out of the crate may only follow symlinks within itself
+        let root = match within_base {
+            true => base,
+            false => absolute.clone(),
+        };
+
+        Ok(LocalesPath {
+            root,
+            absolute,
+            env,
+        })
+    }
+
+    /// Emits an `option_env!` for every interpolated variable, so that changing one rebuilds the
+    /// crate.
+    pub fn env_tracker(&self) -> proc_macro2::TokenStream {
+        let env = &self.env;
+        quote! {
+            #(const _: Option<&str> = option_env!(#env);)*
+        }
+    }
+}
+
+/// Replaces every `$VAR` and `${VAR}` in `path` with the value `lookup` gives for `VAR`, with
+/// `$$` standing for a literal `$`.
+fn interpolate(
+    path: &str,
+    mut lookup: impl FnMut(&str) -> std::result::Result<String, String>,
+) -> std::result::Result<String, String> {
+    let mut interpolated = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(idx) = rest.find('$') {
+        interpolated.push_str(&rest[..idx]);
+        rest = &rest[idx + 1..];
+        let name = if let Some(braced) = rest.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| format!("Unterminated `${{` in the locales path '{path}'"))?;
+            rest = &braced[end + 1..];
+            &braced[..end]
+        } else if let Some(escaped) = rest.strip_prefix('$') {
+            interpolated.push('$');
+            rest = escaped;
+            continue;
+        } else {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let name = &rest[..end];
+            rest = &rest[end..];
+            name
+        };
+
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(format!(
+                "Expected an environment variable name after `$` in the locales path '{path}' (use `$$` for a literal `$`)"
+            ));
+        }
+        interpolated.push_str(&lookup(name)?);
+    }
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+/// Finds the root of the workspace `manifest_dir` belongs to: the closest directory whose
+/// `Cargo.toml` has a `[workspace]` table, or `manifest_dir` itself if there is none.
+fn workspace_root(manifest_dir: &Path) -> PathBuf {
+    manifest_dir
+        .ancestors()
+        .find(|dir| {
+            std::fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|manifest| {
+                manifest.lines().any(|line| {
+                    let line = line.trim();
+                    line == "[workspace]" || line.starts_with("[workspace.")
+                })
+            })
+        })
+        .unwrap_or(manifest_dir)
+        .to_path_buf()
+}
@@ -0,0 +1,102 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use syn::LitStr;
+
+use crate::cache::FileSummary;
+use crate::scan::warning;
+
+/// The messages embedded by `load!`, written to the `report` file and embedded by `manifest`.
+#[derive(Default)]
+pub struct BuildReport {
+    /// The messages of every locale, keyed by the canonical locale and then by the message ID.
+    locales: BTreeMap<String, BTreeMap<String, ReportedMessage>>,
+    /// The messages of the resources shared by every locale.
+    common: BTreeMap<String, ReportedMessage>,
+}
+
+/// A message of a `BuildReport`.
+#[derive(Default, Clone)]
+struct ReportedMessage {
+    /// The attributes of the message, in source order.
+    attributes: Vec<String>,
+    /// The files defining the message, relative to the locales directory.
+    files: Vec<String>,
+}
+
+impl BuildReport {
+    /// Records the messages of an embedded file, which is common to every locale if `locale` is
+    /// `None`.
+    pub fn add_file(&mut self, locale: Option<&str>, file_display: &str, summary: &FileSummary) {
+        let messages = match locale {
+            Some(locale) => self.locales.entry(locale.to_string()).or_default(),
+            None => &mut self.common,
+        };
+        for id in &summary.messages {
+            let message = messages.entry(id.clone()).or_default();
+            message.files.push(file_display.to_string());
+            let attrs = summary
+                .attrs
+                .iter()
+                .filter(|(attr_id, _)| attr_id == id)
+                .flat_map(|(_, attrs)| attrs);
+            for attr in attrs {
+                if !message.attributes.contains(attr) {
+                    message.attributes.push(attr.clone());
+                }
+            }
+        }
+    }
+
+    /// Serializes the report, every locale listing the common messages it doesn't override.
+    pub fn to_json(&self, fallback_lang: &str) -> String {
+        let locales = self
+            .locales
+            .iter()
+            .map(|(locale, messages)| {
+                let mut messages = messages.clone();
+                for (id, message) in &self.common {
+                    messages
+                        .entry(id.clone())
+                        .or_insert_with(|| message.clone());
+                }
+                let messages = messages
+                    .into_iter()
+                    .map(|(id, message)| {
+                        let message = json!({
+                            "attributes": message.attributes,
+                            "files": message.files,
+                        });
+                        (id, message)
+                    })
+                    .collect::<Map<_, _>>();
+                (locale.clone(), Value::Object(messages))
+            })
+            .collect::<Map<_, _>>();
+
+        let report = json!({
+            "fallback": fallback_lang,
+            "locales": locales,
+        });
+        serde_json::to_string_pretty(&report).expect("the report is valid JSON")
+    }
+
+    /// Writes `json` to the `report` path, relative to the crate root, creating its parent
+    /// directories. Failing to write is only a warning, as the build doesn't depend on it.
+    pub fn write(json: &str, path: &LitStr) -> Option<proc_macro2::TokenStream> {
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        let path = manifest_dir.join(path.value());
+        let written = match path.parent() {
+            Some(parent) => std::fs::create_dir_all(parent),
+            None => Ok(()),
+        }
+        .and_then(|_| std::fs::write(&path, json));
+
+        written.err().map(|err| {
+            warning(&format!(
+                "i18n: failed to write the report to {}: {err}",
+                path.display()
+            ))
+        })
+    }
+}
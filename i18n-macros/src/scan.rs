@@ -0,0 +1,206 @@
+use quote::quote;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use unic_langid::LanguageIdentifier;
+
+use crate::paths::PathFilter;
+
+/// An entry that passed all checks of a `DirScanner`.
+pub struct ScannedEntry {
+    /// The path of the entry.
+    pub path: PathBuf,
+    /// The UTF-8 file name of the entry.
+    pub name: String,
+    /// Whether the entry is (or points to) a directory.
+    pub is_dir: bool,
+}
+
+impl ScannedEntry {
+    /// The name of the locale defined by the entry of a locales directory, either a subdirectory
+    /// named after the locale or a flat `<locale>.ftl` file.
+    pub fn locale_name(&self) -> Option<&str> {
+        match self.is_dir {
+            true => Some(&self.name),
+            false => self
+                .name
+                .strip_suffix(".ftl")
+                .filter(|stem| !stem.is_empty()),
+        }
+    }
+
+    /// The canonical form of the `locale_name`, e.g. `hr-HR` for a `hr-hr` directory, as locales
+    /// are looked up by their exact language identifier at runtime. Fails with a description of
+    /// the entry if its name isn't a language identifier, such as `english`.
+    pub fn canonical_locale(&self) -> Option<Result<String, String>> {
+        let name = self.locale_name()?;
+        let kind = match self.is_dir {
+            true => "directory",
+            false => "file",
+        };
+        let expected = format!(
+            "Expected the {kind} '{}' to be named after a language identifier, e.g. `en-US`",
+            self.name
+        );
+        let canonical = match name.parse::<LanguageIdentifier>() {
+            // the 5 to 8 letter language subtags are reserved, so `english` is a valid identifier
+            Ok(lang) if lang.language.as_str().len() > 3 => Err(format!(
+                "{expected}: '{}' is not a 2 or 3 letter ISO 639 language code",
+                lang.language
+            )),
+            Ok(lang) => Ok(lang.to_string()),
+            Err(err) => Err(format!("{expected}: {err}")),
+        };
+        Some(canonical)
+    }
+}
+
+/// A guarded directory walker shared by the macros that scan locale directories.
+///
+/// It breaks symlink cycles by tracking canonicalized visited directories, refuses to follow
+/// symlinks unless asked to (and even then only within the crate root), and skips hidden and
+/// non-UTF-8 entries. Every skipped entry is recorded so it can be surfaced in a single note.
+pub struct DirScanner {
+    /// The canonicalized crate root which followed symlinks must not escape.
+    root: PathBuf,
+    /// Whether symlinks should be followed at all.
+    follow_symlinks: bool,
+    /// The canonicalized directories read so far.
+    visited: HashSet<PathBuf>,
+    /// A human-readable description of every skipped entry.
+    skipped: Vec<String>,
+}
+
+impl DirScanner {
+    /// Creates a new scanner confined to `root`.
+    pub fn new(root: &Path, follow_symlinks: bool) -> Self {
+        Self {
+            root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            follow_symlinks,
+            visited: HashSet::default(),
+            skipped: Vec::default(),
+        }
+    }
+
+    /// Reads the entries of `dir`, sorted by name, skipping any entry that fails the checks.
+    ///
+    /// A directory that was already visited yields no entries.
+    pub fn read_dir(&mut self, dir: &Path) -> std::io::Result<Vec<ScannedEntry>> {
+        let canonical = dir.canonicalize()?;
+        if !self.visited.insert(canonical) {
+            self.skip(dir, "directory was already visited (symlink cycle)");
+            return Ok(Vec::default());
+        }
+
+        let mut entries = Vec::default();
+        for entry in std::fs::read_dir(dir)? {
+            let Ok(entry) = entry else { continue };
+            let path = entry.path();
+
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                self.skip(&path, "file name is not valid UTF-8");
+                continue;
+            };
+            if name.starts_with('.') {
+                self.skip(&path, "hidden entry");
+                continue;
+            }
+
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                self.skip(&path, "could not read metadata");
+                continue;
+            };
+            let is_dir = if metadata.file_type().is_symlink() {
+                if !self.follow_symlinks {
+                    self.skip(&path, "symlink (set `follow_symlinks = true` to follow)");
+                    continue;
+                }
+                match path.canonicalize() {
+                    Ok(target) if target.starts_with(&self.root) => target.is_dir(),
+                    Ok(_) => {
+                        self.skip(&path, "symlink points outside of the crate");
+                        continue;
+                    }
+                    Err(_) => {
+                        self.skip(&path, "broken symlink");
+                        continue;
+                    }
+                }
+            } else {
+                metadata.is_dir()
+            };
+
+            entries.push(ScannedEntry { path, name, is_dir });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(entries)
+    }
+
+    /// Collects the `.ftl` files within `dir` and all of its subdirectories, each along with its
+    /// path relative to `dir` using `/` as the separator, e.g. `pages/settings.ftl`.
+    ///
+    /// The files of a directory come before the files of its subdirectories. Entries are matched
+    /// against `filter` by their path relative to the locales directory, `dir` being at `root`.
+    pub fn read_ftl_tree(
+        &mut self,
+        dir: &Path,
+        root: &str,
+        filter: &PathFilter,
+    ) -> std::io::Result<Vec<(String, ScannedEntry)>> {
+        let mut files = Vec::default();
+        let mut dirs = vec![(String::default(), dir.to_path_buf())];
+        while let Some((prefix, dir)) = dirs.pop() {
+            let mut subdirs = Vec::default();
+            for entry in self.read_dir(&dir)? {
+                let relative = format!("{prefix}{}", entry.name);
+                let path = format!("{root}/{relative}");
+                if entry.is_dir {
+                    if !filter.excludes_dir(&path) {
+                        subdirs.push((format!("{relative}/"), entry.path));
+                    }
+                } else if entry.path.extension().and_then(|ext| ext.to_str()) == Some("ftl")
+                    && filter.includes_file(&path)
+                {
+                    files.push((relative, entry));
+                }
+            }
+            // popped in order, so that sibling directories are walked by name
+            dirs.extend(subdirs.into_iter().rev());
+        }
+
+        Ok(files)
+    }
+
+    /// Emits a single compile-time warning listing every skipped entry, if there are any.
+    pub fn skipped_note(&self) -> proc_macro2::TokenStream {
+        if self.skipped.is_empty() {
+            return quote! {};
+        }
+
+        warning(&format!(
+            "i18n: skipped {} entries while scanning locale directories:\n{}",
+            self.skipped.len(),
+            self.skipped.join("\n")
+        ))
+    }
+
+    /// Records a skipped entry.
+    fn skip(&mut self, path: &Path, reason: &str) {
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        self.skipped
+            .push(format!("\t- {}: {reason}", path.display()));
+    }
+}
+
+/// Emits a compile-time warning with the given note.
+///
+/// Proc macros cannot emit warnings on stable, so this piggybacks on the `deprecated` lint.
+pub fn warning(note: &str) -> proc_macro2::TokenStream {
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct I18nNote;
+            let _ = I18nNote;
+        };
+    }
+}
@@ -1,14 +1,35 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
+use quote::{quote, ToTokens};
 use std::collections::HashMap;
 use syn::parse::{Parse, ParseStream, Result};
-use syn::{Expr, Ident, LitStr, Token};
+use syn::{Expr, Ident, LitStr, Path, Token};
+
+use crate::crate_path::default_crate_path;
+use crate::keys::key_check;
+
+/// The message ID of a `tr!`, either a string literal or the path of a key constant generated by
+/// `load!` with `keys_mod`.
+enum MessageId {
+    Lit(LitStr),
+    Const(Path),
+}
+
+impl ToTokens for MessageId {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            MessageId::Lit(id) => id.to_tokens(tokens),
+            MessageId::Const(id) => id.to_tokens(tokens),
+        }
+    }
+}
 
 struct TrMacroInput {
     lang: Expr,
-    id: LitStr,
-    locales_var: Ident,
+    id: MessageId,
+    locales_var: Path,
+    krate: Path,
+    default: Option<Expr>,
     main_args: Vec<(String, Expr)>,
     attr_args: HashMap<String, Vec<(String, Expr)>>,
 }
@@ -27,11 +48,19 @@ impl Parse for TrMacroInput {
                 "Expected a comma after the language identifier.",
             )
         })?;
-        let id: LitStr = input.parse().map_err(|err| {
-            syn::Error::new(err.span(), "Expected a message ID (a string literal).")
-        })?;
-
-        let mut locales_var = Ident::new("LOCALES", Span::call_site());
+        let id = match input.peek(LitStr) {
+            true => MessageId::Lit(input.parse()?),
+            false => MessageId::Const(input.parse().map_err(|err| {
+                syn::Error::new(
+                    err.span(),
+                    "Expected a message ID (a string literal or a key constant).",
+                )
+            })?),
+        };
+
+        let mut locales_var = Path::from(Ident::new("LOCALES", Span::call_site()));
+        let mut krate = default_crate_path();
+        let mut default = None;
         let mut main_args = Vec::new();
         let mut attr_args: HashMap<String, Vec<(String, Expr)>> = HashMap::new();
 
@@ -47,10 +76,14 @@ impl Parse for TrMacroInput {
 
                 if key_ident == "locales" {
                     locales_var = input.parse()?;
+                } else if key_ident == "crate_path" {
+                    krate = input.parse()?;
+                } else if key_ident == "default" {
+                    default = Some(input.parse()?);
                 } else {
-                    return Err(
-                        input.error("Unexpected identifier. Expected `locales = VAR_NAME`.")
-                    );
+                    return Err(input.error(
+                        "Unexpected identifier. Expected `locales = PATH`, `crate_path = PATH` or `default = EXPR`.",
+                    ));
                 }
             } else if input.peek(LitStr) && input.peek2(Token![=]) {
                 // This is a main message arg: key = value
@@ -58,6 +91,12 @@ impl Parse for TrMacroInput {
                 input.parse::<Token![=]>()?;
 
                 let value: Expr = input.parse()?;
+                if main_args
+                    .iter()
+                    .any(|(existing, _)| *existing == key.value())
+                {
+                    return Err(duplicate_arg(&key));
+                }
                 main_args.push((key.value(), value));
             } else if input.peek(Ident) && input.peek2(syn::token::Paren) {
                 // Check for attr(...)
@@ -76,13 +115,17 @@ impl Parse for TrMacroInput {
                 content.parse::<Token![=]>()?;
                 let arg_value: Expr = content.parse()?;
 
-                attr_args
-                    .entry(attr_id.value())
-                    .or_default()
-                    .push((arg_key.value(), arg_value));
+                let args = attr_args.entry(attr_id.value()).or_default();
+                if args
+                    .iter()
+                    .any(|(existing, _)| *existing == arg_key.value())
+                {
+                    return Err(duplicate_arg(&arg_key));
+                }
+                args.push((arg_key.value(), arg_value));
             } else {
                 return Err(input.error(
-                    "Unexpected token. Expected `locales = VAR_NAME`, `attr(...)`, or `key = value`."
+                    "Unexpected token. Expected `locales = PATH`, `attr(...)`, or `key = value`."
                 ));
             }
         }
@@ -91,17 +134,32 @@ impl Parse for TrMacroInput {
             lang,
             id,
             locales_var,
+            krate,
+            default,
             main_args,
             attr_args,
         })
     }
 }
 
+/// Reports an argument key that was already given, at its second occurrence.
+pub fn duplicate_arg(key: &LitStr) -> syn::Error {
+    syn::Error::new(
+        key.span(),
+        format!(
+            "Duplicate argument `{}`; only the last value would be used",
+            key.value()
+        ),
+    )
+}
+
 pub fn tr_impl(input: TokenStream) -> TokenStream {
     let TrMacroInput {
         lang,
         id,
         locales_var,
+        krate,
+        default,
         main_args,
         attr_args,
     } = match syn::parse(input) {
@@ -109,7 +167,7 @@ pub fn tr_impl(input: TokenStream) -> TokenStream {
         Err(err) => return err.to_compile_error().into(),
     };
 
-    let mut query_builder = quote! { i18n::Query::new(#id) };
+    let mut query_builder = quote! { #krate::Query::new(#id) };
 
     for (key, value) in main_args.into_iter() {
         query_builder = quote! { #query_builder.with_arg(#key, #value) };
@@ -120,19 +178,35 @@ pub fn tr_impl(input: TokenStream) -> TokenStream {
         }
     }
 
-    let query_call = quote! {
-        #locales_var.query(&#lang, &#query_builder)
+    // only the message of a dotted ID such as `login-btn.aria-label` is validated, and a key
+    // constant is validated by resolving it
+    let key_check = match &id {
+        MessageId::Lit(id) => {
+            let key = match id.value().split_once('.') {
+                Some((key, _)) => LitStr::new(key, id.span()),
+                None => id.clone(),
+            };
+            Some(key_check(&krate, &locales_var, &key, default.is_some()))
+        }
+        MessageId::Const(_) => None,
+    };
+    let lang_var = Ident::new("lang", Span::mixed_site());
+    let query_call = match default {
+        Some(default) => quote! {
+            #locales_var.query_or_default(#lang_var, &#query_builder, #default)
+        },
+        None => quote! {
+            #locales_var.query(#lang_var, &#query_builder)
+        },
     };
 
     let final_expansion = quote! {
-        match #query_call {
-            Ok(msg) => msg,
-            Err(_err) => {
-                i18n::Message {
-                    id: #id.to_string(),
-                    value: #id.to_string(),
-                    attrs: Default::default(),
-                }
+        {
+            #key_check
+            let #lang_var = &#lang;
+            match #query_call {
+                Ok(msg) => msg,
+                Err(_err) => #locales_var.placeholder_message(#lang_var, #id),
             }
         }
     };
@@ -0,0 +1,111 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Expr, Ident, LitStr, Path, Token};
+
+use crate::crate_path::default_crate_path;
+use crate::keys::key_check;
+
+struct TrBatchMacroInput {
+    lang: Expr,
+    ids: Vec<LitStr>,
+    locales_var: Path,
+    krate: Path,
+}
+
+impl Parse for TrBatchMacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lang: Expr = input.parse().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "Expected a language identifier (e.g., `langid!(\"en-US\")` or a variable).",
+            )
+        })?;
+        input.parse::<Token![,]>().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "Expected a comma after the language identifier.",
+            )
+        })?;
+
+        let content;
+        syn::bracketed!(content in input);
+        let ids = content
+            .parse_terminated(|input| input.parse::<LitStr>(), Token![,])
+            .map_err(|err| {
+                syn::Error::new(
+                    err.span(),
+                    "Expected an array of message IDs (string literals).",
+                )
+            })?
+            .into_iter()
+            .collect();
+
+        let mut locales_var = Path::from(Ident::new("LOCALES", Span::call_site()));
+        let mut krate = default_crate_path();
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key_ident: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if key_ident == "locales" {
+                locales_var = input.parse()?;
+            } else if key_ident == "crate_path" {
+                krate = input.parse()?;
+            } else {
+                return Err(syn::Error::new(
+                    key_ident.span(),
+                    "Unexpected identifier. Expected `locales = PATH` or `crate_path = PATH`.",
+                ));
+            }
+        }
+
+        Ok(TrBatchMacroInput {
+            lang,
+            ids,
+            locales_var,
+            krate,
+        })
+    }
+}
+
+pub fn tr_batch_impl(input: TokenStream) -> TokenStream {
+    let TrBatchMacroInput {
+        lang,
+        ids,
+        locales_var,
+        krate,
+    } = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let lang_var = Ident::new("lang", Span::mixed_site());
+    let messages = ids.iter().map(|id| {
+        quote! {
+            match results.next().expect("one result per query") {
+                Ok(msg) => msg,
+                Err(_err) => #locales_var.placeholder_message(#lang_var, #id),
+            }
+        }
+    });
+
+    let key_checks = ids
+        .iter()
+        .map(|id| key_check(&krate, &locales_var, id, false));
+    let final_expansion = quote! {
+        {
+            #(#key_checks)*
+            let #lang_var = &#lang;
+            let queries = [#(#krate::Query::new(#ids)),*];
+            let mut results = #locales_var.query_batch(#lang_var, &queries).into_iter();
+            [#(#messages),*]
+        }
+    };
+
+    TokenStream::from(final_expansion)
+}
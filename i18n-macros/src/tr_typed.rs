@@ -0,0 +1,118 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Expr, Ident, Path, Token};
+
+use crate::crate_path::default_crate_path;
+
+struct TrTypedMacroInput {
+    lang: Expr,
+    handle: Expr,
+    locales_var: Path,
+    krate: Path,
+}
+
+impl Parse for TrTypedMacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lang: Expr = input.parse().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "Expected a language identifier (e.g., `langid!(\"en-US\")` or a variable).",
+            )
+        })?;
+        input.parse::<Token![,]>().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "Expected a comma after the language identifier.",
+            )
+        })?;
+        let handle = match input.parse::<Expr>()? {
+            Expr::Struct(mut handle) => {
+                if let Some(rest) = handle.rest {
+                    return Err(syn::Error::new_spanned(
+                        rest,
+                        "Expected every argument of the message, `..` is not supported.",
+                    ));
+                }
+                // the fields are `FluentValue`s, so convert the values like `tr!` does
+                for field in handle.fields.iter_mut() {
+                    let value = &field.expr;
+                    field.expr = syn::parse_quote!(::core::convert::Into::into(#value));
+                    field.colon_token.get_or_insert_with(Default::default);
+                }
+                Expr::Struct(handle)
+            }
+            Expr::Path(handle) => Expr::Path(handle),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "Expected a typed message handle, e.g. `WelcomeBack { username: \"Alice\" }` or `LoginBtn`.",
+                ))
+            }
+        };
+
+        let mut locales_var = Path::from(Ident::new("LOCALES", Span::call_site()));
+        let mut krate = default_crate_path();
+
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if key == "locales" {
+                locales_var = input.parse()?;
+            } else if key == "crate_path" {
+                krate = input.parse()?;
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "Unexpected identifier. Expected `locales = PATH` or `crate_path = PATH`.",
+                ));
+            }
+        }
+
+        Ok(TrTypedMacroInput {
+            lang,
+            handle,
+            locales_var,
+            krate,
+        })
+    }
+}
+
+pub fn tr_typed_impl(input: TokenStream) -> TokenStream {
+    let TrTypedMacroInput {
+        lang,
+        handle,
+        locales_var,
+        krate,
+    } = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let handle_var = Ident::new("handle", Span::mixed_site());
+    let id_var = Ident::new("id", Span::mixed_site());
+    let lang_var = Ident::new("lang", Span::mixed_site());
+    let final_expansion = quote! {
+        {
+            fn __i18n_message_id<M: #krate::MessageKey>(_: &M) -> &'static str {
+                M::ID
+            }
+
+            let #handle_var = #handle;
+            let #id_var = __i18n_message_id(&#handle_var);
+            let #lang_var = &#lang;
+            match #locales_var.query_typed(#lang_var, #handle_var) {
+                Ok(msg) => msg,
+                Err(_err) => #locales_var.placeholder_message(#lang_var, #id_var),
+            }
+        }
+    };
+
+    TokenStream::from(final_expansion)
+}
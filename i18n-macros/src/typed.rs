@@ -0,0 +1,439 @@
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement, Resource};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Ident, Token, Visibility};
+
+/// The module to generate the typed message handles in, as in `typed = pub mod messages`.
+pub struct TypedModule {
+    vis: Visibility,
+    name: Ident,
+}
+
+impl Parse for TypedModule {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vis = input.parse()?;
+        input.parse::<Token![mod]>().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "Expected a module, as in `typed = pub mod messages`.",
+            )
+        })?;
+        let name = input.parse()?;
+        Ok(TypedModule { vis, name })
+    }
+}
+
+/// The variables, message and term references of a pattern.
+#[derive(Default)]
+pub struct PatternRefs {
+    pub vars: BTreeSet<String>,
+    /// The referenced messages, along with the referenced attribute if any.
+    pub messages: BTreeSet<(String, Option<String>)>,
+    /// The referenced terms, without the leading `-`.
+    pub terms: BTreeSet<String>,
+}
+
+/// A message of a locale, as seen by the typed handles.
+#[derive(Default)]
+struct MessageRefs {
+    value: PatternRefs,
+    attrs: BTreeMap<String, PatternRefs>,
+}
+
+/// Collects the messages of the embedded locales to generate their typed handles.
+#[derive(Default)]
+pub struct TypedMessages {
+    /// The messages of every locale, keyed by the locale and then by the message ID.
+    locales: HashMap<String, HashMap<String, MessageRefs>>,
+}
+
+impl TypedMessages {
+    /// Adds the messages of a resource of `locale`. Message variants are skipped, as they are
+    /// selected through the handle of their base message.
+    pub fn add_resource(&mut self, locale: &str, resource: &Resource<&str>) {
+        let messages = self.locales.entry(locale.to_string()).or_default();
+        for entry in resource.body.iter() {
+            let Entry::Message(msg) = entry else {
+                continue;
+            };
+            if msg.id.name.contains(i18n_loader::VARIANT_SEPARATOR) {
+                continue;
+            }
+
+            let refs = messages.entry(msg.id.name.to_string()).or_default();
+            if let Some(value) = &msg.value {
+                collect_pattern(value, &mut refs.value);
+            }
+            for attr in msg.attributes.iter() {
+                collect_pattern(
+                    &attr.value,
+                    refs.attrs.entry(attr.id.name.to_string()).or_default(),
+                );
+            }
+        }
+    }
+
+    /// Generates the module of typed handles, one per message ID across every locale.
+    ///
+    /// The arguments of a handle are the variables of the message value in any locale, including
+    /// those of the messages it references, since they are resolved with the same arguments.
+    pub fn expand(
+        &self,
+        krate: &syn::Path,
+        locales_name: &Ident,
+        module: &TypedModule,
+    ) -> std::result::Result<TokenStream, String> {
+        let mut messages: BTreeMap<&str, (BTreeSet<&str>, BTreeSet<String>)> = BTreeMap::new();
+        for locale in self.locales.values() {
+            for (id, refs) in locale {
+                let (attrs, args) = messages.entry(id.as_str()).or_default();
+                attrs.extend(refs.attrs.keys().map(String::as_str));
+                args.extend(locale_vars(locale, (id.clone(), None)));
+            }
+        }
+
+        let type_names = mangle(messages.keys().copied(), type_name)
+            .map_err(|collisions| format!("Typed message handles collide:\n{collisions}"))?;
+
+        let mut handles = Vec::default();
+        for (id, (attrs, args)) in &messages {
+            let type_ident = &type_names[id];
+            let field_names =
+                mangle(args.iter().map(String::as_str), field_name).map_err(|collisions| {
+                    format!("The arguments of the typed handle of `{id}` collide:\n{collisions}")
+                })?;
+            let type_doc = format!(" The `{id}` message.");
+            let attrs = attrs.iter();
+            let arg_names = args.iter();
+
+            let handle = if args.is_empty() {
+                quote! {
+                    #[doc = #type_doc]
+                    #[derive(Debug, Clone, Copy)]
+                    pub struct #type_ident;
+
+                    impl #krate::MessageKey for #type_ident {
+                        const ID: &'static str = #id;
+                        const ATTRS: &'static [&'static str] = &[#(#attrs),*];
+                        const ARGS: &'static [&'static str] = &[];
+
+                        fn into_query<'a>(self) -> #krate::Query<'a>
+                        where
+                            Self: 'a,
+                        {
+                            #krate::Query::new(#id)
+                        }
+                    }
+                }
+            } else {
+                let fields = args.iter().map(|arg| {
+                    let field = &field_names[arg.as_str()];
+                    let doc = format!(" The `${arg}` argument.");
+                    quote! {
+                        #[doc = #doc]
+                        pub #field: #krate::FluentValue<'a>,
+                    }
+                });
+                let with_args = args.iter().map(|arg| {
+                    let field = &field_names[arg.as_str()];
+                    quote! { .with_arg(#arg, self.#field) }
+                });
+
+                quote! {
+                    #[doc = #type_doc]
+                    #[derive(Debug, Clone)]
+                    pub struct #type_ident<'a> {
+                        #(#fields)*
+                    }
+
+                    impl #krate::MessageKey for #type_ident<'_> {
+                        const ID: &'static str = #id;
+                        const ATTRS: &'static [&'static str] = &[#(#attrs),*];
+                        const ARGS: &'static [&'static str] = &[#(#arg_names),*];
+
+                        fn into_query<'a>(self) -> #krate::Query<'a>
+                        where
+                            Self: 'a,
+                        {
+                            #krate::Query::new(#id) #(#with_args)*
+                        }
+                    }
+                }
+            };
+            handles.push(handle);
+        }
+
+        let TypedModule { vis, name } = module;
+        let module_doc =
+            format!(" The typed message handles of `{locales_name}`, see `MessageKey`.");
+        Ok(quote! {
+            #[doc = #module_doc]
+            #vis mod #name {
+                #(#handles)*
+            }
+        })
+    }
+
+    /// Generates an enum with a variant per message ID across every locale, named after the ID in
+    /// `UpperCamelCase`, along with the attributes of the message in any locale.
+    pub fn expand_keys_enum(
+        &self,
+        krate: &syn::Path,
+        locales_name: &Ident,
+        enum_name: &Ident,
+    ) -> std::result::Result<TokenStream, String> {
+        let mut messages: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for locale in self.locales.values() {
+            for (id, refs) in locale {
+                let attrs = messages.entry(id.as_str()).or_default();
+                attrs.extend(refs.attrs.keys().map(String::as_str));
+            }
+        }
+
+        let variant_names = mangle(messages.keys().copied(), type_name)
+            .map_err(|collisions| format!("Message key variants collide:\n{collisions}"))?;
+
+        let variants = messages.keys().map(|id| {
+            let variant = &variant_names[id];
+            let doc = format!(" The `{id}` message.");
+            quote! {
+                #[doc = #doc]
+                #variant,
+            }
+        });
+        let all = messages.keys().map(|id| &variant_names[id]);
+        let count = messages.len();
+        let ids = messages.keys().map(|id| {
+            let variant = &variant_names[id];
+            quote! { Self::#variant => #id, }
+        });
+        let attrs = messages.iter().map(|(id, attrs)| {
+            let variant = &variant_names[id];
+            let attrs = attrs.iter();
+            quote! { Self::#variant => &[#(#attrs),*], }
+        });
+        let enum_doc = format!(" The message keys of `{locales_name}`, sorted by message ID.");
+        Ok(quote! {
+            #[doc = #enum_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub enum #enum_name {
+                #(#variants)*
+            }
+
+            impl #enum_name {
+                /// Every key, sorted by message ID.
+                pub const ALL: [Self; #count] = [#(Self::#all),*];
+
+                /// The ID of the message.
+                pub const fn id(&self) -> &'static str {
+                    match *self {
+                        #(#ids)*
+                    }
+                }
+
+                /// The attributes of the message in any locale, sorted by name.
+                pub const fn attrs(&self) -> &'static [&'static str] {
+                    match *self {
+                        #(#attrs)*
+                    }
+                }
+            }
+
+            impl ::core::convert::From<#enum_name> for #krate::Query<'static> {
+                fn from(key: #enum_name) -> Self {
+                    #krate::Query::new(key.id())
+                }
+            }
+        })
+    }
+}
+
+/// Generates the module of key constants, one per message ID, e.g. `LOGIN_BTN` for `login-btn`.
+///
+/// Message variants are skipped, as they are selected through the ID of their base message.
+pub fn expand_keys<'a>(
+    ids: impl Iterator<Item = &'a String>,
+    locales_name: &Ident,
+    module: &Ident,
+) -> std::result::Result<TokenStream, String> {
+    let ids = ids
+        .map(String::as_str)
+        .filter(|id| !id.contains(i18n_loader::VARIANT_SEPARATOR))
+        .collect::<BTreeSet<_>>();
+    let const_names = mangle(ids.iter().copied(), const_name)
+        .map_err(|collisions| format!("Message key constants collide:\n{collisions}"))?;
+
+    let consts = ids.iter().map(|id| {
+        let const_ident = &const_names[id];
+        let doc = format!(" The ID of the `{id}` message.");
+        quote! {
+            #[doc = #doc]
+            pub const #const_ident: &str = #id;
+        }
+    });
+    let module_doc = format!(" The message IDs of `{locales_name}`.");
+    Ok(quote! {
+        #[doc = #module_doc]
+        pub mod #module {
+            #(#consts)*
+        }
+    })
+}
+
+/// Collects the variables of a pattern and of the messages it references within the same locale.
+fn locale_vars(
+    locale: &HashMap<String, MessageRefs>,
+    start: (String, Option<String>),
+) -> BTreeSet<String> {
+    let mut vars = BTreeSet::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![start];
+    while let Some(reference) = pending.pop() {
+        if !visited.insert(reference.clone()) {
+            continue;
+        }
+        let Some(msg) = locale.get(&reference.0) else {
+            continue;
+        };
+        let refs = match &reference.1 {
+            Some(attr) => match msg.attrs.get(attr) {
+                Some(refs) => refs,
+                None => continue,
+            },
+            None => &msg.value,
+        };
+        vars.extend(refs.vars.iter().cloned());
+        pending.extend(refs.messages.iter().cloned());
+    }
+
+    vars
+}
+
+pub fn collect_pattern(pattern: &Pattern<&str>, refs: &mut PatternRefs) {
+    for element in pattern.elements.iter() {
+        if let PatternElement::Placeable { expression } = element {
+            collect_expression(expression, refs);
+        }
+    }
+}
+
+fn collect_expression(expression: &Expression<&str>, refs: &mut PatternRefs) {
+    match expression {
+        Expression::Select { selector, variants } => {
+            collect_inline(selector, refs);
+            for variant in variants.iter() {
+                collect_pattern(&variant.value, refs);
+            }
+        }
+        Expression::Inline(inline) => collect_inline(inline, refs),
+    }
+}
+
+fn collect_inline(inline: &InlineExpression<&str>, refs: &mut PatternRefs) {
+    match inline {
+        InlineExpression::VariableReference { id } => _ = refs.vars.insert(id.name.to_string()),
+        InlineExpression::MessageReference { id, attribute } => {
+            _ = refs.messages.insert((
+                id.name.to_string(),
+                attribute.as_ref().map(|attr| attr.name.to_string()),
+            ))
+        }
+        InlineExpression::FunctionReference { arguments, .. } => {
+            for arg in arguments.positional.iter() {
+                collect_inline(arg, refs);
+            }
+            for arg in arguments.named.iter() {
+                collect_inline(&arg.value, refs);
+            }
+        }
+        InlineExpression::Placeable { expression } => collect_expression(expression, refs),
+        // terms are resolved with their own arguments only
+        InlineExpression::TermReference { id, .. } => _ = refs.terms.insert(id.name.to_string()),
+        InlineExpression::StringLiteral { .. } | InlineExpression::NumberLiteral { .. } => {}
+    }
+}
+
+/// Maps names to identifiers with `rule`, failing with a list of the names that map to the same
+/// identifier.
+fn mangle<'n>(
+    names: impl Iterator<Item = &'n str>,
+    rule: fn(&str) -> String,
+) -> std::result::Result<HashMap<&'n str, Ident>, String> {
+    let mut mangled: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for name in names {
+        let ident = ident_of(&rule(name));
+        mangled.entry(ident.to_string()).or_default().push(name);
+    }
+
+    let collisions = mangled
+        .iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(ident, names)| {
+            let names = names
+                .iter()
+                .map(|name| format!("`{name}`"))
+                .collect::<Vec<_>>();
+            format!("\t- {} all map to `{ident}`", names.join(", "))
+        })
+        .collect::<Vec<_>>();
+    if !collisions.is_empty() {
+        return Err(collisions.join("\n"));
+    }
+
+    Ok(mangled
+        .into_iter()
+        .map(|(ident, names)| (names[0], syn::parse_str(&ident).expect("valid identifier")))
+        .collect())
+}
+
+/// Converts a message ID into the name of its handle, e.g. `welcome-back` into `WelcomeBack`.
+pub fn type_name(id: &str) -> String {
+    id.split(['-', '_'])
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let mut chars = segment.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+/// Converts an argument name into the name of its field, e.g. `userName` into `user_name`.
+pub fn field_name(arg: &str) -> String {
+    let mut name = String::with_capacity(arg.len() + 2);
+    let mut prev_lower = false;
+    for c in arg.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            name.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        name.push(if c == '-' {
+            '_'
+        } else {
+            c.to_ascii_lowercase()
+        });
+    }
+
+    name
+}
+
+/// Converts a message ID into the name of its key constant, e.g. `loginBtn` into `LOGIN_BTN`.
+pub fn const_name(id: &str) -> String {
+    field_name(id).to_ascii_uppercase()
+}
+
+/// Creates the identifier `name`, as a raw identifier if it is a keyword, or with a trailing
+/// underscore if it is a keyword that cannot be raw, such as `self`.
+pub fn ident_of(name: &str) -> Ident {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        return Ident::new(name, Span::call_site());
+    }
+    match name {
+        "self" | "Self" | "super" | "crate" | "_" => {
+            Ident::new(&format!("{name}_"), Span::call_site())
+        }
+        _ => Ident::new_raw(name, Span::call_site()),
+    }
+}
@@ -0,0 +1,2 @@
+greeting = Bok!
+farewell = Zbogom!
@@ -0,0 +1,7 @@
+//! A crate owning the translations of the workspace, as queried by the other crates.
+
+i18n::load!("i18n", validate_keys = true);
+
+pub mod nested {
+    i18n::load!("i18n", name = NESTED_LOCALES, validate_keys = true);
+}
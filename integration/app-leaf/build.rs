@@ -0,0 +1,29 @@
+use std::path::Path;
+
+fn main() {
+    // read by `load!("$APP_LEAF_LOCALES")` in the tests
+    println!("cargo:rustc-env=APP_LEAF_LOCALES=../app-i18n/i18n");
+
+    // a copy of the translations for the `dev_reload` tests to edit
+    let dev_locales = Path::new(&std::env::var("OUT_DIR").unwrap()).join("dev-i18n");
+    _ = std::fs::remove_dir_all(&dev_locales);
+    copy_dir(Path::new("../app-i18n/i18n"), &dev_locales);
+    println!(
+        "cargo:rustc-env=APP_LEAF_DEV_LOCALES={}",
+        dev_locales.display()
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=../app-i18n/i18n");
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        match entry.file_type().unwrap().is_dir() {
+            true => copy_dir(&entry.path(), &target),
+            false => _ = std::fs::copy(entry.path(), target).unwrap(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! A crate querying the translations owned by `app-i18n`.
+
+use app_i18n::LOCALES as ALIASED;
+use i18n::{LanguageIdentifier, Message};
+
+/// Queries through the full path of the static.
+pub fn greeting(lang: &LanguageIdentifier) -> Message {
+    i18n::tr!(lang, "greeting", locales = app_i18n::LOCALES)
+}
+
+/// Queries through a `use` alias of the static.
+pub fn farewell(lang: &LanguageIdentifier) -> Message {
+    i18n::tr!(lang, "farewell", locales = ALIASED)
+}
+
+/// Queries through a static nested in a module.
+pub fn both(lang: &LanguageIdentifier) -> [Message; 2] {
+    i18n::tr_batch!(
+        lang,
+        ["greeting", "farewell"],
+        locales = app_i18n::nested::NESTED_LOCALES
+    )
+}
@@ -0,0 +1,13 @@
+use i18n::langid;
+
+#[test]
+fn test_if_keys_are_validated_across_crates() {
+    let lang = langid!("hr-HR");
+    assert_eq!(app_leaf::greeting(&lang).value, "Bok!");
+    assert_eq!(app_leaf::farewell(&lang).value, "Zbogom!");
+    let [greeting, farewell] = app_leaf::both(&langid!("en-US"));
+    assert_eq!(greeting.value, "Hello!");
+    assert_eq!(farewell.value, "Goodbye!");
+
+    assert_eq!(app_i18n::LOCALES::__I18N_KEYS, ["farewell", "greeting"]);
+}
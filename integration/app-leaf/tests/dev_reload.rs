@@ -0,0 +1,54 @@
+use i18n::langid;
+use std::path::Path;
+
+i18n::load!(
+    "$APP_LEAF_DEV_LOCALES",
+    name = DEV_LOCALES,
+    dev_reload = true
+);
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_if_edited_files_are_picked_up_after_reload() {
+    let file = Path::new(env!("APP_LEAF_DEV_LOCALES")).join("hr-HR/main.ftl");
+    let original = std::fs::read_to_string(&file).unwrap();
+    let lang = langid!("hr-HR");
+    assert_eq!(
+        i18n::tr!(&lang, "greeting", locales = DEV_LOCALES).value,
+        "Bok!"
+    );
+
+    std::fs::write(&file, original.replace("Bok!", "Bok, svijete!")).unwrap();
+    let report = DEV_LOCALES.reload();
+    std::fs::write(&file, &original).unwrap();
+
+    assert_eq!(report.unwrap().changed, [langid!("hr-HR")]);
+    assert_eq!(
+        i18n::tr!(&lang, "greeting", locales = DEV_LOCALES).value,
+        "Bok, svijete!"
+    );
+    assert_eq!(DEV_LOCALES.reload().unwrap().changed, [langid!("hr-HR")]);
+    assert_eq!(
+        i18n::tr!(&lang, "greeting", locales = DEV_LOCALES).value,
+        "Bok!"
+    );
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn test_if_release_builds_do_not_reload() {
+    assert!(!DEV_LOCALES.reload().unwrap().has_changes());
+}
+
+#[test]
+fn test_if_embedded_content_is_kept_without_the_files() {
+    // as for a binary deployed without its translations
+    let missing = Path::new(env!("APP_LEAF_DEV_LOCALES")).join("missing/main.ftl");
+    assert!(DEV_LOCALES
+        .reload_from_files([("hr-HR", &missing)])
+        .is_err());
+    assert_eq!(
+        i18n::tr!(langid!("hr-HR"), "farewell", locales = DEV_LOCALES).value,
+        "Zbogom!"
+    );
+}
@@ -0,0 +1,41 @@
+use i18n::langid;
+
+mod from_env {
+    i18n::load!("$APP_LEAF_LOCALES", name = ENV_LOCALES);
+}
+
+mod from_workspace_root {
+    i18n::load!(
+        "tests/i18n_nested",
+        name = WORKSPACE_LOCALES,
+        from_workspace_root = true
+    );
+
+    pub fn langs() -> Vec<String> {
+        i18n::langs!("${WORKSPACE_ROOT}/tests/i18n_nested")
+            .into_iter()
+            .map(|lang| lang.id)
+            .collect()
+    }
+}
+
+#[test]
+fn test_if_locales_path_is_read_from_the_environment() {
+    let lang = langid!("hr-HR");
+    assert_eq!(
+        i18n::tr!(&lang, "greeting", locales = from_env::ENV_LOCALES).value,
+        "Bok!"
+    );
+}
+
+#[test]
+fn test_if_locales_path_is_resolved_from_the_workspace_root() {
+    let lang = langid!("hr-HR");
+    let message = i18n::tr!(
+        &lang,
+        "settings-title",
+        locales = from_workspace_root::WORKSPACE_LOCALES
+    );
+    assert_eq!(message.value, "Postavke");
+    assert_eq!(from_workspace_root::langs(), ["en-US", "hr-HR"]);
+}
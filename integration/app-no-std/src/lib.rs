@@ -0,0 +1,25 @@
+//! A `no_std` crate using the language tables of `i18n-lang`, e.g. for the settings menu of a
+//! firmware UI. Check it on its own with `cargo check -p app-no-std`, which builds `i18n-lang`
+//! without its `std` feature.
+
+#![no_std]
+
+use i18n_lang::{langid_to_dir, langid_to_flag, langid_to_name, LangRef};
+
+/// The languages offered by the settings menu.
+pub const MENU: [&str; 3] = ["en-US", "hr-HR", "ar-EG"];
+
+/// Gets the entry of the settings menu for `id`, i.e. its name, flag and whether it is right-to-left.
+pub fn menu_entry(id: &'static str) -> (&'static str, &'static str, bool) {
+    let lang = LangRef::new(id);
+    (lang.name, lang.flag, lang.dir == "rtl")
+}
+
+/// Gets the same entry through the lookup functions, for a language and region known separately.
+pub fn menu_entry_of(language: &str, region: &str) -> (&'static str, &'static str, bool) {
+    (
+        langid_to_name(language),
+        langid_to_flag(region).unwrap_or_default(),
+        langid_to_dir(language) == "rtl",
+    )
+}
@@ -0,0 +1,13 @@
+#[test]
+fn test_if_menu_entries_are_looked_up() {
+    let entries = app_no_std::MENU.map(app_no_std::menu_entry);
+    assert_eq!(
+        entries,
+        [
+            ("English", "🇺🇸", false),
+            ("Hrvatski", "🇭🇷", false),
+            ("العربية", "🇪🇬", true),
+        ]
+    );
+    assert_eq!(app_no_std::menu_entry_of("ar", "EG"), entries[2]);
+}
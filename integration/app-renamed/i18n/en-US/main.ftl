@@ -0,0 +1,3 @@
+greeting = Hello, { $name }!
+    .title = Greeting for { $name }
+farewell = Goodbye!
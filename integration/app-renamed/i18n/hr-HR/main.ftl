@@ -0,0 +1,3 @@
+greeting = Bok, { $name }!
+    .title = Pozdrav za { $name }
+farewell = Zbogom!
@@ -0,0 +1,32 @@
+//! A crate depending on `i18n` under another name, and on `i18n-loader` and `i18n-macros` directly.
+
+my_i18n::load!("i18n", validate_keys = true, crate_path = ::my_i18n);
+
+pub mod direct {
+    i18n_macros::load!(
+        "i18n",
+        name = DIRECT_LOCALES,
+        validate_keys = true,
+        crate_path = ::i18n_loader
+    );
+}
+
+pub mod internal {
+    my_i18n::load!(
+        "i18n",
+        name = INTERNAL_LOCALES,
+        vis = pub(crate),
+        crate_path = ::my_i18n
+    );
+
+    /// Looks up the farewell without exposing the locales it comes from.
+    pub fn farewell(lang: &my_i18n::LanguageIdentifier) -> String {
+        my_i18n::tr!(
+            lang,
+            "farewell",
+            locales = INTERNAL_LOCALES,
+            crate_path = ::my_i18n
+        )
+        .value
+    }
+}
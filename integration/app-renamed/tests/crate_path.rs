@@ -0,0 +1,47 @@
+use app_renamed::direct::DIRECT_LOCALES;
+use app_renamed::LOCALES;
+
+#[test]
+fn test_if_macros_work_with_a_renamed_dependency() {
+    let lang = my_i18n::langid!("hr-HR");
+    let mut msg = my_i18n::tr!(lang, "greeting", "name" = "Ana", crate_path = ::my_i18n);
+    assert_eq!(msg.value, "Bok, \u{2068}Ana\u{2069}!");
+    let title = my_i18n::attr!(msg, "title", crate_path = ::my_i18n, "name" = "Ana");
+    assert_eq!(title, "Pozdrav za \u{2068}Ana\u{2069}");
+
+    let [farewell] = my_i18n::tr_batch!(lang, ["farewell"], crate_path = ::my_i18n);
+    assert_eq!(farewell.value, "Zbogom!");
+
+    let langs = my_i18n::langs!("i18n", crate_path = ::my_i18n);
+    assert_eq!(langs.map(|lang| lang.id), ["en-US", "hr-HR"]);
+}
+
+#[test]
+fn test_if_macros_work_without_the_facade_crate() {
+    let lang = i18n_loader::langid!("en-US");
+    let mut msg = i18n_macros::tr!(
+        lang,
+        "greeting",
+        "name" = "Alex",
+        locales = DIRECT_LOCALES,
+        crate_path = ::i18n_loader
+    );
+    assert_eq!(msg.value, "Hello, \u{2068}Alex\u{2069}!");
+    let title = i18n_macros::attr!(
+        msg,
+        "title",
+        locales = DIRECT_LOCALES,
+        crate_path = ::i18n_loader,
+        "name" = "Alex"
+    );
+    assert_eq!(title, "Greeting for \u{2068}Alex\u{2069}");
+
+    let langs = i18n_macros::langs!("i18n", crate_path = ::i18n_loader);
+    assert_eq!(langs.len(), 2);
+}
+
+#[test]
+fn test_if_crate_private_locales_are_usable_within_the_crate() {
+    let lang = my_i18n::langid!("hr-HR");
+    assert_eq!(app_renamed::internal::farewell(&lang), "Zbogom!");
+}
@@ -9,3 +9,40 @@ pub trait LocalizedDisplay {
     /// Localizes the type given a `LanguageIdentifier`.
     fn localize(&self, lang: &LanguageIdentifier) -> Message;
 }
+
+/// The compile-time diagnostics of the macros, checked as `compile_fail` doctests.
+///
+/// `tr!` rejects an argument given twice:
+///
+/// ```compile_fail
+/// i18n::load!("./tests/i18n", allow_missing = ["hr-hr"]);
+/// let lang = i18n::langid!("en-US");
+/// let msg = i18n::tr!(lang, "welcome-back", "username" = "Alex", "username" = "Ana");
+/// ```
+///
+/// including when it is given twice for the same attribute, across `attr(...)` groups:
+///
+/// ```compile_fail
+/// i18n::load!("./tests/i18n", allow_missing = ["hr-hr"]);
+/// let lang = i18n::langid!("en-US");
+/// let msg = i18n::tr!(lang, "login-btn", attr("attr-arg", "text" = "a"), attr("attr-arg", "text" = "b"));
+/// ```
+///
+/// `attr!` rejects an argument given twice as well:
+///
+/// ```compile_fail
+/// i18n::load!("./tests/i18n", allow_missing = ["hr-hr"]);
+/// let lang = i18n::langid!("en-US");
+/// let mut msg = i18n::tr!(lang, "login-btn");
+/// let text = i18n::attr!(msg, "attr-arg", "text" = "a", "text" = "b");
+/// ```
+///
+/// `tr_typed!` rejects a handle missing one of the arguments of its message:
+///
+/// ```compile_fail
+/// i18n::load!("./tests/i18n", allow_missing = ["hr-hr"], typed = mod messages);
+/// let lang = i18n::langid!("en-US");
+/// let msg = i18n::tr_typed!(lang, messages::WelcomeBack {});
+/// ```
+#[cfg(doctest)]
+pub struct CompileFailTests;
@@ -0,0 +1,77 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts every allocation made through the global allocator.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Counts the allocations made by `f` over `iterations` runs.
+fn allocations(iterations: usize, mut f: impl FnMut()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..iterations {
+        f();
+    }
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn test_if_static_values_are_formatted_without_allocating() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "label = Static label\nhello = Hello, { $name }!")])
+        .unwrap();
+    let lang = i18n::langid!("en-US");
+
+    let locale = locales.locale(&lang).unwrap();
+    let value = locale.format_value("label", None).unwrap();
+    assert!(matches!(value, std::borrow::Cow::Borrowed("Static label")));
+
+    let borrowed = allocations(100, || {
+        let value = locale.format_value("label", None).unwrap();
+        assert_eq!(value, "Static label");
+    });
+    let format_value = allocations(100, || {
+        let value = locales.format_value(&lang, "label", None).unwrap();
+        assert_eq!(value, "Static label");
+    });
+    let query = allocations(100, || {
+        let msg = locales.query(&lang, &i18n::Query::new("label")).unwrap();
+        assert_eq!(msg.value, "Static label");
+    });
+    // only the snapshots of the locales and overlays are cloned, which does not allocate
+    assert_eq!(borrowed, 0);
+    assert_eq!(format_value, 0);
+    assert!(query >= 200, "query allocated {query} times");
+
+    let mut args = i18n::FluentArgs::new();
+    args.set("name", "John");
+    let value = locales.format_value(&lang, "hello", Some(&args)).unwrap();
+    assert_eq!(value, "Hello, \u{2068}John\u{2069}!");
+    assert_eq!(locales.format_or_key(&lang, "missing", None), "missing");
+
+    // the value keeps its snapshot of the locale alive once the locale is replaced
+    let value = locales.format_value(&lang, "label", None).unwrap();
+    let replaced = i18n::FluentResource::try_new("label = Replaced".to_string()).unwrap();
+    locales
+        .try_add_locale("en-US", vec![replaced], true)
+        .unwrap();
+    assert_eq!(value, "Static label");
+    assert_eq!(
+        locales.format_value(&lang, "label", None).unwrap(),
+        "Replaced"
+    );
+}
@@ -0,0 +1,68 @@
+use i18n::{langid, FluentValue, Locales, Query};
+
+fn locales() -> Locales {
+    Locales::builder()
+        .use_isolating(false)
+        .build_from_strs([
+            (
+                "en-US",
+                "total = Total: { NUMBER($amount, minimumFractionDigits: 2) }\ncount = { $count }\nshare = { NUMBER($ratio, style: \"percent\") }\nitems = { NUMBER($count) ->\n    [one] One item\n   *[other] { $count } items\n}\ndue = Due { DATETIME($date) }\nat = At { DATETIME($date, dateStyle: \"none\", timeStyle: \"short\") }",
+            ),
+            (
+                "hr-HR",
+                "total = Ukupno: { NUMBER($amount, minimumFractionDigits: 2) }\ncount = { $count }\nshare = { NUMBER($ratio, style: \"percent\") }\ndue = Rok { DATETIME($date) }\nat = U { DATETIME($date, dateStyle: \"none\", timeStyle: \"short\") }",
+            ),
+        ])
+        .unwrap()
+}
+
+fn value(locales: &Locales, lang: &str, query: Query) -> String {
+    locales.query(&lang.parse().unwrap(), &query).unwrap().value
+}
+
+#[test]
+fn test_if_numbers_are_formatted_per_locale() {
+    let locales = locales();
+    let total = || Query::new("total").with_arg("amount", 1234567.5);
+    assert_eq!(value(&locales, "en-US", total()), "Total: 1,234,567.50");
+    assert_eq!(value(&locales, "hr-HR", total()), "Ukupno: 1.234.567,50");
+
+    let count = || Query::new("count").with_arg("count", 1234.125);
+    assert_eq!(value(&locales, "en-US", count()), "1,234.125");
+    assert_eq!(value(&locales, "hr-HR", count()), "1.234,125");
+    let count = Query::new("count").with_arg("count", -999);
+    assert_eq!(value(&locales, "hr-HR", count), "-999");
+
+    let share = || Query::new("share").with_arg("ratio", 0.256);
+    assert_eq!(value(&locales, "en-US", share()), "26%");
+    assert_eq!(value(&locales, "hr-HR", share()), "26\u{a0}%");
+}
+
+#[test]
+fn test_if_number_keeps_plural_selection() {
+    let locales = locales();
+    let items = |count: i32| Query::new("items").with_arg("count", count);
+    assert_eq!(value(&locales, "en-US", items(1)), "One item");
+    assert_eq!(value(&locales, "en-US", items(1500)), "1,500 items");
+}
+
+#[test]
+fn test_if_dates_are_formatted_per_locale() {
+    let locales = locales();
+    // 2024-03-05T14:30:00Z
+    let millis = 1_709_649_000_000_i64;
+    let due = || Query::new("due").with_arg("date", FluentValue::from(millis as f64));
+    assert_eq!(value(&locales, "en-US", due()), "Due 3/5/2024");
+    assert_eq!(value(&locales, "hr-HR", due()), "Rok 5. 3. 2024.");
+
+    let due = Query::new("due").with_arg("date", "2024-12-31");
+    assert_eq!(value(&locales, "en-US", due), "Due 12/31/2024");
+    let at = || Query::new("at").with_arg("date", "2024-03-05T14:30:00Z");
+    assert_eq!(value(&locales, "en-US", at()), "At 2:30\u{202f}PM");
+    assert_eq!(value(&locales, "hr-HR", at()), "U 14:30");
+
+    // like any failing function, an invalid date is formatted as the call itself
+    let invalid = Query::new("due").with_arg("date", "tomorrow");
+    let msg = locales.query(&langid!("en-US"), &invalid).unwrap();
+    assert_eq!(msg.value, "Due DATETIME()");
+}
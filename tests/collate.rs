@@ -0,0 +1,65 @@
+use i18n::collate::{compare, sort_key, sort_langs_for_display};
+use i18n::{langid, Lang, Locales};
+use std::cmp::Ordering;
+
+fn sorted(lang: &i18n::LanguageIdentifier, words: &[&'static str]) -> Vec<&'static str> {
+    let mut words = words.to_vec();
+    words.sort_by(|a, b| compare(lang, a, b));
+    words
+}
+
+#[test]
+fn test_if_english_collation_folds_case_and_diacritics() {
+    let en = langid!("en-US");
+    assert_eq!(
+        sorted(&en, &["Zebra", "Ágúst", "apple", "Apple", "banana", "ábc"]),
+        vec!["ábc", "Ágúst", "apple", "Apple", "banana", "Zebra"]
+    );
+    assert_eq!(compare(&en, "a", "ab"), Ordering::Less);
+    assert_eq!(compare(&en, "same", "same"), Ordering::Equal);
+}
+
+#[test]
+fn test_if_german_collation_treats_umlauts_as_base_letters() {
+    let de = langid!("de-DE");
+    assert_eq!(
+        sorted(
+            &de,
+            &["Zucker", "Öl", "Ofen", "Äpfel", "Apfel", "Straße", "Strasse"]
+        ),
+        vec!["Apfel", "Äpfel", "Ofen", "Öl", "Strasse", "Straße", "Zucker"]
+    );
+}
+
+#[test]
+fn test_if_swedish_collation_sorts_extra_letters_after_z() {
+    let sv = langid!("sv-SE");
+    assert_eq!(
+        sorted(&sv, &["öl", "äpple", "ål", "zebra", "apa", "ost"]),
+        vec!["apa", "ost", "zebra", "ål", "äpple", "öl"]
+    );
+}
+
+#[test]
+fn test_if_sort_keys_agree_with_compare() {
+    let locales = Locales::new(langid!("en-US"), None);
+    let sv = langid!("sv");
+    assert!(locales.sort_key(&sv, "zebra") < locales.sort_key(&sv, "ål"));
+    assert_eq!(locales.compare(&sv, "zebra", "ål"), Ordering::Less);
+    assert_eq!(sort_key(&sv, "Ål"), locales.sort_key(&sv, "Ål"));
+}
+
+#[test]
+fn test_if_langs_are_sorted_for_display() {
+    let mut langs = [
+        Lang::from(langid!("sv-SE")),
+        Lang::from(langid!("hr-HR")),
+        Lang::from(langid!("en-US")),
+        Lang::from(langid!("de-DE")),
+    ];
+    sort_langs_for_display(&mut langs, &langid!("en-US"));
+    assert_eq!(
+        langs.map(|lang| lang.name),
+        ["Deutsch", "English", "Hrvatski", "Svenska"]
+    );
+}
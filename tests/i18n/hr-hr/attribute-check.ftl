@@ -0,0 +1,6 @@
+-failed = Neuspjeh
+login-btn = 
+  .idle = Prijava
+  .progress = Prijava u tijeku...
+  .finished-ok = Prijavljen
+  .finished-err = { -failed }
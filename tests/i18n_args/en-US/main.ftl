@@ -0,0 +1,7 @@
+welcome = Welcome, { $username }!
+unread = { $count ->
+    [one] One message for { $username }
+   *[other] { $count } messages for { $username }
+}
+profile = Profile
+    .title = Profile of { $username }
@@ -0,0 +1,8 @@
+welcome = Dobro došli, { $username }!
+unread = { $count ->
+    [one] { $count } poruka za { $username }
+    [few] { $count } poruke za { $username }
+   *[other] { $count } poruka za { $username }
+}
+profile = Profil
+    .title = Profil korisnika { $username }
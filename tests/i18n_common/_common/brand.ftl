@@ -0,0 +1,2 @@
+-brand = Acme
+tagline = Made by { -brand }
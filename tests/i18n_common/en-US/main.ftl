@@ -0,0 +1 @@
+welcome = Welcome to { -brand }!
@@ -0,0 +1,2 @@
+welcome = Dobrodošli u { -brand }!
+tagline = Izradio { -brand }
@@ -0,0 +1 @@
+onboarding = Welcome aboard
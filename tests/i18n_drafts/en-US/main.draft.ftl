@@ -0,0 +1 @@
+promo = Try our new plan
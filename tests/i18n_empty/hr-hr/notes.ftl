@@ -0,0 +1,3 @@
+# Translator notes only, no messages yet.
+
+## Section comment
@@ -0,0 +1,3 @@
+login-btn = Log in
+    .aria-label = A login button
+    .title = Log into your account
@@ -0,0 +1,2 @@
+login-btn = Prijava
+    .title = Prijavite se u svoj račun
@@ -0,0 +1,8 @@
+-brand = Acme
+menu = Menu
+    .label = Open the menu
+welcome = Welcome to { -brand }!
+help = { $count ->
+    [one] Click { menu.label } for help
+   *[other] Click { menu.label } for help, { $count } times
+}
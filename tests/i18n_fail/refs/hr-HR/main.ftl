@@ -0,0 +1,7 @@
+-brand = Acme
+menu = Izbornik
+welcome = Dobrodošli u { -missing-term }!
+help = { $count ->
+    [one] Kliknite { menu.label }
+   *[other] Kliknite { menu-label }
+}
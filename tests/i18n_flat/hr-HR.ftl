@@ -0,0 +1,2 @@
+hello = Bok
+bye = Doviđenja
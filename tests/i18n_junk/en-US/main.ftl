@@ -0,0 +1,5 @@
+foo = Foo
+
+broken = { $unclosed
+
+bar = Bar
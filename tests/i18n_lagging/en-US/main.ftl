@@ -0,0 +1,2 @@
+hello = Hello
+settings = Settings
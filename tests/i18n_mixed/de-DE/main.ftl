@@ -0,0 +1,2 @@
+hello = Hallo
+bye = Tschüss
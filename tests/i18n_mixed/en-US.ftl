@@ -0,0 +1 @@
+bye = Goodbye
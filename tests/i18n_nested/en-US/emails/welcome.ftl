@@ -0,0 +1 @@
+welcome-subject = Welcome aboard
@@ -0,0 +1 @@
+welcome-subject = Dobro došli
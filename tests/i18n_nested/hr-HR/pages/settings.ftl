@@ -0,0 +1 @@
+settings-title = Postavke
@@ -0,0 +1,14 @@
+-brand = Acme
+    .short = AC
+
+app-name = { -brand } Studio
+welcome = Welcome to { app-name }!
+welcome-title = { welcome }
+    .tooltip = About { -brand }
+about = { NUMBER(3) } years of { app-name.title }
+standalone = Nothing references this
+
+# a deliberate cycle, which Fluent reports when formatting
+ping = { pong }
+pong = { ping }
+echo = { echo }
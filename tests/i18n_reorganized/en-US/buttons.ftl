@@ -0,0 +1,2 @@
+save = Save
+cancel = Cancel
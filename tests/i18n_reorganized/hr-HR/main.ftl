@@ -0,0 +1,3 @@
+hello = Bok
+save = Spremi
+cancel = Odustani
@@ -0,0 +1 @@
+foo-a = Hidden A
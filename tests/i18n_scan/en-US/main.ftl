@@ -0,0 +1,3 @@
+foo-a = English A
+foo-b = English B
+foo-c = English C
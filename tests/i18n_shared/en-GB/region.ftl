@@ -0,0 +1 @@
+colour = Colour
@@ -0,0 +1 @@
+colour = Color
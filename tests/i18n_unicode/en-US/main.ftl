@@ -0,0 +1,3 @@
+﻿decomposed = Čćšž Ágúst
+plain = Plain
+    .title = Title
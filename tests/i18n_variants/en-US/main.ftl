@@ -0,0 +1,4 @@
+signup-cta = Sign up
+signup-cta__variant-b = Join now
+    .title = Join us
+signup-cta__variant-c = Get started
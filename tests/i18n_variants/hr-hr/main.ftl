@@ -0,0 +1,2 @@
+signup-cta = Registriraj se
+signup-cta__variant-c = Započni
@@ -0,0 +1,3 @@
+﻿greeting = Hello
+    .title = Greeting
+farewell = Goodbye
@@ -0,0 +1,3 @@
+﻿greeting = Bok
+    .title = Pozdrav
+farewell = Doviđenja
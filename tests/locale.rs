@@ -0,0 +1,2218 @@
+use i18n::{langid, EntryInfo, FluentResource, Locale, ResourceConflict};
+
+const SOURCE: &str = "\
+# A comment that is not an entry.
+-brand = Firefox
+
+greeting = Hello from { -brand }!
+    .title = Greeting
+    .aria-label = A greeting
+
+multiline =
+    First line
+    second line
+";
+
+#[test]
+fn test_if_entries_expose_source_positions() {
+    let resource = FluentResource::try_new(SOURCE.to_string()).unwrap();
+    let locale = Locale::new(langid!("en-US"), vec![resource], ResourceConflict::Error).unwrap();
+
+    let entries = locale.entries().collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            EntryInfo {
+                id: "brand",
+                is_term: true,
+                attrs: vec![],
+                pattern: Some("Firefox"),
+                source: "-brand = Firefox",
+                span: 34..50,
+                resource: 0,
+            },
+            EntryInfo {
+                id: "greeting",
+                is_term: false,
+                attrs: vec!["title", "aria-label"],
+                pattern: Some("Hello from { -brand }!"),
+                source: "greeting = Hello from { -brand }!\n    .title = Greeting\n    .aria-label = A greeting",
+                span: 52..136,
+                resource: 0,
+            },
+            EntryInfo {
+                id: "multiline",
+                is_term: false,
+                attrs: vec![],
+                pattern: Some("First line\n    second line"),
+                source: "multiline =\n    First line\n    second line",
+                span: 138..180,
+                resource: 0,
+            },
+        ]
+    );
+    for entry in entries {
+        assert_eq!(&SOURCE[entry.span], entry.source);
+    }
+}
+
+#[test]
+fn test_if_source_of_returns_the_snippet() {
+    let resource = FluentResource::try_new(SOURCE.to_string()).unwrap();
+    let locale = Locale::new(langid!("en-US"), vec![resource], ResourceConflict::Error).unwrap();
+
+    assert_eq!(
+        locale.source_of("greeting"),
+        Some("greeting = Hello from { -brand }!\n    .title = Greeting\n    .aria-label = A greeting")
+    );
+    assert_eq!(locale.source_of("brand"), None);
+    assert_eq!(locale.source_of("missing"), None);
+}
+
+#[test]
+fn test_if_snippets_keep_select_expressions_closing_at_column_zero() {
+    let source = "\
+emails = { $n ->
+    [one] One
+   *[other] Many
+}
+choice = { $n ->
+*[other] Any
+    [one] One
+   }
+    .title = Choice
+
+# A comment on the next message, which ends the previous one.
+next = Next
+";
+    let resource = FluentResource::try_new(source.to_string()).unwrap();
+    let locale = Locale::new(langid!("en-US"), vec![resource], ResourceConflict::Error).unwrap();
+
+    assert_eq!(
+        locale.source_of("emails"),
+        Some("emails = { $n ->\n    [one] One\n   *[other] Many\n}")
+    );
+    let choice = locale.entries().find(|entry| entry.id == "choice").unwrap();
+    assert_eq!(
+        choice.source,
+        "choice = { $n ->\n*[other] Any\n    [one] One\n   }\n    .title = Choice"
+    );
+    assert_eq!(
+        choice.pattern,
+        Some("{ $n ->\n*[other] Any\n    [one] One\n   }")
+    );
+    assert_eq!(choice.attr_pattern("title"), Some("Choice"));
+    assert_eq!(&source[choice.span], choice.source);
+    assert_eq!(locale.source_of("next"), Some("next = Next"));
+}
+
+#[test]
+fn test_if_builder_sets_the_fallback_language() {
+    let locales = i18n::LocalesBuilder::new()
+        .fallback("hr-HR")
+        .build_from_strs([("en-US", "foo = English"), ("hr-HR", "foo = Croatian")])
+        .unwrap();
+
+    let msg = locales
+        .query(&langid!("de-DE"), &i18n::Query::new("foo"))
+        .unwrap();
+    assert_eq!(msg.value, "Croatian");
+}
+
+#[test]
+fn test_if_the_fallback_language_can_be_changed() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([
+            ("en-US", "foo = English\nbar = English bar"),
+            ("es", "foo = Español"),
+        ])
+        .unwrap();
+    assert_eq!(locales.fallback_lang(), langid!("en-US"));
+
+    locales.set_fallback_lang(langid!("es")).unwrap();
+    assert_eq!(locales.fallback_lang(), langid!("es"));
+    let msg = locales
+        .query(&langid!("de-DE"), &i18n::Query::new("foo"))
+        .unwrap();
+    assert_eq!(msg.value, "Español");
+    // a message missing from the new fallback is no longer resolved through the old one
+    let query = i18n::Query::new("bar").with_fallback(true);
+    assert!(locales.query(&langid!("de-DE"), &query).is_err());
+
+    assert_eq!(
+        locales.set_fallback_lang(langid!("fr")),
+        Err(i18n::UnknownLocale {
+            lang: langid!("fr")
+        })
+    );
+    assert_eq!(locales.fallback_lang(), langid!("es"));
+}
+
+#[test]
+fn test_if_builder_sets_the_error_handler() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+    let locales = i18n::Locales::builder()
+        .on_error(|errs| _ = ERRORS.fetch_add(errs.len(), Ordering::SeqCst))
+        .build_from_strs([("en-US", "foo = English")])
+        .unwrap();
+
+    assert!(locales
+        .query(&langid!("en-US"), &i18n::Query::new("missing"))
+        .is_err());
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_if_builder_disables_isolation() {
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([("en-US", "hello = Hello, { $name }!")])
+        .unwrap();
+
+    let query = i18n::Query::new("hello").with_arg("name", "John");
+    let msg = locales.query(&langid!("en-US"), &query).unwrap();
+    assert_eq!(msg.value, "Hello, John!");
+}
+
+#[test]
+fn test_if_builder_registers_functions() {
+    let locales = i18n::LocalesBuilder::new()
+        .function("PLATFORM", |_, _| "linux".into())
+        .function("SHOUT", |positional, _| match positional {
+            [i18n::FluentValue::String(s)] => s.to_uppercase().into(),
+            _ => i18n::FluentValue::Error,
+        })
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "running = Running on { PLATFORM() }, { SHOUT(\"hey\") }!",
+        )])
+        .unwrap();
+
+    let msg = locales
+        .query(&langid!("en-US"), &i18n::Query::new("running"))
+        .unwrap();
+    assert_eq!(msg.value, "Running on linux, HEY!");
+}
+
+#[test]
+fn test_if_builder_combines_sources_per_language() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Foo"), ("en-US", "bar = Bar")])
+        .unwrap();
+
+    let lang = langid!("en-US");
+    assert_eq!(
+        locales
+            .query(&lang, &i18n::Query::new("foo"))
+            .unwrap()
+            .value,
+        "Foo"
+    );
+    assert_eq!(
+        locales
+            .query(&lang, &i18n::Query::new("bar"))
+            .unwrap()
+            .value,
+        "Bar"
+    );
+}
+
+#[test]
+fn test_if_builder_rejects_invalid_sources() {
+    let result = i18n::LocalesBuilder::new().build_from_strs([("not a langid!", "foo = Foo")]);
+    assert!(matches!(
+        result.err(),
+        Some(i18n::LoadError::Parser(errs)) if matches!(errs[..], [i18n::ParserError::InvalidLangid { .. }])
+    ));
+
+    let result = i18n::LocalesBuilder::new().build_from_strs([("en-US", "foo = { ")]);
+    assert!(matches!(
+        result.err(),
+        Some(i18n::LoadError::Parser(errs)) if matches!(errs[..], [i18n::ParserError::ParserError { .. }])
+    ));
+
+    // every query for a missing language is resolved through the fallback language
+    let result = i18n::LocalesBuilder::new().build_from_strs([("hr-HR", "foo = Fu")]);
+    let err = result.err().unwrap();
+    assert!(matches!(
+        &err,
+        i18n::LoadError::MissingFallback(i18n::UnknownLocale { lang }) if *lang == langid!("en-US")
+    ));
+    assert_eq!(err.to_string(), "the fallback language en-US is not loaded");
+}
+
+#[test]
+fn test_if_builder_loads_from_a_directory() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+
+    let msg = locales
+        .query(&langid!("hr-hr"), &i18n::Query::new("foo-a"))
+        .unwrap();
+    assert_eq!(msg.value, "Croatian A");
+    assert!(matches!(
+        i18n::LocalesBuilder::new().build_from_dir("./tests/missing"),
+        Err(i18n::LoadError::Io { .. })
+    ));
+}
+
+#[test]
+fn test_if_builder_loads_flat_files_from_a_directory() {
+    let query = |locales: &i18n::Locales, lang, id| {
+        locales.query(&lang, &i18n::Query::new(id)).unwrap().value
+    };
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n_flat")
+        .unwrap();
+    assert_eq!(query(&locales, langid!("en-US"), "bye"), "Goodbye");
+    assert_eq!(query(&locales, langid!("hr-HR"), "hello"), "Bok");
+
+    // a locale with both a subdirectory and a flat file merges them
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n_mixed")
+        .unwrap();
+    assert_eq!(query(&locales, langid!("en-US"), "hello"), "Hello");
+    assert_eq!(query(&locales, langid!("en-US"), "bye"), "Goodbye");
+    assert_eq!(query(&locales, langid!("de-DE"), "bye"), "Tschüss");
+    assert_eq!(query(&locales, langid!("hr-HR"), "bye"), "Doviđenja");
+}
+
+#[test]
+fn test_if_builder_loads_organized_directories() {
+    let query = |locales: &i18n::Locales, lang, id| {
+        locales.query(&lang, &i18n::Query::new(id)).unwrap().value
+    };
+    // nested directories are walked recursively
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n_nested")
+        .unwrap();
+    assert_eq!(
+        query(&locales, langid!("hr-HR"), "settings-title"),
+        "Postavke"
+    );
+    assert_eq!(
+        query(&locales, langid!("hr-HR"), "welcome-subject"),
+        "Dobro došli"
+    );
+
+    // the `_common` resources are shared by every locale, whose own entries override them
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_dir("./tests/i18n_common")
+        .unwrap();
+    assert_eq!(
+        query(&locales, langid!("en-US"), "welcome"),
+        "Welcome to Acme!"
+    );
+    assert_eq!(query(&locales, langid!("en-US"), "tagline"), "Made by Acme");
+    assert_eq!(query(&locales, langid!("hr-HR"), "tagline"), "Izradio Acme");
+
+    // a lowercase region is canonicalized
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n_lowercase_region")
+        .unwrap();
+    assert_eq!(
+        locales.locale(&langid!("hr-HR")).unwrap().lang(),
+        &langid!("hr-HR")
+    );
+    assert_eq!(query(&locales, langid!("hr-HR"), "greeting"), "Bok");
+
+    // but two directories of the same locale are rejected
+    let dir = std::env::temp_dir().join(format!("i18n-duplicate-{}", std::process::id()));
+    _ = std::fs::remove_dir_all(&dir);
+    for lang in ["en-US", "hr-HR", "hr-hr"] {
+        std::fs::create_dir_all(dir.join(lang)).unwrap();
+        std::fs::write(dir.join(lang).join("main.ftl"), "greeting = Hi").unwrap();
+    }
+    let result = i18n::LocalesBuilder::new().build_from_dir(&dir);
+    assert!(matches!(
+        result,
+        Err(i18n::LoadError::DuplicateLocale { lang, paths })
+            if lang == "hr-HR" && paths == [dir.join("hr-HR"), dir.join("hr-hr")]
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_if_builder_normalizes_sources() {
+    let locales = i18n::LocalesBuilder::new()
+        .normalize(i18n::Normalization::Nfc)
+        .build_from_dir("./tests/i18n_unicode")
+        .unwrap();
+
+    let msg = locales
+        .query(&langid!("en-US"), &i18n::Query::new("decomposed"))
+        .unwrap();
+    assert_eq!(msg.value, "\u{10c}\u{107}\u{161}\u{17e} \u{c1}g\u{fa}st");
+
+    // without NFC, only the BOM and line endings are normalized
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n_unicode")
+        .unwrap();
+    let msg = locales
+        .query(&langid!("en-US"), &i18n::Query::new("decomposed"))
+        .unwrap();
+    assert_eq!(
+        msg.value,
+        "C\u{30c}c\u{301}s\u{30c}z\u{30c} A\u{301}gu\u{301}st"
+    );
+}
+
+#[test]
+fn test_if_invalid_utf8_is_reported_with_its_offset() {
+    let result = i18n::LocalesBuilder::new().build_from_dir("./tests/i18n_invalid_utf8");
+    assert!(matches!(
+        result,
+        Err(i18n::LoadError::InvalidUtf8 { offset: 15, .. })
+    ));
+}
+
+#[test]
+fn test_if_normalize_source_reports_changes() {
+    let normalized = i18n::normalize_source(
+        "\u{feff}foo = A\u{301}\r\n".to_string(),
+        i18n::Normalization::Nfc,
+    );
+    assert_eq!(normalized.source, "foo = \u{c1}\n");
+    assert_eq!(normalized.changes, vec!["BOM", "CRLF", "NFC"]);
+
+    let normalized = i18n::normalize_source("foo = A\n".to_string(), i18n::Normalization::Nfc);
+    assert!(normalized.changes.is_empty());
+}
+
+#[test]
+fn test_if_locale_sources_are_parsed() {
+    let (resources, errors) = i18n::parse_locale_sources([
+        ("en-US".to_string(), "foo = Foo".to_string()),
+        ("en-US".to_string(), "bar = Bar".to_string()),
+        ("hr-HR".to_string(), "foo = { ".to_string()),
+        ("not a langid!".to_string(), "foo = Foo".to_string()),
+    ]);
+
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[&langid!("en-US")].len(), 2);
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().any(|err| matches!(
+        err,
+        i18n::ParserError::ParserError { langid, .. } if *langid == langid!("hr-HR")
+    )));
+    assert!(errors
+        .iter()
+        .any(|err| err.to_string() == "could not parse langid: not a langid!"));
+}
+
+#[test]
+fn test_if_overlays_take_precedence_per_message() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([
+            ("en-US", "foo = Foo\nbar = Bar\n    .title = Bar title"),
+            ("hr-HR", "foo = Fu"),
+        ])
+        .unwrap();
+    let en = langid!("en-US");
+    let hr = langid!("hr-HR");
+
+    let overlay = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Fixed foo\nbar = Fixed bar")])
+        .unwrap();
+    locales.push_overlay(overlay);
+    let overlay = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Fixed again")])
+        .unwrap();
+    locales.push_overlay(overlay);
+
+    let query = |lang, id| locales.query(lang, &i18n::Query::new(id)).unwrap();
+    assert_eq!(query(&en, "foo").value, "Fixed again");
+    // the whole message is overlaid, including its attributes
+    let bar = query(&en, "bar");
+    assert_eq!(bar.value, "Fixed bar");
+    assert!(bar.attrs.is_empty());
+    // other languages fall through to the base layer
+    assert_eq!(query(&hr, "foo").value, "Fu");
+    assert_eq!(locales.layer_of(&en, "foo"), Some(2));
+    assert_eq!(locales.layer_of(&en, "bar"), Some(1));
+    assert_eq!(locales.layer_of(&hr, "foo"), Some(0));
+    assert_eq!(locales.layer_of(&hr, "bar"), None);
+
+    let popped = locales.pop_overlay().unwrap();
+    assert!(popped.locale(&en).is_some());
+    assert_eq!(query(&en, "foo").value, "Fixed foo");
+
+    locales.clear_overlays();
+    assert_eq!(query(&en, "foo").value, "Foo");
+    assert_eq!(query(&en, "bar").attrs.len(), 1);
+    assert!(locales.pop_overlay().is_none());
+}
+
+#[test]
+fn test_if_overlays_apply_to_the_fallback_language() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Foo")])
+        .unwrap();
+    let overlay = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Fixed foo")])
+        .unwrap();
+    locales.push_overlay(overlay);
+
+    let msg = locales
+        .query(&langid!("de-DE"), &i18n::Query::new("foo"))
+        .unwrap();
+    assert_eq!(msg.value, "Fixed foo");
+}
+
+#[test]
+fn test_if_merged_locales_replace_whole_languages() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([
+            ("en-US", "foo = Foo\nbar = Bar"),
+            ("hr-HR", "foo = Fu\nbaz = Baz"),
+        ])
+        .unwrap();
+    let other = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Fresh foo"), ("de-DE", "foo = Frisch")])
+        .unwrap();
+    assert_eq!(
+        locales.merge_locales(other),
+        vec![langid!("de-DE"), langid!("en-US")]
+    );
+
+    let query = |lang, id| locales.query(&lang, &i18n::Query::new(id));
+    assert_eq!(query(langid!("en-US"), "foo").unwrap().value, "Fresh foo");
+    assert_eq!(query(langid!("de-DE"), "foo").unwrap().value, "Frisch");
+    // languages missing from the merged collection are kept
+    assert_eq!(query(langid!("hr-HR"), "baz").unwrap().value, "Baz");
+    // a replaced language no longer has the messages only the previous locale defined
+    assert!(query(langid!("en-US"), "bar").is_err());
+}
+
+#[test]
+fn test_if_usage_is_tracked_consistently_across_threads() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_strs([("en-US", "foo = Foo\nbar = Bar\n    .title = Bar title")])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    // nothing is recorded before tracking is enabled
+    locales.query(&lang, &i18n::Query::new("foo")).unwrap();
+    assert!(locales.usage_snapshot().is_empty());
+
+    locales.enable_usage_tracking();
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    locales.query(&lang, &i18n::Query::new("foo")).unwrap();
+                }
+            });
+        }
+    });
+    locales.query_batch(
+        &langid!("hr-HR"),
+        &[
+            i18n::Query::new("bar"),
+            // an attribute is attributed to its message
+            i18n::Query::new("bar.title"),
+            i18n::Query::new("missing"),
+        ],
+    );
+
+    assert_eq!(
+        locales.usage_snapshot(),
+        vec![
+            i18n::UsageEntry {
+                lang: langid!("en-US"),
+                id: "foo".to_string(),
+                hits: 8000,
+            },
+            i18n::UsageEntry {
+                lang: langid!("hr-HR"),
+                id: "bar".to_string(),
+                hits: 2,
+            },
+            i18n::UsageEntry {
+                lang: langid!("hr-HR"),
+                id: "missing".to_string(),
+                hits: 1,
+            },
+        ]
+    );
+
+    locales.reset_usage();
+    assert!(locales.usage_snapshot().is_empty());
+}
+
+#[test]
+fn test_if_junk_entries_are_reported() {
+    let result = i18n::LocalesBuilder::new().build_from_dir("./tests/i18n_junk");
+    let Err(i18n::LoadError::Parser(errors)) = result else {
+        panic!("expected a parser error");
+    };
+    let [i18n::ParserError::ParserError { junk, .. }] = &errors[..] else {
+        panic!("expected a single parser error, got {errors:?}");
+    };
+    assert_eq!(junk.len(), 1);
+    assert_eq!(junk[0].line, 3);
+    assert_eq!(junk[0].excerpt, "broken = { $unclosed");
+    assert!(errors[0]
+        .to_string()
+        .contains("junk at line 3: broken = { $unclosed"));
+
+    let locales = i18n::LocalesBuilder::new()
+        .allow_junk(true)
+        .build_from_dir("./tests/i18n_junk")
+        .unwrap();
+    let lang = langid!("en-US");
+    assert_eq!(
+        locales
+            .query(&lang, &i18n::Query::new("bar"))
+            .unwrap()
+            .value,
+        "Bar"
+    );
+    assert!(locales.query(&lang, &i18n::Query::new("broken")).is_err());
+}
+
+#[test]
+fn test_if_locales_are_diffed() {
+    let old = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+
+    let read = |file: &str| std::fs::read_to_string(format!("./tests/i18n/en-US/{file}")).unwrap();
+    let main = read("main.ftl")
+        .replace("English A", "English A, revised")
+        .replace("foo-c = English C\n", "foo-d = English D\n");
+    let attrs = read("attribute-check.ftl")
+        .replace(".idle = Login", ".idle = Log in")
+        .replace(
+            "  .aria-label = A login button\n",
+            "  .tooltip = Logs you in\n",
+        );
+    let new = i18n::LocalesBuilder::new()
+        .build_from_strs([
+            ("en-US", main),
+            ("en-US", attrs),
+            ("en-US", read("argument-check.ftl")),
+            ("de-DE", "foo-a = Deutsch A".to_string()),
+        ])
+        .unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.added_locales, vec![langid!("de-DE")]);
+    assert_eq!(diff.removed_locales, vec![langid!("hr-hr")]);
+    let [en] = &diff.locales[..] else {
+        panic!("expected only en-US to change, got {diff:?}");
+    };
+    assert_eq!(en.lang, langid!("en-US"));
+    assert_eq!(en.added_keys, vec!["foo-d"]);
+    assert_eq!(en.removed_keys, vec!["foo-c"]);
+    assert_eq!(en.changed_keys, vec!["foo-a"]);
+    assert_eq!(en.added_attrs, vec!["login-btn.tooltip"]);
+    assert_eq!(en.removed_attrs, vec!["login-btn.aria-label"]);
+    assert_eq!(en.changed_attrs, vec!["login-btn.idle"]);
+    assert_eq!(
+        diff.to_string(),
+        "+ locale de-DE\n- locale hr-HR\nen-US:\n  + key foo-d\n  - key foo-c\n  ~ key foo-a\n  + attr login-btn.tooltip\n  - attr login-btn.aria-label\n  ~ attr login-btn.idle\n"
+    );
+
+    assert!(old.diff(&old).is_empty());
+    assert_eq!(
+        serde_json::to_value(&diff).unwrap()["locales"][0]["changed_keys"],
+        serde_json::json!(["foo-a"])
+    );
+}
+
+#[test]
+fn test_if_select_args_are_validated() {
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "user-invited = { $gender ->\n    [male] He\n    [female] She\n   *[other] They\n} invited you.\n    .title = { $count ->\n        [one] { $gender ->\n            [nonbinary] Xe\n           *[other] Someone\n        }\n       *[other] Many\n    }",
+        )])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let locale = locales.locale(&lang).unwrap();
+    assert_eq!(
+        locale.select_variants("user-invited", "gender"),
+        Some(vec![
+            "male".to_string(),
+            "female".to_string(),
+            "other".to_string(),
+            "nonbinary".to_string()
+        ])
+    );
+    assert_eq!(
+        locale.select_variants("user-invited", "count"),
+        Some(vec!["one".to_string(), "other".to_string()])
+    );
+    assert_eq!(locale.select_variants("user-invited", "missing"), None);
+    assert_eq!(locale.select_variants("missing", "gender"), None);
+
+    // a matching variant key is accepted
+    let query = i18n::Query::new("user-invited").with_select_arg("gender", "female", &[]);
+    assert_eq!(
+        locales.try_query(&lang, &query).unwrap().value,
+        "She invited you."
+    );
+
+    // an allowed value is accepted even if it falls into the default variant
+    let query = i18n::Query::new("user-invited").with_select_arg("gender", "unknown", &["unknown"]);
+    assert_eq!(
+        locales.try_query(&lang, &query).unwrap().value,
+        "They invited you."
+    );
+
+    let query = i18n::Query::new("user-invited").with_select_arg("gender", "femal", &["unknown"]);
+    let Err(i18n::TryQueryError::InvalidSelectValue(err)) = locales.try_query(&lang, &query) else {
+        panic!("expected the select value to be rejected");
+    };
+    assert_eq!(
+        err,
+        i18n::InvalidSelectValue {
+            key: "gender".to_string(),
+            value: "femal".to_string(),
+            variants: vec![
+                "unknown".to_string(),
+                "male".to_string(),
+                "female".to_string(),
+                "other".to_string(),
+                "nonbinary".to_string()
+            ],
+        }
+    );
+
+    // the plain query still formats the default variant
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "They invited you."
+    );
+}
+
+#[test]
+fn test_if_attr_args_merge_with_the_query_args() {
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([("en-US", "user = User\n    .tooltip = { $name } ({ $role })")])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    // disjoint merge: the query supplies `name`, the later call supplies `role`
+    let query = i18n::Query::new("user").with_attr_arg("tooltip", "name", "Alex");
+    let mut msg = locales.query(&lang, &query).unwrap();
+    let tooltip = msg.attrs.get_mut("tooltip").unwrap();
+    assert_eq!(tooltip.value, None);
+    let mut more = i18n::FluentArgs::new();
+    more.set("role", "admin");
+    assert_eq!(tooltip.query(Some(&more), false).unwrap(), "Alex (admin)");
+
+    // override: the later call wins on the same key
+    more.set("name", "Sam");
+    assert_eq!(tooltip.query(Some(&more), false).unwrap(), "Sam (admin)");
+    // the remembered arguments are left untouched
+    assert!(tooltip.query(None, false).is_err());
+
+    // cached values are returned as is unless replaced
+    let query = i18n::Query::new("user")
+        .with_attr_arg("tooltip", "name", "Alex")
+        .with_attr_arg("tooltip", "role", "user");
+    let mut msg = locales.query(&lang, &query).unwrap();
+    let tooltip = msg.attrs.get_mut("tooltip").unwrap();
+    assert_eq!(tooltip.value.as_deref(), Some("Alex (user)"));
+    assert_eq!(tooltip.query(Some(&more), false).unwrap(), "Alex (user)");
+    assert_eq!(tooltip.query(Some(&more), true).unwrap(), "Sam (admin)");
+    let mut role = i18n::FluentArgs::new();
+    role.set("role", "owner");
+    assert_eq!(tooltip.query(Some(&role), true).unwrap(), "Alex (owner)");
+}
+
+#[test]
+fn test_if_query_args_are_propagated_to_attrs() {
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "user = { $username }\n    .title = Profile of { $username }\n    .tooltip = { $username } ({ $role })",
+        )])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let query = i18n::Query::new("user")
+        .with_arg("username", "Alex")
+        .propagate_args(true);
+    let mut msg = locales.query(&lang, &query).unwrap();
+    assert_eq!(msg.value, "Alex");
+    assert_eq!(msg.attrs["title"].value.as_deref(), Some("Profile of Alex"));
+    // variables supplied nowhere are still left to be resolved lazily
+    let tooltip = msg.attrs.get_mut("tooltip").unwrap();
+    assert_eq!(tooltip.value, None);
+    let mut role = i18n::FluentArgs::new();
+    role.set("role", "admin");
+    assert_eq!(tooltip.query(Some(&role), false).unwrap(), "Alex (admin)");
+
+    // the attribute arguments override the main ones
+    let query = i18n::Query::new("user")
+        .with_arg("username", "Alex")
+        .with_attr_arg("title", "username", "Sam")
+        .propagate_args(true);
+    let msg = locales.query(&lang, &query).unwrap();
+    assert_eq!(msg.attrs["title"].value.as_deref(), Some("Profile of Sam"));
+
+    // not propagated by default
+    let query = i18n::Query::new("user").with_arg("username", "Alex");
+    let msg = locales.query(&lang, &query).unwrap();
+    assert_eq!(msg.attrs["title"].value, None);
+}
+
+#[test]
+fn test_if_args_are_added_in_bulk() {
+    use std::collections::HashMap;
+
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "user = { $name } ({ $role })\n    .tooltip = { $name } is { $status }",
+        )])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let args = HashMap::from([
+        ("name".to_string(), "Alex".to_string()),
+        ("role".to_string(), "admin".to_string()),
+    ]);
+    let query = i18n::Query::new("user")
+        .with_args(args)
+        .with_attr_args("tooltip", vec![("name", "Alex"), ("status", "online")]);
+    let msg = locales.query(&lang, &query).unwrap();
+    assert_eq!(msg.value, "Alex (admin)");
+    assert_eq!(
+        msg.attrs["tooltip"].value.as_deref(),
+        Some("Alex is online")
+    );
+
+    // later duplicates override earlier ones
+    let mut query = i18n::Query::new("user").with_args(vec![("name", "Alex"), ("role", "user")]);
+    query.extend([("role", "owner")]);
+    assert_eq!(locales.query(&lang, &query).unwrap().value, "Alex (owner)");
+    assert_eq!(query.duplicate_args().len(), 1);
+}
+
+#[test]
+fn test_if_owned_queries_outlive_their_arguments() {
+    /// A toast that is shown later, as in a notification queue.
+    struct Toast {
+        query: i18n::OwnedQuery,
+    }
+
+    fn toast(id: &str, name: &str) -> Toast {
+        let attr = String::from("title");
+        let query =
+            i18n::Query::new(id)
+                .with_arg("name", name)
+                .with_attr_arg(attr.as_str(), "name", name);
+        Toast {
+            query: query.into_owned(),
+        }
+    }
+
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "hello = Hello, { $name }!\n    .title = Hi { $name }",
+        )])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let toast = {
+        let name = String::from("Alex");
+        toast(&String::from("hello"), &name)
+    };
+    let msg = locales.query(&lang, &toast.query).unwrap();
+    assert_eq!(msg.value, "Hello, Alex!");
+    assert_eq!(msg.attrs["title"].value.as_deref(), Some("Hi Alex"));
+
+    // clones share nothing but the variant selector
+    let clone = toast.query.clone().with_arg("name", "Sam");
+    assert_eq!(locales.query(&lang, &clone).unwrap().value, "Hello, Sam!");
+    assert_eq!(
+        locales.query(&lang, &toast.query).unwrap().value,
+        "Hello, Alex!"
+    );
+}
+
+#[test]
+fn test_if_batches_match_individual_queries() {
+    let locales = i18n::Locales::builder()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+    let mut ids = locales.message_ids(&langid!("en-US")).unwrap();
+    ids.push("missing".to_string());
+    let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+
+    for lang in [langid!("en-US"), langid!("hr-HR"), langid!("fr-FR")] {
+        let queries = ids
+            .iter()
+            .map(|id| i18n::Query::new(*id).with_arg("username", "Alex"))
+            .collect::<Vec<_>>();
+        let batch = locales.query_batch(&lang, &queries);
+        for (query, result) in queries.iter().zip(batch) {
+            let single = locales.query(&lang, query);
+            assert_eq!(
+                result.as_ref().map(|msg| &msg.value).ok(),
+                single.as_ref().map(|msg| &msg.value).ok(),
+            );
+            assert_eq!(result.is_err(), single.is_err());
+        }
+
+        let values = locales.query_values(&lang, &ids);
+        let singles = ids
+            .iter()
+            .map(|id| locales.query_value(&lang, &i18n::Query::new(*id)))
+            .collect::<Vec<_>>();
+        assert_eq!(values, singles);
+        assert_eq!(values.last().unwrap(), "missing");
+    }
+}
+
+#[test]
+fn test_if_batches_report_every_failed_query() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static IDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    let locales = i18n::Locales::builder()
+        .on_error(|err| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            IDS.lock().unwrap().push(err.message_id.clone());
+        })
+        .build_from_strs([("en-US", "foo = Foo\nhello = Hello, { $name }!")])
+        .unwrap();
+    let queries = [
+        i18n::Query::new("missing"),
+        i18n::Query::new("foo"),
+        i18n::Query::new("hello"),
+    ];
+    let results = locales.query_batch(&langid!("en-US"), &queries);
+    assert_eq!(results.iter().filter(|result| result.is_err()).count(), 2);
+
+    // every error names a single message, so the handler is invoked once per failed query
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    assert_eq!(*IDS.lock().unwrap(), ["missing", "hello"]);
+}
+
+#[test]
+fn test_if_locales_and_bundles_are_accessible() {
+    let locales = i18n::Locales::builder()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+
+    let mut counts = locales
+        .iter()
+        .map(|(lang, locale)| {
+            let bundle = locale.bundle();
+            assert_eq!(bundle.locales[0], lang);
+            let count = locale
+                .message_ids()
+                .filter(|id| bundle.has_message(id))
+                .count();
+            (lang.to_string(), count)
+        })
+        .collect::<Vec<_>>();
+    counts.sort();
+    assert_eq!(counts, [("en-US".to_string(), 5), ("hr-HR".to_string(), 4)]);
+
+    let locale = locales.locale(&langid!("hr-HR")).unwrap();
+    let msg = locale.bundle().get_message("foo-a").unwrap();
+    let mut errors = Vec::new();
+    let value = locale
+        .bundle()
+        .format_pattern(msg.value().unwrap(), None, &mut errors);
+    assert_eq!(value, "Croatian A");
+}
+
+#[test]
+fn test_if_queries_are_observed() {
+    use i18n::{CountingObserver, Query, QueryOutcome};
+    use std::sync::Arc;
+
+    let locales = i18n::Locales::builder()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+    let (en, hr) = (langid!("en-US"), langid!("hr-HR"));
+
+    // nothing is observed until an observer is set
+    locales.query(&en, &Query::new("foo-a")).unwrap();
+    let observer = Arc::new(CountingObserver::default());
+    locales.set_observer(observer.clone());
+
+    locales.query(&en, &Query::new("foo-a")).unwrap();
+    locales.query(&hr, &Query::new("foo-a")).unwrap();
+    let query = Query::new("welcome-back")
+        .with_arg("username", "Alex")
+        .with_fallback(true);
+    locales.query(&hr, &query).unwrap();
+    locales.query(&en, &Query::new("missing")).unwrap_err();
+    locales.query(&en, &Query::new("welcome-back")).unwrap_err();
+    locales.query_batch(&en, &[Query::new("foo-a"), Query::new("foo-b")]);
+
+    assert_eq!(observer.outcomes(QueryOutcome::Hit), 4);
+    assert_eq!(observer.outcomes(QueryOutcome::Fallback), 1);
+    assert_eq!(observer.outcomes(QueryOutcome::Miss), 1);
+    assert_eq!(observer.outcomes(QueryOutcome::Error), 1);
+    assert_eq!(
+        observer.hot_keys(),
+        [
+            ("foo-a".to_string(), 3),
+            ("welcome-back".to_string(), 2),
+            ("foo-b".to_string(), 1),
+            ("missing".to_string(), 1),
+        ]
+    );
+
+    locales.set_observer(Arc::new(i18n::TracingObserver));
+    locales.query(&en, &Query::new("foo-a")).unwrap();
+    assert_eq!(observer.queries_of("foo-a"), 3);
+
+    observer.reset();
+    assert!(observer.hot_keys().is_empty());
+    assert_eq!(observer.total_duration(), std::time::Duration::ZERO);
+}
+
+#[test]
+fn test_if_exported_locales_round_trip() {
+    use i18n::{Locales, Query};
+
+    let locales = Locales::builder().build_from_dir("./tests/i18n").unwrap();
+    let exported = locales.export();
+    let mut langs = exported.keys().cloned().collect::<Vec<_>>();
+    langs.sort();
+    assert_eq!(langs, ["en-US", "hr-HR"]);
+    // every resource of the directory ends up in the export
+    assert!(exported["en-US"].contains("foo-a =") && exported["en-US"].contains("login-btn ="));
+
+    let restored = Locales::from_ftl_map(exported, langid!("en-US"), None).unwrap();
+    for (lang, locale) in locales.iter() {
+        for id in locale.message_ids() {
+            let query = Query::new(id).with_arg("username", "Alex");
+            let (original, restored) =
+                (locales.query(&lang, &query), restored.query(&lang, &query));
+            assert_eq!(
+                original.as_ref().ok(),
+                restored.as_ref().ok(),
+                "{lang} {id}"
+            );
+            assert_eq!(original.is_err(), restored.is_err());
+        }
+    }
+    assert_eq!(restored.export(), locales.export());
+}
+
+#[test]
+fn test_if_locales_are_added_fallibly() {
+    use i18n::{AddLocaleError, FluentResource, Locales, Query};
+
+    let locales = Locales::builder()
+        .build_from_strs([("en-US", "hello = Hello")])
+        .unwrap();
+    let resource = |source: &str| vec![FluentResource::try_new(source.to_string()).unwrap()];
+    let value = |lang| {
+        locales
+            .query(&lang, &Query::new("hello"))
+            .map(|msg| msg.value)
+            .unwrap()
+    };
+
+    assert!(matches!(
+        locales.try_add_locale("not a langid", resource("hello = Bok"), false),
+        Err(AddLocaleError::InvalidLangid { langid, .. }) if langid == "not a langid"
+    ));
+    assert!(matches!(
+        locales.try_add_locale("hr-HR", Vec::new(), false),
+        Err(AddLocaleError::NoResources { .. })
+    ));
+    assert!(matches!(
+        locales.try_add_locale("hr-HR", resource("hello = Bok\nhello = Zdravo"), false),
+        Err(AddLocaleError::ResourceConflict(_))
+    ));
+    assert!(locales.locale(&langid!("hr-HR")).is_none());
+
+    locales
+        .try_add_locale("hr-HR", resource("hello = Bok"), false)
+        .unwrap();
+    assert_eq!(value(langid!("hr-HR")), "Bok");
+
+    // a duplicate only replaces the locale with `overwrite`
+    assert!(matches!(
+        locales.try_add_locale("hr-HR", resource("hello = Zdravo"), false),
+        Err(AddLocaleError::AlreadyLoaded { lang }) if lang == langid!("hr-HR")
+    ));
+    assert_eq!(value(langid!("hr-HR")), "Bok");
+    locales
+        .try_add_locale("hr-HR", resource("hello = Zdravo"), true)
+        .unwrap();
+    assert_eq!(value(langid!("hr-HR")), "Zdravo");
+}
+
+#[test]
+fn test_if_strict_mode_rejects_fallbacks() {
+    use i18n::Query;
+
+    let locales = i18n::Locales::builder()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+    let query = Query::new("welcome-back")
+        .with_arg("username", "Alex")
+        .with_fallback(true);
+    let (en, hr, fr) = (langid!("en-US"), langid!("hr-hr"), langid!("fr-FR"));
+
+    assert!(locales.query(&hr, &query).is_ok());
+    assert!(locales.query(&fr, &Query::new("foo-a")).is_ok());
+
+    locales.set_strict(true);
+    let err = locales.query(&hr, &query).unwrap_err();
+    assert_eq!(
+        (err.lang.clone(), err.message_id.as_str()),
+        (hr.clone(), "welcome-back")
+    );
+    assert!(err.fallback_attempted);
+    assert!(err.to_string().contains("welcome-back in hr-HR"));
+    // a locale that is not loaded falls back as well
+    assert!(locales.query(&fr, &Query::new("foo-a")).is_err());
+    assert!(locales.try_query_value(&fr, &Query::new("foo-a")).is_err());
+    // translated messages and the fallback language itself are unaffected
+    assert!(locales.query(&hr, &Query::new("foo-a")).is_ok());
+    assert!(locales.query(&en, &query).is_ok());
+
+    locales.set_strict(false);
+    assert!(locales.query(&hr, &query).is_ok());
+}
+
+#[test]
+fn test_if_identical_resources_are_shared() {
+    use std::sync::Arc;
+
+    let locales = i18n::Locales::builder()
+        .build_from_strs([
+            ("en-US", "hello = Hello"),
+            ("en-GB", "hello = Hello"),
+            ("de-DE", "hello = Hallo"),
+        ])
+        .unwrap();
+    let resource = |lang| locales.locale(&lang).unwrap().resources()[0].clone();
+    assert!(Arc::ptr_eq(
+        &resource(langid!("en-US")),
+        &resource(langid!("en-GB"))
+    ));
+    assert!(!Arc::ptr_eq(
+        &resource(langid!("en-US")),
+        &resource(langid!("de-DE"))
+    ));
+
+    // locales added later share them as well
+    let source = i18n::FluentResource::try_new("hello = Hello".to_string()).unwrap();
+    locales.add_locale("en-AU", vec![source]).unwrap();
+    assert!(Arc::ptr_eq(
+        &resource(langid!("en-US")),
+        &resource(langid!("en-AU"))
+    ));
+}
+
+#[test]
+fn test_if_language_switcher_is_shared_across_threads() {
+    static SWITCHER: std::sync::OnceLock<i18n::LanguageSwitcher> = std::sync::OnceLock::new();
+
+    let locales = i18n::LocalesBuilder::new()
+        .fallback("hr-HR")
+        .build_from_strs([
+            ("en-US", "foo = Foo"),
+            ("hr-HR", "foo = Fu"),
+            ("de-DE", "foo = Fuh"),
+        ])
+        .unwrap();
+    let switcher = SWITCHER.get_or_init(|| locales.language_switcher().unwrap());
+    assert_eq!(switcher.current(), langid!("hr-HR"));
+    assert_eq!(
+        switcher.available(),
+        [langid!("de-DE"), langid!("en-US"), langid!("hr-HR")]
+    );
+
+    // every thread advances the selection exactly once
+    std::thread::scope(|scope| {
+        for _ in 0..6 {
+            scope.spawn(|| SWITCHER.get().unwrap().next());
+        }
+    });
+    assert_eq!(switcher.current(), langid!("hr-HR"));
+
+    // the callbacks see the concurrent changes in the order they were made
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let subscription = switcher.on_change({
+        let seen = seen.clone();
+        move |lang| {
+            // give the other changes the chance to overtake this one
+            if *lang == langid!("de-DE") {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            seen.lock().unwrap().push(lang.clone());
+        }
+    });
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..20 {
+                    SWITCHER.get().unwrap().next();
+                }
+            });
+        }
+    });
+    switcher.unsubscribe(subscription);
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 160);
+    let position = |lang| switcher.available().iter().position(|l| l == lang).unwrap();
+    for pair in seen.windows(2) {
+        assert_eq!(position(&pair[1]), (position(&pair[0]) + 1) % 3);
+    }
+    assert_eq!(seen.last(), Some(&switcher.current()));
+
+    let empty = i18n::LocalesBuilder::new().build();
+    assert!(matches!(
+        empty.language_switcher(),
+        Err(i18n::SwitchError::Empty)
+    ));
+}
+
+#[test]
+fn test_if_empty_locales_are_reported() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Records the languages of the empty locales.
+    #[derive(Default)]
+    struct EmptyLocales(Mutex<Vec<i18n::LanguageIdentifier>>);
+
+    impl i18n::QueryObserver for EmptyLocales {
+        fn on_query(
+            &self,
+            _: &i18n::LanguageIdentifier,
+            _: &str,
+            _: std::time::Duration,
+            _: i18n::QueryOutcome,
+        ) {
+        }
+
+        fn on_empty_locale(&self, lang: &i18n::LanguageIdentifier) {
+            self.0.lock().unwrap().push(lang.clone());
+        }
+    }
+
+    let locales = i18n::Locales::builder()
+        .on_error(|errs| _ = ERRORS.fetch_add(errs.len(), Ordering::SeqCst))
+        .build_from_strs([("en-US", "foo = Foo\n-term = Term")])
+        .unwrap();
+    let observer = Arc::new(EmptyLocales::default());
+    locales.set_observer(observer.clone());
+
+    locales.add_locale("hr-HR", vec![]).unwrap();
+    locales
+        .add_locale(
+            "de-DE",
+            vec![FluentResource::try_new("# comments only\n-term = Begriff".to_string()).unwrap()],
+        )
+        .unwrap();
+    locales
+        .add_locale(
+            "es",
+            vec![FluentResource::try_new("foo = Foo".to_string()).unwrap()],
+        )
+        .unwrap();
+    assert_eq!(
+        *observer.0.lock().unwrap(),
+        [langid!("hr-HR"), langid!("de-DE")]
+    );
+    // an empty locale is not an error of any query
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 0);
+
+    // the empty locales are still added, falling back on request
+    let msg = locales
+        .query(
+            &langid!("de-DE"),
+            &i18n::Query::new("foo").with_fallback(true),
+        )
+        .unwrap();
+    assert_eq!(msg.value, "Foo");
+}
+
+#[test]
+fn test_if_dynamic_queries_validate_their_input() {
+    use i18n::DynamicQueryError;
+    use serde_json::json;
+
+    let locales = i18n::Locales::builder()
+        .build_from_strs([
+            (
+                "en-US",
+                "hello = Hello, { $name }!\nitems = { $count } items\nonly-en = Only English",
+            ),
+            (
+                "hr-HR",
+                "hello = Bok, { $name }!\nadmin = { $admin ->\n [true] Admin\n *[false] Korisnik\n}",
+            ),
+        ])
+        .unwrap();
+
+    let msg = locales
+        .query_dynamic("hr-hr", "hello", &json!({ "name": "Ana" }))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, \u{2068}Ana\u{2069}!");
+    let msg = locales
+        .query_dynamic("en-US", "items", &json!({ "count": 3 }))
+        .unwrap();
+    assert_eq!(msg.value, "\u{2068}3\u{2069} items");
+    let msg = locales
+        .query_dynamic("hr-HR", "admin", &json!({ "admin": true }))
+        .unwrap();
+    assert_eq!(msg.value, "Admin");
+    // missing keys are resolved in the fallback language
+    let msg = locales
+        .query_dynamic("hr-HR", "only-en", &json!(null))
+        .unwrap();
+    assert_eq!(msg.value, "Only English");
+
+    assert!(matches!(
+        locales.query_dynamic("not a langid!", "hello", &json!({})),
+        Err(DynamicQueryError::InvalidLangid { lang }) if lang == "not a langid!"
+    ));
+    assert!(matches!(
+        locales.query_dynamic("hr-HR", "missing", &json!({})),
+        Err(DynamicQueryError::UnknownKey { id, lang }) if id == "missing" && lang == langid!("hr-HR")
+    ));
+    assert!(matches!(
+        locales.query_dynamic("en-US", "hello", &json!({ "name": ["Alex"] })),
+        Err(DynamicQueryError::UnsupportedArg { key, kind: "an array" }) if key == "name"
+    ));
+    assert!(matches!(
+        locales.query_dynamic("en-US", "hello", &json!({ "name": { "first": "Alex" } })),
+        Err(DynamicQueryError::UnsupportedArg {
+            kind: "an object",
+            ..
+        })
+    ));
+    assert!(matches!(
+        locales.query_dynamic("en-US", "hello", &json!(["Alex"])),
+        Err(DynamicQueryError::InvalidArgs { kind: "an array" })
+    ));
+}
+
+#[test]
+fn test_if_duplicate_arguments_are_detected() {
+    let locales = i18n::Locales::builder()
+        .use_isolating(false)
+        .build_from_strs([("en-US", "hello = Hello, { $name }!\n    .title = { $name }")])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    // the last write wins
+    let query = i18n::Query::new("hello")
+        .with_arg("name", "Alex")
+        .with_arg("name", "Ana")
+        .with_attr_arg("title", "name", "Alex")
+        .with_attr_arg("title", "name", "Ana");
+    assert_eq!(
+        query.duplicate_args(),
+        [
+            i18n::DuplicateArg {
+                attr: None,
+                key: "name".to_string()
+            },
+            i18n::DuplicateArg {
+                attr: Some("title".to_string()),
+                key: "name".to_string()
+            },
+        ]
+    );
+    let msg = locales.try_query(&lang, &query).unwrap();
+    assert_eq!(msg.value, "Hello, Ana!");
+    assert_eq!(msg.attrs["title"].value.as_deref(), Some("Ana"));
+
+    // strict arguments reject the query
+    let query = query.strict_args(true);
+    let err = locales.try_query(&lang, &query).unwrap_err();
+    assert_eq!(err.to_string(), "argument $name was set more than once");
+    assert!(locales.query(&lang, &query).is_ok());
+
+    let query = i18n::Query::new("hello")
+        .with_arg("name", "Alex")
+        .with_attr_arg("title", "name", "Ana")
+        .strict_args(true);
+    assert!(query.duplicate_args().is_empty());
+    assert!(locales.try_query(&lang, &query).is_ok());
+}
+
+#[test]
+fn test_if_reference_graphs_are_built() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n_refs")
+        .unwrap();
+    let lang = langid!("en-US");
+    let graph = locales.locale(&lang).unwrap().reference_graph();
+
+    assert_eq!(
+        graph.dependencies_of("welcome-title"),
+        ["-brand", "welcome"]
+    );
+    assert_eq!(graph.dependents_of("-brand"), ["app-name", "welcome-title"]);
+    assert_eq!(graph.dependents_of("app-name"), ["about", "welcome"]);
+    assert!(graph.dependents_of("standalone").is_empty());
+    assert!(graph.dependencies_of("unknown").is_empty());
+    assert_eq!(graph.cycles(), [vec!["echo"], vec!["ping", "pong"]]);
+    assert!(graph.has_cycles());
+    assert_eq!(
+        serde_json::to_value(&graph).unwrap()["dependents"]["welcome"],
+        serde_json::json!(["welcome-title"])
+    );
+
+    assert_eq!(
+        locales.impact_of_change(&lang, "-brand"),
+        ["about", "app-name", "welcome", "welcome-title"]
+    );
+    assert_eq!(
+        locales.impact_of_change(&lang, "welcome"),
+        ["welcome-title"]
+    );
+    assert_eq!(locales.impact_of_change(&lang, "ping"), ["pong"]);
+    assert!(locales.impact_of_change(&lang, "standalone").is_empty());
+    assert!(locales
+        .impact_of_change(&langid!("de-DE"), "-brand")
+        .is_empty());
+}
+
+#[test]
+fn test_if_control_chars_are_stripped() {
+    use i18n::strip_control_chars;
+
+    assert!(matches!(
+        strip_control_chars("plain\ttext\r\n"),
+        std::borrow::Cow::Borrowed("plain\ttext\r\n")
+    ));
+    assert_eq!(
+        strip_control_chars("\x1b[38;5;196mred\x1b[0m \u{9b}1mbold\0\x7f\u{85}"),
+        "red bold"
+    );
+    // a lone escape is dropped without the character after it
+    assert_eq!(strip_control_chars("a\x1bb"), "ab");
+}
+
+#[test]
+fn test_if_truncation_keeps_characters_whole() {
+    let truncate = i18n::truncate(5);
+
+    assert_eq!(truncate("short"), "short");
+    assert_eq!(truncate("longer"), "long…");
+    // combining marks stay with their base character
+    assert_eq!(truncate("abce\u{301}\u{301}f"), "abc…");
+    assert_eq!(truncate("abcde\u{301}f"), "abcd…");
+    // emoji sequences are kept or dropped whole
+    assert_eq!(truncate("ab👍🏽cd"), "ab👍🏽…");
+    assert_eq!(truncate("abc👍🏽d"), "abc…");
+    assert_eq!(truncate("a👨\u{200d}👩\u{200d}👧b"), "a…");
+    assert_eq!(truncate("abc🇭🇷🇩🇪"), "abc…");
+    assert_eq!(truncate("ab🇭🇷🇩🇪"), "ab🇭🇷…");
+    assert_eq!(i18n::truncate(1)("ab"), "…");
+    assert_eq!(i18n::truncate(0)("ab"), "");
+}
+
+#[test]
+fn test_if_arguments_are_sanitized() {
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "hello = Hello, { $name }!\n    .title = { $name }'s profile\ncount = { $count } items",
+        )])
+        .unwrap();
+    let lang = langid!("en-US");
+    let truncate = i18n::truncate(6);
+    locales.set_arg_sanitizer(move |value| {
+        std::borrow::Cow::Owned(truncate(&i18n::strip_control_chars(value)).into_owned())
+    });
+
+    let query = i18n::Query::new("hello")
+        .with_arg("name", "\x1b[31mAlexander\x1b[0m")
+        .with_attr_arg("title", "name", "Ana\nMaria");
+    let msg = locales.query(&lang, &query).unwrap();
+    assert_eq!(msg.value, "Hello, Alexa…!");
+    assert_eq!(
+        msg.attrs["title"].value.as_deref(),
+        Some("Ana\nM…'s profile")
+    );
+
+    let query = i18n::Query::new("hello").with_raw_arg("name", "Alexander");
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "Hello, Alexander!"
+    );
+    // the last write decides whether the argument is raw
+    let query = i18n::Query::new("hello")
+        .with_raw_arg("name", "Alexander")
+        .with_arg("name", "Alexander");
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "Hello, Alexa…!"
+    );
+
+    // numbers are formatted by the `builtins` feature, but never sanitized
+    let query = i18n::Query::new("count").with_arg("count", 1234567);
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "1,234,567 items"
+    );
+
+    locales.clear_arg_sanitizer();
+    let query = i18n::Query::new("hello").with_arg("name", "Alexander");
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "Hello, Alexander!"
+    );
+}
+
+#[test]
+fn test_if_query_errors_are_grouped_and_deduplicated() {
+    let locales = i18n::Locales::builder()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "profile = { $name } profile\n    .title = { missing } and { missing }\n    .alt = { $name } at { missing }\n    .label = Profile",
+        )])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let errs = locales
+        .query(&lang, &i18n::Query::new("profile"))
+        .unwrap_err();
+    let missing_var = i18n::FluentError::ResolverError(i18n::ResolverError::Reference(
+        i18n::ReferenceKind::Variable {
+            id: "name".to_string(),
+        },
+    ));
+    let missing_msg = i18n::FluentError::ResolverError(i18n::ResolverError::Reference(
+        i18n::ReferenceKind::Message {
+            id: "missing".to_string(),
+            attribute: None,
+        },
+    ));
+    // the missing argument of the attribute can still be supplied lazily
+    assert_eq!(errs.errors.value, std::slice::from_ref(&missing_var));
+    assert_eq!(
+        errs.errors
+            .attrs
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        ["alt", "title"]
+    );
+    assert_eq!(errs.errors.attrs["alt"], std::slice::from_ref(&missing_msg));
+    assert_eq!(
+        errs.errors.attrs["title"],
+        std::slice::from_ref(&missing_msg)
+    );
+    assert_eq!(errs.len(), 2);
+    assert_eq!(Vec::from(errs), [missing_var, missing_msg]);
+
+    let query = i18n::Query::new("profile").with_arg("name", "Ana");
+    let errs = locales.query(&lang, &query).unwrap_err();
+    assert!(errs.errors.value.is_empty());
+    assert_eq!(errs.errors.attrs.len(), 2);
+}
+
+#[test]
+fn test_if_message_info_describes_messages_without_formatting() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+
+    let info = locales
+        .message_info(&langid!("en-US"), "login-btn")
+        .unwrap();
+    assert_eq!(
+        info,
+        i18n::MessageInfo {
+            id: "login-btn".to_string(),
+            lang: langid!("en-US"),
+            is_fallback: false,
+            has_value: false,
+            attrs: [
+                "idle",
+                "progress",
+                "finished-ok",
+                "finished-err",
+                "aria-label",
+                "attr-arg"
+            ]
+            .map(String::from)
+            .to_vec(),
+            attrs_requiring_args: vec!["attr-arg".to_string()],
+            value_requires_args: false,
+        }
+    );
+    assert!(info.requires_args());
+
+    let info = locales
+        .message_info(&langid!("hr-hr"), "login-btn")
+        .unwrap();
+    assert_eq!(info.lang, langid!("hr-hr"));
+    assert_eq!(info.attrs.len(), 4);
+    assert!(!info.requires_args());
+
+    let info = locales
+        .message_info(&langid!("hr-hr"), "welcome-back")
+        .unwrap();
+    assert_eq!(info.lang, langid!("en-US"));
+    assert!(info.is_fallback && info.has_value && info.value_requires_args);
+
+    assert!(locales.message_info(&langid!("hr-hr"), "missing").is_none());
+}
+
+#[test]
+fn test_if_resource_conflicts_follow_the_policy() {
+    let resources = || {
+        [
+            "hello = Hello\n-brand = Acme\nbye = Bye",
+            "hello = Hi\n-brand = App",
+        ]
+        .into_iter()
+        .map(|source| FluentResource::try_new(source.to_string()).unwrap())
+        .collect::<Vec<_>>()
+    };
+    let value = |locale: &Locale, id: &str| locale.query(&i18n::Query::new(id)).unwrap().value;
+
+    let err = Locale::new(langid!("en-US"), resources(), ResourceConflict::Error)
+        .err()
+        .unwrap();
+    assert_eq!(err.lang, langid!("en-US"));
+    assert_eq!(err.ids, ["hello", "-brand"]);
+
+    let locale = Locale::new(langid!("en-US"), resources(), ResourceConflict::KeepFirst).unwrap();
+    assert_eq!(value(&locale, "hello"), "Hello");
+    assert_eq!(value(&locale, "bye"), "Bye");
+    assert_eq!(locale.source_of("hello"), Some("hello = Hello"));
+
+    let locale = Locale::new(langid!("en-US"), resources(), ResourceConflict::Override).unwrap();
+    assert_eq!(value(&locale, "hello"), "Hi");
+    assert_eq!(value(&locale, "bye"), "Bye");
+    assert_eq!(locale.source_of("hello"), Some("hello = Hi"));
+
+    // the builder rejects the whole collection under the default policy
+    let sources = [("en-US", "hello = Hello"), ("en-US", "hello = Hi")];
+    assert!(matches!(
+        i18n::Locales::builder().build_from_strs(sources),
+        Err(i18n::LoadError::ResourceConflict(_))
+    ));
+    let locales = i18n::Locales::builder()
+        .resource_conflict(ResourceConflict::Override)
+        .build_from_strs(sources)
+        .unwrap();
+    let msg = locales
+        .query(&langid!("en-US"), &i18n::Query::new("hello"))
+        .unwrap();
+    assert_eq!(msg.value, "Hi");
+
+    let locales = i18n::Locales::builder()
+        .build_from_strs([("en-US", "foo = Foo")])
+        .unwrap();
+    assert!(locales.add_locale("hr-HR", resources()).is_err());
+    assert!(!locales.has_message(&langid!("hr-HR"), "bye"));
+}
+
+#[test]
+fn test_if_query_errors_carry_their_context() {
+    use std::sync::Mutex;
+    static REPORTED: Mutex<Vec<(String, String, bool)>> = Mutex::new(Vec::new());
+    let locales = i18n::Locales::builder()
+        .fallback("en-US")
+        .on_error(|err| {
+            REPORTED.lock().unwrap().push((
+                err.lang.to_string(),
+                err.message_id.clone(),
+                err.fallback_attempted,
+            ))
+        })
+        .build_from_strs([("en-US", "hello = Hello"), ("de-DE", "hallo = Hallo")])
+        .unwrap();
+
+    let err = locales
+        .query(&langid!("de-DE"), &i18n::Query::new("missing"))
+        .unwrap_err();
+    assert_eq!(err.lang, langid!("de-DE"));
+    assert_eq!(err.message_id, "missing");
+    assert!(!err.fallback_attempted);
+    assert_eq!(err.len(), 1);
+
+    let query = i18n::Query::new("missing").with_fallback(true);
+    assert!(
+        locales
+            .query(&langid!("de-DE"), &query)
+            .unwrap_err()
+            .fallback_attempted
+    );
+    let err = locales
+        .query(&langid!("fr-FR"), &i18n::Query::new("missing"))
+        .unwrap_err();
+    assert!(err.fallback_attempted);
+    assert!(err
+        .to_string()
+        .starts_with("errors occurred while querying missing in fr-FR:"));
+    assert_eq!(Vec::from(err.clone()), Vec::from(err.errors));
+
+    assert_eq!(
+        *REPORTED.lock().unwrap(),
+        [
+            ("de-DE".to_string(), "missing".to_string(), false),
+            ("de-DE".to_string(), "missing".to_string(), true),
+            ("fr-FR".to_string(), "missing".to_string(), true),
+        ]
+    );
+}
+
+#[test]
+fn test_if_on_error_accepts_closures_with_state() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let errors = Arc::new(AtomicUsize::new(0));
+    let counter = errors.clone();
+    let locales = i18n::Locales::builder()
+        .on_error(move |err| _ = counter.fetch_add(err.len(), Ordering::SeqCst))
+        .build_from_strs([("en-US", "hello = Hello")])
+        .unwrap();
+
+    let lang = langid!("en-US");
+    assert!(locales.query(&lang, &i18n::Query::new("hello")).is_ok());
+    assert_eq!(errors.load(Ordering::SeqCst), 0);
+    assert!(locales.query(&lang, &i18n::Query::new("missing")).is_err());
+    assert_eq!(errors.load(Ordering::SeqCst), 1);
+
+    let counter = errors.clone();
+    let locales = i18n::Locales::new(
+        lang.clone(),
+        Some(Arc::new(move |_: &i18n::QueryError| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })),
+    );
+    locales
+        .add_locale(
+            "en-US",
+            vec![FluentResource::try_new("hello = Hello".to_string()).unwrap()],
+        )
+        .unwrap();
+    assert!(locales.query(&lang, &i18n::Query::new("missing")).is_err());
+    assert_eq!(errors.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_if_query_value_skips_the_attributes() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+    let locales = i18n::Locales::builder()
+        .use_isolating(false)
+        .on_error(|errs| _ = ERRORS.fetch_add(errs.len(), Ordering::SeqCst))
+        .build_from_strs([
+            (
+                "en-US",
+                "profile = { $name } profile\n    .title = Edit { missing-field }\nhello = Hello",
+            ),
+            ("de-DE", "profil = Profil"),
+        ])
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let query = i18n::Query::new("profile").with_arg("name", "Ana");
+    assert!(locales.query(&lang, &query).is_err());
+    assert_eq!(ERRORS.swap(0, Ordering::SeqCst), 1);
+    // the attribute references a missing message, but is never formatted
+    assert_eq!(
+        locales.try_query_value(&lang, &query).unwrap(),
+        "Ana profile"
+    );
+    assert_eq!(locales.query_value(&lang, &query), "Ana profile");
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 0);
+
+    let err = locales
+        .try_query_value(&lang, &i18n::Query::new("profile"))
+        .unwrap_err();
+    assert_eq!(err.message_id, "profile");
+    assert_eq!(err.len(), 1);
+    assert_eq!(
+        locales.query_value(&lang, &i18n::Query::new("missing")),
+        "missing"
+    );
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 2);
+
+    let query = i18n::Query::new("hello").with_fallback(true);
+    let err = locales
+        .try_query_value(&langid!("de-DE"), &i18n::Query::new("hello"))
+        .unwrap_err();
+    assert!(!err.fallback_attempted);
+    assert_eq!(locales.query_value(&langid!("de-DE"), &query), "Hello");
+}
+
+#[test]
+fn test_if_message_ids_are_listed_per_locale() {
+    let locales = i18n::LocalesBuilder::new()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+
+    let ids = locales.message_ids(&langid!("en-US")).unwrap();
+    assert_eq!(
+        ids,
+        ["welcome-back", "login-btn", "foo-a", "foo-b", "foo-c"]
+    );
+    let ids = locales.message_ids(&langid!("hr-hr")).unwrap();
+    assert_eq!(ids, ["login-btn", "foo-a", "foo-b", "foo-c"]);
+    assert!(locales.message_ids(&langid!("de-DE")).is_none());
+
+    let hr = locales.locale(&langid!("hr-hr")).unwrap();
+    let login = hr.messages().find(|msg| msg.id == "login-btn").unwrap();
+    assert_eq!(
+        login.attrs,
+        ["idle", "progress", "finished-ok", "finished-err"]
+    );
+
+    // a message defined by several resources is listed once
+    let locale = Locale::new(
+        langid!("en-US"),
+        vec![
+            FluentResource::try_new("a = A\nb = B".to_string()).unwrap(),
+            FluentResource::try_new("b = Other B\n    .title = B\nc = C".to_string()).unwrap(),
+        ],
+        ResourceConflict::Override,
+    )
+    .unwrap();
+    assert_eq!(locale.message_ids().collect::<Vec<_>>(), ["a", "b", "c"]);
+    let b = locale.messages().find(|msg| msg.id == "b").unwrap();
+    assert_eq!(b.attrs, ["title"]);
+}
+
+#[test]
+fn test_if_message_lookups_apply_the_fallback_without_errors() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+    let locales = i18n::Locales::builder()
+        .on_error(|errs| _ = ERRORS.fetch_add(errs.len(), Ordering::SeqCst))
+        .build_from_strs([
+            ("en-US", "hello = Hello\nbye = Bye\n    .title = Goodbye"),
+            ("de-DE", "hello = Hallo"),
+        ])
+        .unwrap();
+    let lang = langid!("de-DE");
+
+    assert!(locales.has_message(&lang, "hello"));
+    assert!(!locales.has_message(&lang, "missing"));
+    // only defined in the fallback language
+    assert!(locales.has_message(&lang, "bye"));
+    assert!(!locales.defines_message(&lang, "bye"));
+    assert!(locales.has_message(&langid!("fr-FR"), "bye"));
+
+    let locale = locales.locale(&langid!("en-US")).unwrap();
+    assert!(locale.has_message("bye"));
+    assert!(!locale.has_message("missing"));
+    assert!(locale.has_attribute("bye", "title"));
+    assert!(!locale.has_attribute("bye", "missing"));
+    assert!(!locale.has_attribute("hello", "title"));
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_if_dotted_ids_query_attributes() {
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+    let lang = langid!("en-US");
+
+    let msg = locales
+        .query(&lang, &i18n::Query::new("login-btn.aria-label"))
+        .unwrap();
+    assert_eq!(msg.id, "login-btn.aria-label");
+    assert_eq!(msg.value, "A login button");
+    assert!(msg.attrs.is_empty());
+    // the main arguments are used for the attribute
+    let query = i18n::Query::attr("login-btn", "attr-arg").with_arg("text", "abc");
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "This is an attribute argument with arbitrary text: abc"
+    );
+    assert_eq!(
+        locales.query_value(&lang, &query),
+        "This is an attribute argument with arbitrary text: abc"
+    );
+
+    let err = locales
+        .query(&lang, &i18n::Query::new("login-btn.missing"))
+        .unwrap_err();
+    assert_eq!(
+        err.errors.attrs["missing"],
+        [i18n::FluentError::ResolverError(
+            i18n::ResolverError::Reference(i18n::ReferenceKind::Message {
+                id: "login-btn".to_string(),
+                attribute: Some("missing".to_string()),
+            })
+        )]
+    );
+
+    let err = locales
+        .query(&lang, &i18n::Query::new("missing.title"))
+        .unwrap_err();
+    assert_eq!(
+        err.errors.value,
+        [i18n::FluentError::ResolverError(
+            i18n::ResolverError::Reference(i18n::ReferenceKind::Message {
+                id: "missing".to_string(),
+                attribute: None,
+            })
+        )]
+    );
+
+    // a missing attribute falls back like a missing message
+    let query = i18n::Query::new("login-btn.aria-label").with_fallback(true);
+    let msg = locales.query(&langid!("hr-hr"), &query).unwrap();
+    assert_eq!(msg.value, "A login button");
+    assert_eq!(msg.resolved_lang, lang);
+}
+
+#[test]
+fn test_if_the_fallback_language_can_be_changed_while_querying() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let locales = i18n::Locales::builder()
+        .build_from_strs([("en-US", "foo = English")])
+        .unwrap();
+    let lang = langid!("de-DE");
+    let changed = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| loop {
+                // every query resolves through either the old or the new fallback
+                let done = changed.load(Ordering::SeqCst);
+                let msg = locales.query(&lang, &i18n::Query::new("foo")).unwrap();
+                let value = locales.format_value(&lang, "foo", None).unwrap();
+                if done {
+                    assert_eq!(msg.value, "Español");
+                    assert_eq!(value, "Español");
+                    break;
+                }
+                assert!(msg.value == "English" || msg.value == "Español");
+                assert!(value == "English" || value == "Español");
+            });
+        }
+        scope.spawn(|| {
+            locales
+                .add_locale(
+                    "es",
+                    vec![FluentResource::try_new("foo = Español".to_string()).unwrap()],
+                )
+                .unwrap();
+            locales.set_fallback_lang(langid!("es")).unwrap();
+            // the previous fallback language is an ordinary locale from then on
+            assert!(locales.remove_locale(&langid!("en-US")).is_some());
+            changed.store(true, Ordering::SeqCst);
+        });
+    });
+
+    assert_eq!(locales.fallback_lang(), langid!("es"));
+    assert!(locales.remove_locale(&langid!("es")).is_none());
+}
+
+#[test]
+fn test_if_locales_can_be_added_while_querying() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let locales = i18n::Locales::builder()
+        .build_from_strs([("en-US", "foo = Foo")])
+        .unwrap();
+    let lang = langid!("hr-HR");
+    let added = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| loop {
+                // every query sees either the old or the new locales, never a mix
+                let done = added.load(Ordering::SeqCst);
+                let msg = locales
+                    .query(&lang, &i18n::Query::new("foo").with_fallback(true))
+                    .unwrap();
+                if done {
+                    assert_eq!(msg.value, "Fu");
+                    break;
+                }
+                assert!(msg.value == "Foo" || msg.value == "Fu");
+            });
+        }
+        scope.spawn(|| {
+            locales
+                .add_locale(
+                    "hr-HR",
+                    vec![FluentResource::try_new("foo = Fu".to_string()).unwrap()],
+                )
+                .unwrap();
+            added.store(true, Ordering::SeqCst);
+        });
+    });
+
+    // the fallback language cannot be removed
+    assert!(locales.remove_locale(&langid!("en-US")).is_none());
+    let removed = locales.remove_locale(&lang).unwrap();
+    assert!(removed.has_message("foo"));
+    assert!(locales.locale(&lang).is_none());
+    assert!(locales.remove_locale(&lang).is_none());
+}
+
+#[test]
+fn test_if_pseudo_locales_transform_only_the_text() {
+    let locales = i18n::Locales::builder()
+        .use_isolating(false)
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+    let base = langid!("en-US");
+    locales
+        .add_pseudo_locale(&base, "en-XA", i18n::PseudoStyle::Accented)
+        .unwrap();
+    locales
+        .add_pseudo_locale(&base, "ar-XB", i18n::PseudoStyle::Bidi)
+        .unwrap();
+
+    let query = i18n::Query::new("welcome-back").with_arg("username", "John");
+    let msg = locales.query(&langid!("en-XA"), &query).unwrap();
+    assert_eq!(msg.value, "[Ŵéļçöɱé ƀåçķ, John!~~~~~]");
+    let msg = locales.query(&langid!("ar-XB"), &query).unwrap();
+    assert_eq!(
+        msg.value,
+        "\u{202E}Welcome back, \u{202C}John\u{202E}!\u{202C}"
+    );
+
+    // attributes are transformed as well, and terms without brackets
+    let value = |id| {
+        locales
+            .query(&langid!("en-XA"), &i18n::Query::new(id))
+            .unwrap()
+            .value
+    };
+    assert_eq!(value("login-btn.aria-label"), "[Å ļöĝîñ ƀûţţöñ~~~~~]");
+    assert_eq!(value("login-btn.finished-err"), "[Ƒåîļéð]");
+
+    // select expressions make the pattern multiline, which still parses
+    let locales = i18n::Locales::builder()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "items = { $count ->\n    [one] One item\n   *[other] { $count } items\n}",
+        )])
+        .unwrap();
+    locales
+        .add_pseudo_locale(&base, "en-XA", i18n::PseudoStyle::Accented)
+        .unwrap();
+    let query = i18n::Query::new("items").with_arg("count", 3);
+    let msg = locales.query(&langid!("en-XA"), &query).unwrap();
+    assert_eq!(msg.value, "[3 îţéɱš~~~~~]");
+    assert!(locales
+        .add_pseudo_locale(&langid!("de-DE"), "de-XA", i18n::PseudoStyle::Accented)
+        .is_err());
+}
+
+#[test]
+fn test_if_misses_and_fallback_hits_are_collected() {
+    let locales = i18n::Locales::builder()
+        .build_from_strs([
+            ("en-US", "hello = Hello\n    .title = Greeting\nbye = Bye"),
+            ("hr-HR", "hello = Bok"),
+        ])
+        .unwrap();
+    let misses = locales.collect_misses();
+    let hr = langid!("hr-HR");
+
+    // a message defined in the requested language is not a miss
+    let mut msg = locales
+        .query(&hr, &i18n::Query::new("hello").with_fallback(true))
+        .unwrap();
+    locales
+        .query(&hr, &i18n::Query::new("bye").with_fallback(true))
+        .unwrap();
+    locales
+        .query(&hr, &i18n::Query::new("missing").with_fallback(true))
+        .unwrap_err();
+    locales
+        .query(&langid!("de-DE"), &i18n::Query::new("missing"))
+        .unwrap_err();
+
+    // the reporter is carried along by the attributes
+    let title = msg.attrs.get_mut("title").unwrap();
+    title.attr_id = "subtitle".to_string();
+    title.query(None, true).unwrap_err();
+
+    let summary =
+        |id: &str, attr: Option<&str>, misses: u64, fallback_hits: u64| i18n::MissSummary {
+            lang: langid!("hr-HR"),
+            id: id.to_string(),
+            attr: attr.map(ToString::to_string),
+            misses,
+            fallback_hits,
+        };
+    let mut missing_de = summary("missing", None, 1, 0);
+    missing_de.lang = langid!("de-DE");
+    assert_eq!(
+        misses.summary(),
+        [
+            missing_de,
+            summary("bye", None, 0, 1),
+            summary("hello", Some("subtitle"), 1, 0),
+            summary("hello", Some("title"), 0, 1),
+            summary("missing", None, 1, 0),
+        ]
+    );
+
+    misses.reset();
+    locales.clear_miss_reporter();
+    locales
+        .query(&hr, &i18n::Query::new("missing"))
+        .unwrap_err();
+    assert!(misses.summary().is_empty());
+}
+
+#[test]
+fn test_if_coverage_is_reported_against_the_fallback() {
+    let locales = i18n::Locales::builder()
+        .build_from_dir("./tests/i18n")
+        .unwrap();
+    locales
+        .add_locale(
+            "de-DE",
+            vec![FluentResource::try_new(
+                "welcome-back = Willkommen\nlogin-btn = Anmelden\nfoo-a = A\nfoo-b = B\nlogout = Abmelden"
+                    .to_string(),
+            )
+            .unwrap()],
+        )
+        .unwrap();
+
+    let report = locales.coverage();
+    assert_eq!(report.reference, langid!("en-US"));
+    assert!(!report.is_complete());
+    let [de, hr] = report.locales.as_slice() else {
+        panic!("expected two locales, got {report:?}");
+    };
+    assert_eq!(de.lang, langid!("de-DE"));
+    assert_eq!(de.missing, ["foo-c"]);
+    assert_eq!(de.extra, ["logout"]);
+    assert_eq!(de.percentage, 80.0);
+    assert_eq!(hr.missing, ["welcome-back"]);
+    assert!(hr.extra.is_empty());
+
+    assert_eq!(
+        report.to_string(),
+        "coverage against en-US:\nde-DE: 80.0% (1 missing, 1 extra)\n  - key foo-c\n  + key logout\nhr-HR: 80.0% (1 missing, 0 extra)\n  - key welcome-back\n"
+    );
+    assert_eq!(
+        serde_json::to_value(&report).unwrap()["locales"][0]["missing"],
+        serde_json::json!(["foo-c"])
+    );
+}
+
+#[test]
+fn test_if_locales_are_built_from_ftl_maps() {
+    use std::collections::HashMap;
+
+    let sources = |entries: &[(&str, &str)]| {
+        entries
+            .iter()
+            .map(|(lang, source)| (lang.to_string(), source.to_string()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    let locales = i18n::Locales::from_ftl_map(
+        sources(&[("en-US", "hello = Hello"), ("hr-HR", "hello = Bok")]),
+        langid!("en-US"),
+        None,
+    )
+    .unwrap();
+    let msg = locales
+        .query(&langid!("hr-HR"), &i18n::Query::new("hello"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok");
+
+    let Err(i18n::LoadError::Parser(errs)) = i18n::Locales::from_ftl_map(
+        sources(&[("en-US", "hello = Hello"), ("not a langid", "hello = Bok")]),
+        langid!("en-US"),
+        None,
+    ) else {
+        panic!("expected an invalid langid");
+    };
+    assert!(matches!(
+        errs.as_slice(),
+        [i18n::ParserError::InvalidLangid { langid }] if langid == "not a langid"
+    ));
+
+    let Err(i18n::LoadError::Parser(errs)) = i18n::Locales::from_ftl_map(
+        sources(&[("en-US", "hello = Hello"), ("hr-HR", "hello = { $")]),
+        langid!("en-US"),
+        None,
+    ) else {
+        panic!("expected an invalid source");
+    };
+    assert!(matches!(
+        errs.as_slice(),
+        [i18n::ParserError::ParserError { langid, .. }] if *langid == langid!("hr-HR")
+    ));
+}
+
+#[test]
+fn test_if_the_missing_value_is_configurable() {
+    use i18n::{FluentError, Locales, MissingValuePolicy, Query, ResolverError};
+
+    let lang = langid!("en-US");
+    let build = |policy| {
+        Locales::builder()
+            .missing_value(policy)
+            .build_from_dir("./tests/i18n")
+            .unwrap()
+    };
+    for (policy, expected) in [
+        (MissingValuePolicy::AngleBrackets, "<login-btn>"),
+        (MissingValuePolicy::Empty, ""),
+        (MissingValuePolicy::Id, "login-btn"),
+    ] {
+        let locales = build(policy);
+        let msg = locales.query(&lang, &Query::new("login-btn")).unwrap();
+        assert_eq!(msg.value, expected, "{policy:?}");
+        assert!(msg.attrs.contains_key("aria-label"));
+        let value = locales.format_value(&lang, "login-btn", None).unwrap();
+        assert_eq!(value, expected, "{policy:?}");
+        let msg = locales.placeholder_message(&lang, "login-btn");
+        assert_eq!(msg.value, expected, "{policy:?}");
+    }
+
+    let locales = build(MissingValuePolicy::Error);
+    let is_no_value = |err: &FluentError| matches!(err, FluentError::ResolverError(ResolverError::NoValue(id)) if id == "login-btn");
+    let errs = locales.query(&lang, &Query::new("login-btn")).unwrap_err();
+    assert!(errs.iter().any(is_no_value));
+    let errs = locales.format_value(&lang, "login-btn", None).unwrap_err();
+    assert!(errs.iter().any(is_no_value));
+    // `tr!` always produces a message
+    assert_eq!(
+        locales.placeholder_message(&lang, "login-btn").value,
+        "login-btn"
+    );
+
+    // messages with a value are unaffected
+    let msg = locales.query(&lang, &Query::new("foo-a")).unwrap();
+    assert_eq!(msg.value, "English A");
+    assert_eq!("error".parse(), Ok(MissingValuePolicy::Error));
+    assert!("brackets".parse::<MissingValuePolicy>().is_err());
+}
@@ -3,7 +3,11 @@ use std::collections::HashMap;
 #[test]
 fn test_if_type_works() {
     use i18n::LocalizedDisplay;
-    i18n::load!("./tests/i18n", fallback_lang = "en-US");
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
 
     enum Foo {
         A,
@@ -28,6 +32,12 @@ fn test_if_type_works() {
 
     let res = a.localize(&i18n::langid!("hr-hr"));
     assert_eq!(res.value, "Croatian A");
+
+    let res = Foo::B.localize(&i18n::langid!("en-US"));
+    assert_eq!(res.value, "English B");
+
+    let res = Foo::C.localize(&i18n::langid!("hr-hr"));
+    assert_eq!(res.value, "Croatian C");
 }
 
 #[test]
@@ -60,7 +70,11 @@ fn test_if_failures_are_reported() {
 
 #[test]
 fn test_if_arguments_work() {
-    i18n::load!("./tests/i18n", fallback_lang = "en-US");
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
 
     let lang = i18n::langid!("en-US");
     let query = i18n::Query::new("welcome-back").with_arg("username", "John");
@@ -71,13 +85,18 @@ fn test_if_arguments_work() {
             id: "welcome-back".to_string(),
             value: "Welcome back, \u{2068}John\u{2069}!".to_string(),
             attrs: Default::default(),
+            resolved_lang: i18n::langid!("en-US"),
         }
     )
 }
 
 #[test]
 fn test_if_attributes_work() {
-    i18n::load!("./tests/i18n", fallback_lang = "en-US");
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
 
     let lang = i18n::langid!("en-US");
     let query =
@@ -96,13 +115,14 @@ fn test_if_attributes_work() {
             id: "login-btn".to_string(),
             value: "<login-btn>".to_string(),
             attrs: HashMap::from_iter([
-                ("idle", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "idle".into(), value: Some("Login".into()), bundle: bundle.clone() }),
-                ("progress", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "progress".into(), value: Some("Logging in...".into()), bundle: bundle.clone() }),
-                ("finished-ok", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "finished-ok".into(), value: Some("Logged in".into()), bundle: bundle.clone() }),
-                ("finished-err", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "finished-err".into(), value: Some("Failed".into()), bundle: bundle.clone() }),
-                ("aria-label", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "aria-label".into(), value: Some("A login button".into()), bundle: bundle.clone() }),
-                ("attr-arg", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "attr-arg".into(), value: Some("This is an attribute argument with arbitrary text: \u{2068}this is arbitrary text\u{2069}".into()), bundle: bundle.clone() }),
+                ("idle", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "idle".into(), value: Some("Login".into()), bundle: bundle.clone(), is_fallback: false, args: Default::default(), miss_reporter: None }),
+                ("progress", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "progress".into(), value: Some("Logging in...".into()), bundle: bundle.clone(), is_fallback: false, args: Default::default(), miss_reporter: None }),
+                ("finished-ok", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "finished-ok".into(), value: Some("Logged in".into()), bundle: bundle.clone(), is_fallback: false, args: Default::default(), miss_reporter: None }),
+                ("finished-err", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "finished-err".into(), value: Some("Failed".into()), bundle: bundle.clone(), is_fallback: false, args: Default::default(), miss_reporter: None }),
+                ("aria-label", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "aria-label".into(), value: Some("A login button".into()), bundle: bundle.clone(), is_fallback: false, args: Default::default(), miss_reporter: None }),
+                ("attr-arg", i18n::AttrCache { entry_id: "login-btn".into(), attr_id: "attr-arg".into(), value: Some("This is an attribute argument with arbitrary text: \u{2068}this is arbitrary text\u{2069}".into()), bundle: bundle.clone(), is_fallback: false, args: Default::default(), miss_reporter: None }),
             ].map(|(attr, value)| (attr.to_string(), value))),
+            resolved_lang: i18n::langid!("en-US"),
         }
     )
 }
@@ -111,6 +131,7 @@ fn test_if_attributes_work() {
 fn test_if_tr_macro_with_arguments_works() {
     i18n::load!(
         "./tests/i18n",
+        allow_missing = ["hr-hr"],
         fallback_lang = "en-US",
         on_error = |errs| {
             for err in errs {
@@ -127,13 +148,18 @@ fn test_if_tr_macro_with_arguments_works() {
             id: "welcome-back".to_string(),
             value: "Welcome back, \u{2068}John\u{2069}!".to_string(),
             attrs: Default::default(),
+            resolved_lang: i18n::langid!("en-US"),
         }
     )
 }
 
 #[test]
 fn test_if_lazily_queried_attr_works() {
-    i18n::load!("./tests/i18n", fallback_lang = "en-US");
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
 
     let lang = i18n::langid!("en-US");
     let query = i18n::Query::new("login-btn");
@@ -158,7 +184,10 @@ fn test_if_lazily_queried_attr_works() {
                             entry_id: "login-btn".into(),
                             attr_id: "idle".into(),
                             value: Some("Login".into()),
-                            bundle: bundle.clone()
+                            bundle: bundle.clone(),
+                            is_fallback: false,
+                            args: Default::default(),
+                            miss_reporter: None,
                         }
                     ),
                     (
@@ -167,7 +196,10 @@ fn test_if_lazily_queried_attr_works() {
                             entry_id: "login-btn".into(),
                             attr_id: "progress".into(),
                             value: Some("Logging in...".into()),
-                            bundle: bundle.clone()
+                            bundle: bundle.clone(),
+                            is_fallback: false,
+                            args: Default::default(),
+                            miss_reporter: None,
                         }
                     ),
                     (
@@ -176,7 +208,10 @@ fn test_if_lazily_queried_attr_works() {
                             entry_id: "login-btn".into(),
                             attr_id: "finished-ok".into(),
                             value: Some("Logged in".into()),
-                            bundle: bundle.clone()
+                            bundle: bundle.clone(),
+                            is_fallback: false,
+                            args: Default::default(),
+                            miss_reporter: None,
                         }
                     ),
                     (
@@ -185,7 +220,10 @@ fn test_if_lazily_queried_attr_works() {
                             entry_id: "login-btn".into(),
                             attr_id: "finished-err".into(),
                             value: Some("Failed".into()),
-                            bundle: bundle.clone()
+                            bundle: bundle.clone(),
+                            is_fallback: false,
+                            args: Default::default(),
+                            miss_reporter: None,
                         }
                     ),
                     (
@@ -194,7 +232,10 @@ fn test_if_lazily_queried_attr_works() {
                             entry_id: "login-btn".into(),
                             attr_id: "aria-label".into(),
                             value: Some("A login button".into()),
-                            bundle: bundle.clone()
+                            bundle: bundle.clone(),
+                            is_fallback: false,
+                            args: Default::default(),
+                            miss_reporter: None,
                         }
                     ),
                     (
@@ -203,12 +244,16 @@ fn test_if_lazily_queried_attr_works() {
                             entry_id: "login-btn".into(),
                             attr_id: "attr-arg".into(),
                             value: None,
-                            bundle: bundle.clone()
+                            bundle: bundle.clone(),
+                            is_fallback: false,
+                            args: Default::default(),
+                            miss_reporter: None,
                         }
                     ),
                 ]
                 .map(|(attr, value)| (attr.to_string(), value))
             ),
+            resolved_lang: i18n::langid!("en-US"),
         }
     );
 
@@ -230,7 +275,11 @@ fn test_if_lazily_queried_attr_works() {
 
 #[test]
 fn test_if_attr_macro_works() {
-    i18n::load!("./tests/i18n", fallback_lang = "en-US");
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
 
     let lang = i18n::langid!("en-US");
     let mut msg = i18n::tr!(lang, "login-btn");
@@ -240,3 +289,1146 @@ fn test_if_attr_macro_works() {
         "This is an attribute argument with arbitrary text: \u{2068}this is arbitrary text\u{2069}"
     );
 }
+
+#[test]
+#[allow(deprecated)] // the skipped entries are reported through the `deprecated` lint
+fn test_if_hidden_dirs_are_skipped() {
+    i18n::load!("./tests/i18n_scan", fallback_lang = "en-US");
+
+    assert_eq!(
+        LOCALES.langs(),
+        vec![i18n::Lang::from(i18n::langid!("en-US"))]
+    );
+    assert_eq!(
+        i18n::langs!("./tests/i18n_scan"),
+        [i18n::Lang {
+            id: "en-US".to_string(),
+            name: "English",
+            flag: "🇺🇸",
+            dir: "ltr"
+        }]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+#[allow(deprecated)] // the skipped entries are reported through the `deprecated` lint
+fn test_if_symlinks_are_followed_on_request() {
+    // the followed `hr-hr` is not a translation of `en-US`, so only shared files are compared
+    i18n::load!(
+        "./tests/i18n_scan",
+        fallback_lang = "en-US",
+        follow_symlinks = true,
+        check_keys_per_file = true
+    );
+
+    let msg = i18n::tr!(i18n::langid!("hr-hr"), "foo-a");
+    assert_eq!(msg.value, "Croatian A");
+    assert_eq!(
+        i18n::langs!("./tests/i18n_scan", follow_symlinks = true).map(|lang| lang.id),
+        ["en-US".to_string(), "hr-HR".to_string()]
+    );
+}
+
+#[test]
+fn test_if_missing_attributes_fall_back() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+
+    let lang = i18n::langid!("hr-hr");
+    let query = i18n::Query::new("login-btn").with_fallback(true);
+    let msg = LOCALES.query(&lang, &query).unwrap();
+
+    let idle = msg.attrs.get("idle").expect("should exist");
+    assert_eq!(idle.value.as_deref(), Some("Prijava"));
+    assert!(!idle.is_fallback);
+
+    let aria_label = msg.attrs.get("aria-label").expect("should exist");
+    assert_eq!(aria_label.value.as_deref(), Some("A login button"));
+    assert!(aria_label.is_fallback);
+
+    // lazily resolved attributes are resolved against the fallback bundle
+    let mut msg = msg;
+    let attr = msg.attrs.get_mut("attr-arg").expect("should exist");
+    assert!(attr.is_fallback);
+    let mut args = i18n::FluentArgs::default();
+    args.set("text", "text");
+    assert_eq!(
+        attr.query(Some(&args), false),
+        Ok("This is an attribute argument with arbitrary text: \u{2068}text\u{2069}".into())
+    );
+
+    // without fallback, the attribute is simply missing
+    let msg = LOCALES
+        .query(&lang, &i18n::Query::new("login-btn"))
+        .unwrap();
+    assert!(!msg.attrs.contains_key("aria-label"));
+}
+
+#[test]
+fn test_if_missing_messages_fall_back() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+
+    let lang = i18n::langid!("hr-hr");
+    let query = i18n::Query::new("welcome-back")
+        .with_arg("username", "John")
+        .with_fallback(true);
+    let msg = LOCALES.query(&lang, &query).unwrap();
+    assert_eq!(msg.value, "Welcome back, \u{2068}John\u{2069}!");
+
+    let query = i18n::Query::new("welcome-back").with_arg("username", "John");
+    assert!(LOCALES.query(&lang, &query).is_err());
+}
+
+#[test]
+fn test_if_query_batch_works() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US",
+        on_error = |errs| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            ERRORS.fetch_add(errs.len(), Ordering::SeqCst);
+        }
+    );
+
+    let lang = i18n::langid!("hr-hr");
+    let queries = [
+        i18n::Query::new("foo-a"),
+        i18n::Query::new("missing-a"),
+        i18n::Query::new("welcome-back")
+            .with_arg("username", "John")
+            .with_fallback(true),
+        i18n::Query::new("missing-b"),
+    ];
+    let results = LOCALES.query_batch(&lang, &queries);
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap().value, "Croatian A");
+    assert!(results[1].is_err());
+    assert_eq!(
+        results[2].as_ref().unwrap().value,
+        "Welcome back, \u{2068}John\u{2069}!"
+    );
+    assert!(results[3].is_err());
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    assert_eq!(ERRORS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_if_tr_batch_macro_works() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+
+    let lang = i18n::langid!("en-US");
+    let [a, b, missing] = i18n::tr_batch!(lang, ["foo-a", "foo-b", "missing"]);
+    assert_eq!(a.value, "English A");
+    assert_eq!(b.value, "English B");
+    assert_eq!(missing.value, "missing");
+}
+
+#[test]
+#[allow(deprecated)] // the normalized files are reported through the `deprecated` lint
+fn test_if_sources_are_normalized() {
+    i18n::load!("./tests/i18n_unicode", normalize = "nfc");
+
+    let lang = i18n::langid!("en-US");
+    let msg = i18n::tr!(lang, "decomposed");
+    assert_eq!(msg.value, "\u{10c}\u{107}\u{161}\u{17e} \u{c1}g\u{fa}st");
+    let msg = i18n::tr!(lang, "plain");
+    assert_eq!(msg.value, "Plain");
+    assert_eq!(msg.attrs["title"].value.as_deref(), Some("Title"));
+}
+
+#[test]
+#[allow(deprecated)] // the normalized and blank files are reported through the `deprecated` lint
+fn test_if_windows_sources_are_loaded() {
+    // both locales start with a BOM and use CRLF, and `extra.ftl` is blank in both
+    i18n::load!(
+        "./tests/i18n_windows",
+        name = WINDOWS_LOCALES,
+        check_keys = true,
+        check_keys_per_file = true,
+        check_attrs = true
+    );
+
+    let lang = i18n::langid!("hr-HR");
+    let msg = i18n::tr!(lang, "greeting", locales = WINDOWS_LOCALES);
+    assert_eq!(msg.value, "Bok");
+    assert_eq!(msg.attrs["title"].value.as_deref(), Some("Pozdrav"));
+    let msg = i18n::tr!(lang, "farewell", locales = WINDOWS_LOCALES);
+    assert_eq!(msg.value, "Doviđenja");
+
+    // the blank files are not embedded
+    let locale = WINDOWS_LOCALES.locale(&lang).unwrap();
+    assert_eq!(locale.own_resources().len(), 1);
+}
+
+#[test]
+fn test_if_message_values_can_be_cased() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+
+    let lang = i18n::langid!("en-US");
+    let msg = i18n::tr!(lang, "foo-a");
+    assert_eq!(msg.value_cased(i18n::Case::Upper, &lang), "ENGLISH A");
+    assert_eq!(msg.value_cased(i18n::Case::Lower, &lang), "english a");
+}
+
+#[test]
+fn test_if_attr_usage_is_attributed_to_the_message() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+    LOCALES.enable_usage_tracking();
+
+    let lang = i18n::langid!("en-US");
+    let mut msg = i18n::tr!(lang, "login-btn");
+    let _ = i18n::attr!(msg, "idle");
+    let _ = i18n::attr!(msg, "progress");
+
+    let usage = LOCALES.usage_snapshot();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].id, "login-btn");
+    assert_eq!(usage[0].hits, 3);
+}
+
+#[test]
+fn test_if_junk_entries_can_be_allowed() {
+    i18n::load!("./tests/i18n_junk", allow_junk = true);
+
+    let lang = i18n::langid!("en-US");
+    assert_eq!(i18n::tr!(lang, "foo").value, "Foo");
+    assert_eq!(i18n::tr!(lang, "bar").value, "Bar");
+    assert!(LOCALES.query(&lang, &i18n::Query::new("broken")).is_err());
+}
+
+#[test]
+fn test_if_only_allowed_locales_are_embedded() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        locales = ["en-US"],
+        locales_env = "I18N_TEST_UNSET_LOCALES",
+        check_excluded = true
+    );
+
+    let msg = LOCALES
+        .query(&i18n::langid!("hr-hr"), &i18n::Query::new("foo-a"))
+        .unwrap();
+    assert_eq!(msg.value, "English A");
+    assert!(LOCALES.locale(&i18n::langid!("hr-hr")).is_none());
+
+    let langs = i18n::langs!("./tests/i18n", locales = ["hr-HR"]);
+    assert_eq!(langs.len(), 1);
+    assert_eq!(langs[0].id, "hr-HR");
+}
+
+#[test]
+fn test_if_language_switcher_works_with_langs() {
+    use std::sync::{Arc, Mutex};
+
+    let switcher = i18n::LanguageSwitcher::from_langs(i18n::langs!("./tests/i18n")).unwrap();
+    assert_eq!(
+        switcher.available(),
+        [i18n::langid!("en-US"), i18n::langid!("hr-HR")]
+    );
+    assert_eq!(switcher.current(), i18n::langid!("en-US"));
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let subscription = switcher.on_change({
+        let changes = changes.clone();
+        move |lang| changes.lock().unwrap().push(lang.to_string())
+    });
+
+    // validation
+    assert_eq!(
+        switcher.set("de-DE"),
+        Err(i18n::SwitchError::Unknown {
+            lang: i18n::langid!("de-DE")
+        })
+    );
+    assert!(matches!(
+        switcher.set("not a langid!"),
+        Err(i18n::SwitchError::InvalidLangid { .. })
+    ));
+    switcher.set("hr-hr").unwrap();
+    assert_eq!(switcher.current(), i18n::langid!("hr-HR"));
+    // selecting the current language does not notify
+    switcher.set(i18n::langid!("hr-HR")).unwrap();
+
+    // cycling wraps around in a stable order
+    assert_eq!(switcher.next(), i18n::langid!("en-US"));
+    assert_eq!(switcher.next(), i18n::langid!("hr-HR"));
+    assert_eq!(switcher.prev(), i18n::langid!("en-US"));
+    assert_eq!(switcher.prev(), i18n::langid!("hr-HR"));
+
+    switcher.unsubscribe(subscription);
+    switcher.next();
+    assert_eq!(
+        *changes.lock().unwrap(),
+        ["hr-HR", "en-US", "hr-HR", "en-US", "hr-HR"]
+    );
+}
+
+#[test]
+#[allow(deprecated)] // the empty locales are reported through the `deprecated` lint
+fn test_if_empty_locales_can_be_allowed() {
+    i18n::load!(
+        "./tests/i18n_empty",
+        allow_empty = true,
+        allow_missing = ["hr-hr"]
+    );
+
+    // the comments-only locale is embedded, the one without `.ftl` files is not
+    assert!(LOCALES.locale(&i18n::langid!("hr-hr")).is_some());
+    assert!(LOCALES.locale(&i18n::langid!("de-DE")).is_none());
+    let msg = LOCALES
+        .query(
+            &i18n::langid!("hr-hr"),
+            &i18n::Query::new("foo").with_fallback(true),
+        )
+        .unwrap();
+    assert_eq!(msg.value, "Foo");
+}
+
+#[test]
+fn test_if_message_variants_are_selected() {
+    // the variants missing in `hr-hr` do not fail the key-consistency check
+    i18n::load!("./tests/i18n_variants");
+
+    let en = i18n::langid!("en-US");
+    let hr = i18n::langid!("hr-hr");
+    let pick = |name: &'static str| {
+        move |variants: &[&str]| variants.iter().position(|variant| *variant == name)
+    };
+    assert_eq!(
+        LOCALES.variants_of("signup-cta"),
+        ["variant-b", "variant-c"]
+    );
+
+    LOCALES.enable_usage_tracking();
+    let query = i18n::Query::new("signup-cta").with_variant(pick("variant-b"));
+    let msg = LOCALES.query(&en, &query).unwrap();
+    assert_eq!(msg.id, "signup-cta__variant-b");
+    assert_eq!(msg.value, "Join now");
+    assert!(msg.attrs.contains_key("title"));
+
+    // the variant is missing in the current locale
+    let msg = LOCALES.query(&hr, &query).unwrap();
+    assert_eq!(msg.id, "signup-cta");
+    assert_eq!(msg.value, "Registriraj se");
+
+    let query = i18n::Query::new("signup-cta").with_variant(pick("variant-c"));
+    assert_eq!(LOCALES.query(&hr, &query).unwrap().value, "Započni");
+
+    // the selector declines
+    let query = i18n::Query::new("signup-cta").with_variant(|_| None);
+    assert_eq!(LOCALES.query(&en, &query).unwrap().value, "Sign up");
+    // the selector is out of range
+    let query = i18n::Query::new("signup-cta").with_variant(|variants| Some(variants.len()));
+    assert_eq!(LOCALES.query(&en, &query).unwrap().value, "Sign up");
+
+    let usage = LOCALES
+        .usage_snapshot()
+        .into_iter()
+        .map(|entry| (entry.lang.to_string(), entry.id, entry.hits))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        usage,
+        [
+            // variants are attributed to the message that was queried
+            ("en-US".to_string(), "signup-cta".to_string(), 3),
+            ("hr-HR".to_string(), "signup-cta".to_string(), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_if_distinct_arguments_are_accepted() {
+    // the counterparts of the duplicate arguments rejected in the `compile_fail` doctests
+    i18n::load!("./tests/i18n", allow_missing = ["hr-hr"]);
+    let lang = i18n::langid!("en-US");
+
+    let msg = i18n::tr!(lang, "welcome-back", "username" = "Alex");
+    assert_eq!(msg.value, "Welcome back, \u{2068}Alex\u{2069}!");
+    let mut msg = i18n::tr!(
+        lang,
+        "login-btn",
+        attr("attr-arg", "text" = "a"),
+        attr("aria-label", "text" = "b")
+    );
+    let text = i18n::attr!(msg, "attr-arg", "text" = "a");
+    assert_eq!(
+        text,
+        "This is an attribute argument with arbitrary text: \u{2068}a\u{2069}"
+    );
+}
+
+#[test]
+#[allow(deprecated)] // the unknown message id with a default is reported through the `deprecated` lint
+fn test_if_inline_defaults_replace_missing_messages() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static DEFAULTS: AtomicUsize = AtomicUsize::new(0);
+    fn on_inline_default(lang: &i18n::LanguageIdentifier, id: &str) {
+        assert_eq!(lang, &i18n::langid!("hr-hr"));
+        assert_eq!(id, "new-feature-banner");
+        DEFAULTS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        validate_keys = true,
+        on_inline_default = on_inline_default
+    );
+    let lang = i18n::langid!("hr-hr");
+
+    // present key, the default is ignored
+    let msg = i18n::tr!(lang, "foo-a", default = "Default");
+    assert_eq!(msg.value, "Croatian A");
+    // missing key, the default is used
+    let msg = i18n::tr!(lang, "new-feature-banner", default = "Try our new feature!");
+    assert_eq!(msg.id, "new-feature-banner");
+    assert_eq!(msg.value, "Try our new feature!");
+    assert_eq!(DEFAULTS.load(Ordering::SeqCst), 1);
+    // formatting error, the default is not used
+    let msg = i18n::tr!(
+        i18n::langid!("en-US"),
+        "welcome-back",
+        default = "Welcome back!"
+    );
+    assert_eq!(msg.value, "welcome-back");
+    assert_eq!(DEFAULTS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_if_typed_handles_work() {
+    use i18n::MessageKey;
+    i18n::load!("./tests/i18n", allow_missing = ["hr-hr"], name = TYPED_LOCALES, typed = mod messages);
+    use messages::*;
+    let lang = i18n::langid!("en-US");
+
+    let username = String::from("Alice");
+    let msg = i18n::tr_typed!(
+        lang,
+        WelcomeBack {
+            username: username.as_str()
+        },
+        locales = TYPED_LOCALES
+    );
+    assert_eq!(msg.id, "welcome-back");
+    assert_eq!(msg.value, "Welcome back, \u{2068}Alice\u{2069}!");
+
+    let mut msg = i18n::tr_typed!(i18n::langid!("hr-hr"), LoginBtn, locales = TYPED_LOCALES);
+    assert_eq!(i18n::attr!(msg, "idle", locales = TYPED_LOCALES), "Prijava");
+
+    let msg = TYPED_LOCALES.query_typed::<FooB>(&lang, FooB).unwrap();
+    assert_eq!(msg.value, "English B");
+
+    assert_eq!(WelcomeBack::ID, "welcome-back");
+    assert_eq!(WelcomeBack::ARGS, ["username"]);
+    assert!(LoginBtn::ATTRS.contains(&"attr-arg"));
+    assert!(LoginBtn::ARGS.is_empty());
+}
+
+#[test]
+fn test_if_the_resolved_language_is_reported() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+
+    let hr = i18n::langid!("hr-hr");
+    let msg = i18n::tr!(hr, "foo-a");
+    assert_eq!(msg.resolved_lang, hr);
+
+    // a missing locale and a message missing from the locale both fall back
+    let msg = i18n::tr!(i18n::langid!("de-DE"), "welcome-back", "username" = "John");
+    assert_eq!(msg.resolved_lang, i18n::langid!("en-US"));
+    let query = i18n::Query::new("welcome-back")
+        .with_arg("username", "John")
+        .with_fallback(true);
+    let msg = LOCALES.query(&hr, &query).unwrap();
+    assert_eq!(msg.resolved_lang, i18n::langid!("en-US"));
+    assert_ne!(msg.resolved_lang, hr);
+
+    // the ID substituted on error is not resolved from any locale
+    let msg = i18n::tr!(hr, "missing-key");
+    assert_eq!(msg.resolved_lang, i18n::LanguageIdentifier::default());
+}
+
+#[test]
+fn test_if_load_macro_can_disable_isolation() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US",
+        use_isolating = false
+    );
+    i18n::load!(
+        "./tests/i18n",
+        name = ISOLATED_LOCALES,
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US"
+    );
+
+    let lang = i18n::langid!("en-US");
+    let msg = i18n::tr!(lang, "welcome-back", "username" = "Alice");
+    assert_eq!(msg.value, "Welcome back, Alice!");
+    assert!(!msg.value.contains('\u{2068}'));
+    let msg = i18n::tr!(
+        lang,
+        "welcome-back",
+        "username" = "Alice",
+        locales = ISOLATED_LOCALES
+    );
+    assert!(msg.value.contains('\u{2068}'));
+}
+
+#[test]
+fn test_if_tr_macro_accepts_dotted_ids() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        fallback_lang = "en-US",
+        validate_keys = true
+    );
+
+    let lang = i18n::langid!("en-US");
+    let msg = i18n::tr!(lang, "login-btn.aria-label");
+    assert_eq!(msg.value, "A login button");
+    let msg = i18n::tr!(lang, "login-btn.missing");
+    assert_eq!(msg.value, "login-btn.missing");
+}
+
+#[test]
+fn test_if_load_macro_configures_the_missing_value() {
+    let lang = i18n::langid!("en-US");
+    {
+        i18n::load!(
+            "./tests/i18n",
+            allow_missing = ["hr-hr"],
+            fallback_lang = "en-US"
+        );
+        assert_eq!(i18n::tr!(lang, "login-btn").value, "<login-btn>");
+    }
+    {
+        i18n::load!(
+            "./tests/i18n",
+            allow_missing = ["hr-hr"],
+            fallback_lang = "en-US",
+            missing_value = "empty"
+        );
+        assert_eq!(i18n::tr!(lang, "login-btn").value, "");
+    }
+    {
+        // the query fails, so `tr!` falls back to the ID along with the attributes
+        i18n::load!(
+            "./tests/i18n",
+            allow_missing = ["hr-hr"],
+            fallback_lang = "en-US",
+            missing_value = "error"
+        );
+        assert_eq!(i18n::tr!(lang, "login-btn").value, "login-btn");
+    }
+}
+
+#[test]
+fn test_if_query_args_are_derived_from_structs() {
+    #[derive(i18n::IntoQueryArgs)]
+    struct Account<'a> {
+        #[arg(rename = "username")]
+        name: &'a str,
+        item_count: u32,
+        plan: Option<String>,
+        discount: Option<f64>,
+    }
+
+    let locales = i18n::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_strs([(
+            "en-US",
+            "summary = { $username } has { $item-count } items on { $plan } ({ $discount })",
+        )])
+        .unwrap();
+    let lang = i18n::langid!("en-US");
+
+    let account = Account {
+        name: "Alice",
+        item_count: 3,
+        plan: Some("Pro".to_string()),
+        discount: None,
+    };
+    let args = i18n::IntoQueryArgs::into_args(&account);
+    assert_eq!(args.iter().count(), 3);
+
+    let errs = locales
+        .query(
+            &lang,
+            &i18n::Query::new("summary").with_arg_struct(&account),
+        )
+        .unwrap_err();
+    assert_eq!(errs.len(), 1, "the omitted discount is missing");
+
+    let account = Account {
+        discount: Some(0.5),
+        ..account
+    };
+    let query = i18n::Query::new("summary").with_arg_struct(&account);
+    assert_eq!(
+        locales.query(&lang, &query).unwrap().value,
+        "Alice has 3 items on Pro (0.5)"
+    );
+}
+
+#[test]
+fn test_if_load_macro_shares_identical_files() {
+    use std::sync::Arc;
+    i18n::load!("./tests/i18n_shared", fallback_lang = "en-US");
+
+    let en = LOCALES.locale(&i18n::langid!("en-US")).unwrap();
+    let gb = LOCALES.locale(&i18n::langid!("en-GB")).unwrap();
+    assert!(std::sync::Arc::ptr_eq(
+        &en.resources()[0],
+        &gb.resources()[0]
+    ));
+    assert!(!Arc::ptr_eq(&en.resources()[1], &gb.resources()[1]));
+
+    let lang = i18n::langid!("en-GB");
+    assert_eq!(i18n::tr!(lang, "hello").value, "Hello");
+    assert_eq!(i18n::tr!(lang, "colour").value, "Colour");
+}
+
+#[test]
+fn test_if_key_constants_are_generated() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        name = KEYED_LOCALES,
+        keys_mod = my_keys
+    );
+
+    assert_eq!(my_keys::LOGIN_BTN, "login-btn");
+    assert_eq!(my_keys::WELCOME_BACK, "welcome-back");
+    let lang = i18n::langid!("hr-hr");
+    let query = i18n::Query::new(my_keys::LOGIN_BTN).with_fallback(true);
+    let msg = KEYED_LOCALES.query(&lang, &query).unwrap();
+    assert_eq!(msg.attrs["idle"].value.as_deref(), Some("Prijava"));
+    assert_eq!(
+        i18n::tr!(lang, my_keys::FOO_A, locales = KEYED_LOCALES).value,
+        i18n::tr!(lang, "foo-a", locales = KEYED_LOCALES).value
+    );
+}
+
+#[test]
+fn test_if_key_enum_covers_every_message() {
+    i18n::load!(
+        "./tests/i18n_nested",
+        name = ENUM_LOCALES,
+        keys_enum = TranslationKey
+    );
+
+    assert_eq!(
+        TranslationKey::ALL,
+        [
+            TranslationKey::Hello,
+            TranslationKey::SettingsTitle,
+            TranslationKey::WelcomeSubject
+        ]
+    );
+    assert_eq!(TranslationKey::SettingsTitle.id(), "settings-title");
+    assert!(TranslationKey::Hello.attrs().is_empty());
+    for (lang, _) in ENUM_LOCALES.iter() {
+        for key in TranslationKey::ALL {
+            let query = i18n::Query::from(key);
+            assert!(
+                ENUM_LOCALES.query(&lang, &query).is_ok(),
+                "{key:?} in {lang}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_if_key_enum_lists_attributes() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        name = ATTR_LOCALES,
+        keys_enum = TranslationKey
+    );
+
+    assert_eq!(
+        TranslationKey::LoginBtn.attrs(),
+        [
+            "aria-label",
+            "attr-arg",
+            "finished-err",
+            "finished-ok",
+            "idle",
+            "progress"
+        ]
+    );
+    let msg = ATTR_LOCALES
+        .query(&i18n::langid!("en-US"), &TranslationKey::LoginBtn.into())
+        .unwrap();
+    assert_eq!(msg.attrs.len(), TranslationKey::LoginBtn.attrs().len());
+}
+
+#[test]
+fn test_if_unknown_key_constants_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/unknown_key_constant.rs");
+}
+
+#[test]
+fn test_if_args_are_checked_across_locales() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/check_args_match.rs");
+    cases.compile_fail("tests/ui/check_args_mismatch.rs");
+}
+
+#[test]
+fn test_if_arg_check_can_be_disabled() {
+    i18n::load!(
+        "./tests/i18n_args_mismatch",
+        name = MISMATCHED_LOCALES,
+        check_args = false
+    );
+
+    let msg = i18n::tr!(
+        i18n::langid!("en-US"),
+        "welcome",
+        locales = MISMATCHED_LOCALES,
+        "username" = "Ana"
+    );
+    assert_eq!(msg.value, "Welcome, \u{2068}Ana\u{2069}!");
+}
+
+#[test]
+fn test_if_the_visibility_of_the_static_can_be_restricted() {
+    trybuild::TestCases::new().compile_fail("tests/ui/private_locales.rs");
+}
+
+#[test]
+fn test_if_unknown_fallback_languages_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/unknown_fallback_lang.rs");
+}
+
+#[test]
+fn test_if_directories_without_locales_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/no_locales.rs");
+}
+
+#[test]
+fn test_if_unresolved_references_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/unresolved_refs.rs");
+}
+
+#[test]
+fn test_if_reference_check_can_be_disabled() {
+    i18n::load!(
+        "./tests/i18n_fail/refs",
+        name = DANGLING_LOCALES,
+        check_refs = false
+    );
+
+    let lang = i18n::langid!("en-US");
+    let msg = i18n::tr!(lang, "help", locales = DANGLING_LOCALES, "count" = 1);
+    assert_eq!(msg.value, "Click Open the menu for help");
+    // the dangling reference only fails at runtime
+    let result = DANGLING_LOCALES.query(&i18n::langid!("hr-HR"), &i18n::Query::new("welcome"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_if_locales_are_canonicalized() {
+    i18n::load!("./tests/i18n_lowercase_region", name = LOWERCASE_LOCALES);
+
+    // the `hr-hr` directory is embedded as `hr-HR`, instead of falling back to `en-US`
+    let msg = i18n::tr!(
+        i18n::langid!("hr-HR"),
+        "greeting",
+        locales = LOWERCASE_LOCALES
+    );
+    assert_eq!(msg.value, "Bok");
+
+    let langs = i18n::langs!("./tests/i18n_lowercase_region");
+    assert_eq!(langs.map(|lang| lang.id), ["en-US", "hr-HR"]);
+}
+
+#[test]
+fn test_if_the_build_report_lists_the_embedded_messages() {
+    i18n::load!(
+        "./tests/i18n",
+        allow_missing = ["hr-hr"],
+        name = REPORTED_LOCALES,
+        report = "target/i18n-report.json",
+        manifest = true
+    );
+
+    let report = std::fs::read_to_string("target/i18n-report.json").unwrap();
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(I18N_MANIFEST).unwrap();
+    assert_eq!(report, manifest);
+    assert_eq!(report["fallback"], "en-US");
+
+    let locales = report["locales"].as_object().unwrap();
+    assert_eq!(locales.keys().collect::<Vec<_>>(), ["en-US", "hr-HR"]);
+    for (locale, messages) in locales {
+        let locale = REPORTED_LOCALES.locale(&locale.parse().unwrap()).unwrap();
+        let mut embedded = locale
+            .messages()
+            .map(|msg| (msg.id.to_string(), msg.attrs.join(",")))
+            .collect::<Vec<_>>();
+        embedded.sort();
+        let reported = messages
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(id, msg)| {
+                let attrs = msg["attributes"].as_array().unwrap().iter();
+                let attrs = attrs.map(|attr| attr.as_str().unwrap()).collect::<Vec<_>>();
+                (id.clone(), attrs.join(","))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(reported, embedded);
+
+        // every file named by the report defines the message
+        for (id, msg) in messages.as_object().unwrap() {
+            for file in msg["files"].as_array().unwrap() {
+                let source =
+                    std::fs::read_to_string(format!("tests/i18n/{}", file.as_str().unwrap()))
+                        .unwrap();
+                assert!(source
+                    .lines()
+                    .any(|line| line.starts_with(&format!("{id} ="))));
+            }
+        }
+    }
+    assert_eq!(
+        report["locales"]["hr-HR"]["login-btn"]["files"],
+        serde_json::json!(["hr-hr/attribute-check.ftl"])
+    );
+}
+
+#[test]
+#[allow(deprecated)] // the failure to write the report is reported through the `deprecated` lint
+fn test_if_unwritable_reports_only_warn() {
+    // the parent of the report is a file, so it cannot be written
+    i18n::load!(
+        "./tests/i18n_flat",
+        name = UNREPORTED_LOCALES,
+        report = "Cargo.toml/i18n-report.json"
+    );
+
+    let msg = i18n::tr!(
+        i18n::langid!("hr-HR"),
+        "hello",
+        locales = UNREPORTED_LOCALES
+    );
+    assert_eq!(msg.value, "Bok");
+}
+
+#[test]
+fn test_if_invalid_locale_names_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/invalid_locale_name.rs");
+}
+
+#[test]
+fn test_if_missing_attributes_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/check_attrs_mismatch.rs");
+}
+
+#[test]
+#[allow(deprecated)] // the missing attributes are reported through the `deprecated` lint
+fn test_if_missing_attributes_only_warn_on_request() {
+    i18n::load!(
+        "./tests/i18n_fail/attrs",
+        name = ATTR_WARN_LOCALES,
+        check_attrs = "warn"
+    );
+
+    let query = i18n::Query::new("login-btn").with_fallback(true);
+    let msg = ATTR_WARN_LOCALES
+        .query(&i18n::langid!("hr-HR"), &query)
+        .unwrap();
+    assert!(msg.attrs["aria-label"].is_fallback);
+    assert!(!msg.attrs["title"].is_fallback);
+}
+
+#[test]
+fn test_if_allowlisted_locales_may_miss_keys() {
+    i18n::load!(
+        "./tests/i18n_lagging",
+        name = LAGGING_LOCALES,
+        allow_missing = ["hr-HR", "de-DE"]
+    );
+
+    let query = i18n::Query::new("settings").with_fallback(true);
+    let msg = LAGGING_LOCALES
+        .query(&i18n::langid!("hr-HR"), &query)
+        .unwrap();
+    assert_eq!(msg.value, "Settings");
+}
+
+#[test]
+fn test_if_locales_missing_from_the_allowlist_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/allow_missing_other_locale.rs");
+}
+
+#[test]
+#[allow(deprecated)] // the missing keys are reported through the `deprecated` lint
+fn test_if_missing_keys_only_warn_on_request() {
+    i18n::load!(
+        "./tests/i18n_lagging",
+        name = WARN_LOCALES,
+        check_keys = "warn"
+    );
+
+    assert_eq!(
+        i18n::tr!(i18n::langid!("de-DE"), "hello", locales = WARN_LOCALES).value,
+        "Hallo"
+    );
+}
+
+#[test]
+fn test_if_keys_split_across_files_are_complete() {
+    // `en-US` keeps its buttons in `buttons.ftl`, while `hr-HR` has everything in `main.ftl`
+    i18n::load!("./tests/i18n_reorganized", name = REORGANIZED_LOCALES);
+
+    let hr = i18n::langid!("hr-HR");
+    assert_eq!(
+        i18n::tr!(hr, "cancel", locales = REORGANIZED_LOCALES).value,
+        "Odustani"
+    );
+    assert_eq!(
+        i18n::tr!(
+            i18n::langid!("en-US"),
+            "save",
+            locales = REORGANIZED_LOCALES
+        )
+        .value,
+        "Save"
+    );
+}
+
+#[test]
+fn test_if_excluded_drafts_are_skipped() {
+    i18n::load!(
+        "./tests/i18n_drafts",
+        name = DRAFT_LOCALES,
+        exclude = ["_drafts/**", "*.draft.ftl"]
+    );
+
+    let en = i18n::langid!("en-US");
+    assert_eq!(
+        i18n::tr!(en, "hello", locales = DRAFT_LOCALES).value,
+        "Hello"
+    );
+    assert_eq!(DRAFT_LOCALES.locale(&en).unwrap().resources().len(), 1);
+    assert!(DRAFT_LOCALES.query(&en, &"promo".into()).is_err());
+    assert!(DRAFT_LOCALES.query(&en, &"onboarding".into()).is_err());
+}
+
+#[test]
+fn test_if_only_included_files_are_loaded() {
+    i18n::load!(
+        "./tests/i18n_drafts",
+        name = INCLUDED_LOCALES,
+        include = ["*/main.ftl"]
+    );
+
+    let en = i18n::langid!("en-US");
+    assert_eq!(INCLUDED_LOCALES.locale(&en).unwrap().resources().len(), 1);
+    assert!(INCLUDED_LOCALES.query(&en, &"promo".into()).is_err());
+}
+
+#[test]
+#[allow(deprecated)] // the lagging locales are reported through the `deprecated` lint
+fn test_if_available_langs_match_the_embedded_locales() {
+    i18n::load!(
+        "./tests/i18n_lagging",
+        name = LAGGING_LOCALES,
+        langs_name = LAGGING_LANGS,
+        locales = ["hr-HR", "en-US"],
+        check_keys = "warn"
+    );
+
+    let ids = LAGGING_LANGS.iter().map(|lang| lang.id.as_str());
+    assert!(ids.clone().eq(["en-US", "hr-HR"]));
+    let mut embedded = LAGGING_LOCALES
+        .iter()
+        .map(|(lang, _)| lang.to_string())
+        .collect::<Vec<_>>();
+    embedded.sort();
+    assert!(ids.eq(embedded.iter().map(String::as_str)));
+    assert_eq!(
+        *LAGGING_LANGS,
+        i18n::langs!("./tests/i18n_lagging", locales = ["en-US", "hr-HR"])
+    );
+}
+
+#[test]
+fn test_if_common_resources_are_shared_by_every_locale() {
+    i18n::load!(
+        "./tests/i18n_common",
+        name = COMMON_LOCALES,
+        use_isolating = false
+    );
+
+    let (en, hr) = (i18n::langid!("en-US"), i18n::langid!("hr-HR"));
+    let value = |lang: &i18n::LanguageIdentifier, id: &str| {
+        i18n::tr!(lang, id, locales = COMMON_LOCALES).value
+    };
+    assert_eq!(value(&en, "welcome"), "Welcome to Acme!");
+    assert_eq!(value(&hr, "welcome"), "Dobrodošli u Acme!");
+    // the common message is overridden in hr-HR only, which `check_keys` accepts
+    assert_eq!(value(&en, "tagline"), "Made by Acme");
+    assert_eq!(value(&hr, "tagline"), "Izradio Acme");
+
+    let locale = COMMON_LOCALES.locale(&hr).unwrap();
+    assert_eq!(locale.common_resources().len(), 1);
+    assert!(std::sync::Arc::ptr_eq(
+        &locale.common_resources()[0],
+        &COMMON_LOCALES.locale(&en).unwrap().common_resources()[0]
+    ));
+    assert_eq!(
+        i18n::langs!("./tests/i18n_common").map(|lang| lang.id),
+        ["en-US".to_string(), "hr-HR".to_string()]
+    );
+}
+
+#[test]
+fn test_if_locales_are_initialized_once_across_threads() {
+    i18n::load!("./tests/i18n_nested", name = THREADED_LOCALES);
+
+    // every thread races to initialize the static on its first query
+    let barrier = std::sync::Barrier::new(8);
+    std::thread::scope(|scope| {
+        let handles = (0..8)
+            .map(|_| {
+                scope.spawn(|| {
+                    barrier.wait();
+                    let lang = i18n::langid!("hr-HR");
+                    let msg = i18n::tr!(lang, "settings-title", locales = THREADED_LOCALES);
+                    (
+                        msg.value,
+                        &*THREADED_LOCALES as *const i18n::Locales as usize,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        assert!(results.iter().all(|result| *result == results[0]));
+        assert_eq!(results[0].0, "Postavke");
+    });
+}
+
+#[test]
+fn test_if_lazy_static_can_still_be_generated() {
+    i18n::load!(
+        "./tests/i18n_nested",
+        name = LEGACY_LOCALES,
+        langs_name = LEGACY_LANGS,
+        validate_keys = true,
+        legacy_lazy_static = true
+    );
+
+    let lang = i18n::langid!("hr-HR");
+    let msg = i18n::tr!(lang, "settings-title", locales = LEGACY_LOCALES);
+    assert_eq!(msg.value, "Postavke");
+    assert_eq!(LEGACY_LANGS.len(), 2);
+    assert!(LEGACY_LOCALES::__I18N_KEYS.contains(&"hello"));
+}
+
+#[test]
+fn test_if_locales_path_interpolates_environment_variables() {
+    i18n::load!(
+        "${CARGO_MANIFEST_DIR}/tests/i18n_nested",
+        name = ENV_LOCALES
+    );
+
+    let hr = i18n::langid!("hr-HR");
+    assert_eq!(
+        i18n::tr!(hr, "settings-title", locales = ENV_LOCALES).value,
+        "Postavke"
+    );
+    assert_eq!(
+        i18n::langs!("$CARGO_MANIFEST_DIR/tests/i18n_nested").map(|lang| lang.id),
+        ["en-US".to_string(), "hr-HR".to_string()]
+    );
+}
+
+#[test]
+fn test_if_unset_path_variables_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/unset_path_variable.rs");
+}
+
+#[test]
+fn test_if_flat_locale_files_are_loaded() {
+    i18n::load!("./tests/i18n_flat", check_keys = true);
+
+    assert_eq!(i18n::tr!(i18n::langid!("hr-HR"), "bye").value, "Doviđenja");
+    assert_eq!(i18n::tr!(i18n::langid!("en-US"), "hello").value, "Hello");
+    assert_eq!(
+        i18n::langs!("./tests/i18n_flat").map(|lang| lang.id),
+        ["en-US".to_string(), "hr-HR".to_string()]
+    );
+}
+
+#[test]
+fn test_if_nested_locale_files_are_loaded() {
+    i18n::load!("./tests/i18n_nested", check_keys = true);
+
+    let en = i18n::langid!("en-US");
+    let hr = i18n::langid!("hr-HR");
+    assert_eq!(i18n::tr!(en, "settings-title").value, "Settings");
+    assert_eq!(i18n::tr!(hr, "settings-title").value, "Postavke");
+    assert_eq!(i18n::tr!(en, "welcome-subject").value, "Welcome aboard");
+    assert_eq!(i18n::tr!(hr, "welcome-subject").value, "Dobro došli");
+    assert_eq!(LOCALES.locale(&hr).unwrap().resources().len(), 3);
+}
+
+#[test]
+fn test_if_flat_files_merge_with_locale_directories() {
+    i18n::load!("./tests/i18n_mixed");
+
+    // `en-US/main.ftl` and `en-US.ftl` make up a single locale
+    let en = LOCALES.locale(&i18n::langid!("en-US")).unwrap();
+    assert_eq!(en.resources().len(), 2);
+    assert_eq!(i18n::tr!(i18n::langid!("en-US"), "hello").value, "Hello");
+    assert_eq!(i18n::tr!(i18n::langid!("en-US"), "bye").value, "Goodbye");
+    // a locale may be only a directory or only a flat file
+    assert_eq!(i18n::tr!(i18n::langid!("de-DE"), "hello").value, "Hallo");
+    assert_eq!(i18n::tr!(i18n::langid!("hr-HR"), "bye").value, "Doviđenja");
+    assert_eq!(
+        i18n::langs!("./tests/i18n_mixed").map(|lang| lang.id),
+        [
+            "de-DE".to_string(),
+            "en-US".to_string(),
+            "hr-HR".to_string()
+        ]
+    );
+}
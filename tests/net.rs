@@ -41,3 +41,1207 @@ async fn test_from_url_success() {
         .unwrap();
     assert_eq!(msg.value, "Bok, svijete!");
 }
+
+#[tokio::test]
+async fn test_build_from_url_applies_options() {
+    let mut server = mockito::Server::new_async().await;
+    let json_content = json!({
+        "en-US": "hello-user = Hello, { $name }!",
+    });
+    let _mock = server
+        .mock("GET", "/locales.json")
+        .with_status(200)
+        .with_body(json_content.to_string())
+        .create_async()
+        .await;
+
+    let url = &format!("{}/locales.json", server.url());
+    let locales = i18n_loader::LocalesBuilder::new()
+        .use_isolating(false)
+        .build_from_url(url)
+        .await
+        .unwrap();
+
+    let query = Query::new("hello-user").with_arg("name", "John");
+    let msg = locales.query(&langid!("en-US"), &query).unwrap();
+    assert_eq!(msg.value, "Hello, John!");
+}
+
+#[tokio::test]
+async fn test_from_url_lenient_skips_invalid_locales() {
+    let mut server = mockito::Server::new_async().await;
+    let json_content = json!({
+        "en-US": "hello-world = Hello, world!",
+        "hr-HR": "hello-world = { ",
+        "de-DE": "hello-world = Hallo, Welt!",
+    });
+    let _mock = server
+        .mock("GET", "/locales.json")
+        .with_status(200)
+        .with_body(json_content.to_string())
+        .create_async()
+        .await;
+
+    let url = &format!("{}/locales.json", server.url());
+    assert!(matches!(
+        Locales::from_url(url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::ParserError(_))
+    ));
+
+    let partial = Locales::from_url_lenient(url, langid!("en-US"), None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        &partial.errors[..],
+        [i18n_loader::ParserError::ParserError { langid, .. }] if *langid == langid!("hr-HR")
+    ));
+    let msg = partial
+        .locales
+        .query(&langid!("de-DE"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hallo, Welt!");
+    // the skipped locale resolves through the fallback language
+    let msg = partial
+        .locales
+        .query(&langid!("hr-HR"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+
+    // the fallback language failing to parse is still an error
+    assert!(matches!(
+        Locales::from_url_lenient(url, langid!("hr-HR"), None).await,
+        Err(i18n_loader::NetError::ParserError(errors)) if errors.len() == 1
+    ));
+    // and so is the fallback language missing altogether
+    assert!(matches!(
+        Locales::from_url_lenient(url, langid!("fr-FR"), None).await,
+        Err(i18n_loader::NetError::MissingFallback(i18n_loader::UnknownLocale { lang }))
+            if lang == langid!("fr-FR")
+    ));
+}
+
+#[tokio::test]
+async fn test_from_url_lenient_skips_invalid_langids() {
+    let mut server = mockito::Server::new_async().await;
+    let json_content = json!({
+        "en-US": "hello-world = Hello, world!",
+        "not a langid!": "hello-world = Broken",
+    });
+    let _mock = server
+        .mock("GET", "/locales.json")
+        .with_status(200)
+        .with_body(json_content.to_string())
+        .create_async()
+        .await;
+    let without_fallback = json!({
+        "hr-HR": "hello-world = Bok, svijete!",
+        "de-DE": "hello-world = {",
+        "not a langid!": "hello-world = Broken",
+    });
+    let _mock = server
+        .mock("GET", "/without-fallback.json")
+        .with_status(200)
+        .with_body(without_fallback.to_string())
+        .create_async()
+        .await;
+
+    let url = &format!("{}/locales.json", server.url());
+    assert!(matches!(
+        Locales::from_url(url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::ParserError(_))
+    ));
+
+    let partial = Locales::from_url_lenient(url, langid!("en-US"), None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        &partial.errors[..],
+        [i18n_loader::ParserError::InvalidLangid { .. }]
+    ));
+    let msg = partial
+        .locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+
+    // skipping the broken entries must leave the fallback language to resolve every query
+    let url = &format!("{}/without-fallback.json", server.url());
+    assert!(matches!(
+        Locales::from_url_lenient(url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::MissingFallback(i18n_loader::UnknownLocale { lang }))
+            if lang == langid!("en-US")
+    ));
+}
+
+/// Serves a valid resource, but only after `delay`.
+async fn delayed_server(delay: std::time::Duration) -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+    let body = json!({ "en-US": "hello-world = Hello, world!" }).to_string();
+    server
+        .mock("GET", "/locales.json")
+        .with_status(200)
+        .with_chunked_body(move |w| {
+            std::thread::sleep(delay);
+            w.write_all(body.as_bytes())
+        })
+        .create_async()
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_build_from_url_times_out() {
+    use std::time::Duration;
+
+    let server = delayed_server(Duration::from_secs(5)).await;
+    let url = &format!("{}/locales.json", server.url());
+    let result = i18n_loader::LocalesBuilder::new()
+        .timeout(Duration::from_millis(100))
+        .build_from_url(url)
+        .await;
+    assert!(matches!(
+        result,
+        Err(i18n_loader::NetError::Timeout { elapsed })
+            if elapsed >= Duration::from_millis(100) && elapsed < Duration::from_secs(5)
+    ));
+
+    // a timeout longer than the delay does not interfere
+    let server = delayed_server(Duration::from_millis(10)).await;
+    let url = &format!("{}/locales.json", server.url());
+    let locales = i18n_loader::LocalesBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build_from_url(url)
+        .await
+        .unwrap();
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+}
+
+#[tokio::test]
+async fn test_build_from_url_can_be_cancelled() {
+    use std::time::Duration;
+
+    let server = delayed_server(Duration::from_secs(5)).await;
+    let url = &format!("{}/locales.json", server.url());
+    let token = i18n_loader::CancellationToken::new();
+    let supervisor = tokio::spawn({
+        let token = token.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            token.cancel();
+        }
+    });
+
+    let result = i18n_loader::LocalesBuilder::new()
+        .cancel_token(token.clone())
+        .build_from_url_lenient(url)
+        .await;
+    supervisor.await.unwrap();
+    assert!(matches!(result, Err(i18n_loader::NetError::Cancelled)));
+
+    // an already cancelled token abandons the load right away
+    assert!(matches!(
+        i18n_loader::LocalesBuilder::new()
+            .cancel_token(token)
+            .build_from_url(url)
+            .await,
+        Err(i18n_loader::NetError::Cancelled)
+    ));
+}
+
+#[tokio::test]
+async fn test_from_fetcher_without_a_server() {
+    let payload = json!({
+        "en-US": "hello-world = Hello, world!",
+        "hr-HR": "hello-world = Bok, svijete!"
+    })
+    .to_string()
+    .into_bytes();
+
+    let locales = Locales::from_fetcher(
+        "memory://locales.json",
+        |url| {
+            assert_eq!(url, "memory://locales.json");
+            async { Ok::<_, std::io::Error>(payload) }
+        },
+        langid!("en-US"),
+        None,
+    )
+    .await
+    .unwrap();
+    let msg = locales
+        .query(&langid!("hr-HR"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, svijete!");
+
+    // the error of the fetcher is passed through
+    let result = Locales::from_fetcher(
+        "memory://locales.json",
+        |_| async { Err::<Vec<u8>, _>("offline") },
+        langid!("en-US"),
+        None,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(i18n_loader::FetchError::Fetch("offline"))
+    ));
+
+    let result = Locales::from_fetcher(
+        "memory://locales.json",
+        |_| async { Ok::<_, std::io::Error>(b"not json".to_vec()) },
+        langid!("en-US"),
+        None,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(i18n_loader::FetchError::InvalidFormat(_))
+    ));
+
+    let payload = json!({ "en-US": "hello = Hello", "hr-HR": "hello = { $" }).to_string();
+    let load = i18n_loader::LocalesBuilder::new()
+        .build_from_fetcher_lenient("memory://locales.json", |_| async {
+            Ok::<_, std::io::Error>(payload.into_bytes())
+        })
+        .await
+        .unwrap();
+    assert_eq!(load.errors.len(), 1);
+    assert!(load.locales.locale(&langid!("hr-HR")).is_none());
+}
+
+#[tokio::test]
+async fn test_ensure_loaded_fetches_on_demand() {
+    let mut server = mockito::Server::new_async().await;
+    let hr_mock = server
+        .mock("GET", "/i18n/hr-HR.ftl")
+        .with_status(200)
+        .with_body("hello-world = Bok, svijete!")
+        .expect(1)
+        .create_async()
+        .await;
+    let de_mock = server
+        .mock("GET", "/i18n/de-DE.ftl")
+        .with_status(200)
+        .with_body("hello-world = Hallo, Welt!")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let locales = i18n_loader::LocalesBuilder::new()
+        .build_from_strs([("en-US", "hello-world = Hello, world!")])
+        .unwrap();
+    assert!(matches!(
+        locales.ensure_loaded(&langid!("hr-HR")).await,
+        Err(i18n_loader::NetError::NoRemoteSource)
+    ));
+
+    locales.set_remote_source(format!("{}/i18n/{{lang}}.ftl", server.url()));
+    locales.ensure_loaded(&langid!("hr-HR")).await.unwrap();
+    // an already loaded language is not fetched again
+    locales.ensure_loaded(&langid!("hr-HR")).await.unwrap();
+    locales.ensure_loaded(&langid!("en-US")).await.unwrap();
+    hr_mock.assert_async().await;
+    de_mock.assert_async().await;
+
+    let msg = locales
+        .query(&langid!("hr-HR"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, svijete!");
+
+    server
+        .mock("GET", "/i18n/fr-FR.ftl")
+        .with_status(404)
+        .create_async()
+        .await;
+    assert!(matches!(
+        locales.ensure_loaded(&langid!("fr-FR")).await,
+        Err(i18n_loader::NetError::HttpStatus { status: 404, .. })
+    ));
+    assert!(locales.locale(&langid!("fr-FR")).is_none());
+}
+
+#[tokio::test]
+async fn test_ensure_loaded_times_out_and_can_be_cancelled() {
+    use std::time::Duration;
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/i18n/hr-HR.ftl")
+        .with_status(200)
+        .with_chunked_body(|w| {
+            std::thread::sleep(Duration::from_secs(5));
+            w.write_all(b"hello-world = Bok, svijete!")
+        })
+        .create_async()
+        .await;
+    let remote_source = format!("{}/i18n/{{lang}}.ftl", server.url());
+
+    let locales = i18n_loader::LocalesBuilder::new()
+        .timeout(Duration::from_millis(100))
+        .remote_source(&remote_source)
+        .build_from_strs([("en-US", "hello-world = Hello, world!")])
+        .unwrap();
+    assert!(matches!(
+        locales.ensure_loaded(&langid!("hr-HR")).await,
+        Err(i18n_loader::NetError::Timeout { elapsed })
+            if elapsed >= Duration::from_millis(100) && elapsed < Duration::from_secs(5)
+    ));
+
+    let token = i18n_loader::CancellationToken::new();
+    let locales = i18n_loader::LocalesBuilder::new()
+        .cancel_token(token.clone())
+        .remote_source(&remote_source)
+        .build_from_strs([("en-US", "hello-world = Hello, world!")])
+        .unwrap();
+    let supervisor = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        token.cancel();
+    });
+    assert!(matches!(
+        locales.ensure_loaded(&langid!("hr-HR")).await,
+        Err(i18n_loader::NetError::Cancelled)
+    ));
+    supervisor.await.unwrap();
+    assert!(locales.locale(&langid!("hr-HR")).is_none());
+}
+
+#[tokio::test]
+async fn test_from_url_negotiates_the_content_type() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/hr.json")
+        .with_header("content-type", "application/json; charset=utf-8")
+        .with_body(json!({ "hr-HR": "hello-world = Bok, svijete!" }).to_string())
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/hr.ftl")
+        .with_header("content-type", "text/plain; charset=utf-8")
+        .with_header("content-language", "hr-HR")
+        .with_body("hello-world = Bok, svijete!")
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/en.ftl")
+        .with_header("content-type", "application/fluent")
+        .with_body("hello-world = Hello, world!")
+        .create_async()
+        .await;
+
+    for path in ["/hr.json", "/hr.ftl"] {
+        let locales = Locales::from_url(&format!("{}{path}", server.url()), langid!("hr-HR"), None)
+            .await
+            .unwrap();
+        let msg = locales
+            .query(&langid!("hr-HR"), &Query::new("hello-world"))
+            .unwrap();
+        assert_eq!(msg.value, "Bok, svijete!");
+    }
+
+    // the language of an FTL source without a header is set on the builder
+    let url = format!("{}/en.ftl", server.url());
+    let locales = i18n_loader::LocalesBuilder::new()
+        .url_lang(langid!("en-US"))
+        .build_from_url(&url)
+        .await
+        .unwrap();
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+
+    assert!(matches!(
+        Locales::from_url(&url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::MissingContentLanguage { .. })
+    ));
+    let result = i18n_loader::LocalesBuilder::new()
+        .url_lang(langid!("en-US"))
+        .build_from_url(&format!("{}/hr.ftl", server.url()))
+        .await;
+    assert!(matches!(
+        result,
+        Err(i18n_loader::NetError::ContentLanguageMismatch { found, .. }) if found == "hr-HR"
+    ));
+}
+
+#[tokio::test]
+async fn test_add_locale_from_url() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/hr.ftl")
+        .with_header("content-type", "text/plain")
+        .with_header("content-language", "hr-HR")
+        .with_body("hello-world = Bok, svijete!")
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/all.json")
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "de-DE": "hello-world = Hallo, Welt!" }).to_string())
+        .create_async()
+        .await;
+
+    let locales = i18n_loader::LocalesBuilder::new()
+        .build_from_strs([("en-US", "hello-world = Hello, world!")])
+        .unwrap();
+    let url = format!("{}/hr.ftl", server.url());
+    locales
+        .add_locale_from_url(&url, &langid!("hr-HR"))
+        .await
+        .unwrap();
+    let msg = locales
+        .query(&langid!("hr-HR"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, svijete!");
+
+    let url = format!("{}/all.json", server.url());
+    locales
+        .add_locale_from_url(&url, &langid!("de-DE"))
+        .await
+        .unwrap();
+    let msg = locales
+        .query(&langid!("de-DE"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hallo, Welt!");
+
+    assert!(matches!(
+        locales.add_locale_from_url(&url, &langid!("fr-FR")).await,
+        Err(i18n_loader::NetError::MissingLocale { .. })
+    ));
+    let url = format!("{}/hr.ftl", server.url());
+    assert!(matches!(
+        locales.add_locale_from_url(&url, &langid!("fr-FR")).await,
+        Err(i18n_loader::NetError::ContentLanguageMismatch { .. })
+    ));
+    assert!(locales.locale(&langid!("fr-FR")).is_none());
+
+    // an FTL body is not decoded lossily
+    server
+        .mock("GET", "/invalid.ftl")
+        .with_header("content-type", "text/plain")
+        .with_body(b"hello-world = Bok, svijete\xff!")
+        .create_async()
+        .await;
+    let url = format!("{}/invalid.ftl", server.url());
+    assert!(matches!(
+        locales.add_locale_from_url(&url, &langid!("hr-HR")).await,
+        Err(i18n_loader::NetError::InvalidUtf8 { url: found, offset: 26 }) if found == url
+    ));
+    let msg = locales
+        .query(&langid!("hr-HR"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, svijete!");
+
+    // the request is bounded by the timeout set on the builder
+    let server = delayed_server(std::time::Duration::from_secs(5)).await;
+    let url = format!("{}/locales.json", server.url());
+    let locales = i18n_loader::LocalesBuilder::new()
+        .timeout(std::time::Duration::from_millis(100))
+        .build_from_strs([("en-US", "hello-world = Hello, world!")])
+        .unwrap();
+    assert!(matches!(
+        locales.add_locale_from_url(&url, &langid!("en-US")).await,
+        Err(i18n_loader::NetError::Timeout { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_build_from_url_retries_transient_failures() {
+    use i18n_loader::{LocalesBuilder, NetConfig, NetError};
+    use std::time::Duration;
+
+    let config = NetConfig::new()
+        .retries(2)
+        .backoff(Duration::from_millis(10));
+    let mut server = mockito::Server::new_async().await;
+    let failing = server
+        .mock("GET", "/locales.json")
+        .with_status(500)
+        .expect(1)
+        .create_async()
+        .await;
+    let succeeding = server
+        .mock("GET", "/locales.json")
+        .with_status(200)
+        .with_body(json!({ "en-US": "hello-world = Hello, world!" }).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    let url = &format!("{}/locales.json", server.url());
+    let locales = LocalesBuilder::new()
+        .net_config(config)
+        .build_from_url(url)
+        .await
+        .unwrap();
+    failing.assert_async().await;
+    succeeding.assert_async().await;
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+
+    // every attempt failing gives up with the last error
+    let mut server = mockito::Server::new_async().await;
+    let failing = server
+        .mock("GET", "/locales.json")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+    let url = &format!("{}/locales.json", server.url());
+    let result = LocalesBuilder::new()
+        .net_config(config)
+        .build_from_url(url)
+        .await;
+    failing.assert_async().await;
+    assert!(matches!(
+        result,
+        Err(NetError::RetriesExhausted { attempts: 3, source })
+            if matches!(*source, NetError::HttpStatus { status: 503, .. })
+    ));
+
+    // client errors and invalid bodies are not retried
+    let mut server = mockito::Server::new_async().await;
+    let not_found = server
+        .mock("GET", "/missing.json")
+        .with_status(404)
+        .expect(1)
+        .create_async()
+        .await;
+    let invalid = server
+        .mock("GET", "/invalid.json")
+        .with_status(200)
+        .with_body("not json")
+        .expect(1)
+        .create_async()
+        .await;
+    let result = LocalesBuilder::new()
+        .net_config(config)
+        .build_from_url(&format!("{}/missing.json", server.url()))
+        .await;
+    assert!(matches!(
+        result,
+        Err(NetError::HttpStatus { status: 404, .. })
+    ));
+    let result = LocalesBuilder::new()
+        .net_config(config)
+        .build_from_url(&format!("{}/invalid.json", server.url()))
+        .await;
+    assert!(matches!(result, Err(NetError::InvalidFormat(_))));
+    not_found.assert_async().await;
+    invalid.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_refresh_from_url() {
+    use i18n_loader::{NetError, RefreshOutcome};
+
+    let mut server = mockito::Server::new_async().await;
+    let url = &format!("{}/locales.json", server.url());
+    let initial = server
+        .mock("GET", "/locales.json")
+        .match_header("if-none-match", mockito::Matcher::Missing)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v1\"")
+        .with_body(json!({ "en-US": "hello-world = Hello, world!" }).to_string())
+        .create_async()
+        .await;
+    let locales = Locales::from_url(url, langid!("en-US"), None)
+        .await
+        .unwrap();
+
+    // the first refresh knows no validators yet and downloads the resource again
+    let outcome = locales.refresh_from_url(url).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::Reloaded(report) if !report.has_changes()));
+    initial.remove_async().await;
+
+    let not_modified = server
+        .mock("GET", "/locales.json")
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(304)
+        .expect(1)
+        .create_async()
+        .await;
+    let outcome = locales.refresh_from_url(url).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::Unchanged));
+    not_modified.assert_async().await;
+    not_modified.remove_async().await;
+
+    // a source that fails to parse leaves the previous locales and validators in place
+    let invalid = server
+        .mock("GET", "/locales.json")
+        .match_header("if-none-match", "\"v1\"")
+        .with_header("etag", "\"v2\"")
+        .with_body(json!({ "en-US": "hello-world = { $" }).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    assert!(matches!(
+        locales.refresh_from_url(url).await,
+        Err(NetError::ParserError(_))
+    ));
+    invalid.assert_async().await;
+    invalid.remove_async().await;
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+
+    let changed = server
+        .mock("GET", "/locales.json")
+        .match_header("if-none-match", "\"v1\"")
+        .with_header("etag", "\"v3\"")
+        .with_body(
+            json!({
+                "en-US": "hello-world = Hello again!",
+                "hr-HR": "hello-world = Bok opet!"
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let outcome = locales.refresh_from_url(url).await.unwrap();
+    changed.assert_async().await;
+    let RefreshOutcome::Reloaded(report) = outcome else {
+        panic!("expected the locales to be reloaded");
+    };
+    assert_eq!(report.changed, vec![langid!("en-US")]);
+    assert_eq!(report.added, vec![langid!("hr-HR")]);
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello again!");
+
+    // the validators of the latest response are sent from then on
+    let not_modified = server
+        .mock("GET", "/locales.json")
+        .match_header("if-none-match", "\"v3\"")
+        .with_status(304)
+        .expect(1)
+        .create_async()
+        .await;
+    let outcome = locales.refresh_from_url(url).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::Unchanged));
+    not_modified.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_refresh_from_url_times_out() {
+    use std::time::Duration;
+
+    let server = delayed_server(Duration::from_secs(5)).await;
+    let url = &format!("{}/locales.json", server.url());
+    let locales = i18n_loader::LocalesBuilder::new()
+        .timeout(Duration::from_millis(100))
+        .build_from_strs([("en-US", "hello-world = Hi!")])
+        .unwrap();
+    assert!(matches!(
+        locales.refresh_from_url(url).await,
+        Err(i18n_loader::NetError::Timeout { elapsed })
+            if elapsed >= Duration::from_millis(100) && elapsed < Duration::from_secs(5)
+    ));
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hi!");
+}
+
+/// Polls `condition` until it holds, failing the test after a few seconds.
+async fn eventually(condition: impl Fn() -> bool) {
+    for _ in 0..200 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    panic!("the condition did not hold in time");
+}
+
+#[tokio::test]
+async fn test_spawn_auto_refresh() {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let mut server = mockito::Server::new_async().await;
+    let url = format!("{}/locales.json", server.url());
+    let initial = server
+        .mock("GET", "/locales.json")
+        .with_body(json!({ "en-US": "hello-world = Hello, world!" }).to_string())
+        .create_async()
+        .await;
+    let locales = Arc::new(
+        Locales::from_url(&url, langid!("en-US"), None)
+            .await
+            .unwrap(),
+    );
+    let query = {
+        let locales = locales.clone();
+        move || {
+            locales
+                .query(&langid!("en-US"), &Query::new("hello-world"))
+                .unwrap()
+                .value
+        }
+    };
+    assert_eq!(query(), "Hello, world!");
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let handle = locales.clone().spawn_auto_refresh(
+        url.clone(),
+        Duration::from_millis(20),
+        move |outcome| _ = sender.send(matches!(outcome, i18n_loader::RefreshOutcome::Reloaded(report) if report.has_changes())),
+    );
+    eventually(|| handle.last_refresh().is_some()).await;
+    initial.remove_async().await;
+
+    // a failed refresh is reported and keeps the previous locales
+    let failing = server
+        .mock("GET", "/locales.json")
+        .with_status(500)
+        .create_async()
+        .await;
+    eventually(|| handle.last_error().is_some()).await;
+    assert_eq!(query(), "Hello, world!");
+    failing.remove_async().await;
+
+    server
+        .mock("GET", "/locales.json")
+        .with_body(json!({ "en-US": "hello-world = Hello again!" }).to_string())
+        .create_async()
+        .await;
+    eventually(|| query() == "Hello again!").await;
+    assert!(receiver.try_iter().any(|changed| changed));
+    eventually(|| handle.last_error().is_none()).await;
+
+    handle.stop();
+    assert!(handle.is_stopped());
+}
+
+#[tokio::test]
+async fn test_spawn_auto_refresh_outlives_hung_requests() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // a server that accepts every connection but never responds
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/locales.json", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn({
+        let requests = requests.clone();
+        move || {
+            let mut connections = Vec::new();
+            for stream in listener.incoming() {
+                requests.fetch_add(1, Ordering::SeqCst);
+                connections.push(stream);
+            }
+        }
+    });
+
+    let locales = Arc::new(
+        i18n_loader::LocalesBuilder::new()
+            .timeout(Duration::from_millis(100))
+            .build_from_strs([("en-US", "hello-world = Hello, world!")])
+            .unwrap(),
+    );
+    let handle = locales
+        .clone()
+        .spawn_auto_refresh(url, Duration::from_millis(20), |_| {});
+    eventually(|| {
+        matches!(
+            handle.last_error().as_deref(),
+            Some(i18n_loader::NetError::Timeout { .. })
+        )
+    })
+    .await;
+    // the hung request does not stop the following refreshes
+    eventually(|| requests.load(Ordering::SeqCst) >= 2).await;
+    assert!(handle.last_refresh().is_none());
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+}
+
+#[tokio::test]
+async fn test_build_from_url_reports_http_errors() {
+    use i18n_loader::{LocalesBuilder, NetConfig, NetError};
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/not-found.json")
+        .with_status(404)
+        .with_header("content-type", "text/html")
+        .with_body("<html><body>Not Found</body></html>")
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/error.json")
+        .with_status(500)
+        .with_body("x".repeat(1000))
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/empty.json")
+        .with_status(200)
+        .create_async()
+        .await;
+    let load = |path: &str| {
+        let url = format!("{}{path}", server.url());
+        async move {
+            LocalesBuilder::new()
+                .net_config(NetConfig::new().retries(0))
+                .build_from_url(&url)
+                .await
+        }
+    };
+
+    let err = load("/not-found.json").await.err().unwrap();
+    assert!(matches!(
+        &err,
+        NetError::HttpStatus { status: 404, body_snippet, .. } if body_snippet.contains("Not Found")
+    ));
+    let message = err.to_string();
+    assert!(message.contains(&format!("{}/not-found.json", server.url())));
+    assert!(message.contains("404"));
+
+    let err = load("/error.json").await.err().unwrap();
+    assert!(matches!(
+        err,
+        NetError::HttpStatus { status: 500, body_snippet, .. } if body_snippet.len() < 1000
+    ));
+
+    let err = load("/empty.json").await.err().unwrap();
+    assert!(matches!(&err, NetError::EmptyBody { url } if url.ends_with("/empty.json")));
+    assert!(err.to_string().contains("/empty.json"));
+}
+
+#[tokio::test]
+async fn test_from_url_or_keeps_the_embedded_translations() {
+    let embedded = || {
+        i18n_loader::LocalesBuilder::new()
+            .build_from_strs([
+                ("en-US", "hello-world = Hello, world!"),
+                (
+                    "hr-HR",
+                    "hello-world = Bok, svijete!\nonly-embedded = Samo ugrađeno",
+                ),
+            ])
+            .unwrap()
+    };
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/locales.json")
+        .with_body(json!({ "en-US": "hello-world = Hello from the web!" }).to_string())
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/down.json")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let url = &format!("{}/locales.json", server.url());
+    let (locales, error) = Locales::from_url_or(url, embedded()).await;
+    assert!(error.is_none());
+    let query = |lang, id| locales.query(&lang, &Query::new(id)).unwrap().value;
+    assert_eq!(
+        query(langid!("en-US"), "hello-world"),
+        "Hello from the web!"
+    );
+    // the languages absent from the remote payload remain available
+    assert_eq!(query(langid!("hr-HR"), "hello-world"), "Bok, svijete!");
+    assert_eq!(query(langid!("hr-HR"), "only-embedded"), "Samo ugrađeno");
+
+    // transient failures are retried before giving up
+    let url = &format!("{}/down.json", server.url());
+    let (locales, error) = Locales::from_url_or(url, embedded()).await;
+    assert!(matches!(
+        error,
+        Some(i18n_loader::NetError::RetriesExhausted { attempts: 4, source })
+            if matches!(*source, i18n_loader::NetError::HttpStatus { status: 503, .. })
+    ));
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+
+    // a server that hangs falls back once the timeout of the embedded collection elapses
+    let server = delayed_server(std::time::Duration::from_secs(5)).await;
+    let url = &format!("{}/locales.json", server.url());
+    let embedded = i18n_loader::LocalesBuilder::new()
+        .timeout(std::time::Duration::from_millis(100))
+        .build_from_strs([("en-US", "hello-world = Hello, world!")])
+        .unwrap();
+    let (locales, error) = Locales::from_url_or(url, embedded).await;
+    assert!(matches!(error, Some(i18n_loader::NetError::Timeout { .. })));
+    let msg = locales
+        .query(&langid!("en-US"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Hello, world!");
+}
+
+#[tokio::test]
+async fn test_set_miss_uploader_posts_the_misses() {
+    use mockito::Matcher;
+    use std::time::Duration;
+
+    let locales = i18n_loader::LocalesBuilder::new()
+        .build_from_strs([
+            ("en-US", "hello = Hello\nbye = Bye\n    .title = Leave"),
+            ("hr-HR", "bye = Bok"),
+        ])
+        .unwrap();
+    let mut server = mockito::Server::new_async().await;
+    let upload = server
+        .mock("POST", "/misses")
+        .match_header("content-type", "application/json")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""id":"missing","lang":"hr-HR""#.to_string()),
+            Matcher::Regex(r#""attr":null,"id":"hello","lang":"hr-HR""#.to_string()),
+            Matcher::Regex(r#""attr":"title","id":"bye","lang":"hr-HR""#.to_string()),
+            Matcher::Regex(r#""ts":\d+"#.to_string()),
+        ]))
+        .expect(1)
+        .create_async()
+        .await;
+
+    // a long interval leaves the upload to `flush`
+    let uploader = locales.set_miss_uploader(
+        format!("{}/misses", server.url()),
+        Duration::from_secs(3600),
+    );
+    let hr = langid!("hr-HR");
+    assert!(locales.query(&hr, &Query::new("missing")).is_err());
+    locales
+        .query(&hr, &Query::new("hello").with_fallback(true))
+        .unwrap();
+    locales
+        .query(&hr, &Query::new("bye").with_fallback(true))
+        .unwrap();
+    uploader.flush().await.unwrap();
+    upload.assert_async().await;
+    // nothing is left to upload
+    uploader.flush().await.unwrap();
+    upload.assert_async().await;
+
+    // a short interval uploads in the background
+    let periodic = server
+        .mock("POST", "/periodic")
+        .match_body(Matcher::Regex(r#""id":"missing""#.to_string()))
+        .create_async()
+        .await;
+    let _uploader = locales.set_miss_uploader(
+        format!("{}/periodic", server.url()),
+        Duration::from_millis(20),
+    );
+    assert!(locales.query(&hr, &Query::new("missing")).is_err());
+    for _ in 0..200 {
+        if periodic.matched_async().await {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    periodic.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_from_url_decompresses_responses() {
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use std::io::Write;
+
+    let payload = json!({ "en-US": "hello-world = Hello, world!" }).to_string();
+    let mut gzip = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gzip.write_all(payload.as_bytes()).unwrap();
+    let mut deflate = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    deflate.write_all(payload.as_bytes()).unwrap();
+
+    let mut server = mockito::Server::new_async().await;
+    for (path, encoding, body) in [
+        ("/gzip.json", "gzip", gzip.finish().unwrap()),
+        ("/deflate.json", "deflate", deflate.finish().unwrap()),
+    ] {
+        server
+            .mock("GET", path)
+            .match_header("accept-encoding", "gzip, deflate")
+            .with_header("content-type", "application/json")
+            .with_header("content-encoding", encoding)
+            .with_body(body)
+            .create_async()
+            .await;
+    }
+    server
+        .mock("GET", "/brotli.json")
+        .with_header("content-encoding", "br")
+        .with_body(payload.as_bytes())
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/corrupt.json")
+        .with_header("content-encoding", "gzip")
+        .with_body(payload.as_bytes())
+        .create_async()
+        .await;
+
+    for path in ["/gzip.json", "/deflate.json"] {
+        let url = &format!("{}{path}", server.url());
+        let locales = Locales::from_url(url, langid!("en-US"), None)
+            .await
+            .unwrap();
+        let msg = locales
+            .query(&langid!("en-US"), &Query::new("hello-world"))
+            .unwrap();
+        assert_eq!(msg.value, "Hello, world!");
+    }
+
+    let url = &format!("{}/brotli.json", server.url());
+    assert!(matches!(
+        Locales::from_url(url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::UnsupportedEncoding(encoding)) if encoding == "br"
+    ));
+    let url = &format!("{}/corrupt.json", server.url());
+    assert!(matches!(
+        Locales::from_url(url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::Decompress { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_from_url_with_meta_reads_versioned_payloads() {
+    use i18n_loader::{NetError, RemoteMeta};
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v2.json")
+        .with_body(
+            json!({
+                "version": 2,
+                "generated_at": "2024-05-01T12:00:00Z",
+                "fallback": "hr-HR",
+                "locales": {
+                    "en-US": "hello-world = Hello, world!",
+                    "hr-HR": "hello-world = Bok, svijete!"
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/legacy.json")
+        .with_body(json!({ "en-US": "hello-world = Hello, world!" }).to_string())
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/v3.json")
+        .with_body(json!({ "version": 3, "locales": {} }).to_string())
+        .create_async()
+        .await;
+
+    // the fallback of the payload applies unless the caller sets one
+    let url = &format!("{}/v2.json", server.url());
+    let (locales, meta) = Locales::from_url_with_meta(url, None, None).await.unwrap();
+    assert_eq!(
+        meta,
+        RemoteMeta {
+            version: 2,
+            generated_at: Some("2024-05-01T12:00:00Z".to_string()),
+            fallback: Some(langid!("hr-HR")),
+        }
+    );
+    assert_eq!(locales.fallback_lang(), langid!("hr-HR"));
+    let msg = locales
+        .query(&langid!("de-DE"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, svijete!");
+    let (locales, _) = Locales::from_url_with_meta(url, Some(langid!("en-US")), None)
+        .await
+        .unwrap();
+    assert_eq!(locales.fallback_lang(), langid!("en-US"));
+    let locales = Locales::from_url(url, langid!("en-US"), None)
+        .await
+        .unwrap();
+    assert_eq!(locales.fallback_lang(), langid!("en-US"));
+
+    let url = &format!("{}/legacy.json", server.url());
+    let (locales, meta) = Locales::from_url_with_meta(url, None, None).await.unwrap();
+    assert_eq!(meta, RemoteMeta::default());
+    assert_eq!(meta.version, 1);
+    assert_eq!(locales.fallback_lang(), langid!("en-US"));
+
+    let url = &format!("{}/v3.json", server.url());
+    let err = Locales::from_url_with_meta(url, None, None)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, NetError::UnsupportedVersion(3)));
+    assert_eq!(err.to_string(), "unsupported payload version 3, expected 2");
+}
+
+#[tokio::test]
+async fn test_from_url_with_meta_ignores_a_fallback_missing_from_the_payload() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/locales.json")
+        .with_body(
+            json!({
+                "version": 2,
+                "fallback": "de-DE",
+                "locales": { "en-US": "hello = Hi" }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    // the caller's fallback is kept, so that unloaded languages still resolve
+    let url = &format!("{}/locales.json", server.url());
+    let (locales, meta) = Locales::from_url_with_meta(url, None, None).await.unwrap();
+    assert_eq!(meta.fallback, Some(langid!("de-DE")));
+    assert_eq!(locales.fallback_lang(), langid!("en-US"));
+    let msg = locales
+        .query(&langid!("fr-FR"), &Query::new("hello"))
+        .unwrap();
+    assert_eq!(msg.value, "Hi");
+}
+
+#[tokio::test]
+async fn test_from_url_filtered_loads_only_the_requested_languages() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/locales.json")
+        .with_body(
+            json!({
+                "en-US": "hello-world = Hello, world!",
+                "hr-HR": "hello-world = Bok, svijete!",
+                // never parsed, or the load would fail
+                "de-DE": "hello-world = { $",
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let url = &format!("{}/locales.json", server.url());
+    // the language subtag alone selects `hr-HR`, and the fallback language is always loaded
+    let locales = Locales::from_url_filtered(url, &[langid!("hr")], langid!("en-US"), None)
+        .await
+        .unwrap();
+    let mut langs = locales.iter().map(|(lang, _)| lang).collect::<Vec<_>>();
+    langs.sort();
+    assert_eq!(langs, vec![langid!("en-US"), langid!("hr-HR")]);
+    let msg = locales
+        .query(&langid!("hr-HR"), &Query::new("hello-world"))
+        .unwrap();
+    assert_eq!(msg.value, "Bok, svijete!");
+
+    assert!(matches!(
+        Locales::from_url(url, langid!("en-US"), None).await,
+        Err(i18n_loader::NetError::ParserError(_))
+    ));
+}
@@ -0,0 +1,146 @@
+use i18n::{langid, FluentResource, Locales, Query};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Creates an empty directory unique to the test `name`.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("i18n-reload-{name}-{}", std::process::id()));
+    _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, lang: &str, file: &str, source: &str) {
+    std::fs::create_dir_all(dir.join(lang)).unwrap();
+    std::fs::write(dir.join(lang).join(file), source).unwrap();
+}
+
+fn value(locales: &Locales, lang: &str, id: &str) -> String {
+    locales
+        .query(&lang.parse().unwrap(), &Query::new(id).with_fallback(true))
+        .unwrap()
+        .value
+}
+
+#[test]
+fn test_if_changed_locales_are_reloaded() {
+    let dir = temp_dir("changed");
+    write(&dir, "en-US", "main.ftl", "hello = Hello\nbye = Bye");
+    write(&dir, "hr-HR", "main.ftl", "hello = Bok");
+    let locales = Locales::builder().build_from_dir(&dir).unwrap();
+    assert_eq!(value(&locales, "hr-HR", "hello"), "Bok");
+
+    // nothing changed on disk
+    let report = locales.reload_from_dir(&dir).unwrap();
+    assert!(!report.has_changes() && !report.has_errors());
+
+    write(&dir, "hr-HR", "main.ftl", "hello = Zdravo");
+    write(&dir, "de-DE", "main.ftl", "hello = Hallo");
+    let report = locales.reload_from_dir(&dir).unwrap();
+    assert_eq!(report.added, [langid!("de-DE")]);
+    assert_eq!(report.changed, [langid!("hr-HR")]);
+    assert!(!report.has_errors());
+    assert_eq!(value(&locales, "hr-HR", "hello"), "Zdravo");
+    assert_eq!(value(&locales, "de-DE", "hello"), "Hallo");
+    assert_eq!(value(&locales, "en-US", "bye"), "Bye");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_if_added_locales_share_the_common_directory() {
+    let dir = temp_dir("common-dir");
+    write(&dir, "_common", "brand.ftl", "-brand = Acme");
+    write(&dir, "en-US", "main.ftl", "hello = Hello from { -brand }");
+    let locales = Locales::builder()
+        .use_isolating(false)
+        .build_from_dir(&dir)
+        .unwrap();
+    assert_eq!(value(&locales, "en-US", "hello"), "Hello from Acme");
+
+    write(&dir, "hr-HR", "main.ftl", "hello = Pozdrav od { -brand }");
+    let report = locales.reload_from_dir(&dir).unwrap();
+    assert_eq!(report.added, [langid!("hr-HR")]);
+    assert_eq!(value(&locales, "hr-HR", "hello"), "Pozdrav od Acme");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_if_common_resources_survive_reloads() {
+    let dir = temp_dir("common");
+    write(&dir, "hr-HR", "main.ftl", "hello = Bok, { -brand }");
+    let common = Arc::new(FluentResource::try_new("-brand = Acme\nbye = Bye".into()).unwrap());
+    let locales = Locales::builder().use_isolating(false).build();
+    let own = FluentResource::try_new("hello = Bok, { -brand }\nbye = Bok bok".into()).unwrap();
+    locales
+        .add_shared_locale_with_common("hr-HR", vec![Arc::new(own)], vec![common])
+        .unwrap();
+    // the own definition wins despite `ResourceConflict::Error`
+    assert_eq!(value(&locales, "hr-HR", "bye"), "Bok bok");
+
+    let report = locales.reload_from_dir(&dir).unwrap();
+    assert_eq!(report.changed, [langid!("hr-HR")]);
+    assert_eq!(value(&locales, "hr-HR", "hello"), "Bok, Acme");
+    assert_eq!(value(&locales, "hr-HR", "bye"), "Bye");
+    assert!(!locales.reload_from_dir(&dir).unwrap().has_changes());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_if_invalid_sources_keep_the_previous_locale() {
+    let dir = temp_dir("invalid");
+    write(&dir, "en-US", "main.ftl", "hello = Hello");
+    write(&dir, "hr-HR", "main.ftl", "hello = Bok");
+    write(&dir, "hr-HR", "other.ftl", "bye = Bok bok");
+    let locales = Locales::builder().build_from_dir(&dir).unwrap();
+
+    // a single invalid file keeps the whole language as it was
+    write(&dir, "hr-HR", "main.ftl", "hello = Zdravo");
+    write(&dir, "hr-HR", "other.ftl", "bye = { $");
+    write(&dir, "en-US", "main.ftl", "hello = Hi");
+    let report = locales.reload_from_dir(&dir).unwrap();
+    assert_eq!(report.changed, [langid!("en-US")]);
+    assert!(matches!(
+        report.errors.as_slice(),
+        [i18n::ParserError::ParserError { langid, .. }] if *langid == langid!("hr-HR")
+    ));
+    assert_eq!(value(&locales, "hr-HR", "hello"), "Bok");
+    assert_eq!(value(&locales, "hr-HR", "bye"), "Bok bok");
+    assert_eq!(value(&locales, "en-US", "hello"), "Hi");
+
+    // an unreadable directory changes nothing
+    assert!(matches!(
+        locales.reload_from_dir(dir.join("missing")),
+        Err(i18n::LoadError::Io { .. })
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_if_watched_directories_are_reloaded() {
+    let dir = temp_dir("watched");
+    write(&dir, "en-US", "main.ftl", "hello = Hello");
+    let locales: &'static Locales =
+        Box::leak(Box::new(Locales::builder().build_from_dir(&dir).unwrap()));
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let _watcher = locales
+        .watch_dir(&dir, move |report| _ = sender.send(report))
+        .unwrap();
+    write(&dir, "en-US", "main.ftl", "hello = Hi");
+
+    // several events may be reported for the same write, some while the file is still truncated
+    let reloaded = || {
+        locales
+            .query(&langid!("en-US"), &Query::new("hello"))
+            .is_ok_and(|msg| msg.value == "Hi")
+    };
+    let timeout = std::time::Duration::from_secs(10);
+    while !reloaded() && receiver.recv_timeout(timeout).is_ok() {}
+    assert!(reloaded());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
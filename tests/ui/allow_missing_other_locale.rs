@@ -0,0 +1,4 @@
+// trybuild compiles this from `target/tests/trybuild/i18n`, which is its crate root
+i18n::load!("../../../../tests/i18n_lagging", allow_missing = ["hr-HR"]);
+
+fn main() {}
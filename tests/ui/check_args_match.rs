@@ -0,0 +1,7 @@
+// trybuild compiles this from `target/tests/trybuild/i18n`, which is its crate root
+i18n::load!("../../../../tests/i18n_args");
+
+fn main() {
+    let lang = i18n::langid!("hr-HR");
+    let _ = i18n::tr!(lang, "welcome", "username" = "Ana");
+}
@@ -0,0 +1,4 @@
+// trybuild compiles this from `target/tests/trybuild/i18n`, which is its crate root
+i18n::load!("../../../../tests/i18n_args_mismatch");
+
+fn main() {}
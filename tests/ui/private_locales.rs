@@ -0,0 +1,8 @@
+mod embedded {
+    // trybuild compiles this from `target/tests/trybuild/i18n`, which is its crate root
+    i18n::load!("../../../../tests/i18n_flat", vis = pub(self));
+}
+
+fn main() {
+    let _ = &*embedded::LOCALES;
+}
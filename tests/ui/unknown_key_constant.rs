@@ -0,0 +1,10 @@
+// trybuild compiles this from `target/tests/trybuild/i18n`, which is its crate root
+i18n::load!(
+    "../../../../tests/i18n",
+    allow_missing = ["hr-hr"],
+    keys_mod = keys
+);
+
+fn main() {
+    let _ = i18n::Query::new(keys::LOGIN_BUTTON);
+}
@@ -0,0 +1,3 @@
+i18n::load!("$I18N_UNSET_LOCALES_DIR/i18n");
+
+fn main() {}